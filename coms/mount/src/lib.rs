@@ -1,6 +1,35 @@
-//! Mount is the entry for the mount point monitoring of sysmaster. sysmaster refers to systemd, but it is not the same.
-//! sysmaster mainly provides the monitoring function and does not actively mount/unmount the mount point, which is implemented by other processes.
-//! Mount does not support configuration files.
+//! Mount is the entry for the mount point management of sysmaster. sysmaster refers to systemd, but it is not the same.
+//! sysmaster monitors the mount points in /proc/self/mountinfo, and also mounts/unmounts the mount point by running mount(8)/umount(8).
+//! A mount point mounted or unmounted by other processes is picked up by the monitor.
+//!
+//! The Mount configuration file contains the [Unit] [Mount] [Install] sections. The unit file name must match the mount point,
+//! for example, "/boot/efi" corresponds to "boot-efi.mount".
+//!
+//! ```toml
+//! [Mount]
+//! What = "/dev/sdb1"
+//! Where = "/mnt/data"
+//! Type = "ext4"
+//! Options = "rw,noatime"
+//! TimeoutSec = "90s"
+//! ```
+//!
+//! ### What
+//! The absolute path of the device, file or other resource to mount. A unit without What= can not be mounted by sysmaster,
+//! it only tracks the mount point mounted by others.
+//!
+//! ### Where
+//! The absolute path of the mount point, created if it does not exist. Derived from the unit name if not configured.
+//!
+//! ### Type
+//! The file system type, passed to mount(8) with "-t".
+//!
+//! ### Options
+//! The comma separated mount options, passed to mount(8) with "-o".
+//!
+//! ### TimeoutSec
+//! The time to wait for the mount/umount command, the command is killed with SIGKILL and the unit fails on timeout.
+//! Defaults to DefaultTimeoutSec, 0 means no timeout.
 //!
 //! ## Automatic dependency
 //! NA
@@ -9,10 +38,12 @@
 //! ### Default Dependency
 //! NA
 
-// dependency: mount_base -> mount_rentry -> mount_comm -> {mount_mng -> mount_unit} -> mount_manager
+// dependency: mount_base -> mount_rentry -> mount_comm -> mount_config -> mount_spawn -> {mount_mng -> mount_unit} -> mount_manager
 mod mount_base;
 mod mount_comm;
+mod mount_config;
 mod mount_manager;
 mod mount_mng;
 mod mount_rentry;
+mod mount_spawn;
 mod mount_unit;
@@ -1,2 +1,89 @@
+use libmount::mountinfo;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
 pub(super) const LOG_LEVEL: u32 = 4;
 pub(super) const PLUGIN_NAME: &str = "MountUnit";
+pub(super) const MOUNT_BIN: &str = "mount";
+pub(super) const UMOUNT_BIN: &str = "umount";
+
+// /bin is not the same directory as /usr/bin on the systems without merged /usr
+const BIN_DIRS: [&str; 4] = ["/usr/bin", "/bin", "/usr/sbin", "/sbin"];
+
+// the absolute path of the binary, in /usr/bin if it is not found anywhere
+pub(super) fn find_bin(name: &str) -> String {
+    BIN_DIRS
+        .iter()
+        .map(|dir| format!("{}/{}", dir, name))
+        .find(|path| Path::new(path).is_file())
+        .unwrap_or_else(|| format!("{}/{}", BIN_DIRS[0], name))
+}
+
+pub(super) fn mount_point_to_unit_name(mount_point: &str) -> String {
+    let mut res = String::from(mount_point).replace('/', "-") + ".mount";
+    if res != "-.mount" {
+        res = String::from(&res[1..])
+    }
+    res
+}
+
+pub(super) fn unit_name_to_mount_point(unit_name: &str) -> String {
+    let name = unit_name.trim_end_matches(".mount");
+    if name == "-" {
+        return "/".to_string();
+    }
+    format!("/{}", name.replace('-', "/"))
+}
+
+// check whether the mount point is listed in /proc/self/mountinfo
+pub(super) fn mount_point_is_mounted(mount_point: &str) -> bool {
+    let mut mountinfo_content = String::new();
+    if let Err(e) = File::open("/proc/self/mountinfo")
+        .and_then(|mut f| f.read_to_string(&mut mountinfo_content))
+    {
+        log::error!("Failed to read /proc/self/mountinfo: {}", e);
+        return false;
+    }
+
+    let parser = mountinfo::Parser::new(mountinfo_content.as_bytes());
+    for mount in parser.flatten() {
+        if mount.mount_point.to_str() == Some(mount_point) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_bin, mount_point_is_mounted, mount_point_to_unit_name, unit_name_to_mount_point,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test_mount_point_unit_name() {
+        assert_eq!(mount_point_to_unit_name("/"), "-.mount");
+        assert_eq!(mount_point_to_unit_name("/boot/efi"), "boot-efi.mount");
+        assert_eq!(unit_name_to_mount_point("-.mount"), "/");
+        assert_eq!(unit_name_to_mount_point("boot-efi.mount"), "/boot/efi");
+    }
+
+    #[test]
+    fn test_mount_point_is_mounted() {
+        assert!(mount_point_is_mounted("/"));
+        assert!(!mount_point_is_mounted("/sysmaster-not-exist"));
+    }
+
+    #[test]
+    fn test_find_bin() {
+        let sh = find_bin("sh");
+        assert!(sh.ends_with("/sh"));
+        assert!(Path::new(&sh).is_file());
+        assert_eq!(
+            find_bin("sysmaster-not-exist"),
+            "/usr/bin/sysmaster-not-exist"
+        );
+    }
+}
@@ -3,9 +3,10 @@
 //! *  Get the attributes of the unit object
 //! *  Call relation: mount_ unit->mount_ mng->mount_ comm
 
-use super::mount_rentry::{MountRe, MountState};
+use super::mount_rentry::{MountRe, MountResult, MountState, SectionMount};
 use libsysmaster::manager::{UmIf, Unit};
 use libsysmaster::Reliability;
+use nix::unistd::Pid;
 use once_cell::sync::Lazy;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
@@ -40,11 +41,25 @@ impl MountUnitComm {
         self.data.borrow().unit()
     }
 
-    pub(super) fn rentry_mng_insert(&self, state: MountState) {
-        self.rentry().mng_insert(self.unit().id(), state)
+    pub(super) fn rentry_conf_insert(&self, mount: &SectionMount) {
+        self.rentry().conf_insert(self.unit().id(), mount)
     }
 
-    pub(super) fn rentry_mng_get(&self) -> Option<MountState> {
+    pub(super) fn rentry_conf_get(&self) -> Option<SectionMount> {
+        self.rentry().conf_get(self.unit().id())
+    }
+
+    pub(super) fn rentry_mng_insert(
+        &self,
+        state: MountState,
+        result: MountResult,
+        control_pid: Option<Pid>,
+    ) {
+        self.rentry()
+            .mng_insert(self.unit().id(), state, result, control_pid)
+    }
+
+    pub(super) fn rentry_mng_get(&self) -> Option<(MountState, MountResult, Option<Pid>)> {
         self.rentry().mng_get(self.unit().id())
     }
 
@@ -52,7 +67,7 @@ impl MountUnitComm {
         self.umcomm.rentry()
     }
 
-    pub(super) fn _um(&self) -> Rc<dyn UmIf> {
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
         self.umcomm.um()
     }
 }
@@ -0,0 +1,143 @@
+//! mount_config mod load the conf file list and convert it to structure which is defined in this mod.
+//!
+#![allow(non_snake_case)]
+use super::mount_base::{mount_point_to_unit_name, unit_name_to_mount_point};
+use super::mount_comm::MountUnitComm;
+use super::mount_rentry::SectionMount;
+use confique::Config;
+use libsysmaster::ReStation;
+use std::cell::RefCell;
+use std::error::Error;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+pub(super) struct MountConfig {
+    // associated objects
+    comm: Rc<MountUnitComm>,
+
+    // owned objects
+    data: Rc<RefCell<MountConfigData>>,
+}
+
+impl ReStation for MountConfig {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        if let Some(conf) = self.comm.rentry_conf_get() {
+            self.data.replace(MountConfigData::new(conf));
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_conf_insert(&self.data.borrow().Mount);
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl MountConfig {
+    pub(super) fn new(commr: &Rc<MountUnitComm>) -> Self {
+        MountConfig {
+            comm: Rc::clone(commr),
+            data: Rc::new(RefCell::new(MountConfigData::default())),
+        }
+    }
+
+    pub(super) fn load(&self, paths: Vec<PathBuf>, update: bool) -> Result<(), Box<dyn Error>> {
+        let mut builder = MountConfigData::builder().env();
+
+        log::debug!("mount load path: {:?}", paths);
+        // fragment
+        for v in paths {
+            builder = builder.file(&v);
+        }
+
+        *self.data.borrow_mut() = builder.load()?;
+
+        if update {
+            self.db_update();
+        }
+
+        Ok(())
+    }
+
+    /// the mount point must be consistent with the unit name, like "/boot/efi" with "boot-efi.mount"
+    pub(super) fn verify(&self, unit_id: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(mount_point) = &self.data.borrow().Mount.Where {
+            if mount_point_to_unit_name(mount_point) != unit_id {
+                return Err(format!(
+                    "Where={} does not match the unit name {}",
+                    mount_point, unit_id
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the mount point of the unit, derived from the unit name if Where= is not configured
+    pub(super) fn mount_where(&self, unit_id: &str) -> String {
+        match &self.data.borrow().Mount.Where {
+            Some(mount_point) => mount_point.clone(),
+            None => unit_name_to_mount_point(unit_id),
+        }
+    }
+
+    pub(super) fn mount_what(&self) -> Option<String> {
+        self.data.borrow().Mount.What.clone()
+    }
+
+    pub(super) fn mount_type(&self) -> Option<String> {
+        self.data.borrow().Mount.Type.clone()
+    }
+
+    pub(super) fn mount_options(&self) -> Option<String> {
+        self.data.borrow().Mount.Options.clone()
+    }
+
+    pub(super) fn timeout_sec(&self) -> Option<u64> {
+        self.data.borrow().Mount.TimeoutSec
+    }
+}
+
+#[derive(Config, Default, Debug)]
+pub(super) struct MountConfigData {
+    #[config(nested)]
+    pub Mount: SectionMount,
+}
+
+impl MountConfigData {
+    pub(self) fn new(Mount: SectionMount) -> MountConfigData {
+        MountConfigData { Mount }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mount_comm::MountUnitComm;
+    use crate::mount_config::MountConfig;
+    use libtests::get_project_root;
+    use libutils::time_util::USEC_PER_SEC;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_mount_parse() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/mnt-data.mount.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(MountUnitComm::new());
+        let config = MountConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        assert_eq!(config.mount_what(), Some("/dev/sdb1".to_string()));
+        assert_eq!(config.mount_where("mnt-data.mount"), "/mnt/data");
+        assert_eq!(config.mount_type(), Some("ext4".to_string()));
+        assert_eq!(config.mount_options(), Some("rw,noatime".to_string()));
+        assert_eq!(config.timeout_sec(), Some(30 * USEC_PER_SEC));
+        assert!(config.verify("mnt-data.mount").is_ok());
+        assert!(config.verify("mnt-other.mount").is_err());
+    }
+}
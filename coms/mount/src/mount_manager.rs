@@ -1,4 +1,4 @@
-use super::mount_base::{LOG_LEVEL, PLUGIN_NAME};
+use super::mount_base::{mount_point_to_unit_name, LOG_LEVEL, PLUGIN_NAME};
 use super::mount_comm::MountUmComm;
 use super::mount_rentry::{MountRe, MountReFrame};
 use libevent::{EventState, EventType, Events, Source};
@@ -347,15 +347,23 @@ impl MountMonitorData {
                     if dead_mount_set.contains(unit_name.as_str()) {
                         dead_mount_set.remove(unit_name.as_str());
                     } else if let Some(unit) = self.comm.um().load_unit(unit_name.as_str()) {
+                        // The mount/umount process is running, the state is settled when it exits.
+                        if matches!(
+                            unit.current_active_state(),
+                            UnitActiveState::UnitActivating | UnitActiveState::UnitDeActivating
+                        ) {
+                            continue;
+                        }
+
                         // record + action
                         self.comm.reli().set_last_unit(unit.id());
                         let start_err = unit.start().is_err();
                         self.comm.reli().clear_last_unit();
 
                         if start_err {
-                            log::debug!("{} change to mounted.", unit_name);
-                        } else {
                             log::error!("Failed to start {}", unit_name);
+                        } else {
+                            log::debug!("{} change to mounted.", unit_name);
                         }
                     }
                 }
@@ -391,14 +399,6 @@ fn drain_out(epfd: i32) {
     while epoll::wait(epfd, 0, &mut me_events).unwrap() > 0 {}
 }
 
-fn mount_point_to_unit_name(mount_point: &str) -> String {
-    let mut res = String::from(mount_point).replace('/', "-") + ".mount";
-    if res != "-.mount" {
-        res = String::from(&res[1..])
-    }
-    res
-}
-
 impl UnitMngUtil for MountManager {
     fn attach_um(&self, um: Rc<dyn UmIf>) {
         self.comm.attach_um(um);
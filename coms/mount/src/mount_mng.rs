@@ -1,23 +1,46 @@
 //!  The core logic of the mount subclass
 
+use super::mount_base::{find_bin, mount_point_is_mounted, MOUNT_BIN, UMOUNT_BIN};
 use super::mount_comm::MountUnitComm;
-use super::mount_rentry::MountState;
-use libsysmaster::manager::{UnitActionError, UnitActiveState, UnitNotifyFlags};
+use super::mount_config::MountConfig;
+use super::mount_rentry::{MountResult, MountState};
+use super::mount_spawn::MountSpawn;
+use libevent::{EventState, EventType, Events, Source};
+use libsysmaster::manager::{
+    ExecCommand, ExecContext, UnitActionError, UnitActiveState, UnitNotifyFlags,
+};
 use libsysmaster::ReStation;
-use std::{cell::RefCell, rc::Rc};
+use libutils::Error;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::cell::RefCell;
+use std::fs;
+use std::rc::{Rc, Weak};
 
 impl MountState {
     fn mount_state_to_unit_state(&self) -> UnitActiveState {
         match *self {
             MountState::Dead => UnitActiveState::UnitInActive,
+            MountState::Mounting => UnitActiveState::UnitActivating,
             MountState::Mounted => UnitActiveState::UnitActive,
+            MountState::Unmounting => UnitActiveState::UnitDeActivating,
+            MountState::Failed => UnitActiveState::UnitFailed,
         }
     }
+
+    fn is_control_state(&self) -> bool {
+        matches!(*self, MountState::Mounting | MountState::Unmounting)
+    }
 }
 
 pub(super) struct MountMng {
     comm: Rc<MountUnitComm>,
+    config: Rc<MountConfig>,
+    spawn: MountSpawn,
     state: RefCell<MountState>,
+    result: RefCell<MountResult>,
+    control_pid: RefCell<Option<Pid>>,
+    timer: Rc<MountTimer>,
 }
 
 impl ReStation for MountMng {
@@ -25,31 +48,57 @@ impl ReStation for MountMng {
 
     // data
     fn db_map(&self) {
-        if let Some(state) = self.comm.rentry_mng_get() {
+        if let Some((state, result, control_pid)) = self.comm.rentry_mng_get() {
             *self.state.borrow_mut() = state;
+            *self.result.borrow_mut() = result;
+            *self.control_pid.borrow_mut() = control_pid;
         }
     }
 
     fn db_insert(&self) {
-        self.comm.rentry_mng_insert(self.state());
+        self.comm
+            .rentry_mng_insert(self.state(), self.result(), self.control_pid());
     }
 
-    // reload: no external connections, no entry
+    // reload: no external connections, entry-only
+    fn entry_coldplug(&self) {
+        if self.state().is_control_state() {
+            self.enable_timer(self.timeout_usec());
+        }
+    }
+
+    fn entry_clear(&self) {
+        self.disable_timer();
+    }
 }
 
 impl MountMng {
-    pub(super) fn new(_comm: &Rc<MountUnitComm>) -> Self {
+    pub(super) fn new(_comm: &Rc<MountUnitComm>, configr: &Rc<MountConfig>) -> Self {
         MountMng {
             comm: Rc::clone(_comm),
+            config: Rc::clone(configr),
+            spawn: MountSpawn::new(_comm, &Rc::new(ExecContext::new())),
             state: RefCell::new(MountState::Dead),
+            result: RefCell::new(MountResult::Success),
+            control_pid: RefCell::new(None),
+            timer: Rc::new(MountTimer::new()),
         }
     }
 
-    // process doesn't support manually mount/umount like systemd.
-    // We only monitor the state of mountpoint.
+    pub(super) fn attach_timer(&self, mngr: &Rc<MountMng>) {
+        self.timer.attach(Rc::downgrade(mngr));
+    }
+
+    // The mount point may be mounted or unmounted by other processes, which is
+    // picked up from /proc/self/mountinfo by the mount monitor. So before spawning
+    // mount/umount, the current mountinfo is always checked first.
 
     pub(super) fn enter_dead(&self, notify: bool) {
-        self.set_state(MountState::Dead, notify);
+        if self.result() == MountResult::Success {
+            self.set_state(MountState::Dead, notify);
+        } else {
+            self.set_state(MountState::Failed, notify);
+        }
     }
 
     pub(super) fn enter_mounted(&self, notify: bool) {
@@ -57,6 +106,12 @@ impl MountMng {
     }
 
     pub(super) fn start_check(&self) -> Result<bool, UnitActionError> {
+        match self.state() {
+            MountState::Mounting => return Ok(true),
+            MountState::Unmounting => return Err(UnitActionError::UnitActionEAgain),
+            _ => {}
+        }
+
         if !self.comm.unit().test_start_limit() {
             self.enter_dead(true);
             return Err(UnitActionError::UnitActionECanceled);
@@ -65,10 +120,212 @@ impl MountMng {
         Ok(false)
     }
 
+    pub(super) fn start_action(&self) -> Result<(), UnitActionError> {
+        self.set_result(MountResult::Success, true);
+
+        let mount_point = self.config.mount_where(self.comm.unit().id());
+        if mount_point_is_mounted(&mount_point) {
+            self.enter_mounted(true);
+            return Ok(());
+        }
+
+        let what = match self.config.mount_what() {
+            Some(what) => what,
+            None => {
+                log::error!(
+                    "{} is not mounted and What= is not configured, can not mount it",
+                    self.comm.unit().id()
+                );
+                return Err(UnitActionError::UnitActionEInval);
+            }
+        };
+
+        self.enter_mounting(&what, &mount_point);
+        Ok(())
+    }
+
+    pub(super) fn stop_action(&self) {
+        match self.state() {
+            MountState::Mounting | MountState::Mounted => {
+                self.kill_control();
+                let mount_point = self.config.mount_where(self.comm.unit().id());
+                if mount_point_is_mounted(&mount_point) {
+                    self.enter_unmounting(&mount_point);
+                } else {
+                    self.enter_dead(true);
+                }
+            }
+            MountState::Unmounting => {
+                log::debug!("mount already in unmounting, just return immediately");
+            }
+            MountState::Dead | MountState::Failed => {}
+        }
+    }
+
+    pub(super) fn sigchld_event(&self, pid: Pid, code: i32, status: Signal) {
+        if self.control_pid() != Some(pid) {
+            return;
+        }
+        self.control_pid.replace(None);
+
+        let result = if code == 0 && status == Signal::SIGCHLD {
+            MountResult::Success
+        } else if status == Signal::SIGCHLD {
+            MountResult::FailureExitCode
+        } else {
+            MountResult::FailureSignal
+        };
+
+        log::debug!(
+            "{} control process {} exited, code: {}, signal: {:?}",
+            self.comm.unit().id(),
+            pid,
+            code,
+            status
+        );
+        self.control_finished(result);
+    }
+
+    fn enter_mounting(&self, what: &str, mount_point: &str) {
+        if let Err(e) = fs::create_dir_all(mount_point) {
+            log::warn!("Failed to create mount point {}: {}", mount_point, e);
+        }
+
+        let mut argv = Vec::new();
+        if let Some(fs_type) = self.config.mount_type() {
+            argv.push("-t".to_string());
+            argv.push(fs_type);
+        }
+        if let Some(options) = self.config.mount_options() {
+            argv.push("-o".to_string());
+            argv.push(options);
+        }
+        argv.push(what.to_string());
+        argv.push(mount_point.to_string());
+
+        let cmd = ExecCommand::new(find_bin(MOUNT_BIN), argv);
+        match self.spawn.spawn_cmd(&cmd) {
+            Ok(pid) => {
+                self.control_pid.replace(Some(pid));
+                self.set_state(MountState::Mounting, true);
+            }
+            Err(e) => {
+                log::error!("Failed to run mount for {}: {}", mount_point, e);
+                self.set_result(MountResult::FailureResources, false);
+                self.enter_dead(true);
+            }
+        }
+    }
+
+    fn enter_unmounting(&self, mount_point: &str) {
+        let cmd = ExecCommand::new(find_bin(UMOUNT_BIN), vec![mount_point.to_string()]);
+        match self.spawn.spawn_cmd(&cmd) {
+            Ok(pid) => {
+                self.control_pid.replace(Some(pid));
+                self.set_state(MountState::Unmounting, true);
+            }
+            Err(e) => {
+                log::error!("Failed to run umount for {}: {}", mount_point, e);
+                self.set_result(MountResult::FailureResources, false);
+                self.enter_mounted(true);
+            }
+        }
+    }
+
+    fn control_finished(&self, result: MountResult) {
+        self.set_result(result, false);
+
+        let mount_point = self.config.mount_where(self.comm.unit().id());
+        let mounted = mount_point_is_mounted(&mount_point);
+        match self.state() {
+            MountState::Mounting => {
+                if result == MountResult::Success || mounted {
+                    self.enter_mounted(true);
+                } else {
+                    self.enter_dead(true);
+                }
+            }
+            MountState::Unmounting => {
+                if result != MountResult::Success && mounted {
+                    self.enter_mounted(true);
+                } else {
+                    self.enter_dead(true);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn kill_control(&self) {
+        if let Some(pid) = self.control_pid.take() {
+            if let Err(e) = nix::sys::signal::kill(pid, Signal::SIGKILL) {
+                log::debug!("failed to kill the control process {}: {}", pid, e);
+            }
+            self.comm.um().child_unwatch_pid(self.comm.unit().id(), pid);
+        }
+    }
+
+    fn timeout_usec(&self) -> u64 {
+        let usec = self
+            .config
+            .timeout_sec()
+            .unwrap_or_else(|| self.comm.um().default_timeout_usec());
+        match usec {
+            0 => u64::MAX,
+            v => v,
+        }
+    }
+
+    fn enable_timer(&self, usec: u64) {
+        let events = self.comm.um().events();
+        let timer = self.timer.clone();
+        if usec == u64::MAX {
+            self.disable_timer();
+            return;
+        }
+
+        timer.set_time(usec);
+        if let Err(e) = events
+            .add_source(timer.clone())
+            .and_then(|_| events.set_enabled(timer, EventState::On))
+        {
+            log::error!("failed to arm the mount timer: {}", e);
+        }
+    }
+
+    fn disable_timer(&self) {
+        let events = self.comm.um().events();
+        if let Err(e) = events.set_enabled(self.timer.clone(), EventState::Off) {
+            log::debug!("failed to disable the mount timer: {}", e);
+        }
+    }
+
+    fn dispatch_timer(&self) {
+        if !self.state().is_control_state() {
+            return;
+        }
+
+        log::warn!(
+            "{} timed out in state {:?}, killing the control process",
+            self.comm.unit().id(),
+            self.state()
+        );
+        self.kill_control();
+        self.control_finished(MountResult::FailureTimeout);
+    }
+
     fn set_state(&self, new_state: MountState, notify: bool) {
         let old_state = self.state();
         self.change_state(new_state);
 
+        if new_state != old_state {
+            if new_state.is_control_state() {
+                self.enable_timer(self.timeout_usec());
+            } else if old_state.is_control_state() {
+                self.disable_timer();
+            }
+        }
+
         if notify {
             self.state_notify(new_state, old_state);
         }
@@ -103,22 +360,103 @@ impl MountMng {
         *self.state.borrow()
     }
 
+    // keep the first failure, unless it is forced to be reset
+    fn set_result(&self, result: MountResult, force: bool) {
+        if force || self.result() == MountResult::Success {
+            self.result.replace(result);
+        }
+    }
+
+    fn result(&self) -> MountResult {
+        *self.result.borrow()
+    }
+
+    fn control_pid(&self) -> Option<Pid> {
+        *self.control_pid.borrow()
+    }
+
     pub(super) fn mount_state_to_unit_state(&self) -> UnitActiveState {
         self.state().mount_state_to_unit_state()
     }
+
+    pub(super) fn get_state(&self) -> String {
+        self.state().to_string()
+    }
+
+    pub(super) fn get_result(&self) -> String {
+        self.result().to_string()
+    }
+}
+
+struct MountTimer {
+    usec: RefCell<u64>,
+    mng: RefCell<Weak<MountMng>>,
+}
+
+impl MountTimer {
+    fn new() -> Self {
+        MountTimer {
+            usec: RefCell::new(0),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(self) fn attach(&self, mng: Weak<MountMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    pub(self) fn set_time(&self, usec: u64) {
+        *self.usec.borrow_mut() = usec;
+    }
+
+    pub(self) fn mng(&self) -> Rc<MountMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for MountTimer {
+    fn event_type(&self) -> EventType {
+        EventType::TimerMonotonic
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn time_relative(&self) -> u64 {
+        *self.usec.borrow()
+    }
+
+    fn dispatch(&self, _: &Events) -> Result<i32, Error> {
+        self.mng().dispatch_timer();
+        self.mng().db_update();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::MountConfig;
     use super::MountMng;
     use super::MountState;
     use super::MountUnitComm;
+    use libsysmaster::manager::UnitActiveState;
     use std::rc::Rc;
 
     #[test]
     fn test_mount_set_state() {
         let _comm = Rc::new(MountUnitComm::new());
-        let tm = MountMng::new(&_comm);
+        let _config = Rc::new(MountConfig::new(&_comm));
+        let tm = MountMng::new(&_comm, &_config);
         tm.set_state(MountState::Mounted, false);
         assert_eq!(tm.state(), MountState::Mounted)
     }
@@ -126,7 +464,8 @@ mod tests {
     #[test]
     fn test_mount_enter_dead() {
         let _comm = Rc::new(MountUnitComm::new());
-        let tm = MountMng::new(&_comm);
+        let _config = Rc::new(MountConfig::new(&_comm));
+        let tm = MountMng::new(&_comm, &_config);
         tm.enter_dead(false);
         assert_eq!(tm.state(), MountState::Dead)
     }
@@ -134,8 +473,27 @@ mod tests {
     #[test]
     fn test_mount_enter_mounted() {
         let _comm = Rc::new(MountUnitComm::new());
-        let tm = MountMng::new(&_comm);
+        let _config = Rc::new(MountConfig::new(&_comm));
+        let tm = MountMng::new(&_comm, &_config);
         tm.enter_mounted(false);
         assert_eq!(tm.state(), MountState::Mounted)
     }
+
+    #[test]
+    fn test_mount_state_to_unit_state() {
+        assert_eq!(
+            MountState::Mounting.mount_state_to_unit_state(),
+            UnitActiveState::UnitActivating
+        );
+        assert_eq!(
+            MountState::Unmounting.mount_state_to_unit_state(),
+            UnitActiveState::UnitDeActivating
+        );
+        assert_eq!(
+            MountState::Failed.mount_state_to_unit_state(),
+            UnitActiveState::UnitFailed
+        );
+        assert!(MountState::Mounting.is_control_state());
+        assert!(!MountState::Mounted.is_control_state());
+    }
 }
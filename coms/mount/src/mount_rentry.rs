@@ -1,26 +1,80 @@
+#![allow(non_snake_case)]
+use confique::Config;
+use libsysmaster::manager::deserialize_timespan;
 use libsysmaster::{ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, Reliability};
+use nix::unistd::Pid;
 use serde::{Deserialize, Serialize};
 use std::rc::Rc;
+use strum::Display;
 
+const RELI_DB_HMOUNT_CONF: &str = "mntconf";
 const RELI_DB_HMOUNT_MNG: &str = "mntmng";
 const RELI_DB_HMOUNTM_FRAME: &str = "mntm-frame";
 const RELI_LAST_KEY: u32 = 0; // singleton
 
-//Mount contains two states: unmounted Dead and mounted Mounted. The corresponding unit status is inactive and active
-#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Config, Default, Clone, Debug, Serialize, Deserialize)]
+pub(super) struct SectionMount {
+    pub What: Option<String>,
+    pub Where: Option<String>,
+    pub Type: Option<String>,
+    pub Options: Option<String>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub TimeoutSec: Option<u64>,
+}
+
+// Mount has two stable states: unmounted Dead and mounted Mounted, the corresponding unit status is inactive and active.
+// Mounting and Unmounting are the intermediate states while the mount/umount process is running.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "kebab-case")]
 pub(super) enum MountState {
     Dead,
+    Mounting,
     Mounted,
+    Unmounting,
+    Failed,
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+pub(super) enum MountResult {
+    #[strum(serialize = "success")]
+    Success,
+    #[strum(serialize = "resources")]
+    FailureResources,
+    #[strum(serialize = "timeout")]
+    FailureTimeout,
+    #[strum(serialize = "exit-code")]
+    FailureExitCode,
+    #[strum(serialize = "signal")]
+    FailureSignal,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct MountReConf {
+    mount: SectionMount,
+}
+
+impl MountReConf {
+    fn new(mountr: &SectionMount) -> MountReConf {
+        MountReConf {
+            mount: mountr.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct MountReMng {
     state: MountState,
+    result: MountResult,
+    control_pid: Option<i32>, // i32 ==> nix::unistd::Pid ==> libc::pid_t
 }
 
 impl MountReMng {
-    fn new(state: MountState) -> MountReMng {
-        MountReMng { state }
+    fn new(state: MountState, result: MountResult, control_pid: Option<Pid>) -> MountReMng {
+        MountReMng {
+            state,
+            result,
+            control_pid: control_pid.map(|x| x.as_raw()),
+        }
     }
 }
 
@@ -33,7 +87,8 @@ struct MountReDb<K, V>(ReDb<K, V>);
 
 pub(super) struct MountRe {
     // database: multi-instance(N)
-    mng: Rc<MountReDb<String, MountReMng>>, // RELI_DB_HMOUNT_MNG; key: unit_id, data: state;
+    conf: Rc<MountReDb<String, MountReConf>>, // RELI_DB_HMOUNT_CONF; key: unit_id, data: config;
+    mng: Rc<MountReDb<String, MountReMng>>,   // RELI_DB_HMOUNT_MNG; key: unit_id, data: state;
 
     // database: singleton(1)
     frame: Rc<MountReDb<u32, MountReFrame>>, // RELI_DB_HMOUNTM_FRAME; key: RELI_LAST_KEY, data: MountReFrame;
@@ -41,21 +96,41 @@ pub(super) struct MountRe {
 
 impl MountRe {
     pub(super) fn new(relir: &Reliability) -> MountRe {
+        let conf = Rc::new(MountReDb(ReDb::new(relir, RELI_DB_HMOUNT_CONF)));
         let mng = Rc::new(MountReDb(ReDb::new(relir, RELI_DB_HMOUNT_MNG)));
         let frame = Rc::new(MountReDb(ReDb::new(relir, RELI_DB_HMOUNTM_FRAME)));
-        let rentry = MountRe { mng, frame };
+        let rentry = MountRe { conf, mng, frame };
         rentry.register(relir);
         rentry
     }
 
-    pub(super) fn mng_insert(&self, unit_id: &str, state: MountState) {
-        let mng = MountReMng::new(state);
+    pub(super) fn conf_insert(&self, unit_id: &str, mount: &SectionMount) {
+        let conf = MountReConf::new(mount);
+        self.conf.0.insert(unit_id.to_string(), conf);
+    }
+
+    pub(super) fn conf_get(&self, unit_id: &String) -> Option<SectionMount> {
+        let conf = self.conf.0.get(unit_id);
+        conf.map(|c| c.mount)
+    }
+
+    pub(super) fn mng_insert(
+        &self,
+        unit_id: &str,
+        state: MountState,
+        result: MountResult,
+        control_pid: Option<Pid>,
+    ) {
+        let mng = MountReMng::new(state, result, control_pid);
         self.mng.0.insert(unit_id.to_string(), mng);
     }
 
-    pub(super) fn mng_get(&self, unit_id: &String) -> Option<MountState> {
+    pub(super) fn mng_get(
+        &self,
+        unit_id: &String,
+    ) -> Option<(MountState, MountResult, Option<Pid>)> {
         let mng = self.mng.0.get(unit_id);
-        mng.map(|m| m.state)
+        mng.map(|m| (m.state, m.result, m.control_pid.map(Pid::from_raw)))
     }
 
     pub(super) fn set_last_frame(&self, frame: MountReFrame) {
@@ -71,6 +146,10 @@ impl MountRe {
     }
 
     fn register(&self, relir: &Reliability) {
+        // reliability-db: RELI_DB_HMOUNT_CONF
+        let db = Rc::clone(&self.conf);
+        relir.history_db_register(RELI_DB_HMOUNT_CONF, db);
+
         // reliability-db: RELI_DB_HMOUNT_MNG
         let db = Rc::clone(&self.mng);
         relir.history_db_register(RELI_DB_HMOUNT_MNG, db);
@@ -81,6 +160,24 @@ impl MountRe {
     }
 }
 
+impl ReDbTable for MountReDb<String, MountReConf> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.0.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.0.set_ignore(ignore);
+    }
+}
+
 impl ReDbTable for MountReDb<String, MountReMng> {
     fn clear(&self, wtxn: &mut ReDbRwTxn) {
         self.0.do_clear(wtxn);
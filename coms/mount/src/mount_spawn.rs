@@ -0,0 +1,52 @@
+//! mount_spawn run the mount/umount command of the mount unit.
+//!
+
+use super::mount_comm::MountUnitComm;
+use libsysmaster::manager::{ExecCommand, ExecContext, ExecParameters};
+use nix::unistd::Pid;
+use std::env;
+use std::error::Error;
+use std::rc::Rc;
+
+pub(super) struct MountSpawn {
+    comm: Rc<MountUnitComm>,
+    exec_ctx: Rc<ExecContext>,
+}
+
+impl MountSpawn {
+    pub(super) fn new(comm: &Rc<MountUnitComm>, exec_ctx: &Rc<ExecContext>) -> MountSpawn {
+        MountSpawn {
+            comm: comm.clone(),
+            exec_ctx: exec_ctx.clone(),
+        }
+    }
+
+    pub(super) fn spawn_cmd(&self, cmdline: &ExecCommand) -> Result<Pid, Box<dyn Error>> {
+        let params = ExecParameters::new();
+        params.add_env(
+            "PATH",
+            env::var("PATH").unwrap_or_else(|_| {
+                "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin".to_string()
+            }),
+        );
+
+        let unit = self.comm.unit();
+        let um = self.comm.um();
+        unit.prepare_exec()?;
+        match um.exec_spawn(&unit, cmdline, &params, self.exec_ctx.clone()) {
+            Ok(pid) => {
+                um.child_watch_pid(unit.id(), pid);
+                Ok(pid)
+            }
+            Err(e) => {
+                log::error!(
+                    "failed to spawn {} for {}: {:?}",
+                    cmdline.path(),
+                    unit.id(),
+                    e
+                );
+                Err("spawn exec return error".to_string().into())
+            }
+        }
+    }
+}
@@ -3,8 +3,9 @@
 
 use super::mount_base::{LOG_LEVEL, PLUGIN_NAME};
 use super::mount_comm::MountUnitComm;
+use super::mount_config::MountConfig;
 use super::mount_mng::MountMng;
-use libsysmaster::manager::{SubUnit, UmIf, UnitActiveState, UnitMngUtil};
+use libsysmaster::manager::{SubUnit, UmIf, UnitActionError, UnitActiveState, UnitMngUtil};
 use libsysmaster::{ReStation, Reliability};
 use libutils::logger;
 use nix::{sys::signal::Signal, unistd::Pid};
@@ -13,6 +14,7 @@ use std::rc::Rc;
 
 struct MountUnit {
     comm: Rc<MountUnitComm>,
+    config: Rc<MountConfig>,
     mng: Rc<MountMng>,
 }
 
@@ -21,46 +23,61 @@ impl ReStation for MountUnit {
 
     // data
     fn db_map(&self) {
+        self.config.db_map();
         self.mng.db_map();
     }
 
     fn db_insert(&self) {
+        self.config.db_insert();
         self.mng.db_insert();
     }
 
     // reload: no external connections, entry-only
     fn entry_coldplug(&self) {
         // rebuild external connections, like: timer, ...
-        // do nothing now
+        self.mng.entry_coldplug();
     }
 
     fn entry_clear(&self) {
         // release external connection, like: timer, ...
-        // do nothing now
+        self.mng.entry_clear();
     }
 }
 
 impl MountUnit {
     fn new(_um: Rc<dyn UmIf>) -> MountUnit {
         let _comm = Rc::new(MountUnitComm::new());
+        let _config = Rc::new(MountConfig::new(&_comm));
+        let _mng = Rc::new(MountMng::new(&_comm, &_config));
+        _mng.attach_timer(&_mng);
         MountUnit {
             comm: Rc::clone(&_comm),
-            mng: Rc::new(MountMng::new(&_comm)),
+            config: Rc::clone(&_config),
+            mng: _mng,
         }
     }
 }
 
 impl SubUnit for MountUnit {
-    fn load(&self, _paths: Vec<PathBuf>) -> libutils::Result<(), Box<dyn std::error::Error>> {
+    fn load(&self, paths: Vec<PathBuf>) -> libutils::Result<(), Box<dyn std::error::Error>> {
         self.comm.unit().set_ignore_on_isolate(true);
 
-        Ok(())
+        self.config.load(paths, true)?;
+        self.config.verify(self.comm.unit().id())
     }
 
     fn current_active_state(&self) -> UnitActiveState {
         self.mng.mount_state_to_unit_state()
     }
 
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_subunit_result(&self) -> String {
+        self.mng.get_result()
+    }
+
     fn attach_unit(&self, unit: Rc<libsysmaster::manager::Unit>) {
         self.comm.attach_unit(unit);
         self.db_insert();
@@ -72,20 +89,18 @@ impl SubUnit for MountUnit {
 
     fn dump(&self) {}
 
-    fn start(&self) -> libutils::Result<(), libsysmaster::manager::UnitActionError> {
+    fn start(&self) -> libutils::Result<(), UnitActionError> {
         let started = self.mng.start_check()?;
         if started {
             log::debug!("mount already in starting, just return immediately");
             return Ok(());
         }
 
-        self.mng.enter_mounted(true);
-
-        Ok(())
+        self.mng.start_action()
     }
 
-    fn stop(&self, _force: bool) -> libutils::Result<(), libsysmaster::manager::UnitActionError> {
-        self.mng.enter_dead(true);
+    fn stop(&self, _force: bool) -> libutils::Result<(), UnitActionError> {
+        self.mng.stop_action();
         Ok(())
     }
 
@@ -95,7 +110,9 @@ impl SubUnit for MountUnit {
 
     fn release_resources(&self) {}
 
-    fn sigchld_events(&self, _pid: Pid, _code: i32, _status: Signal) {}
+    fn sigchld_events(&self, pid: Pid, code: i32, status: Signal) {
+        self.mng.sigchld_event(pid, code, status)
+    }
 
     fn reset_failed(&self) {}
 }
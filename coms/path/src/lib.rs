@@ -0,0 +1,61 @@
+//! Path is the entry for the path based activation of sysmaster. sysmaster refers to systemd, but it is not the same.
+//! A path unit watches the paths with inotify, and starts the unit it triggers when a path is good.
+//!
+//! The Path configuration file contains the [Unit] [Path] [Install] sections.
+//!
+//! ```toml
+//! [Path]
+//! PathExists = "/run/spool/ready"
+//! PathExistsGlob = "/var/spool/incoming/*.job"
+//! PathChanged = "/etc/spool.conf"
+//! PathModified = "/var/log/spool.log"
+//! DirectoryNotEmpty = "/var/spool/incoming"
+//! MakeDirectory = true
+//! TriggerLimitIntervalSec = "2s"
+//! TriggerLimitBurst = 200
+//! Unit = "spool.service"
+//! ```
+//!
+//! ### PathExists
+//! The paths separated by ";", the unit is triggered as long as any of them exists.
+//!
+//! ### PathExistsGlob
+//! Like PathExists, but the paths are glob patterns, the unit is triggered as long as any file matches.
+//!
+//! ### PathChanged
+//! The unit is triggered when the path is closed after being written, created, removed or renamed.
+//!
+//! ### PathModified
+//! Like PathChanged, but the unit is also triggered on every write.
+//!
+//! ### DirectoryNotEmpty
+//! The unit is triggered as long as the directory contains any file.
+//!
+//! ### MakeDirectory
+//! true or false(default). The directories of PathChanged, PathModified and DirectoryNotEmpty are created
+//! before they are watched.
+//!
+//! ### TriggerLimitIntervalSec, TriggerLimitBurst
+//! The unit is triggered at most TriggerLimitBurst times within TriggerLimitIntervalSec, defaults to 200 times
+//! in 2s. The path unit fails with "trigger-limit-hit" if the limit is hit.
+//!
+//! ### Unit
+//! The unit to trigger, defaults to the service with the same name as the path unit, like "spool.path" with
+//! "spool.service". It can not be a path unit.
+//!
+//! ## Automatic dependency
+//! The path unit is ordered before the unit it triggers.
+//! ### Implicit dependency
+//! NA
+//! ### Default Dependency
+//! NA
+
+// dependency: path_base -> path_rentry -> path_comm -> path_spec -> path_config -> {path_mng -> path_unit} -> path_manager
+mod path_base;
+mod path_comm;
+mod path_config;
+mod path_manager;
+mod path_mng;
+mod path_rentry;
+mod path_spec;
+mod path_unit;
@@ -0,0 +1,84 @@
+use std::ffi::CString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(super) const LOG_LEVEL: u32 = 4;
+pub(super) const PLUGIN_NAME: &str = "PathUnit";
+
+/// whether any file matches the glob pattern
+pub(super) fn glob_exists(pattern: &str) -> bool {
+    let cpattern = match CString::new(pattern) {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+
+    let mut g: libc::glob_t = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::glob(cpattern.as_ptr(), libc::GLOB_NOSORT, None, &mut g) };
+    let found = ret == 0 && g.gl_pathc > 0;
+    unsafe { libc::globfree(&mut g) };
+    found
+}
+
+/// the leading directory of the glob pattern which contains no wildcard
+pub(super) fn glob_prefix_dir(pattern: &str) -> PathBuf {
+    let mut dir = PathBuf::from("/");
+    for component in Path::new(pattern).iter().skip(1) {
+        let c = component.to_string_lossy();
+        if c.contains(['*', '?', '[']) {
+            break;
+        }
+        dir.push(component);
+    }
+
+    // the last component names the files, not the directory
+    if dir.as_os_str() == pattern {
+        if let Some(parent) = dir.parent() {
+            return parent.to_path_buf();
+        }
+    }
+    dir
+}
+
+pub(super) fn directory_not_empty(path: &Path) -> bool {
+    match fs::read_dir(path) {
+        Ok(mut entries) => entries.next().is_some(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{directory_not_empty, glob_exists, glob_prefix_dir};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_glob_prefix_dir() {
+        assert_eq!(glob_prefix_dir("/tmp/*.txt"), PathBuf::from("/tmp"));
+        assert_eq!(
+            glob_prefix_dir("/var/spool/*/new"),
+            PathBuf::from("/var/spool")
+        );
+        assert_eq!(glob_prefix_dir("/tmp/foo"), PathBuf::from("/tmp"));
+        assert_eq!(glob_prefix_dir("/*"), PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_path_check() {
+        let dir = PathBuf::from("/tmp/sysmaster-path-test-base");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        assert!(!directory_not_empty(&dir));
+        assert!(!glob_exists("/tmp/sysmaster-path-test-base/*.txt"));
+
+        fs::write(dir.join("foo.txt"), "foo").unwrap();
+        assert!(directory_not_empty(&dir));
+        assert!(glob_exists("/tmp/sysmaster-path-test-base/*.txt"));
+        assert!(!directory_not_empty(Path::new(
+            "/tmp/sysmaster-path-test-base/none"
+        )));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
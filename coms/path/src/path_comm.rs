@@ -0,0 +1,241 @@
+//!  Path association unit object
+//! *  You need to notify the Unit object and change the method
+//! *  Get the attributes of the unit object
+//! *  Call relation: path_ unit->path_ mng->path_ comm
+
+use super::path_mng::PathMng;
+use super::path_rentry::{PathRe, PathResult, PathState, SectionPath};
+use libsysmaster::manager::{UmIf, Unit};
+use libsysmaster::Reliability;
+use nix::sys::inotify::WatchDescriptor;
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, RwLock};
+
+pub(super) struct PathUnitComm {
+    data: RefCell<PathUnitCommData>,
+    umcomm: Arc<PathUmComm>,
+}
+
+impl PathUnitComm {
+    pub(super) fn new() -> Self {
+        PathUnitComm {
+            data: RefCell::new(PathUnitCommData::new()),
+            umcomm: PathUmComm::get_instance(),
+        }
+    }
+
+    pub(super) fn attach_unit(&self, unit: Rc<Unit>) {
+        self.data.borrow_mut().attach_unit(unit);
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.umcomm.attach_um(um)
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.umcomm.attach_reli(reli)
+    }
+
+    pub(super) fn unit(&self) -> Rc<Unit> {
+        self.data.borrow().unit()
+    }
+
+    pub(super) fn rentry_conf_insert(&self, path: &SectionPath, unit: Option<String>) {
+        self.rentry().conf_insert(self.unit().id(), path, unit)
+    }
+
+    pub(super) fn rentry_conf_get(&self) -> Option<(SectionPath, Option<String>)> {
+        self.rentry().conf_get(self.unit().id())
+    }
+
+    pub(super) fn rentry_mng_insert(&self, state: PathState, result: PathResult) {
+        self.rentry().mng_insert(self.unit().id(), state, result)
+    }
+
+    pub(super) fn rentry_mng_get(&self) -> Option<(PathState, PathResult)> {
+        self.rentry().mng_get(self.unit().id())
+    }
+
+    fn rentry(&self) -> Rc<PathRe> {
+        self.umcomm.rentry()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        self.umcomm.um()
+    }
+
+    pub(super) fn watch_insert(&self, wd: WatchDescriptor, mng: Weak<PathMng>) {
+        self.umcomm.watch_insert(wd, mng)
+    }
+
+    pub(super) fn watch_remove(&self, wd: WatchDescriptor, mng: &Weak<PathMng>) -> bool {
+        self.umcomm.watch_remove(wd, mng)
+    }
+}
+struct PathUnitCommData {
+    unit: Weak<Unit>,
+}
+
+impl PathUnitCommData {
+    pub(self) fn new() -> PathUnitCommData {
+        PathUnitCommData { unit: Weak::new() }
+    }
+
+    fn attach_unit(&mut self, unit: Rc<Unit>) {
+        self.unit = Rc::downgrade(&unit);
+    }
+
+    pub(self) fn unit(&self) -> Rc<Unit> {
+        self.unit.clone().upgrade().unwrap()
+    }
+}
+
+static TIMER_UM_COMM: Lazy<Arc<PathUmComm>> = Lazy::new(|| {
+    let comm = PathUmComm::new();
+    Arc::new(comm)
+});
+
+pub(super) struct PathUmComm {
+    data: RwLock<PathUmCommData>,
+}
+
+unsafe impl Send for PathUmComm {}
+
+unsafe impl Sync for PathUmComm {}
+
+impl PathUmComm {
+    pub(super) fn new() -> Self {
+        PathUmComm {
+            data: RwLock::new(PathUmCommData::new()),
+        }
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_um(um);
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_reli(reli);
+    }
+
+    pub(super) fn get_instance() -> Arc<PathUmComm> {
+        TIMER_UM_COMM.clone()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        let rdata = self.data.read().unwrap();
+        rdata.um().unwrap()
+    }
+
+    pub(super) fn rentry(&self) -> Rc<PathRe> {
+        let rdata = self.data.read().unwrap();
+        rdata.rentry()
+    }
+
+    pub(super) fn watch_insert(&self, wd: WatchDescriptor, mng: Weak<PathMng>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.watch_insert(wd, mng);
+    }
+
+    /// return true if nobody watches the descriptor anymore
+    pub(super) fn watch_remove(&self, wd: WatchDescriptor, mng: &Weak<PathMng>) -> bool {
+        let mut wdata = self.data.write().unwrap();
+        wdata.watch_remove(wd, mng)
+    }
+
+    /// the kernel has removed the watch, like the watched path is deleted
+    pub(super) fn watch_forget(&self, wd: WatchDescriptor) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.watches.remove(&wd);
+    }
+
+    pub(super) fn watchers(&self, wd: WatchDescriptor) -> Vec<Rc<PathMng>> {
+        let rdata = self.data.read().unwrap();
+        rdata.watchers(wd)
+    }
+}
+
+struct PathUmCommData {
+    // associated objects
+    um: Option<Rc<dyn UmIf>>,
+    reli: Weak<Reliability>,
+    rentry: Option<Rc<PathRe>>,
+
+    // owned objects
+    // the inotify fd is shared by all path units, a path may be watched by several of them
+    watches: HashMap<WatchDescriptor, Vec<Weak<PathMng>>>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl PathUmCommData {
+    pub(self) fn new() -> PathUmCommData {
+        PathUmCommData {
+            um: None,
+            reli: Weak::new(),
+            rentry: None,
+            watches: HashMap::new(),
+        }
+    }
+
+    pub(self) fn attach_um(&mut self, um: Rc<dyn UmIf>) {
+        if self.um.is_none() {
+            log::debug!("PathUmComm attach_um action.");
+            self.um = Some(um);
+        }
+    }
+
+    pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
+        let old = self.reli.clone().upgrade();
+        if old.is_none() {
+            log::debug!("PathUmComm attach_reli action.");
+            self.reli = Rc::downgrade(&reli);
+            self.rentry.replace(Rc::new(PathRe::new(&reli)));
+        }
+    }
+
+    pub(self) fn um(&self) -> Option<Rc<dyn UmIf>> {
+        if let Some(ref um) = self.um {
+            Some(Rc::clone(um))
+        } else {
+            None
+        }
+    }
+
+    pub(self) fn rentry(&self) -> Rc<PathRe> {
+        self.rentry.as_ref().cloned().unwrap()
+    }
+
+    pub(self) fn watch_insert(&mut self, wd: WatchDescriptor, mng: Weak<PathMng>) {
+        let watchers = self.watches.entry(wd).or_default();
+        if !watchers.iter().any(|w| w.ptr_eq(&mng)) {
+            watchers.push(mng);
+        }
+    }
+
+    pub(self) fn watch_remove(&mut self, wd: WatchDescriptor, mng: &Weak<PathMng>) -> bool {
+        let watchers = match self.watches.get_mut(&wd) {
+            Some(w) => w,
+            None => return false,
+        };
+
+        watchers.retain(|w| !w.ptr_eq(mng));
+        if !watchers.is_empty() {
+            return false;
+        }
+
+        self.watches.remove(&wd);
+        true
+    }
+
+    pub(self) fn watchers(&self, wd: WatchDescriptor) -> Vec<Rc<PathMng>> {
+        match self.watches.get(&wd) {
+            Some(w) => w.iter().filter_map(|m| m.upgrade()).collect(),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! path_config mod load the conf file list and convert it to structure which is defined in this mod.
+//!
+#![allow(non_snake_case)]
+use super::path_comm::PathUnitComm;
+use super::path_rentry::SectionPath;
+use super::path_spec::{PathSpec, PathType};
+use confique::Config;
+use libsysmaster::manager::UnitRef;
+use libsysmaster::ReStation;
+use libutils::time_util::USEC_PER_SEC;
+use std::cell::RefCell;
+use std::error::Error;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+pub(super) struct PathConfig {
+    // associated objects
+    comm: Rc<PathUnitComm>,
+
+    // owned objects
+    /* original */
+    data: Rc<RefCell<PathConfigData>>,
+    /* processed */
+    unit: RefCell<UnitRef>,
+    specs: RefCell<Vec<PathSpec>>,
+}
+
+impl ReStation for PathConfig {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        if let Some((path, unit)) = self.comm.rentry_conf_get() {
+            self.data.replace(PathConfigData::new(path));
+
+            // UnitRef
+            if let Some(u) = unit {
+                self.set_unit_ref(u).unwrap();
+            }
+
+            // PathSpec
+            self.parse_specs().unwrap();
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm
+            .rentry_conf_insert(&self.data.borrow().Path, self.unit_ref_target());
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl PathConfig {
+    pub(super) fn new(commr: &Rc<PathUnitComm>) -> Self {
+        PathConfig {
+            comm: Rc::clone(commr),
+            data: Rc::new(RefCell::new(PathConfigData::default())),
+            unit: RefCell::new(UnitRef::new()),
+            specs: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(super) fn load(&self, paths: Vec<PathBuf>, update: bool) -> Result<(), Box<dyn Error>> {
+        let mut builder = PathConfigData::builder().env();
+
+        log::debug!("path load path: {:?}", paths);
+        // fragment
+        for v in paths {
+            builder = builder.file(&v);
+        }
+
+        *self.data.borrow_mut() = builder.load()?;
+        self.parse_specs()?;
+
+        if update {
+            self.db_update();
+        }
+
+        Ok(())
+    }
+
+    /// a path unit without any path setting never triggers
+    pub(super) fn verify(&self) -> Result<(), Box<dyn Error>> {
+        if self.specs.borrow().is_empty() {
+            return Err("the path unit has no PathExists=, PathExistsGlob=, PathChanged=, PathModified= or DirectoryNotEmpty= setting".into());
+        }
+
+        Ok(())
+    }
+
+    /// the unit to trigger, the service with the same name if Unit= is not configured
+    pub(super) fn unit_name(&self, unit_id: &str) -> String {
+        match &self.data.borrow().Path.Unit {
+            Some(unit) => unit.clone(),
+            None => {
+                let stem = unit_id.trim_end_matches(".path");
+                format!("{}.service", stem)
+            }
+        }
+    }
+
+    pub(super) fn set_unit_ref(&self, unit: String) -> Result<(), Box<dyn Error>> {
+        if unit.ends_with(".path") {
+            return Err(format!("path can not trigger the path unit {}", unit).into());
+        }
+
+        if !self.comm.um().load_unit_success(&unit) {
+            return Err(format!("failed to load unit {}", unit).into());
+        }
+
+        let source = self.comm.unit().id().to_string();
+        self.unit.borrow_mut().set_ref(source, unit);
+        self.db_update();
+
+        Ok(())
+    }
+
+    pub(super) fn unit_ref_target(&self) -> Option<String> {
+        self.unit.borrow().target().map(|v| v.to_string())
+    }
+
+    pub(super) fn specs(&self) -> Vec<PathSpec> {
+        self.specs.borrow().clone()
+    }
+
+    pub(super) fn make_directory(&self) -> bool {
+        self.data.borrow().Path.MakeDirectory
+    }
+
+    /// the triggered unit is started at most TriggerLimitBurst= times within TriggerLimitIntervalSec=,
+    /// defaults to 200 times in 2s
+    pub(super) fn trigger_limit(&self) -> (u64, u32) {
+        let data = self.data.borrow();
+        let interval = data
+            .Path
+            .TriggerLimitIntervalSec
+            .unwrap_or(2 * USEC_PER_SEC);
+        let burst = data.Path.TriggerLimitBurst.unwrap_or(200);
+        (interval / USEC_PER_SEC, burst)
+    }
+
+    fn parse_specs(&self) -> Result<(), Box<dyn Error>> {
+        let mut specs = Vec::new();
+        {
+            let data = self.data.borrow();
+            for (path_type, paths) in [
+                (PathType::Exists, &data.Path.PathExists),
+                (PathType::ExistsGlob, &data.Path.PathExistsGlob),
+                (PathType::Changed, &data.Path.PathChanged),
+                (PathType::Modified, &data.Path.PathModified),
+                (PathType::DirectoryNotEmpty, &data.Path.DirectoryNotEmpty),
+            ] {
+                if let Some(paths) = paths {
+                    for path in paths.iter().filter(|p| !p.is_empty()) {
+                        specs.push(PathSpec::new(path_type, path)?);
+                    }
+                }
+            }
+        }
+        self.specs.replace(specs);
+        Ok(())
+    }
+}
+
+#[derive(Config, Default, Debug)]
+pub(super) struct PathConfigData {
+    #[config(nested)]
+    pub Path: SectionPath,
+}
+
+impl PathConfigData {
+    pub(self) fn new(Path: SectionPath) -> PathConfigData {
+        PathConfigData { Path }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path_comm::PathUnitComm;
+    use crate::path_config::PathConfig;
+    use crate::path_spec::PathType;
+    use libtests::get_project_root;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_path_parse() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/spool.path.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(PathUnitComm::new());
+        let config = PathConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+        assert!(config.verify().is_ok());
+
+        let types: Vec<PathType> = config.specs().iter().map(|s| s.path_type()).collect();
+        assert_eq!(
+            types,
+            vec![
+                PathType::Exists,
+                PathType::ExistsGlob,
+                PathType::Changed,
+                PathType::DirectoryNotEmpty
+            ]
+        );
+        assert!(config.make_directory());
+        assert_eq!(config.trigger_limit(), (10, 5));
+        assert_eq!(config.unit_name("spool.path"), "spool.service");
+    }
+
+    #[test]
+    fn test_path_verify() {
+        let comm = Rc::new(PathUnitComm::new());
+        let config = PathConfig::new(&comm);
+        assert!(config.verify().is_err());
+        assert_eq!(config.trigger_limit(), (2, 200));
+        assert_eq!(config.unit_name("foo.path"), "foo.service");
+    }
+}
@@ -0,0 +1,148 @@
+use super::path_base::{LOG_LEVEL, PLUGIN_NAME};
+use super::path_comm::PathUmComm;
+use super::path_mng::PathMng;
+use libevent::{EventState, EventType, Events, Source};
+use libsysmaster::manager::{UmIf, UnitManagerObj, UnitMngUtil};
+use libsysmaster::{ReStation, Reliability};
+use libutils::logger;
+use libutils::Error;
+use nix::sys::inotify::{AddWatchFlags, WatchDescriptor};
+use std::os::unix::io::RawFd;
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct PathManager {
+    // owned objects
+    comm: Arc<PathUmComm>,
+    monitor: Rc<PathMonitor>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl PathManager {
+    pub(self) fn new() -> PathManager {
+        let _comm = PathUmComm::get_instance();
+        PathManager {
+            comm: Arc::clone(&_comm),
+            monitor: Rc::new(PathMonitor::new(&_comm)),
+        }
+    }
+}
+
+impl UnitManagerObj for PathManager {
+    // nothing to customize
+}
+
+impl ReStation for PathManager {
+    // no input, no compensate: the paths are checked again when they are watched
+
+    // no data
+
+    // reload
+    fn register_ex(&self) {
+        let events = self.comm.um().events();
+        let monitor = Rc::clone(&self.monitor);
+        if let Err(e) = events
+            .add_source(monitor.clone())
+            .and_then(|_| events.set_enabled(monitor, EventState::On))
+        {
+            log::error!("failed to enable the path monitor: {}", e);
+        }
+    }
+}
+
+impl UnitMngUtil for PathManager {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+impl Default for PathManager {
+    fn default() -> Self {
+        PathManager::new()
+    }
+}
+
+// the inotify events of a path unit, with the watch descriptors they come from
+type PathEvents = Vec<(WatchDescriptor, AddWatchFlags)>;
+
+/// the inotify fd of the event loop is shared by all path units, the events are dispatched
+/// to the units by the watch descriptor
+struct PathMonitor {
+    // associated objects
+    comm: Arc<PathUmComm>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl PathMonitor {
+    pub(self) fn new(commr: &Arc<PathUmComm>) -> PathMonitor {
+        PathMonitor {
+            comm: Arc::clone(commr),
+        }
+    }
+
+    fn dispatch_inotify(&self, events: &Events) -> Result<i32, Error> {
+        let mut units: Vec<(Rc<PathMng>, PathEvents)> = Vec::new();
+        for event in events.read_events()? {
+            if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+                log::warn!("The inotify event queue overflowed, some path changes may be lost.");
+                continue;
+            }
+
+            for mng in self.comm.watchers(event.wd) {
+                match units.iter_mut().find(|(m, _)| Rc::ptr_eq(m, &mng)) {
+                    Some((_, v)) => v.push((event.wd, event.mask)),
+                    None => units.push((mng, vec![(event.wd, event.mask)])),
+                }
+            }
+
+            // the watch is removed by the kernel, the units watch the paths again later
+            if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+                self.comm.watch_forget(event.wd);
+            }
+        }
+
+        for (mng, v) in units.iter() {
+            mng.dispatch_io(v);
+            mng.db_update();
+        }
+        Ok(0)
+    }
+}
+
+impl Source for PathMonitor {
+    fn event_type(&self) -> EventType {
+        EventType::Inotify
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn dispatch(&self, e: &Events) -> Result<i32, Error> {
+        if let Err(err) = self.dispatch_inotify(e) {
+            log::error!("Failed to dispatch the inotify events, ignoring: {}", err);
+        }
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    // the inotify fd is owned by the event loop
+    fn fd(&self) -> RawFd {
+        0
+    }
+}
+
+use libsysmaster::declure_umobj_plugin;
+declure_umobj_plugin!(PathManager, PathManager::default, PLUGIN_NAME, LOG_LEVEL);
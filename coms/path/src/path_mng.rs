@@ -0,0 +1,448 @@
+//!  The core logic of the path subclass
+
+use super::path_comm::PathUnitComm;
+use super::path_config::PathConfig;
+use super::path_rentry::{PathResult, PathState};
+use super::path_spec::watch_mask;
+use libevent::{EventState, EventType, Events, Source};
+use libsysmaster::manager::{RateLimit, Unit, UnitActionError, UnitActiveState, UnitNotifyFlags};
+use libsysmaster::ReStation;
+use libutils::Error;
+use nix::sys::inotify::{AddWatchFlags, WatchDescriptor};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+impl PathState {
+    fn path_state_to_unit_state(&self) -> UnitActiveState {
+        match *self {
+            PathState::Dead => UnitActiveState::UnitInActive,
+            PathState::Waiting | PathState::Running => UnitActiveState::UnitActive,
+            PathState::Failed => UnitActiveState::UnitFailed,
+        }
+    }
+}
+
+pub(super) struct PathMng {
+    comm: Rc<PathUnitComm>,
+    config: Rc<PathConfig>,
+    state: RefCell<PathState>,
+    result: RefCell<PathResult>,
+    // the watch of each path spec in order, and whether it watches the path itself
+    watches: RefCell<Vec<(WatchDescriptor, bool)>>,
+    trigger_limit: RefCell<RateLimit>,
+    defer: Rc<PathDefer>,
+    me: RefCell<Weak<PathMng>>,
+}
+
+impl ReStation for PathMng {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        if let Some((state, result)) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+            *self.result.borrow_mut() = result;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_mng_insert(self.state(), self.result());
+    }
+
+    // reload: no external connections, entry-only
+    fn entry_coldplug(&self) {
+        if self.state() == PathState::Waiting {
+            self.enter_waiting(false);
+        }
+    }
+
+    fn entry_clear(&self) {
+        self.unwatch();
+        self.disable_defer();
+    }
+}
+
+impl PathMng {
+    pub(super) fn new(_comm: &Rc<PathUnitComm>, configr: &Rc<PathConfig>) -> Self {
+        PathMng {
+            comm: Rc::clone(_comm),
+            config: Rc::clone(configr),
+            state: RefCell::new(PathState::Dead),
+            result: RefCell::new(PathResult::Success),
+            watches: RefCell::new(Vec::new()),
+            trigger_limit: RefCell::new(RateLimit::new(0, 0)),
+            defer: Rc::new(PathDefer::new()),
+            me: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(super) fn attach_path(&self, mngr: &Rc<PathMng>) {
+        self.defer.attach(Rc::downgrade(mngr));
+        *self.me.borrow_mut() = Rc::downgrade(mngr);
+    }
+
+    pub(super) fn start_check(&self) -> Result<(), UnitActionError> {
+        if self.config.unit_ref_target().is_none() {
+            return Err(UnitActionError::UnitActionENoent);
+        }
+
+        if !self.comm.unit().test_start_limit() {
+            self.set_result(PathResult::FailureStartLimitHit);
+            self.enter_dead(true);
+            return Err(UnitActionError::UnitActionECanceled);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn start_action(&self) {
+        self.result.replace(PathResult::Success);
+        let (interval, burst) = self.config.trigger_limit();
+        self.trigger_limit.replace(RateLimit::new(interval, burst));
+
+        if self.config.make_directory() {
+            for spec in self.config.specs() {
+                spec.make_directory();
+            }
+        }
+
+        self.enter_waiting(true);
+    }
+
+    pub(super) fn stop_action(&self) {
+        self.enter_dead(true);
+    }
+
+    pub(super) fn trigger_notify(&self, _other: Rc<Unit>) {
+        // the state of the triggered unit is being dispatched, re-check later
+        self.enable_defer();
+    }
+
+    /// the inotify events of the watches of this unit
+    pub(super) fn dispatch_io(&self, events: &[(WatchDescriptor, AddWatchFlags)]) {
+        if self.state() != PathState::Waiting {
+            return;
+        }
+
+        let specs = self.config.specs();
+        let changed = self
+            .watches
+            .borrow()
+            .iter()
+            .zip(specs.iter())
+            .filter(|((_, primary), _)| *primary)
+            .find(|((wd, _), spec)| events.iter().any(|(w, m)| w == wd && spec.changed(*m)))
+            .map(|(_, spec)| spec.path().to_path_buf());
+
+        match changed {
+            Some(path) => {
+                log::debug!("{} got an event on {:?}.", self.comm.unit().id(), path);
+                self.enter_running();
+            }
+            // the watched paths may be created or removed, watch them again
+            None => self.enter_waiting(true),
+        }
+    }
+
+    fn enter_dead(&self, notify: bool) {
+        if self.result() == PathResult::Success {
+            self.set_state(PathState::Dead, notify);
+        } else {
+            self.set_state(PathState::Failed, notify);
+        }
+    }
+
+    fn enter_waiting(&self, notify: bool) {
+        // watch before checking, so the change between them is not lost
+        self.unwatch();
+        if let Err(e) = self.watch() {
+            log::error!(
+                "Failed to watch the paths of {}: {}",
+                self.comm.unit().id(),
+                e
+            );
+            self.set_result(PathResult::FailureResources);
+            self.enter_dead(true);
+            return;
+        }
+
+        self.set_state(PathState::Waiting, notify);
+
+        // the unit may be starting, trigger it later
+        if self.check_good() {
+            self.enable_defer();
+        }
+    }
+
+    fn enter_running(&self) {
+        let unit = match self.config.unit_ref_target() {
+            Some(u) => u,
+            None => {
+                self.set_result(PathResult::FailureResources);
+                self.enter_dead(true);
+                return;
+            }
+        };
+
+        // the triggered unit exits at once while the path stays good, stop the busy loop
+        if !self.trigger_limit.borrow_mut().ratelimit_below() {
+            log::error!(
+                "{} triggered {} too often, refusing.",
+                self.comm.unit().id(),
+                unit
+            );
+            self.set_result(PathResult::FailureTriggerLimitHit);
+            self.enter_dead(true);
+            return;
+        }
+
+        if let Err(e) = self.comm.um().start_unit(&unit) {
+            log::error!(
+                "Failed to start {} triggered by {}: {:?}",
+                unit,
+                self.comm.unit().id(),
+                e
+            );
+            self.set_result(PathResult::FailureResources);
+            self.enter_dead(true);
+            return;
+        }
+
+        self.set_state(PathState::Running, true);
+    }
+
+    fn check_good(&self) -> bool {
+        match self.config.specs().iter().find(|spec| spec.check_good()) {
+            Some(spec) => {
+                log::debug!(
+                    "{}: {:?} of {:?} is good.",
+                    self.comm.unit().id(),
+                    spec.path_type(),
+                    spec.path()
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn watch(&self) -> Result<(), Error> {
+        let events = self.comm.um().events();
+        for spec in self.config.specs() {
+            let (target, primary) = spec.watch_target();
+            let wd = events.add_watch(&target, watch_mask())?;
+            self.comm.watch_insert(wd, self.me.borrow().clone());
+            self.watches.borrow_mut().push((wd, primary));
+        }
+        Ok(())
+    }
+
+    fn unwatch(&self) {
+        let events = self.comm.um().events();
+        let me = self.me.borrow().clone();
+        for (wd, _) in self.watches.take() {
+            // the watch is shared with the other units watching the same path
+            if self.comm.watch_remove(wd, &me) {
+                if let Err(e) = events.rm_watch(wd) {
+                    log::debug!("failed to remove the watch, ignoring: {}", e);
+                }
+            }
+        }
+    }
+
+    fn enable_defer(&self) {
+        let events = self.comm.um().events();
+        let defer = self.defer.clone();
+        if let Err(e) = events
+            .add_source(defer.clone())
+            .and_then(|_| events.set_enabled(defer, EventState::OneShot))
+        {
+            log::error!("failed to enable the path defer event: {}", e);
+        }
+    }
+
+    fn disable_defer(&self) {
+        let events = self.comm.um().events();
+        if let Err(e) = events.set_enabled(self.defer.clone(), EventState::Off) {
+            log::debug!("failed to disable the path defer event: {}", e);
+        }
+    }
+
+    fn trigger_unit(&self) -> Option<Rc<Unit>> {
+        let name = self.config.unit_ref_target()?;
+        self.comm.um().units_get(&name)
+    }
+
+    fn dispatch_defer(&self) {
+        match self.state() {
+            PathState::Waiting => {
+                if self.check_good() {
+                    self.enter_running();
+                }
+            }
+            PathState::Running => {
+                // watch again once the triggered unit is finished
+                if let Some(unit) = self.trigger_unit() {
+                    if matches!(
+                        unit.current_active_state(),
+                        UnitActiveState::UnitInActive | UnitActiveState::UnitFailed
+                    ) {
+                        self.enter_waiting(true);
+                    }
+                }
+            }
+            PathState::Dead | PathState::Failed => {}
+        }
+    }
+
+    fn set_state(&self, new_state: PathState, notify: bool) {
+        let old_state = self.state();
+        self.change_state(new_state);
+
+        if new_state != PathState::Waiting {
+            self.unwatch();
+        }
+
+        if notify {
+            self.state_notify(new_state, old_state);
+        }
+    }
+
+    fn state_notify(&self, new_state: PathState, old_state: PathState) {
+        if new_state != old_state {
+            log::debug!(
+                "{} original state[{:?}] -> new state[{:?}]",
+                self.comm.unit().id(),
+                old_state,
+                new_state,
+            );
+        }
+
+        let old_unit_state = old_state.path_state_to_unit_state();
+        let new_unit_state = new_state.path_state_to_unit_state();
+        self.comm.unit().notify(
+            old_unit_state,
+            new_unit_state,
+            UnitNotifyFlags::UNIT_NOTIFY_RELOAD_FAILURE,
+        );
+
+        self.db_update();
+    }
+
+    fn change_state(&self, new_state: PathState) {
+        self.state.replace(new_state);
+    }
+
+    fn state(&self) -> PathState {
+        *self.state.borrow()
+    }
+
+    // keep the first failure
+    fn set_result(&self, result: PathResult) {
+        if self.result() == PathResult::Success {
+            self.result.replace(result);
+        }
+    }
+
+    fn result(&self) -> PathResult {
+        *self.result.borrow()
+    }
+
+    pub(super) fn path_state_to_unit_state(&self) -> UnitActiveState {
+        self.state().path_state_to_unit_state()
+    }
+
+    pub(super) fn get_state(&self) -> String {
+        self.state().to_string()
+    }
+
+    pub(super) fn get_result(&self) -> String {
+        self.result().to_string()
+    }
+}
+
+struct PathDefer {
+    mng: RefCell<Weak<PathMng>>,
+}
+
+impl PathDefer {
+    fn new() -> Self {
+        PathDefer {
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(self) fn attach(&self, mng: Weak<PathMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    pub(self) fn mng(&self) -> Rc<PathMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for PathDefer {
+    fn event_type(&self) -> EventType {
+        EventType::Defer
+    }
+
+    fn epoll_event(&self) -> u32 {
+        0
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn dispatch(&self, _: &Events) -> Result<i32, Error> {
+        self.mng().dispatch_defer();
+        self.mng().db_update();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathConfig, PathMng, PathResult, PathState, PathUnitComm};
+    use libsysmaster::manager::UnitActiveState;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_path_set_state() {
+        let _comm = Rc::new(PathUnitComm::new());
+        let _config = Rc::new(PathConfig::new(&_comm));
+        let pm = PathMng::new(&_comm, &_config);
+        pm.change_state(PathState::Running);
+        assert_eq!(pm.state(), PathState::Running);
+        assert_eq!(pm.get_state(), "running");
+
+        pm.set_result(PathResult::FailureTriggerLimitHit);
+        pm.set_result(PathResult::FailureResources);
+        assert_eq!(pm.get_result(), "trigger-limit-hit");
+    }
+
+    #[test]
+    fn test_path_state_to_unit_state() {
+        assert_eq!(
+            PathState::Dead.path_state_to_unit_state(),
+            UnitActiveState::UnitInActive
+        );
+        assert_eq!(
+            PathState::Waiting.path_state_to_unit_state(),
+            UnitActiveState::UnitActive
+        );
+        assert_eq!(
+            PathState::Running.path_state_to_unit_state(),
+            UnitActiveState::UnitActive
+        );
+        assert_eq!(
+            PathState::Failed.path_state_to_unit_state(),
+            UnitActiveState::UnitFailed
+        );
+    }
+}
@@ -0,0 +1,164 @@
+#![allow(non_snake_case)]
+use confique::Config;
+use libsysmaster::manager::{deserialize_timespan, DeserializeWith};
+use libsysmaster::{ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, Reliability};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use strum::Display;
+
+const RELI_DB_HPATH_CONF: &str = "pathconf";
+const RELI_DB_HPATH_MNG: &str = "pathmng";
+
+#[derive(Config, Default, Clone, Debug, Serialize, Deserialize)]
+pub(super) struct SectionPath {
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub PathExists: Option<Vec<String>>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub PathExistsGlob: Option<Vec<String>>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub PathChanged: Option<Vec<String>>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub PathModified: Option<Vec<String>>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub DirectoryNotEmpty: Option<Vec<String>>,
+    #[config(default = false)]
+    pub MakeDirectory: bool,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub TriggerLimitIntervalSec: Option<u64>,
+    pub TriggerLimitBurst: Option<u32>,
+    pub Unit: Option<String>,
+}
+
+// Path waits in Waiting until one of the paths is good, then it stays in Running while the triggered unit
+// is running, the paths are not watched in Running. Waiting and Running are both active.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub(super) enum PathState {
+    Dead,
+    Waiting,
+    Running,
+    Failed,
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+pub(super) enum PathResult {
+    #[strum(serialize = "success")]
+    Success,
+    #[strum(serialize = "resources")]
+    FailureResources,
+    #[strum(serialize = "start-limit-hit")]
+    FailureStartLimitHit,
+    #[strum(serialize = "trigger-limit-hit")]
+    FailureTriggerLimitHit,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PathReConf {
+    path: SectionPath,
+    unit: Option<String>,
+}
+
+impl PathReConf {
+    fn new(pathr: &SectionPath, unit: Option<String>) -> PathReConf {
+        PathReConf {
+            path: pathr.clone(),
+            unit,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PathReMng {
+    state: PathState,
+    result: PathResult,
+}
+
+impl PathReMng {
+    fn new(state: PathState, result: PathResult) -> PathReMng {
+        PathReMng { state, result }
+    }
+}
+
+struct PathReDb<K, V>(ReDb<K, V>);
+
+pub(super) struct PathRe {
+    // database: multi-instance(N)
+    conf: Rc<PathReDb<String, PathReConf>>, // RELI_DB_HPATH_CONF; key: unit_id, data: config+unit;
+    mng: Rc<PathReDb<String, PathReMng>>,   // RELI_DB_HPATH_MNG; key: unit_id, data: state+result;
+}
+
+impl PathRe {
+    pub(super) fn new(relir: &Reliability) -> PathRe {
+        let conf = Rc::new(PathReDb(ReDb::new(relir, RELI_DB_HPATH_CONF)));
+        let mng = Rc::new(PathReDb(ReDb::new(relir, RELI_DB_HPATH_MNG)));
+        let rentry = PathRe { conf, mng };
+        rentry.register(relir);
+        rentry
+    }
+
+    pub(super) fn conf_insert(&self, unit_id: &str, path: &SectionPath, unit: Option<String>) {
+        let conf = PathReConf::new(path, unit);
+        self.conf.0.insert(unit_id.to_string(), conf);
+    }
+
+    pub(super) fn conf_get(&self, unit_id: &String) -> Option<(SectionPath, Option<String>)> {
+        let conf = self.conf.0.get(unit_id);
+        conf.map(|c| (c.path, c.unit))
+    }
+
+    pub(super) fn mng_insert(&self, unit_id: &str, state: PathState, result: PathResult) {
+        let mng = PathReMng::new(state, result);
+        self.mng.0.insert(unit_id.to_string(), mng);
+    }
+
+    pub(super) fn mng_get(&self, unit_id: &String) -> Option<(PathState, PathResult)> {
+        let mng = self.mng.0.get(unit_id);
+        mng.map(|m| (m.state, m.result))
+    }
+
+    fn register(&self, relir: &Reliability) {
+        // reliability-db: RELI_DB_HPATH_CONF
+        let db = Rc::clone(&self.conf);
+        relir.history_db_register(RELI_DB_HPATH_CONF, db);
+
+        // reliability-db: RELI_DB_HPATH_MNG
+        let db = Rc::clone(&self.mng);
+        relir.history_db_register(RELI_DB_HPATH_MNG, db);
+    }
+}
+
+impl ReDbTable for PathReDb<String, PathReConf> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.0.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.0.set_ignore(ignore);
+    }
+}
+
+impl ReDbTable for PathReDb<String, PathReMng> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.0.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.0.set_ignore(ignore);
+    }
+}
@@ -0,0 +1,172 @@
+//! path_spec is one path setting of the path unit, it decides what to watch and when the path is good.
+//!
+use super::path_base::{directory_not_empty, glob_exists, glob_prefix_dir};
+use nix::sys::inotify::AddWatchFlags;
+use std::error::Error;
+use std::fs::DirBuilder;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+
+/// The inotify fd is shared by all the path units, a path watched by several units gets the same watch
+/// descriptor and every add_watch replaces the mask of it. So all the watches use the same mask, and
+/// each path spec picks the events it cares about.
+pub(super) fn watch_mask() -> AddWatchFlags {
+    AddWatchFlags::IN_DELETE_SELF
+        | AddWatchFlags::IN_MOVE_SELF
+        | AddWatchFlags::IN_ATTRIB
+        | AddWatchFlags::IN_CLOSE_WRITE
+        | AddWatchFlags::IN_CREATE
+        | AddWatchFlags::IN_DELETE
+        | AddWatchFlags::IN_MOVED_FROM
+        | AddWatchFlags::IN_MOVED_TO
+        | AddWatchFlags::IN_MODIFY
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub(super) enum PathType {
+    Exists,
+    ExistsGlob,
+    Changed,
+    Modified,
+    DirectoryNotEmpty,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PathSpec {
+    path_type: PathType,
+    path: PathBuf,
+}
+
+impl PathSpec {
+    pub(super) fn new(path_type: PathType, path: &str) -> Result<PathSpec, Box<dyn Error>> {
+        if !path.starts_with('/') {
+            return Err(format!("the path {} is not absolute", path).into());
+        }
+
+        Ok(PathSpec {
+            path_type,
+            path: PathBuf::from(path),
+        })
+    }
+
+    pub(super) fn path_type(&self) -> PathType {
+        self.path_type
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// the path to watch and whether it is the path itself, the nearest existing
+    /// parent is watched instead until the path is created
+    pub(super) fn watch_target(&self) -> (PathBuf, bool) {
+        let path = match self.path_type {
+            PathType::ExistsGlob => glob_prefix_dir(&self.path.to_string_lossy()),
+            _ => self.path.clone(),
+        };
+
+        let mut target = path.as_path();
+        while !target.exists() {
+            match target.parent() {
+                Some(p) => target = p,
+                None => break,
+            }
+        }
+
+        let primary = target == path && self.path_type != PathType::ExistsGlob;
+        (target.to_path_buf(), primary)
+    }
+
+    /// PathChanged= and PathModified= are triggered by the events of the path itself
+    pub(super) fn changed(&self, mask: AddWatchFlags) -> bool {
+        let changed = AddWatchFlags::IN_DELETE_SELF
+            | AddWatchFlags::IN_MOVE_SELF
+            | AddWatchFlags::IN_ATTRIB
+            | AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO;
+        match self.path_type {
+            PathType::Changed => mask.intersects(changed),
+            PathType::Modified => mask.intersects(changed | AddWatchFlags::IN_MODIFY),
+            _ => false,
+        }
+    }
+
+    /// PathExists=, PathExistsGlob= and DirectoryNotEmpty= are triggered as long as the condition holds
+    pub(super) fn check_good(&self) -> bool {
+        match self.path_type {
+            PathType::Exists => self.path.exists(),
+            PathType::ExistsGlob => glob_exists(&self.path.to_string_lossy()),
+            PathType::DirectoryNotEmpty => directory_not_empty(&self.path),
+            PathType::Changed | PathType::Modified => false,
+        }
+    }
+
+    /// MakeDirectory= creates the watched directories, it is meaningless for the existence checks
+    pub(super) fn make_directory(&self) {
+        if matches!(self.path_type, PathType::Exists | PathType::ExistsGlob) {
+            return;
+        }
+
+        if let Err(e) = DirBuilder::new()
+            .recursive(true)
+            .mode(0o755)
+            .create(&self.path)
+        {
+            log::warn!("Failed to create directory {:?}: {}", self.path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathSpec, PathType};
+    use nix::sys::inotify::AddWatchFlags;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_path_spec_new() {
+        assert!(PathSpec::new(PathType::Exists, "foo").is_err());
+        let spec = PathSpec::new(PathType::Modified, "/tmp/foo").unwrap();
+        assert_eq!(spec.path_type(), PathType::Modified);
+        assert_eq!(spec.path(), PathBuf::from("/tmp/foo"));
+    }
+
+    #[test]
+    fn test_path_spec_watch() {
+        let dir = "/tmp/sysmaster-path-test-spec";
+        let _ = fs::remove_dir_all(dir);
+
+        let spec = PathSpec::new(PathType::Changed, "/tmp/sysmaster-path-test-spec/a/b").unwrap();
+        assert_eq!(spec.watch_target(), (PathBuf::from("/tmp"), false));
+        assert!(!spec.changed(AddWatchFlags::IN_MODIFY));
+        assert!(spec.changed(AddWatchFlags::IN_CLOSE_WRITE));
+        let modified = PathSpec::new(PathType::Modified, "/tmp/foo").unwrap();
+        assert!(modified.changed(AddWatchFlags::IN_MODIFY));
+
+        spec.make_directory();
+        assert_eq!(
+            spec.watch_target(),
+            (PathBuf::from("/tmp/sysmaster-path-test-spec/a/b"), true)
+        );
+        assert!(!spec.check_good());
+
+        let spec =
+            PathSpec::new(PathType::ExistsGlob, "/tmp/sysmaster-path-test-spec/a/*").unwrap();
+        assert_eq!(
+            spec.watch_target(),
+            (PathBuf::from("/tmp/sysmaster-path-test-spec/a"), false)
+        );
+        assert!(spec.check_good());
+
+        let spec = PathSpec::new(PathType::DirectoryNotEmpty, dir).unwrap();
+        assert!(spec.check_good());
+        assert!(!spec.changed(AddWatchFlags::IN_CREATE));
+
+        fs::remove_dir_all(dir).unwrap();
+        assert!(!spec.check_good());
+    }
+}
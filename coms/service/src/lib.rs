@@ -28,13 +28,17 @@
 //! The forking mode indicates that when the fork sub process exits, the service startup is completed. The pid of the sub process needs to be obtained through PIDFile.
 //! The oneshot mode exits after the service is executed.
 //! Notify the status message to the sysmaster after the notify mode service is started.
-//! Supported notification messages MAINPID=$val, READY=$val, STOPPING=$val, ERRNO=$val
+//! Supported notification messages MAINPID=$val, READY=1, RELOADING=1, STOPPING=1, ERRNO=$val, STATUS=$text, EXTEND_TIMEOUT_USEC=$usec, WATCHDOG=1, WATCHDOG=trigger, FDSTORE=1, FDSTOREREMOVE=1 and FDNAME=$name.
+//! STATUS= is shown by "pctrl status". RELOADING=1 puts the running service into reloading until READY=1 is sent. EXTEND_TIMEOUT_USEC= gives the current start, reload or stop operation at least the time from now.
+//! WATCHDOG=trigger terminates the service with the watchdog result, WATCHDOG_USEC= changes the timeout of the watchdog until the service is started again.
 //!
 //!
 //! ExecCondition、ExecStartPre、ExecStart、ExecStop、ExecStartPost
 //!
 //! The commands that a service needs to execute at different startup stages support configuring multiple commands division. For example, "/usr/bin/sleep 5;/bin/echo 'test'".
 //!
+//! The path of the command can be prefixed with "-", "@", "+" and "!" or "!!". With "-", the failure exit of the command is ignored and the service proceeds as if it succeeded. With "@", the first argument after the path is passed as argv[0]. With "+", the command runs with full privileges, User, Group, the capabilities, the seccomp filter and the sandbox are not applied. With "!", the command runs without changing User and Group, and "!!" is the same as "!" only on the systems without the ambient capabilities. "+" can not be combined with "!". For example, "-@/usr/bin/sleep sleeper 5".
+//!
 //! PIDFile
 //!
 //! When the Type field is forking, you need to configure this field to obtain the PID of the child process
@@ -45,15 +49,172 @@
 //!
 //! NotifyAccess
 //!
-//! The support configuration is main, which means that the notification sent by the MAINPID process is supported.
+//! Which processes of the service can send the notification messages, support none, main, exec and all. main is the main process, exec is the main process and the control process, and all is all the processes in the cgroup of the service.
+//! The default configuration is main for the notify service or when WatchdogUSec or FileDescriptorStoreMax is configured, and none for the others. The NOTIFY_SOCKET environment variable is passed unless it is none.
+//!
+//! FileDescriptorStoreMax
+//!
+//! The maximal number of the fds the service can store with FDSTORE=1, the default configuration is 0. The stored fds are passed to the main process after the fds of the sockets, named by FDNAME= or "stored".
+//! They are kept open across the restart of the service and the reexec of sysmaster, and closed when the service is stopped explicitly or removed with FDSTOREREMOVE=1.
+//!
+//! WatchdogUSec
+//!
+//! The timeout of the software watchdog in microseconds, 0 disables it and is the default configuration. The watchdog is armed when the service is started and reset by every WATCHDOG=1, WATCHDOG_USEC and WATCHDOG_PID are passed to the main process.
+//! When it expires, the service is terminated with WatchdogSignal and failed with the watchdog result.
+//!
+//! WatchdogSignal
+//!
+//! The signal sent to the service when the watchdog expires, can be the name or the number, such as "SIGABRT" or "6". The default configuration is SIGABRT.
 //!
 //! Environment
 //!
 //! The environment variable parameter passed to the child process can be configured with more than one The configuration format is "key=value".
 //!
+//! EnvironmentFile
+//!
+//! The files which the environment variables are read from when the commands start, use ";" when configuring multiple files. Every line of the file is an assignment like "key=value", the empty lines and the lines starting with "#" or ";" are ignored. The variables read from the files override the ones configured by Environment. The file must be an absolute path, and the file prefixed with "-" is ignored if it does not exist.
+//!
+//! The arguments of the commands are split at whitespace, the quotes and the backslash can be used to pass an argument containing whitespace. "$NAME" as a separate argument is replaced by the value of the variable split at whitespace, and "${NAME}" is replaced by the value as is. "$$" is a literal "$". The specifiers %n (the full unit name), %N (the unit name without the type suffix), %p (the prefix), %i (the instance), %t (the runtime directory), %h (the home directory of the user), %H (the hostname) and %m (the machine id) are replaced too, and "%%" is a literal "%".
+//!
 //! Sockets
 //!
 //! Indicates the socket service that the current service depends on. The dependency is Wants. Support multiple configurations, and use ";" when configuring multiple division.
+//! The fds of all the sockets are passed to the service in the order of the socket unit names, with LISTEN_FDS, LISTEN_PID and LISTEN_FDNAMES, which is the names of the fds joined by ":".
+//!
+//! Restart
+//!
+//! Whether the service is restarted when its process exits, be killed or a timeout is reached. Support no, on-success, on-failure, on-abnormal, on-watchdog, on-abort and always. on-abnormal includes the watchdog timeout. The default configuration is no.
+//!
+//! RestartSec
+//!
+//! The time to sleep before restarting the service, such as "100ms" or 5 seconds. The default is the DefaultRestartSec of the manager.
+//!
+//! RestartPreventExitStatus
+//!
+//! The exit codes or signals of the main process that will not restart the service even if Restart is configured, such as "1 6 SIGABRT".
+//!
+//! SuccessExitStatus
+//!
+//! The additional exit codes or signals of the main process that are considered as successful termination, such as "1 SIGHUP".
+//!
+//! TimeoutStartSec, TimeoutStopSec, TimeoutSec
+//!
+//! The time to wait for the start or stop of the service, such as "1min 30s" or 90 seconds. TimeoutSec configures both of them, 0 means no timeout. The default is the DefaultTimeoutSec of the manager, and no start timeout for oneshot service.
+//! The service is terminated and failed with timeout result when the start times out, and it is killed when the stop times out.
+//!
+//! User, Group
+//!
+//! The user and the group which the commands run as, can be the name or the numeric id. The commands run as root if not configured, the group defaults to the primary group of the user.
+//!
+//! SupplementaryGroups
+//!
+//! The additional groups of the commands, use ";" when configuring multiple groups.
+//!
+//! UMask
+//!
+//! The file mode creation mask of the commands in octal, such as "0022".
+//!
+//! WorkingDirectory
+//!
+//! The absolute path of the working directory of the commands, the directory is entered with the permission of the user.
+//!
+//! DynamicUser
+//!
+//! Support the configuration of true and false. When the configuration is true, the commands run as a transient user whose uid is allocated from the range 61184-65519 when the unit starts, and released when the unit is inactive. The default configuration is false.
+//!
+//! StandardInput
+//!
+//! The standard input of the commands, support null, tty, file:path, socket and fd. null connects to /dev/null, tty connects to the console, file:path reads the absolute path.
+//! socket connects to the socket passed by the socket unit, which must be exactly one socket, and fd connects to the first passed file descriptor. The default configuration is null.
+//!
+//! StandardOutput, StandardError
+//!
+//! The standard output and error of the commands, support log, inherit, null, tty, file:path, append:path, truncate:path, socket and fd.
+//! log collects the lines into the logs of the unit under /var/log/sysmaster, which are queried by "pctrl logs", journal is the same as log.
+//! inherit of the output uses the input, or the output of sysmaster when the input is null, and inherit of the error uses the output. file:path writes the file from its beginning, append:path appends to it and truncate:path truncates it first.
+//! The default configuration of StandardOutput is log, and the default configuration of StandardError is inherit.
+//!
+//! LimitCPU, LimitFSIZE, LimitDATA, LimitSTACK, LimitCORE, LimitRSS, LimitNOFILE, LimitAS, LimitNPROC, LimitMEMLOCK, LimitLOCKS, LimitSIGPENDING, LimitMSGQUEUE, LimitNICE, LimitRTPRIO, LimitRTTIME
+//!
+//! The resource limits of the processes set by setrlimit, such as "65536" or "soft:hard" like "1K:1M". The value is a number, a size with the suffix K, M, G and so on, or infinity.
+//! LimitCPU and LimitRTTIME take a time span like "1h" or "500ms" instead of a size, a number without unit is in seconds for LimitCPU and in microseconds for LimitRTTIME.
+//!
+//! Nice
+//!
+//! The nice level of the processes, from -20 (the highest priority) to 19.
+//!
+//! CPUSchedulingPolicy, CPUSchedulingPriority
+//!
+//! The CPU scheduling policy of the processes, support other, batch, idle, fifo and rr. The priority is from 1 to 99 for fifo and rr, and 0 for the others.
+//!
+//! CPUAffinity
+//!
+//! The CPUs which the processes are bound to, such as "0 2-3" or "0,1".
+//!
+//! IOSchedulingClass, IOSchedulingPriority
+//!
+//! The IO scheduling class of the processes, support none, realtime, best-effort and idle. The priority is from 0 (the highest) to 7, the default is 4.
+//!
+//! OOMScoreAdjust
+//!
+//! The adjustment of the score which the OOM killer selects the processes by, from -1000 (never killed) to 1000.
+//!
+//! PrivateTmp
+//!
+//! Support the configuration of true and false. When the configuration is true, the processes run in a new mount namespace with private /tmp and /var/tmp, which are removed when the unit is inactive. The default configuration is false.
+//!
+//! ProtectSystem
+//!
+//! Support no, yes, full and strict. yes mounts /usr and /boot read-only, full mounts /etc read-only too, and strict mounts the whole file system read-only except /dev, /proc and /sys. The default configuration is no.
+//!
+//! ProtectHome
+//!
+//! Support no, yes, read-only and tmpfs. yes makes /home, /root and /run/user inaccessible, read-only mounts them read-only, and tmpfs replaces them with empty read-only tmpfs. The default configuration is no.
+//!
+//! ReadOnlyPaths, ReadWritePaths, InaccessiblePaths
+//!
+//! The absolute paths which are read-only, writable or inaccessible to the processes, such as "/var/lib;/etc/ssl". The path prefixed with "-" is ignored if it does not exist. ReadWritePaths takes precedence over ReadOnlyPaths for the paths under it.
+//!
+//! PrivateDevices
+//!
+//! Support the configuration of true and false. When the configuration is true, the processes see a private /dev with only the pseudo devices like /dev/null, /dev/zero and /dev/urandom. The default configuration is false.
+//!
+//! PrivateNetwork
+//!
+//! Support the configuration of true and false. When the configuration is true, the processes run in a new network namespace with only the loopback device. The default configuration is false.
+//!
+//! The private /tmp, /var/tmp and network namespace are shared with the units listed in JoinsNamespaceOf of the [Unit] section.
+//!
+//! CapabilityBoundingSet
+//!
+//! The capabilities which are kept in the bounding set of the processes, such as "CAP_NET_BIND_SERVICE CAP_NET_RAW". The list prefixed with "~" drops the listed capabilities and keeps the others. All the capabilities are kept by default.
+//!
+//! AmbientCapabilities
+//!
+//! The capabilities which are passed to the commands running as a user other than root, such as "CAP_NET_BIND_SERVICE". They must be in the bounding set.
+//!
+//! NoNewPrivileges
+//!
+//! Support the configuration of true and false. When the configuration is true, the processes can never gain privileges by execve, such as by the setuid programs. It is implied by SystemCallFilter when the commands do not run as root. The default configuration is false.
+//!
+//! SecureBits
+//!
+//! The secure bits of the processes, support keep-caps, keep-caps-locked, no-setuid-fixup, no-setuid-fixup-locked, noroot and noroot-locked, such as "noroot noroot-locked".
+//!
+//! SystemCallFilter
+//!
+//! The syscalls which are allowed by seccomp, such as "@system-service" or "@basic-io @network-io sendfile". The list prefixed with "~" is the syscalls which are denied, such as "~@mount @reboot".
+//! The process calling the syscall which is not allowed is killed with SIGSYS. The groups are prefixed with "@", support @default, @aio, @basic-io, @chown, @clock, @debug, @file-system, @io-event, @ipc, @keyring, @memlock, @module, @mount,
+//! @network-io, @privileged, @process, @raw-io, @reboot, @resources, @setuid, @signal, @swap, @sync, @timer and @system-service. The syscalls of @default and execve are always allowed.
+//!
+//! SystemCallArchitectures
+//!
+//! The architectures whose syscalls are allowed, support native, x86-64, x86, arm64, arm, riscv64 and loongarch64, such as "native". The syscalls of all the architectures are allowed by default.
+//! With SystemCallFilter, only the native architecture can be allowed.
+//!
+//! [ResourceControl] section related configuration
+//!
+//! The cgroup v2 resource limits of the service, see the slice unit for the supported settings.
 //!
 
 #[macro_use]
@@ -61,9 +222,8 @@ extern crate strum;
 
 // dependency:
 // service_base -> service_rentry -> {service_comm | service_config}
-// {service_pid | service_spawn} ->
-// {service_mng} ->
-// {service_monitor} -> service_unit -> service_manager
+// {service_pid | service_monitor} ->
+// {service_mng | service_spawn} -> service_unit -> service_manager
 mod service_base;
 mod service_comm;
 mod service_config;
@@ -6,6 +6,7 @@ use libsysmaster::Reliability;
 use nix::unistd::Pid;
 use once_cell::sync::Lazy;
 use std::cell::RefCell;
+use std::os::unix::prelude::RawFd;
 use std::rc::{Rc, Weak};
 use std::sync::{Arc, RwLock};
 
@@ -61,6 +62,8 @@ impl ServiceUnitComm {
         control_cmd_type: Option<ServiceCommand>,
         control_cmd_len: usize,
         notify_state: NotifyState,
+        restarts: u32,
+        fds: Vec<(RawFd, String)>,
     ) {
         self.rentry().mng_insert(
             self.unit().id(),
@@ -72,6 +75,8 @@ impl ServiceUnitComm {
             control_cmd_type,
             control_cmd_len,
             notify_state,
+            restarts,
+            fds,
         );
     }
 
@@ -87,12 +92,14 @@ impl ServiceUnitComm {
         Option<ServiceCommand>,
         usize,
         NotifyState,
+        u32,
+        Vec<(RawFd, String)>,
     )> {
         self.rentry().mng_get(self.unit().id())
     }
 
-    pub(super) fn _reli(&self) -> Rc<Reliability> {
-        self.umcomm._reli()
+    pub(super) fn reli(&self) -> Rc<Reliability> {
+        self.umcomm.reli()
     }
 
     fn rentry(&self) -> Rc<ServiceRe> {
@@ -152,9 +159,9 @@ impl ServiceUmComm {
         SERVICE_UM_COMM.clone()
     }
 
-    pub(super) fn _reli(&self) -> Rc<Reliability> {
+    pub(super) fn reli(&self) -> Rc<Reliability> {
         let rdata = self.data.read().unwrap();
-        rdata._reli()
+        rdata.reli()
     }
 
     pub(super) fn um(&self) -> Rc<dyn UmIf> {
@@ -171,7 +178,7 @@ impl ServiceUmComm {
 struct ServiceUmCommData {
     // associated objects
     um: Option<Rc<dyn UmIf>>,
-    _reli: Weak<Reliability>,
+    reli: Weak<Reliability>,
     rentry: Option<Rc<ServiceRe>>,
 }
 
@@ -180,7 +187,7 @@ impl ServiceUmCommData {
     pub(self) fn new() -> ServiceUmCommData {
         ServiceUmCommData {
             um: None,
-            _reli: Weak::new(),
+            reli: Weak::new(),
             rentry: None,
         }
     }
@@ -193,10 +200,10 @@ impl ServiceUmCommData {
     }
 
     pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
-        let old = self._reli.clone().upgrade();
+        let old = self.reli.clone().upgrade();
         if old.is_none() {
             log::debug!("ServiceUmComm attach_reli action.");
-            self._reli = Rc::downgrade(&reli);
+            self.reli = Rc::downgrade(&reli);
             self.rentry.replace(Rc::new(ServiceRe::new(&reli)));
         }
     }
@@ -209,8 +216,8 @@ impl ServiceUmCommData {
         }
     }
 
-    pub(self) fn _reli(&self) -> Rc<Reliability> {
-        self._reli.clone().upgrade().unwrap()
+    pub(self) fn reli(&self) -> Rc<Reliability> {
+        self.reli.clone().upgrade().unwrap()
     }
 
     pub(self) fn rentry(&self) -> Rc<ServiceRe> {
@@ -4,7 +4,9 @@ use super::service_rentry::{NotifyAccess, SectionService, ServiceCommand, Servic
 use confique::Config;
 use libsysmaster::manager::{ExecCommand, KillContext};
 use libsysmaster::ReStation;
+use nix::sys::signal::Signal;
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -27,6 +29,7 @@ impl ReStation for ServiceConfig {
     fn db_map(&self) {
         if let Some(conf) = self.comm.rentry_conf_get() {
             self.data.replace(ServiceConfigData::new(conf));
+            self.parse_kill_context();
         }
     }
 
@@ -108,6 +111,11 @@ impl ServiceConfig {
     fn parse_kill_context(&self) {
         self.kill_context
             .set_kill_mode(self.config_data().borrow().Service.kill_mode);
+
+        let watchdog_signal = self.config_data().borrow().Service.WatchdogSignal;
+        if let Some(signal) = watchdog_signal.and_then(|s| Signal::try_from(s).ok()) {
+            self.kill_context.set_watchdog_signal(signal);
+        }
     }
 }
 
@@ -144,8 +152,16 @@ impl ServiceConfigData {
 mod tests {
     use crate::service_comm::ServiceUnitComm;
     use crate::service_config::ServiceConfig;
+    use crate::service_rentry::{NotifyAccess, ServiceRestart};
+    use libsysmaster::manager::{
+        CapabilitySet, CpuSchedPolicy, ExecCommand, ExecCommandFlags, ExecInput, ExecOutput,
+        ExecRlimit, IoSchedClass, ProtectHome, ProtectSystem, SecureBits,
+    };
     use libtests::get_project_root;
+    use nix::sys::signal::Signal;
+    use std::path::PathBuf;
     use std::rc::Rc;
+    use std::str::FromStr;
 
     #[test]
     fn test_service_parse() {
@@ -162,4 +178,297 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_service_parse_restart() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/restart.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(service.Restart, ServiceRestart::OnFailure);
+        assert_eq!(service.RestartSec, Some(500000));
+
+        let prevent = service.RestartPreventExitStatus.as_ref().unwrap();
+        assert!(prevent.contains(3, Signal::SIGCHLD));
+        assert!(prevent.contains(-1, Signal::SIGKILL));
+        assert!(!prevent.contains(1, Signal::SIGCHLD));
+
+        let success = service.SuccessExitStatus.as_ref().unwrap();
+        assert!(success.contains(1, Signal::SIGCHLD));
+        assert!(success.contains(-1, Signal::SIGHUP));
+        assert!(!success.contains(-1, Signal::SIGTERM));
+
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/restart-invalid.service.toml");
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(vec![file_path], false).is_err());
+    }
+
+    #[test]
+    fn test_service_parse_timeout() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/timeout.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(service.TimeoutSec, Some(10000000));
+        assert_eq!(service.TimeoutStartSec, None);
+        assert_eq!(service.TimeoutStopSec, Some(90000000));
+    }
+
+    #[test]
+    fn test_service_parse_notify() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/fdstore.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(service.NotifyAccess, Some(NotifyAccess::All));
+        assert_eq!(service.FileDescriptorStoreMax, 8);
+    }
+
+    #[test]
+    fn test_service_parse_watchdog() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/watchdog.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(service.WatchdogUSec, Some(3000000));
+        assert_eq!(service.WatchdogSignal, Some(Signal::SIGKILL as i32));
+        assert_eq!(service.Restart, ServiceRestart::OnWatchdog);
+    }
+
+    #[test]
+    fn test_service_parse_credential() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/credential.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(service.User, Some("nobody".to_string()));
+        assert_eq!(service.Group, Some("nobody".to_string()));
+        assert_eq!(
+            service.SupplementaryGroups,
+            Some(vec!["adm".to_string(), "wheel".to_string()])
+        );
+        assert_eq!(service.UMask, Some(0o027));
+        assert_eq!(service.WorkingDirectory, Some("/tmp".to_string()));
+        assert!(service.DynamicUser);
+    }
+
+    #[test]
+    fn test_service_parse_stdio() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/stdio.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(service.StandardInput, ExecInput::Socket);
+        assert_eq!(
+            service.StandardOutput,
+            ExecOutput::Append(PathBuf::from("/var/log/stdio.log"))
+        );
+        assert_eq!(service.StandardError, ExecOutput::Inherit);
+    }
+
+    #[test]
+    fn test_service_parse_limits() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/limits.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(
+            service.LimitNOFILE,
+            Some(ExecRlimit::from_str("65536").unwrap())
+        );
+        assert_eq!(
+            service.LimitCORE,
+            Some(ExecRlimit::from_str("infinity").unwrap())
+        );
+        assert_eq!(
+            service.LimitMEMLOCK,
+            Some(ExecRlimit::from_str("65536:1048576").unwrap())
+        );
+        assert_eq!(
+            service.LimitCPU,
+            Some(ExecRlimit::from_str("3600").unwrap())
+        );
+        assert_eq!(
+            service.LimitRTTIME,
+            Some(ExecRlimit::from_str("500000").unwrap())
+        );
+        assert_eq!(service.LimitNPROC, None);
+        assert_eq!(service.Nice, Some(-5));
+        assert_eq!(service.CPUSchedulingPolicy, Some(CpuSchedPolicy::Batch));
+        assert_eq!(service.CPUAffinity, Some(vec![0, 2, 3]));
+        assert_eq!(service.IOSchedulingClass, Some(IoSchedClass::BestEffort));
+        assert_eq!(service.IOSchedulingPriority, Some(2));
+        assert_eq!(service.OOMScoreAdjust, Some(-500));
+    }
+
+    #[test]
+    fn test_service_parse_sandbox() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/sandbox.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert!(service.PrivateTmp);
+        assert_eq!(service.ProtectSystem, ProtectSystem::Strict);
+        assert_eq!(service.ProtectHome, ProtectHome::ReadOnly);
+        assert_eq!(
+            service.ReadWritePaths,
+            Some(vec![
+                "/var/lib/sandbox".to_string(),
+                "-/run/sandbox".to_string()
+            ])
+        );
+        assert_eq!(
+            service.InaccessiblePaths,
+            Some(vec!["/etc/ssl/private".to_string()])
+        );
+        assert_eq!(service.ReadOnlyPaths, None);
+        assert!(!service.PrivateDevices);
+        assert!(service.PrivateNetwork);
+    }
+
+    #[test]
+    fn test_service_parse_security() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/security.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        assert_eq!(
+            service.CapabilityBoundingSet,
+            Some(CapabilitySet::from_str("CAP_NET_BIND_SERVICE CAP_NET_RAW").unwrap())
+        );
+        assert_eq!(
+            service.AmbientCapabilities,
+            Some(CapabilitySet::from_str("CAP_NET_BIND_SERVICE").unwrap())
+        );
+        assert!(service.NoNewPrivileges);
+        assert_eq!(
+            service.SecureBits,
+            Some(SecureBits::from_str("noroot noroot-locked").unwrap())
+        );
+        let filter = service.SystemCallFilter.as_ref().unwrap();
+        assert!(filter.is_deny_list());
+        assert_eq!(
+            service.SystemCallArchitectures.as_ref().map(|a| a.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_service_parse_env() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/env.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        let argv = ["hello world", "%n", "$OPTS", "--name=${NAME}"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            service.ExecStart,
+            Some(vec![
+                ExecCommand::new("/bin/echo".to_string(), argv),
+                ExecCommand::new("/bin/true".to_string(), Vec::new()),
+            ])
+        );
+        assert_eq!(
+            service.EnvironmentFile,
+            Some(vec![
+                "/etc/default/env".to_string(),
+                "-/etc/sysconfig/env".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_service_parse_prefix() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/prefix.service.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(ServiceUnitComm::new());
+        let config = ServiceConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+
+        let data = config.config_data();
+        let service = &data.borrow().Service;
+        let pre = service.ExecStartPre.as_ref().unwrap();
+        assert_eq!(pre[0].flags(), ExecCommandFlags::IGNORE_FAILURE);
+        assert!(pre[0].ignore_failure());
+
+        let start = service.ExecStart.as_ref().unwrap();
+        assert_eq!(start[0].path(), "/bin/sleep");
+        assert_eq!(start[0].argv0(), "sleeper");
+        assert_eq!(start[0].argv(), vec!["1"]);
+        assert_eq!(start[0].flags(), ExecCommandFlags::empty());
+        assert_eq!(start[1].flags(), ExecCommandFlags::FULLY_PRIVILEGED);
+        assert_eq!(start[1].argv0(), "/bin/true");
+        assert_eq!(start[2].flags(), ExecCommandFlags::AMBIENT_MAGIC);
+
+        let stop = service.ExecStop.as_ref().unwrap();
+        assert_eq!(
+            stop[0].flags(),
+            ExecCommandFlags::IGNORE_FAILURE | ExecCommandFlags::NO_SETUID
+        );
+    }
 }
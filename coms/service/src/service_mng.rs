@@ -1,16 +1,19 @@
 use super::service_comm::ServiceUnitComm;
 use super::service_config::ServiceConfig;
+use super::service_monitor::ServiceMonitor;
 use super::service_pid::ServicePid;
 use super::service_rentry::{
-    NotifyState, ServiceCommand, ServiceResult, ServiceState, ServiceType,
+    NotifyAccess, NotifyState, ServiceCommand, ServiceRestart, ServiceResult, ServiceState,
+    ServiceType,
 };
 use super::service_spawn::ServiceSpawn;
 use libevent::{EventState, EventType, Events, Source};
 use libsysmaster::manager::{
-    ExecCommand, ExecContext, ExecFlags, KillOperation, UnitActionError, UnitActiveState,
-    UnitNotifyFlags,
+    ExecCmdError, ExecCommand, ExecContext, ExecFlags, KillOperation, UnitActionError,
+    UnitActiveState, UnitNotifyFlags,
 };
 use libsysmaster::ReStation;
+use libutils::time_util::USEC_PER_SEC;
 use libutils::{fd_util, Error, IN_SET};
 use libutils::{file_util, process_util};
 use nix::errno::Errno;
@@ -18,6 +21,7 @@ use nix::libc;
 use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
 use nix::sys::signal::Signal;
 use nix::sys::socket::UnixCredentials;
+use nix::time::{clock_gettime, ClockId};
 use nix::unistd::Pid;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -35,15 +39,21 @@ pub(super) struct ServiceMng {
     // associated objects
     comm: Rc<ServiceUnitComm>,
     config: Rc<ServiceConfig>,
+    monitor: Rc<ServiceMonitor>,
 
     // owned objects
     pid: Rc<ServicePid>,
     spawn: ServiceSpawn,
     state: RefCell<ServiceState>,
     result: RefCell<ServiceResult>,
+    reload_result: RefCell<ServiceResult>,
     main_command: RefCell<Vec<ExecCommand>>,
     control_cmd_type: RefCell<Option<ServiceCommand>>,
     control_command: RefCell<Vec<ExecCommand>>,
+    main_exit_status: RefCell<Option<(i32, Signal)>>,
+    forbid_restart: RefCell<bool>,
+    will_auto_restart: RefCell<bool>,
+    restarts: RefCell<u32>,
     rd: Rc<RunningData>,
 }
 
@@ -63,6 +73,8 @@ impl ReStation for ServiceMng {
             control_cmd_type,
             control_cmd_len,
             notify_state,
+            restarts,
+            fds,
         )) = self.comm.rentry_mng_get()
         {
             *self.state.borrow_mut() = state;
@@ -72,6 +84,13 @@ impl ReStation for ServiceMng {
             self.main_command_update(main_cmd_len);
             self.control_command_update(control_cmd_type, control_cmd_len);
             self.rd.set_notify_state(notify_state);
+            *self.restarts.borrow_mut() = restarts;
+            let reli = self.comm.reli();
+            self.rd.set_fd_store(
+                fds.into_iter()
+                    .map(|(fd, name)| (reli.fd_take(fd), name))
+                    .collect(),
+            );
         }
     }
 
@@ -85,6 +104,8 @@ impl ReStation for ServiceMng {
             *self.control_cmd_type.borrow(),
             self.control_command.borrow().len(),
             self.rd.notify_state(),
+            *self.restarts.borrow(),
+            self.rd.fd_store(),
         );
     }
 
@@ -95,6 +116,7 @@ impl ServiceMng {
     pub(super) fn new(
         commr: &Rc<ServiceUnitComm>,
         configr: &Rc<ServiceConfig>,
+        monitorr: &Rc<ServiceMonitor>,
         rd: &Rc<RunningData>,
         exec_ctx: &Rc<ExecContext>,
     ) -> ServiceMng {
@@ -102,13 +124,19 @@ impl ServiceMng {
         ServiceMng {
             comm: Rc::clone(commr),
             config: Rc::clone(configr),
+            monitor: Rc::clone(monitorr),
             pid: Rc::clone(&_pid),
-            spawn: ServiceSpawn::new(commr, &_pid, configr, exec_ctx),
+            spawn: ServiceSpawn::new(commr, &_pid, configr, monitorr, rd, exec_ctx),
             state: RefCell::new(ServiceState::Dead),
             result: RefCell::new(ServiceResult::Success),
+            reload_result: RefCell::new(ServiceResult::Success),
             main_command: RefCell::new(Vec::new()),
             control_cmd_type: RefCell::new(None),
             control_command: RefCell::new(Vec::new()),
+            main_exit_status: RefCell::new(None),
+            forbid_restart: RefCell::new(false),
+            will_auto_restart: RefCell::new(false),
+            restarts: RefCell::new(0),
             rd: rd.clone(),
         }
     }
@@ -149,12 +177,20 @@ impl ServiceMng {
     }
 
     pub(super) fn start_action(&self) {
+        // the restart counter is kept only when the start is triggered by the auto restart
+        if self.state() != ServiceState::AutoRestart {
+            *self.restarts.borrow_mut() = 0;
+        }
+        *self.forbid_restart.borrow_mut() = false;
+        *self.main_exit_status.borrow_mut() = None;
+        self.rd.set_status_text(String::new());
         self.set_result(ServiceResult::Success);
         self.enter_contion();
         self.db_update();
     }
 
-    pub(super) fn stop_check(&self) -> Result<(), UnitActionError> {
+    pub(super) fn stop_check(&self) -> Result<bool, UnitActionError> {
+        // service is in stopping
         if IN_SET!(
             self.state(),
             ServiceState::Stop,
@@ -164,13 +200,22 @@ impl ServiceMng {
             ServiceState::FinalSigterm,
             ServiceState::FinalSigkill
         ) {
-            return Ok(());
+            return Ok(true);
         }
 
-        Ok(())
+        Ok(false)
     }
 
     pub(super) fn stop_action(&self) {
+        // the service is stopped explicitly, do not restart it any more
+        *self.forbid_restart.borrow_mut() = true;
+        if self.state() == ServiceState::AutoRestart {
+            self.set_state(ServiceState::Dead);
+            self.release_fd_store();
+            self.db_update();
+            return;
+        }
+
         let starting_state = vec![
             ServiceState::Condition,
             ServiceState::StartPre,
@@ -190,6 +235,7 @@ impl ServiceMng {
     }
 
     pub(super) fn reload_action(&self) {
+        *self.reload_result.borrow_mut() = ServiceResult::Success;
         self.enter_reload();
         self.db_update();
     }
@@ -198,6 +244,45 @@ impl ServiceMng {
         service_state_to_unit_state(self.config.service_type(), self.state())
     }
 
+    pub(super) fn get_state(&self) -> String {
+        self.state().to_string()
+    }
+
+    pub(super) fn get_result(&self) -> String {
+        self.result().to_string()
+    }
+
+    pub(super) fn main_pid(&self) -> Option<Pid> {
+        self.pid.main()
+    }
+
+    pub(super) fn main_exit_status(&self) -> Option<(i32, Signal)> {
+        *self.main_exit_status.borrow()
+    }
+
+    pub(super) fn status_text(&self) -> String {
+        self.rd.status_text()
+    }
+
+    pub(super) fn entry_coldplug(&self) {
+        if self.state() == ServiceState::AutoRestart {
+            if let Err(e) = self.enable_timer(self.restart_usec()) {
+                log::error!("failed to rearm the restart timer: {}", e);
+            }
+        } else {
+            self.arm_state_timer(self.state());
+        }
+
+        if self.service_alive_state() {
+            self.start_watchdog();
+        }
+    }
+
+    pub(super) fn entry_clear(&self) {
+        self.disable_timer();
+        self.stop_watchdog();
+    }
+
     fn enter_contion(&self) {
         log::debug!("enter running service condition command");
         self.control_command_fill(ServiceCommand::Condition);
@@ -205,8 +290,8 @@ impl ServiceMng {
             Some(cmd) => {
                 match self.spawn.start_service(&cmd, 0, ExecFlags::CONTROL) {
                     Ok(pid) => self.pid.set_control(pid),
-                    Err(_e) => {
-                        self.enter_dead(ServiceResult::FailureResources);
+                    Err(e) => {
+                        self.enter_dead(exec_failure_result(&e));
                         return;
                     }
                 }
@@ -226,8 +311,8 @@ impl ServiceMng {
             Some(cmd) => {
                 match self.spawn.start_service(&cmd, 0, ExecFlags::CONTROL) {
                     Ok(pid) => self.pid.set_control(pid),
-                    Err(_e) => {
-                        self.enter_dead(ServiceResult::FailureResources);
+                    Err(e) => {
+                        self.enter_dead(exec_failure_result(&e));
                         return;
                     }
                 }
@@ -269,12 +354,14 @@ impl ServiceMng {
             .spawn
             .start_service(&cmd.unwrap(), 0, ExecFlags::PASS_FDS);
 
-        if ret.is_err() {
-            log::error!("failed to start service: {}", self.comm.unit().id());
-            self.enter_signal(ServiceState::StopSigterm, ServiceResult::FailureResources);
-        }
-
-        let pid = ret.unwrap();
+        let pid = match ret {
+            Ok(pid) => pid,
+            Err(e) => {
+                log::error!("failed to start service: {}: {}", self.comm.unit().id(), e);
+                self.enter_signal(ServiceState::StopSigterm, exec_failure_result(&e));
+                return;
+            }
+        };
         log::debug!("service type is: {}, forking pid is: {}", service_type, pid);
 
         match service_type {
@@ -301,6 +388,7 @@ impl ServiceMng {
 
     fn enter_start_post(&self) {
         log::debug!("enter running service startpost command");
+        self.start_watchdog();
         self.pid.unwatch_control();
         self.control_command_fill(ServiceCommand::StartPost);
         match self.control_command_pop() {
@@ -353,12 +441,9 @@ impl ServiceMng {
             Some(cmd) => {
                 match self.spawn.start_service(&cmd, 0, ExecFlags::CONTROL) {
                     Ok(pid) => self.pid.set_control(pid),
-                    Err(_e) => {
+                    Err(e) => {
                         log::error!("Failed to run stop service: {}", self.comm.unit().id());
-                        self.enter_signal(
-                            ServiceState::StopSigterm,
-                            ServiceResult::FailureResources,
-                        );
+                        self.enter_signal(ServiceState::StopSigterm, exec_failure_result(&e));
                         return;
                     }
                 }
@@ -385,11 +470,8 @@ impl ServiceMng {
             Some(cmd) => {
                 match self.spawn.start_service(&cmd, 0, ExecFlags::CONTROL) {
                     Ok(pid) => self.pid.set_control(pid),
-                    Err(_e) => {
-                        self.enter_signal(
-                            ServiceState::FinalSigterm,
-                            ServiceResult::FailureResources,
-                        );
+                    Err(e) => {
+                        self.enter_signal(ServiceState::FinalSigterm, exec_failure_result(&e));
                         log::error!("Failed to run stop service: {}", self.comm.unit().id());
                         return;
                     }
@@ -406,13 +488,166 @@ impl ServiceMng {
             self.set_result(res);
         }
 
-        let state = if self.result() == ServiceResult::Success {
+        let state = if IN_SET!(
+            self.result(),
+            ServiceResult::Success,
+            ServiceResult::SkipCondition
+        ) {
             ServiceState::Dead
         } else {
             ServiceState::Failed
         };
 
+        let restart = self.shall_restart();
+        *self.will_auto_restart.borrow_mut() = restart;
         self.set_state(state);
+        *self.will_auto_restart.borrow_mut() = false;
+
+        if !restart {
+            // the stored fds are kept for the restart, until the service is stopped explicitly
+            if self.comm.um().has_stop_job(self.comm.unit().id()) {
+                self.release_fd_store();
+            }
+            return;
+        }
+
+        let usec = self.restart_usec();
+        if let Err(e) = self.enable_timer(usec) {
+            log::error!(
+                "failed to arm the restart timer of {}: {}",
+                self.comm.unit().id(),
+                e
+            );
+            return;
+        }
+        log::debug!(
+            "service {} will be restarted in {}us",
+            self.comm.unit().id(),
+            usec
+        );
+        self.set_state(ServiceState::AutoRestart);
+    }
+
+    fn enter_restart(&self) {
+        *self.restarts.borrow_mut() += 1;
+        log::info!(
+            "restarting service {}, restart counter is at {}",
+            self.comm.unit().id(),
+            *self.restarts.borrow()
+        );
+
+        if let Err(e) = self.comm.um().start_unit(self.comm.unit().id()) {
+            log::error!(
+                "failed to schedule the restart job of {}: {:?}",
+                self.comm.unit().id(),
+                e
+            );
+            *self.forbid_restart.borrow_mut() = true;
+            self.enter_dead(ServiceResult::FailureResources);
+        }
+    }
+
+    fn shall_restart(&self) -> bool {
+        if *self.forbid_restart.borrow() {
+            return false;
+        }
+
+        let result = self.result();
+        if IN_SET!(
+            result,
+            ServiceResult::FailureStartLimitHit,
+            ServiceResult::SkipCondition
+        ) {
+            return false;
+        }
+
+        let config = self.config.config_data();
+        let service = &config.borrow().Service;
+        if let (Some(prevent), Some((code, signal))) = (
+            service.RestartPreventExitStatus.as_ref(),
+            *self.main_exit_status.borrow(),
+        ) {
+            if prevent.contains(code, signal) {
+                return false;
+            }
+        }
+
+        match service.Restart {
+            ServiceRestart::No => false,
+            ServiceRestart::OnSuccess => result == ServiceResult::Success,
+            ServiceRestart::OnFailure => result != ServiceResult::Success,
+            ServiceRestart::OnAbnormal => IN_SET!(
+                result,
+                ServiceResult::FailureSignal,
+                ServiceResult::FailureTimeout,
+                ServiceResult::FailureWatchdog
+            ),
+            ServiceRestart::OnWatchdog => result == ServiceResult::FailureWatchdog,
+            ServiceRestart::OnAbort => result == ServiceResult::FailureSignal,
+            ServiceRestart::Always => true,
+        }
+    }
+
+    fn restart_usec(&self) -> u64 {
+        let restart_sec = self.config.config_data().borrow().Service.RestartSec;
+        restart_sec.unwrap_or_else(|| self.comm.um().default_restart_usec())
+    }
+
+    fn timeout_start_usec(&self) -> u64 {
+        let config = self.config.config_data();
+        let service = &config.borrow().Service;
+        let usec = match service.TimeoutStartSec.or(service.TimeoutSec) {
+            Some(usec) => usec,
+            // oneshot service may run for a long time, no timeout unless configured explicitly.
+            None if service.Type == ServiceType::Oneshot => 0,
+            None => self.comm.um().default_timeout_usec(),
+        };
+
+        timeout_to_usec(usec)
+    }
+
+    fn timeout_stop_usec(&self) -> u64 {
+        let config = self.config.config_data();
+        let service = &config.borrow().Service;
+        let usec = service
+            .TimeoutStopSec
+            .or(service.TimeoutSec)
+            .unwrap_or_else(|| self.comm.um().default_timeout_usec());
+
+        timeout_to_usec(usec)
+    }
+
+    fn arm_state_timer(&self, state: ServiceState) {
+        let usec = match state {
+            ServiceState::Condition
+            | ServiceState::StartPre
+            | ServiceState::Start
+            | ServiceState::StartPost
+            | ServiceState::Reload => self.timeout_start_usec(),
+            ServiceState::Stop
+            | ServiceState::StopWatchdog
+            | ServiceState::StopSigterm
+            | ServiceState::StopSigkill
+            | ServiceState::StopPost
+            | ServiceState::FinalWatchdog
+            | ServiceState::FinalSigterm
+            | ServiceState::FinalSigkill => self.timeout_stop_usec(),
+            // the restart timer is armed when entering the state
+            ServiceState::AutoRestart => return,
+            _ => {
+                self.disable_timer();
+                return;
+            }
+        };
+
+        if let Err(e) = self.enable_timer(usec) {
+            log::error!(
+                "failed to arm the timer of {} in state {:?}: {}",
+                self.comm.unit().id(),
+                state,
+                e
+            );
+        }
     }
 
     fn enter_reload(&self) {
@@ -424,8 +659,9 @@ impl ServiceMng {
             Some(cmd) => {
                 match self.spawn.start_service(&cmd, 0, ExecFlags::CONTROL) {
                     Ok(pid) => self.pid.set_control(pid),
-                    Err(_e) => {
+                    Err(e) => {
                         log::error!("failed to start service: {}", self.comm.unit().id());
+                        *self.reload_result.borrow_mut() = exec_failure_result(&e);
                         self.enter_running(ServiceResult::Success);
                         return;
                     }
@@ -442,6 +678,9 @@ impl ServiceMng {
             state,
             res
         );
+        if self.result() == ServiceResult::Success {
+            self.set_result(res);
+        }
 
         self.comm.um().child_watch_all_pids(self.comm.unit().id());
 
@@ -467,6 +706,12 @@ impl ServiceMng {
             }
         }
 
+        // wait for the exit of the processes, or the timeout of the stop operation.
+        if self.pid.main().is_some() || self.pid.control().is_some() {
+            self.set_state(state);
+            return;
+        }
+
         if vec![
             ServiceState::StopWatchdog,
             ServiceState::StopSigterm,
@@ -486,6 +731,14 @@ impl ServiceMng {
         let original_state = self.state();
         *self.state.borrow_mut() = state;
 
+        if state != original_state {
+            self.arm_state_timer(state);
+        }
+
+        if !self.service_alive_state() {
+            self.stop_watchdog();
+        }
+
         // TODO
         // check the new state
         if !vec![
@@ -541,9 +794,14 @@ impl ServiceMng {
 
         let os = service_state_to_unit_state(self.config.service_type(), original_state);
         let ns = service_state_to_unit_state(self.config.service_type(), state);
-        self.comm
-            .unit()
-            .notify(os, ns, UnitNotifyFlags::UNIT_NOTIFY_RELOAD_FAILURE);
+        let mut flags = UnitNotifyFlags::empty();
+        if *self.reload_result.borrow() != ServiceResult::Success {
+            flags |= UnitNotifyFlags::UNIT_NOTIFY_RELOAD_FAILURE;
+        }
+        if *self.will_auto_restart.borrow() {
+            flags |= UnitNotifyFlags::UNIT_NOTIFY_WILL_AUTO_RESTART;
+        }
+        self.comm.unit().notify(os, ns, flags);
     }
 
     fn service_alive(&self) -> bool {
@@ -610,6 +868,7 @@ impl ServiceMng {
     }
 
     fn control_command_fill(&self, cmd_type: ServiceCommand) {
+        *self.control_cmd_type.borrow_mut() = Some(cmd_type);
         if let Some(cmds) = self.config.get_exec_cmds(cmd_type) {
             *self.control_command.borrow_mut() = cmds
         }
@@ -619,6 +878,27 @@ impl ServiceMng {
         self.control_command.borrow_mut().pop()
     }
 
+    // the running command is the last popped one, whose failure is ignored with the "-" prefix
+    fn main_command_ignore_failure(&self) -> bool {
+        let len = self.main_command.borrow().len();
+        self.config
+            .get_exec_cmds(ServiceCommand::Start)
+            .and_then(|cmds| cmds.get(len).map(|cmd| cmd.ignore_failure()))
+            .unwrap_or(false)
+    }
+
+    fn control_command_ignore_failure(&self) -> bool {
+        let cmd_type = match *self.control_cmd_type.borrow() {
+            Some(cmd_type) => cmd_type,
+            None => return false,
+        };
+        let len = self.control_command.borrow().len();
+        self.config
+            .get_exec_cmds(cmd_type)
+            .and_then(|cmds| cmds.get(len).map(|cmd| cmd.ignore_failure()))
+            .unwrap_or(false)
+    }
+
     fn control_command_update(&self, cmd_type: Option<ServiceCommand>, len: usize) {
         if let Some(c_type) = cmd_type {
             self.control_command.borrow_mut().clear();
@@ -796,6 +1076,127 @@ impl ServiceMng {
 
         false
     }
+
+    fn enable_timer(&self, usec: u64) -> Result<i32, Error> {
+        let events = self.comm.um().events();
+        let timer = self.rd.timer();
+        if usec == u64::MAX {
+            timer.disarm();
+            events.set_enabled(timer, EventState::Off)?;
+            return Ok(0);
+        }
+
+        timer.set_time(usec);
+        events.add_source(timer.clone())?;
+        events.set_enabled(timer, EventState::On)?;
+        Ok(0)
+    }
+
+    fn disable_timer(&self) {
+        let events = self.comm.um().events();
+        self.rd.timer().disarm();
+        if let Err(e) = events.set_enabled(self.rd.timer(), EventState::Off) {
+            log::debug!("failed to disable the service timer: {}", e);
+        }
+    }
+
+    /// arm the watchdog with the full timeout, it is armed again by every WATCHDOG=1
+    fn start_watchdog(&self) {
+        let usec = self.monitor.watchdog_usec();
+        if usec == 0 {
+            self.stop_watchdog();
+            return;
+        }
+
+        let events = self.comm.um().events();
+        let watchdog = self.rd.watchdog();
+        watchdog.set_time(usec);
+        if let Err(e) = events
+            .add_source(watchdog.clone())
+            .and_then(|_| events.set_enabled(watchdog, EventState::On))
+        {
+            log::error!(
+                "failed to arm the watchdog of {}: {}",
+                self.comm.unit().id(),
+                e
+            );
+        }
+    }
+
+    fn stop_watchdog(&self) {
+        let events = self.comm.um().events();
+        if let Err(e) = events.set_enabled(self.rd.watchdog(), EventState::Off) {
+            log::debug!("failed to disable the service watchdog: {}", e);
+        }
+    }
+
+    fn dispatch_watchdog(&self) {
+        log::error!(
+            "service {} watchdog timeout (limit {}us), terminating",
+            self.comm.unit().id(),
+            self.monitor.watchdog_usec()
+        );
+        self.enter_signal(ServiceState::StopWatchdog, ServiceResult::FailureWatchdog);
+    }
+
+    fn dispatch_timer(&self) {
+        log::debug!(
+            "service {} timer expired in state {:?}",
+            self.comm.unit().id(),
+            self.state()
+        );
+        let id = self.comm.unit().id().to_string();
+        match self.state() {
+            ServiceState::Condition | ServiceState::StartPre | ServiceState::Start => {
+                log::warn!("{} start operation timed out, terminating", id);
+                self.enter_signal(ServiceState::StopSigterm, ServiceResult::FailureTimeout);
+            }
+            ServiceState::StartPost => {
+                log::warn!("{} start-post operation timed out, stopping", id);
+                self.enter_stop(ServiceResult::FailureTimeout);
+            }
+            ServiceState::Reload => {
+                log::warn!("{} reload operation timed out, killing reload process", id);
+                if let Some(pid) = self.pid.control() {
+                    if let Err(e) = nix::sys::signal::kill(pid, Signal::SIGKILL) {
+                        log::debug!("failed to kill the reload process {}: {}", pid, e);
+                    }
+                }
+                // the service keeps running, only the reload job fails
+                *self.reload_result.borrow_mut() = ServiceResult::FailureTimeout;
+                self.enter_running(ServiceResult::Success);
+            }
+            ServiceState::Stop => {
+                log::warn!("{} stopping timed out, terminating", id);
+                self.enter_signal(ServiceState::StopSigterm, ServiceResult::FailureTimeout);
+            }
+            ServiceState::StopWatchdog | ServiceState::StopSigterm => {
+                log::warn!("{} stop-sigterm timed out, killing", id);
+                self.enter_signal(ServiceState::StopSigkill, ServiceResult::FailureTimeout);
+            }
+            ServiceState::StopSigkill => {
+                log::warn!("{} processes still around after SIGKILL, ignoring", id);
+                self.enter_stop_post(ServiceResult::FailureTimeout);
+            }
+            ServiceState::StopPost => {
+                log::warn!("{} stop-post timed out, terminating", id);
+                self.enter_signal(ServiceState::FinalSigterm, ServiceResult::FailureTimeout);
+            }
+            ServiceState::FinalWatchdog | ServiceState::FinalSigterm => {
+                log::warn!("{} stop-final-sigterm timed out, killing", id);
+                self.enter_signal(ServiceState::FinalSigkill, ServiceResult::FailureTimeout);
+            }
+            ServiceState::FinalSigkill => {
+                log::warn!(
+                    "{} processes still around after final SIGKILL, entering failed",
+                    id
+                );
+                self.enter_dead(ServiceResult::FailureTimeout);
+            }
+            ServiceState::AutoRestart => self.enter_restart(),
+            _ => {}
+        }
+    }
 }
 
 impl ServiceMng {
@@ -817,16 +1218,51 @@ impl ServiceMng {
             self.pid.control(),
             self.state()
         );
-        let res: ServiceResult;
+        let mut res: ServiceResult;
         if code == 0 {
             res = ServiceResult::Success;
         } else if status != Signal::SIGCHLD {
             res = ServiceResult::FailureSignal;
         } else {
-            res = ServiceResult::Success
+            res = ServiceResult::FailureExitCode;
         }
 
         if self.pid.main() == Some(pid) {
+            *self.main_exit_status.borrow_mut() = Some((code, status));
+            // the daemon terminated by these signals is considered as clean exit
+            if res == ServiceResult::FailureSignal
+                && self.config.service_type() != ServiceType::Oneshot
+                && IN_SET!(
+                    status,
+                    Signal::SIGHUP,
+                    Signal::SIGINT,
+                    Signal::SIGTERM,
+                    Signal::SIGPIPE
+                )
+            {
+                res = ServiceResult::Success;
+            }
+            if let Some(success) = self
+                .config
+                .config_data()
+                .borrow()
+                .Service
+                .SuccessExitStatus
+                .as_ref()
+            {
+                if success.contains(code, status) {
+                    res = ServiceResult::Success;
+                }
+            }
+
+            if res != ServiceResult::Success && self.main_command_ignore_failure() {
+                log::debug!(
+                    "ignore the failure of the main command of {}",
+                    self.comm.unit().id()
+                );
+                res = ServiceResult::Success;
+            }
+
             // for main pid updated by the process before its exited, updated the main pid.
             if let Ok(v) = self.load_pid_file() {
                 if v {
@@ -878,10 +1314,16 @@ impl ServiceMng {
                     ServiceState::Stop => {}
                     ServiceState::StopWatchdog
                     | ServiceState::StopSigkill
-                    | ServiceState::StopSigterm => {
+                    | ServiceState::StopSigterm
+                        if self.pid.control().is_none() =>
+                    {
                         self.enter_stop_post(res);
                     }
-                    ServiceState::FinalSigterm | ServiceState::FinalSigkill => {
+                    ServiceState::FinalWatchdog
+                    | ServiceState::FinalSigterm
+                    | ServiceState::FinalSigkill
+                        if self.pid.control().is_none() =>
+                    {
                         self.enter_dead(res);
                     }
                     _ => {}
@@ -890,6 +1332,14 @@ impl ServiceMng {
         } else if self.pid.control() == Some(pid) {
             self.pid.reset_control();
 
+            if res != ServiceResult::Success && self.control_command_ignore_failure() {
+                log::debug!(
+                    "ignore the failure of the control command of {}",
+                    self.comm.unit().id()
+                );
+                res = ServiceResult::Success;
+            }
+
             if !self.control_command.borrow().is_empty() && res == ServiceResult::Success {
                 self.run_next_control();
                 return;
@@ -900,6 +1350,10 @@ impl ServiceMng {
                 ServiceState::Condition => {
                     if res == ServiceResult::Success {
                         self.enter_prestart();
+                    } else if res == ServiceResult::FailureExitCode && code < 255 {
+                        // exit code 1 ~ 254 means the condition is not met, skip the service
+                        log::info!("condition of {} is not met, skip", self.comm.unit().id());
+                        self.enter_signal(ServiceState::StopSigterm, ServiceResult::SkipCondition);
                     } else {
                         self.enter_signal(ServiceState::StopSigterm, res);
                     }
@@ -999,13 +1453,19 @@ impl ServiceMng {
                 }
                 ServiceState::StopSigterm
                 | ServiceState::StopSigkill
-                | ServiceState::StopWatchdog => {
+                | ServiceState::StopWatchdog
+                    if self.pid.main().is_none() =>
+                {
                     self.enter_stop_post(res);
                 }
                 ServiceState::StopPost => {
                     self.enter_signal(ServiceState::FinalSigterm, res);
                 }
-                ServiceState::FinalSigterm | ServiceState::FinalSigkill => {
+                ServiceState::FinalWatchdog
+                | ServiceState::FinalSigterm
+                | ServiceState::FinalSigkill
+                    if self.pid.main().is_none() =>
+                {
                     self.enter_dead(res);
                 }
                 _ => {}
@@ -1019,9 +1479,9 @@ impl ServiceMng {
         &self,
         ucred: &UnixCredentials,
         messages: &HashMap<&str, &str>,
-        _fds: Vec<i32>,
+        fds: Vec<i32>,
     ) -> Result<(), Error> {
-        let ret = self.do_notify_message(ucred, messages, _fds);
+        let ret = self.do_notify_message(ucred, messages, fds);
         self.db_update();
         ret
     }
@@ -1030,8 +1490,28 @@ impl ServiceMng {
         &self,
         ucred: &UnixCredentials,
         messages: &HashMap<&str, &str>,
-        _fds: Vec<i32>,
+        fds: Vec<i32>,
     ) -> Result<(), Error> {
+        if !self.notify_access_allowed(Pid::from_raw(ucred.pid())) {
+            close_fds(&fds);
+            return Ok(());
+        }
+
+        // the fds are stored or closed here, the message may be invalid in the following
+        if messages.get("FDSTORE") == Some(&"1") {
+            let name = messages.get("FDNAME").copied().unwrap_or("stored");
+            self.add_fd_store(fds, name);
+        } else {
+            close_fds(&fds);
+        }
+
+        if messages.get("FDSTOREREMOVE") == Some(&"1") {
+            match messages.get("FDNAME") {
+                Some(name) => self.remove_fd_store(name),
+                None => log::warn!("FDSTOREREMOVE=1 is sent without FDNAME=, ignoring"),
+            }
+        }
+
         if let Some(&pidr) = messages.get("MAINPID") {
             if IN_SET!(
                 self.state(),
@@ -1057,14 +1537,63 @@ impl ServiceMng {
         for (&key, &value) in messages {
             if key == "READY" && value == "1" {
                 log::debug!("service plugin get READY=1");
+                let reloading = self.rd.notify_state() == NotifyState::Reloading;
                 self.rd.set_notify_state(NotifyState::Ready);
                 if self.config.service_type() == ServiceType::Notify
                     && self.state() == ServiceState::Start
                 {
                     self.enter_start_post();
+                } else if reloading
+                    && self.state() == ServiceState::Reload
+                    && self.pid.control().is_none()
+                {
+                    // the reload started by RELOADING=1 is finished
+                    self.enter_running(ServiceResult::Success);
+                }
+            }
+
+            if key == "RELOADING" && value == "1" {
+                self.rd.set_notify_state(NotifyState::Reloading);
+                if self.state() == ServiceState::Running {
+                    self.set_state(ServiceState::Reload);
                 }
             }
 
+            if key == "STATUS" {
+                self.rd.set_status_text(value.to_string());
+            }
+
+            if key == "EXTEND_TIMEOUT_USEC" {
+                match value.parse::<u64>() {
+                    Ok(usec) => self.extend_timeout(usec),
+                    Err(_) => log::warn!("parse EXTEND_TIMEOUT_USEC failed in received messages"),
+                }
+            }
+
+            if key == "WATCHDOG" && value == "1" && self.service_alive_state() {
+                self.start_watchdog();
+            }
+
+            if key == "WATCHDOG_USEC" {
+                match value.parse::<u64>() {
+                    Ok(usec) => {
+                        self.monitor.override_watchdog_usec(usec);
+                        if self.service_alive_state() {
+                            self.start_watchdog();
+                        }
+                    }
+                    Err(_) => log::warn!("parse WATCHDOG_USEC failed in received messages"),
+                }
+            }
+
+            if key == "WATCHDOG" && value == "trigger" && self.service_alive_state() {
+                log::warn!(
+                    "service {} triggered the watchdog, terminating",
+                    self.comm.unit().id()
+                );
+                self.enter_signal(ServiceState::StopWatchdog, ServiceResult::FailureWatchdog);
+            }
+
             if key == "STOPPING" && value == "1" {
                 self.rd.set_notify_state(NotifyState::Stopping);
                 if self.state() == ServiceState::Running {
@@ -1085,6 +1614,135 @@ impl ServiceMng {
 
         Ok(())
     }
+
+    fn notify_access_allowed(&self, pid: Pid) -> bool {
+        let access = self
+            .config
+            .config_data()
+            .borrow()
+            .Service
+            .NotifyAccess
+            .unwrap_or(NotifyAccess::None);
+        let allowed = match access {
+            NotifyAccess::None => false,
+            NotifyAccess::Main => self.pid.main() == Some(pid),
+            NotifyAccess::Exec => self.pid.main() == Some(pid) || self.pid.control() == Some(pid),
+            NotifyAccess::All => true,
+        };
+
+        if !allowed {
+            log::warn!(
+                "notify message of {} from {} is rejected by NotifyAccess={}",
+                self.comm.unit().id(),
+                pid,
+                access
+            );
+        }
+        allowed
+    }
+
+    fn service_alive_state(&self) -> bool {
+        IN_SET!(
+            self.state(),
+            ServiceState::Start,
+            ServiceState::StartPost,
+            ServiceState::Running,
+            ServiceState::Reload
+        )
+    }
+
+    /// give the current operation at least usec from now, the timeout is never shortened
+    fn extend_timeout(&self, usec: u64) {
+        if !IN_SET!(
+            self.state(),
+            ServiceState::Condition,
+            ServiceState::StartPre,
+            ServiceState::Start,
+            ServiceState::StartPost,
+            ServiceState::Reload,
+            ServiceState::Stop,
+            ServiceState::StopSigterm,
+            ServiceState::StopSigkill,
+            ServiceState::StopPost,
+            ServiceState::FinalSigterm,
+            ServiceState::FinalSigkill
+        ) {
+            return;
+        }
+
+        let deadline = self.rd.timer().deadline();
+        if deadline == u64::MAX || now_monotonic().saturating_add(usec) <= deadline {
+            return;
+        }
+
+        log::debug!(
+            "service {} extends the timeout of state {:?} by {}us",
+            self.comm.unit().id(),
+            self.state(),
+            usec
+        );
+        if let Err(e) = self.enable_timer(usec) {
+            log::error!(
+                "failed to extend the timer of {}: {}",
+                self.comm.unit().id(),
+                e
+            );
+        }
+    }
+
+    fn add_fd_store(&self, fds: Vec<i32>, name: &str) {
+        let max = self
+            .config
+            .config_data()
+            .borrow()
+            .Service
+            .FileDescriptorStoreMax as usize;
+        let reli = self.comm.reli();
+        for fd in fds {
+            if self.rd.fd_store_len() >= max {
+                log::warn!(
+                    "the fd store of {} is full with FileDescriptorStoreMax={}, closing fd {}",
+                    self.comm.unit().id(),
+                    max,
+                    fd
+                );
+                fd_util::close(fd);
+                continue;
+            }
+
+            // the stored fds are kept open across the reexec
+            if let Err(e) = reli.fd_cloexec(fd, false) {
+                log::error!(
+                    "failed to store fd {} of {}: {}",
+                    fd,
+                    self.comm.unit().id(),
+                    e
+                );
+                fd_util::close(fd);
+                continue;
+            }
+
+            log::debug!("store fd {} of {} as {}", fd, self.comm.unit().id(), name);
+            self.rd.push_fd_store(fd, name.to_string());
+        }
+    }
+
+    fn remove_fd_store(&self, name: &str) {
+        let reli = self.comm.reli();
+        for fd in self.rd.take_fd_store(|n| n == name) {
+            log::debug!("remove stored fd {} of {}", fd, self.comm.unit().id());
+            let _ = reli.fd_cloexec(fd, true);
+            fd_util::close(fd);
+        }
+    }
+
+    fn release_fd_store(&self) {
+        let reli = self.comm.reli();
+        for fd in self.rd.take_fd_store(|_| true) {
+            let _ = reli.fd_cloexec(fd, true);
+            fd_util::close(fd);
+        }
+    }
 }
 
 impl ServiceState {
@@ -1106,6 +1764,7 @@ impl ServiceState {
             | ServiceState::FinalSigkill
             | ServiceState::FinalWatchdog => UnitActiveState::UnitDeActivating,
             ServiceState::Failed => UnitActiveState::UnitFailed,
+            ServiceState::AutoRestart => UnitActiveState::UnitActivating,
             ServiceState::Cleaning => UnitActiveState::UnitMaintenance,
         }
     }
@@ -1129,6 +1788,7 @@ impl ServiceState {
             | ServiceState::FinalSigkill
             | ServiceState::FinalWatchdog => UnitActiveState::UnitDeActivating,
             ServiceState::Failed => UnitActiveState::UnitFailed,
+            ServiceState::AutoRestart => UnitActiveState::UnitActivating,
             ServiceState::Cleaning => UnitActiveState::UnitMaintenance,
         }
     }
@@ -1143,6 +1803,36 @@ impl ServiceState {
     }
 }
 
+fn now_monotonic() -> u64 {
+    match clock_gettime(ClockId::CLOCK_MONOTONIC) {
+        Ok(ts) => ts.tv_sec() as u64 * USEC_PER_SEC + ts.tv_nsec() as u64 / 1000,
+        Err(_) => 0,
+    }
+}
+
+fn close_fds(fds: &[i32]) {
+    for fd in fds {
+        fd_util::close(*fd);
+    }
+}
+
+/// the timeout 0 means no timeout
+fn timeout_to_usec(usec: u64) -> u64 {
+    if usec == 0 {
+        u64::MAX
+    } else {
+        usec
+    }
+}
+
+/// the failure in the child before executing the command is distinguished from the lack of resources
+fn exec_failure_result(e: &ExecCmdError) -> ServiceResult {
+    match e {
+        ExecCmdError::ExecFailed(..) => ServiceResult::FailureExec,
+        _ => ServiceResult::FailureResources,
+    }
+}
+
 fn service_state_to_unit_state(service_type: ServiceType, state: ServiceState) -> UnitActiveState {
     if service_type == ServiceType::Idle {
         return state.to_unit_active_state_idle();
@@ -1166,6 +1856,16 @@ impl RunningData {
 
     pub(super) fn attach_mng(&self, mng: Rc<ServiceMng>) {
         *self.mng.borrow_mut() = Rc::downgrade(&mng);
+        self.data.borrow().timer().attach(Rc::downgrade(&mng));
+        self.data.borrow().watchdog.attach(Rc::downgrade(&mng));
+    }
+
+    pub(self) fn timer(&self) -> Rc<ServiceTimer> {
+        self.data.borrow().timer()
+    }
+
+    pub(self) fn watchdog(&self) -> Rc<WatchdogTimer> {
+        self.data.borrow().watchdog.clone()
     }
 
     pub(self) fn attach_inotify(&self, path_inotify: Rc<PathIntofy>) {
@@ -1188,12 +1888,54 @@ impl RunningData {
     pub(self) fn notify_state(&self) -> NotifyState {
         self.data.borrow().notify_state()
     }
+
+    pub(self) fn set_status_text(&self, text: String) {
+        self.data.borrow_mut().status_text = text;
+    }
+
+    pub(self) fn status_text(&self) -> String {
+        self.data.borrow().status_text.clone()
+    }
+
+    pub(self) fn set_fd_store(&self, fds: Vec<(RawFd, String)>) {
+        self.data.borrow_mut().fd_store = fds;
+    }
+
+    pub(self) fn push_fd_store(&self, fd: RawFd, name: String) {
+        self.data.borrow_mut().fd_store.push((fd, name));
+    }
+
+    pub(self) fn fd_store_len(&self) -> usize {
+        self.data.borrow().fd_store.len()
+    }
+
+    /// remove the stored fds whose names match, and return them
+    pub(self) fn take_fd_store<F: Fn(&str) -> bool>(&self, matches: F) -> Vec<RawFd> {
+        let mut data = self.data.borrow_mut();
+        let (taken, kept) = data
+            .fd_store
+            .drain(..)
+            .partition::<Vec<_>, _>(|(_, name)| matches(name));
+        data.fd_store = kept;
+        taken.into_iter().map(|(fd, _)| fd).collect()
+    }
+
+    /// the stored fds with their names, which are passed to the main process
+    pub(super) fn fd_store(&self) -> Vec<(RawFd, String)> {
+        self.data.borrow().fd_store.clone()
+    }
 }
 
 struct Rtdata {
     errno: i32,
     notify_state: NotifyState,
+    // the status text sent with STATUS=
+    status_text: String,
+    // the fds stored with FDSTORE=1, with their names
+    fd_store: Vec<(RawFd, String)>,
     path_inotify: Option<Rc<PathIntofy>>,
+    timer: Rc<ServiceTimer>,
+    watchdog: Rc<WatchdogTimer>,
 }
 
 impl Rtdata {
@@ -1201,10 +1943,18 @@ impl Rtdata {
         Rtdata {
             errno: 0,
             notify_state: NotifyState::Unknown,
+            status_text: String::new(),
+            fd_store: Vec::new(),
             path_inotify: None,
+            timer: Rc::new(ServiceTimer::new()),
+            watchdog: Rc::new(WatchdogTimer::new()),
         }
     }
 
+    pub(self) fn timer(&self) -> Rc<ServiceTimer> {
+        self.timer.clone()
+    }
+
     pub(self) fn set_notify_state(&mut self, notify_state: NotifyState) {
         self.notify_state = notify_state;
     }
@@ -1427,3 +2177,127 @@ impl Source for PathIntofy {
         data
     }
 }
+
+struct ServiceTimer {
+    usec: RefCell<u64>,
+    // the monotonic time when the timer expires, u64::MAX if it is not armed
+    deadline: RefCell<u64>,
+    mng: RefCell<Weak<ServiceMng>>,
+}
+
+impl ServiceTimer {
+    fn new() -> Self {
+        ServiceTimer {
+            usec: RefCell::new(0),
+            deadline: RefCell::new(u64::MAX),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(self) fn attach(&self, mng: Weak<ServiceMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    pub(self) fn set_time(&self, usec: u64) {
+        *self.usec.borrow_mut() = usec;
+        *self.deadline.borrow_mut() = now_monotonic().saturating_add(usec);
+    }
+
+    pub(self) fn disarm(&self) {
+        *self.deadline.borrow_mut() = u64::MAX;
+    }
+
+    pub(self) fn deadline(&self) -> u64 {
+        *self.deadline.borrow()
+    }
+
+    pub(self) fn mng(&self) -> Rc<ServiceMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for ServiceTimer {
+    fn event_type(&self) -> EventType {
+        EventType::TimerMonotonic
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn time_relative(&self) -> u64 {
+        *self.usec.borrow()
+    }
+
+    fn dispatch(&self, _: &Events) -> Result<i32, Error> {
+        self.mng().dispatch_timer();
+        self.mng().db_update();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+/// the software watchdog of the service, the service is terminated if it is not reset by
+/// WATCHDOG=1 within WatchdogUSec
+struct WatchdogTimer {
+    usec: RefCell<u64>,
+    mng: RefCell<Weak<ServiceMng>>,
+}
+
+impl WatchdogTimer {
+    fn new() -> Self {
+        WatchdogTimer {
+            usec: RefCell::new(0),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(self) fn attach(&self, mng: Weak<ServiceMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    pub(self) fn set_time(&self, usec: u64) {
+        *self.usec.borrow_mut() = usec;
+    }
+
+    pub(self) fn mng(&self) -> Rc<ServiceMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for WatchdogTimer {
+    fn event_type(&self) -> EventType {
+        EventType::TimerMonotonic
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn time_relative(&self) -> u64 {
+        *self.usec.borrow()
+    }
+
+    fn dispatch(&self, _: &Events) -> Result<i32, Error> {
+        self.mng().dispatch_watchdog();
+        self.mng().db_update();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
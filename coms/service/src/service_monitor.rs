@@ -16,6 +16,16 @@ impl ServiceMonitor {
     pub(super) fn start_action(&self) {
         self.data.borrow_mut().start_action()
     }
+
+    /// the timeout of the software watchdog, 0 means the watchdog is disabled
+    pub(super) fn watchdog_usec(&self) -> u64 {
+        self.data.borrow().watchdog_usec()
+    }
+
+    /// override the configured timeout with WATCHDOG_USEC= sent by the service, until it restarts
+    pub(super) fn override_watchdog_usec(&self, usec: u64) {
+        self.data.borrow_mut().override_watchdog_usec(usec)
+    }
 }
 
 struct ServiceMonitorData {
@@ -23,60 +33,51 @@ struct ServiceMonitorData {
     config: Rc<ServiceConfig>,
 
     // owned objects
-    watchdog_original_usec: u64,
     watchdog_override_usec: u64,
     watchdog_override_enable: bool,
 }
 
 // the declaration "pub(self)" is for identification only.
-#[allow(dead_code)]
 impl ServiceMonitorData {
     pub(self) fn new(configr: &Rc<ServiceConfig>) -> ServiceMonitorData {
         ServiceMonitorData {
             config: Rc::clone(configr),
-            watchdog_original_usec: u64::MAX,
             watchdog_override_usec: 0,
             watchdog_override_enable: false,
         }
     }
 
     pub(self) fn start_action(&mut self) {
-        if self
-            .config
-            .config_data()
-            .borrow()
-            .Service
-            .WatchdogUSec
-            .is_some()
-        {
-            if let Some(wd_sec) = self.config.config_data().borrow().Service.WatchdogUSec {
-                self.watchdog_original_usec = wd_sec;
-            }
-        }
         self.watchdog_override_enable = false;
-        self.watchdog_override_usec = u64::MAX;
+        self.watchdog_override_usec = 0;
     }
 
-    /// software watchdog, if the watchdog not receive the READY=1 message within the timeout period, the kill the servcie.
-    /// start the watchdog, compare the original and override timeout value, if it's invalid value then stop the watchdog.
-    /// call recvmsg and read messages from the socket, and judge if it is the expected value, like READY=1.
-    /// not implemented all function, depend on the timer and sd-event.
-    fn _start_watchdog(self) {
-        // if watchdog_override_enable is enabled, the override the timeout with the watchdog_override_usec
-        let watchdog_usec = if self.watchdog_override_enable {
+    /// the original timeout is read from the configuration every time, so it is kept across the reexec
+    pub(self) fn watchdog_usec(&self) -> u64 {
+        let usec = if self.watchdog_override_enable {
             self.watchdog_override_usec
         } else {
-            self.watchdog_original_usec
+            self.watchdog_original_usec()
         };
-        // if timeout is 0 then stop the watchdog
-        if watchdog_usec == 0 || watchdog_usec == u64::MAX {
-            self._stop_watchdog()
+
+        if usec == u64::MAX {
+            0
+        } else {
+            usec
         }
-        libwatchdog::register_timer();
-        libwatchdog::event_source_set_enabled(true);
     }
 
-    fn _stop_watchdog(self) {
-        libwatchdog::event_source_set_enabled(false);
+    pub(self) fn override_watchdog_usec(&mut self, usec: u64) {
+        self.watchdog_override_enable = true;
+        self.watchdog_override_usec = usec;
+    }
+
+    fn watchdog_original_usec(&self) -> u64 {
+        self.config
+            .config_data()
+            .borrow()
+            .Service
+            .WatchdogUSec
+            .unwrap_or(0)
     }
 }
@@ -1,10 +1,19 @@
 #![allow(non_snake_case)]
 use confique::Config;
-use libsysmaster::manager::{DeserializeWith, ExecCommand, KillMode};
+use libsysmaster::manager::{
+    deserialize_cpu_affinity, deserialize_rlimit_sec, deserialize_rlimit_usec,
+    deserialize_syscall_archs, deserialize_timespan, deserialize_umask, CapabilitySet,
+    CpuSchedPolicy, DeserializeWith, ExecCommand, ExecInput, ExecOutput, ExecRlimit, IoSchedClass,
+    KillMode, ProtectHome, ProtectSystem, SecureBits, SyscallFilter,
+};
 use libsysmaster::{ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, Reliability};
+use nix::sys::signal::Signal;
 use nix::unistd::Pid;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::convert::TryFrom;
+use std::os::unix::prelude::RawFd;
 use std::rc::Rc;
+use std::str::FromStr;
 
 struct ServiceReDb<K, V>(ReDb<K, V>);
 
@@ -64,6 +73,123 @@ pub(super) enum NotifyAccess {
     #[strum(serialize = "main")]
     #[serde(alias = "main")]
     Main,
+    #[strum(serialize = "exec")]
+    #[serde(alias = "exec")]
+    Exec,
+    #[strum(serialize = "all")]
+    #[serde(alias = "all")]
+    All,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone, Copy)]
+pub(super) enum ServiceRestart {
+    No,
+    OnSuccess,
+    OnFailure,
+    OnAbnormal,
+    OnWatchdog,
+    OnAbort,
+    Always,
+}
+
+impl Default for ServiceRestart {
+    fn default() -> Self {
+        ServiceRestart::No
+    }
+}
+
+impl DeserializeWith for ServiceRestart {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+
+        match s.as_ref() {
+            "no" => Ok(ServiceRestart::No),
+            "on-success" => Ok(ServiceRestart::OnSuccess),
+            "on-failure" => Ok(ServiceRestart::OnFailure),
+            "on-abnormal" => Ok(ServiceRestart::OnAbnormal),
+            "on-watchdog" => Ok(ServiceRestart::OnWatchdog),
+            "on-abort" => Ok(ServiceRestart::OnAbort),
+            "always" => Ok(ServiceRestart::Always),
+            &_ => Err(de::Error::invalid_value(
+                de::Unexpected::Str(&s),
+                &"no, on-success, on-failure, on-abnormal, on-watchdog, on-abort or always",
+            )),
+        }
+    }
+}
+
+/// the exit codes and the signals configured by RestartPreventExitStatus= or SuccessExitStatus=
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub(super) struct ExitStatusSet {
+    status: Vec<i32>,
+    signal: Vec<i32>,
+}
+
+impl ExitStatusSet {
+    /// the process exits with code when signal is SIGCHLD, otherwise it is killed by signal
+    pub(super) fn contains(&self, code: i32, signal: Signal) -> bool {
+        if signal == Signal::SIGCHLD {
+            self.status.contains(&code)
+        } else {
+            self.signal.contains(&(signal as i32))
+        }
+    }
+}
+
+impl DeserializeWith for ExitStatusSet {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+        let mut set = ExitStatusSet::default();
+
+        for item in s.split_whitespace() {
+            if let Ok(code) = item.parse::<i32>() {
+                if (0..=255).contains(&code) {
+                    set.status.push(code);
+                    continue;
+                }
+            }
+
+            let name = if item.starts_with("SIG") {
+                item.to_string()
+            } else {
+                format!("SIG{}", item)
+            };
+            match Signal::from_str(&name) {
+                Ok(signal) => set.signal.push(signal as i32),
+                Err(_) => log::warn!("invalid exit status {}, ignoring", item),
+            }
+        }
+
+        Ok(set)
+    }
+}
+
+/// the signal is configured by the name with or without the "SIG" prefix, or the number
+fn deserialize_signal<'de, D>(de: D) -> Result<i32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(de)?;
+    if let Ok(signal) = s.parse::<i32>() {
+        return Signal::try_from(signal)
+            .map(|signal| signal as i32)
+            .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&s), &"a signal"));
+    }
+
+    let name = if s.starts_with("SIG") {
+        s.clone()
+    } else {
+        format!("SIG{}", s)
+    };
+    Signal::from_str(&name)
+        .map(|signal| signal as i32)
+        .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&s), &"a signal"))
 }
 
 #[derive(Config, Default, Clone, Debug, Serialize, Deserialize)]
@@ -88,15 +214,126 @@ pub(super) struct SectionService {
     #[config(deserialize_with = Vec::<String>::deserialize_with)]
     pub Sockets: Option<Vec<String>>,
     pub WatchdogUSec: Option<u64>,
+    #[config(deserialize_with = deserialize_signal)]
+    pub WatchdogSignal: Option<i32>,
     pub PIDFile: Option<String>,
     #[config(default = false)]
     pub RemainAfterExit: bool,
     pub NotifyAccess: Option<NotifyAccess>,
+    #[config(default = 0)]
+    pub FileDescriptorStoreMax: u32,
     #[config(deserialize_with = Vec::<String>::deserialize_with)]
     pub Environment: Option<Vec<String>>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub EnvironmentFile: Option<Vec<String>>,
     #[config(deserialize_with = KillMode::deserialize_with)]
     #[config(default = "none")]
     pub kill_mode: KillMode,
+    #[config(deserialize_with = ServiceRestart::deserialize_with)]
+    #[config(default = "no")]
+    pub Restart: ServiceRestart,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub RestartSec: Option<u64>,
+    #[config(deserialize_with = ExitStatusSet::deserialize_with)]
+    pub RestartPreventExitStatus: Option<ExitStatusSet>,
+    #[config(deserialize_with = ExitStatusSet::deserialize_with)]
+    pub SuccessExitStatus: Option<ExitStatusSet>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub TimeoutSec: Option<u64>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub TimeoutStartSec: Option<u64>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub TimeoutStopSec: Option<u64>,
+    pub User: Option<String>,
+    pub Group: Option<String>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub SupplementaryGroups: Option<Vec<String>>,
+    #[config(deserialize_with = deserialize_umask)]
+    pub UMask: Option<u32>,
+    pub WorkingDirectory: Option<String>,
+    #[config(default = false)]
+    pub DynamicUser: bool,
+    #[config(deserialize_with = ExecInput::deserialize_with)]
+    #[config(default = "null")]
+    pub StandardInput: ExecInput,
+    #[config(deserialize_with = ExecOutput::deserialize_with)]
+    #[config(default = "log")]
+    pub StandardOutput: ExecOutput,
+    #[config(deserialize_with = ExecOutput::deserialize_with)]
+    #[config(default = "inherit")]
+    pub StandardError: ExecOutput,
+    #[config(deserialize_with = deserialize_rlimit_sec)]
+    pub LimitCPU: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitFSIZE: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitDATA: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitSTACK: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitCORE: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitRSS: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitNOFILE: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitAS: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitNPROC: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitMEMLOCK: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitLOCKS: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitSIGPENDING: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitMSGQUEUE: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitNICE: Option<ExecRlimit>,
+    #[config(deserialize_with = ExecRlimit::deserialize_with)]
+    pub LimitRTPRIO: Option<ExecRlimit>,
+    #[config(deserialize_with = deserialize_rlimit_usec)]
+    pub LimitRTTIME: Option<ExecRlimit>,
+    pub Nice: Option<i32>,
+    #[config(deserialize_with = CpuSchedPolicy::deserialize_with)]
+    pub CPUSchedulingPolicy: Option<CpuSchedPolicy>,
+    pub CPUSchedulingPriority: Option<i32>,
+    #[config(deserialize_with = deserialize_cpu_affinity)]
+    pub CPUAffinity: Option<Vec<usize>>,
+    #[config(deserialize_with = IoSchedClass::deserialize_with)]
+    pub IOSchedulingClass: Option<IoSchedClass>,
+    pub IOSchedulingPriority: Option<u32>,
+    pub OOMScoreAdjust: Option<i32>,
+    #[config(default = false)]
+    pub PrivateTmp: bool,
+    #[config(deserialize_with = ProtectSystem::deserialize_with)]
+    #[config(default = "no")]
+    pub ProtectSystem: ProtectSystem,
+    #[config(deserialize_with = ProtectHome::deserialize_with)]
+    #[config(default = "no")]
+    pub ProtectHome: ProtectHome,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub ReadOnlyPaths: Option<Vec<String>>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub ReadWritePaths: Option<Vec<String>>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub InaccessiblePaths: Option<Vec<String>>,
+    #[config(default = false)]
+    pub PrivateDevices: bool,
+    #[config(default = false)]
+    pub PrivateNetwork: bool,
+    #[config(deserialize_with = CapabilitySet::deserialize_with)]
+    pub CapabilityBoundingSet: Option<CapabilitySet>,
+    #[config(deserialize_with = CapabilitySet::deserialize_with)]
+    pub AmbientCapabilities: Option<CapabilitySet>,
+    #[config(default = false)]
+    pub NoNewPrivileges: bool,
+    #[config(deserialize_with = SecureBits::deserialize_with)]
+    pub SecureBits: Option<SecureBits>,
+    #[config(deserialize_with = SyscallFilter::deserialize_with)]
+    pub SystemCallFilter: Option<SyscallFilter>,
+    #[config(deserialize_with = deserialize_syscall_archs)]
+    pub SystemCallArchitectures: Option<Vec<u32>>,
 }
 
 impl SectionService {
@@ -118,7 +355,8 @@ impl ServiceReConf {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "kebab-case")]
 pub(super) enum ServiceState {
     Dead,
     Condition,
@@ -137,6 +375,7 @@ pub(super) enum ServiceState {
     FinalSigterm,
     FinalSigkill,
     Failed,
+    AutoRestart,
     Cleaning,
 }
 
@@ -146,13 +385,29 @@ impl Default for ServiceState {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
 pub(super) enum ServiceResult {
+    #[strum(serialize = "success")]
     Success,
+    #[strum(serialize = "protocol")]
     FailureProtocol,
+    #[strum(serialize = "resources")]
     FailureResources,
+    #[strum(serialize = "exit-code")]
+    FailureExitCode,
+    #[strum(serialize = "signal")]
     FailureSignal,
+    #[strum(serialize = "timeout")]
+    FailureTimeout,
+    #[strum(serialize = "watchdog")]
+    FailureWatchdog,
+    #[strum(serialize = "start-limit-hit")]
     FailureStartLimitHit,
+    #[strum(serialize = "exec")]
+    FailureExec,
+    #[strum(serialize = "skip-condition")]
+    SkipCondition,
+    #[strum(serialize = "invalid")]
     ResultInvalid,
 }
 
@@ -177,6 +432,7 @@ pub(super) enum ServiceCommand {
 pub(super) enum NotifyState {
     Unknown,
     Ready,
+    Reloading,
     Stopping,
 }
 
@@ -190,6 +446,8 @@ struct ServiceReMng {
     control_cmd_type: Option<ServiceCommand>,
     control_cmd_len: usize,
     notify_state: NotifyState,
+    restarts: u32,
+    fds: Vec<(i32, String)>, // i32 ==> std::os::unix::prelude::RawFd ==> std::os::raw::c_int
 }
 
 impl ServiceReMng {
@@ -203,6 +461,8 @@ impl ServiceReMng {
         control_cmd_type: Option<ServiceCommand>,
         control_cmd_len: usize,
         notify_state: NotifyState,
+        restarts: u32,
+        fds: Vec<(i32, String)>,
     ) -> ServiceReMng {
         ServiceReMng {
             state,
@@ -213,6 +473,8 @@ impl ServiceReMng {
             control_cmd_type,
             control_cmd_len,
             notify_state,
+            restarts,
+            fds,
         }
     }
 }
@@ -220,7 +482,7 @@ impl ServiceReMng {
 pub(super) struct ServiceRe {
     // database: multi-instance(N)
     conf: Rc<ServiceReDb<String, ServiceReConf>>, // RELI_DB_ESERVICE_CONF; key: unit_id, data: config;
-    mng: Rc<ServiceReDb<String, ServiceReMng>>, // RELI_DB_HSERVICE_MNG; key: unit_id, data: state+result+main(pid+cmd)+control(pid+cmd)+notify_state;
+    mng: Rc<ServiceReDb<String, ServiceReMng>>, // RELI_DB_HSERVICE_MNG; key: unit_id, data: state+result+main(pid+cmd)+control(pid+cmd)+notify_state+restarts+fds(fd-store);
 }
 
 impl ServiceRe {
@@ -258,6 +520,8 @@ impl ServiceRe {
         control_cmd_type: Option<ServiceCommand>,
         control_cmd_len: usize,
         notify_state: NotifyState,
+        restarts: u32,
+        fds: Vec<(RawFd, String)>,
     ) {
         let m_pid = main_pid.map(|x| x.as_raw() as i32);
         let c_pid = control_pid.map(|x| x.as_raw() as i32);
@@ -270,6 +534,8 @@ impl ServiceRe {
             control_cmd_type,
             control_cmd_len,
             notify_state,
+            restarts,
+            fds,
         );
         self.mng.0.insert(unit_id.to_string(), mng);
     }
@@ -291,6 +557,8 @@ impl ServiceRe {
         Option<ServiceCommand>,
         usize,
         NotifyState,
+        u32,
+        Vec<(RawFd, String)>,
     )> {
         let mng = self.mng.0.get(unit_id);
         mng.map(|m| {
@@ -303,6 +571,8 @@ impl ServiceRe {
                 m.control_cmd_type,
                 m.control_cmd_len,
                 m.notify_state,
+                m.restarts,
+                m.fds,
             )
         })
     }
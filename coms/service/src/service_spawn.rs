@@ -1,17 +1,20 @@
 use super::service_comm::ServiceUnitComm;
 use super::service_config::ServiceConfig;
+use super::service_mng::RunningData;
+use super::service_monitor::ServiceMonitor;
 use super::service_pid::ServicePid;
-use super::service_rentry::ServiceType;
-use libsysmaster::manager::{ExecCommand, ExecContext, ExecFlags, ExecParameters};
+use super::service_rentry::NotifyAccess;
+use libsysmaster::manager::{ExecCmdError, ExecCommand, ExecContext, ExecFlags, ExecParameters};
 use nix::unistd::Pid;
 use std::env;
-use std::error::Error;
 use std::rc::Rc;
 
 pub(super) struct ServiceSpawn {
     comm: Rc<ServiceUnitComm>,
     pid: Rc<ServicePid>,
     config: Rc<ServiceConfig>,
+    monitor: Rc<ServiceMonitor>,
+    rd: Rc<RunningData>,
     exec_ctx: Rc<ExecContext>,
 }
 
@@ -20,12 +23,16 @@ impl ServiceSpawn {
         commr: &Rc<ServiceUnitComm>,
         pidr: &Rc<ServicePid>,
         configr: &Rc<ServiceConfig>,
+        monitorr: &Rc<ServiceMonitor>,
+        rd: &Rc<RunningData>,
         exec_ctx: &Rc<ExecContext>,
     ) -> ServiceSpawn {
         ServiceSpawn {
             comm: Rc::clone(commr),
             pid: Rc::clone(pidr),
             config: configr.clone(),
+            monitor: monitorr.clone(),
+            rd: rd.clone(),
             exec_ctx: exec_ctx.clone(),
         }
     }
@@ -35,7 +42,7 @@ impl ServiceSpawn {
         cmdline: &ExecCommand,
         _time_out: u64,
         ec_flags: ExecFlags,
-    ) -> Result<Pid, Box<dyn Error>> {
+    ) -> Result<Pid, ExecCmdError> {
         let mut params = ExecParameters::new();
 
         params.add_env(
@@ -51,13 +58,25 @@ impl ServiceSpawn {
 
         let unit = self.comm.unit();
         let um = self.comm.um();
-        unit.prepare_exec()?;
+        unit.prepare_exec()
+            .map_err(|e| ExecCmdError::CgroupError(e.to_string()))?;
 
         if ec_flags.contains(ExecFlags::PASS_FDS) {
-            params.insert_fds(self.collect_socket_fds());
+            // the stored fds follow the socket fds
+            let mut fds = self.collect_socket_fds();
+            fds.extend(self.rd.fd_store());
+            let (fds, names) = fds.into_iter().unzip();
+            params.insert_fds(fds);
+            params.insert_fd_names(names);
         }
 
-        if self.config.service_type() == ServiceType::Notify {
+        // the watchdog is for the main process
+        if !ec_flags.contains(ExecFlags::CONTROL) {
+            params.set_watchdog_usec(self.monitor.watchdog_usec());
+        }
+
+        let notify_access = self.config.config_data().borrow().Service.NotifyAccess;
+        if notify_access.map_or(false, |access| access != NotifyAccess::None) {
             let notify_sock = um.notify_socket().unwrap();
             log::debug!("add NOTIFY_SOCKET env: {}", notify_sock.to_str().unwrap());
             params.add_env("NOTIFY_SOCKET", notify_sock.to_str().unwrap().to_string());
@@ -71,13 +90,13 @@ impl ServiceSpawn {
                 Ok(pid)
             }
             Err(e) => {
-                log::error!("failed to start service: {}, error:{:?}", unit.id(), e);
-                Err("spawn exec return error".to_string().into())
+                log::error!("failed to start service: {}, error: {}", unit.id(), e);
+                Err(e)
             }
         }
     }
 
-    fn collect_socket_fds(&self) -> Vec<i32> {
+    fn collect_socket_fds(&self) -> Vec<(i32, String)> {
         self.comm.um().collect_socket_fds(self.comm.unit().id())
     }
 }
@@ -4,26 +4,30 @@ use super::service_config::ServiceConfig;
 use super::service_mng::RunningData;
 use super::service_mng::ServiceMng;
 use super::service_monitor::ServiceMonitor;
-use super::service_rentry::{NotifyAccess, ServiceCommand, ServiceType};
+use super::service_rentry::{NotifyAccess, ServiceCommand, ServiceRestart, ServiceType};
 use libsysmaster::manager::{
-    ExecContext, SubUnit, UmIf, Unit, UnitActionError, UnitActiveState, UnitMngUtil, UnitRelations,
+    ExecContext, ExecLimits, ExecSandbox, ExecSecurity, SubUnit, UmIf, Unit, UnitActionError,
+    UnitActiveState, UnitMngUtil, UnitRelations,
 };
 use libsysmaster::{ReStation, Reliability};
 use libutils::error::Error as ServiceError;
 use libutils::logger;
+use libutils::IN_SET;
+use nix::sys::resource::Resource;
 use nix::sys::signal::Signal;
 use nix::sys::socket::UnixCredentials;
+use nix::sys::stat::Mode;
 use nix::unistd::Pid;
 use std::collections::HashMap;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 struct ServiceUnit {
     comm: Rc<ServiceUnitComm>,
     config: Rc<ServiceConfig>,
     mng: Rc<ServiceMng>,
-    monitor: ServiceMonitor,
+    monitor: Rc<ServiceMonitor>,
     exec_ctx: Rc<ExecContext>,
 }
 
@@ -46,12 +50,12 @@ impl ReStation for ServiceUnit {
     // reload: no external connections, entry-only
     fn entry_coldplug(&self) {
         // rebuild external connections, like: timer, ...
-        // do nothing now
+        self.mng.entry_coldplug();
     }
 
     fn entry_clear(&self) {
         // release external connection, like: timer, ...
-        // do nothing now
+        self.mng.entry_clear();
     }
 }
 
@@ -95,7 +99,11 @@ impl SubUnit for ServiceUnit {
     fn stop(&self, force: bool) -> Result<(), UnitActionError> {
         log::debug!("begin to stop the service unit, force: {}.", force);
         if !force {
-            self.mng.stop_check()?;
+            let stopping = self.mng.stop_check()?;
+            if stopping {
+                log::debug!("service already in stopping, just return immediately");
+                return Ok(());
+            }
         }
         self.mng.stop_action();
         Ok(())
@@ -125,6 +133,26 @@ impl SubUnit for ServiceUnit {
         self.mng.current_active_state()
     }
 
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_subunit_result(&self) -> String {
+        self.mng.get_result()
+    }
+
+    fn get_main_pid(&self) -> Option<Pid> {
+        self.mng.main_pid()
+    }
+
+    fn get_main_exit_status(&self) -> Option<(i32, Signal)> {
+        self.mng.main_exit_status()
+    }
+
+    fn get_status_text(&self) -> String {
+        self.mng.status_text()
+    }
+
     fn attach_unit(&self, unit: Rc<Unit>) {
         self.comm.attach_unit(unit);
         self.db_insert();
@@ -162,14 +190,16 @@ impl ServiceUnit {
         let config = Rc::new(ServiceConfig::new(&comm));
         let context = Rc::new(ExecContext::new());
 
+        let monitor = Rc::new(ServiceMonitor::new(&config));
+
         let rt = Rc::new(RunningData::new());
-        let _mng = Rc::new(ServiceMng::new(&comm, &config, &rt, &context));
+        let _mng = Rc::new(ServiceMng::new(&comm, &config, &monitor, &rt, &context));
         rt.attach_mng(_mng.clone());
         ServiceUnit {
             comm: Rc::clone(&comm),
             config: Rc::clone(&config),
             mng: Rc::clone(&_mng),
-            monitor: ServiceMonitor::new(&config),
+            monitor: Rc::clone(&monitor),
             exec_ctx: Rc::clone(&context),
         }
     }
@@ -187,6 +217,8 @@ impl ServiceUnit {
             }
         }
 
+        self.parse_exec_context()?;
+
         if let Some(sockets) = self.config.sockets() {
             for socket in sockets {
                 self.comm.unit().insert_two_deps(
@@ -204,8 +236,114 @@ impl ServiceUnit {
         Ok(())
     }
 
+    fn parse_exec_context(&self) -> Result<(), Box<dyn Error>> {
+        let config = self.config.config_data();
+        let data = config.borrow();
+        let service = &data.Service;
+        if let Some(dir) = &service.WorkingDirectory {
+            if !Path::new(dir).is_absolute() {
+                return Err(Box::new(ServiceError::Other {
+                    msg: "WorkingDirectory= is not an absolute path",
+                }));
+            }
+        }
+
+        let env_files = service.EnvironmentFile.clone().unwrap_or_default();
+        for file in &env_files {
+            if !Path::new(file.trim_start_matches('-')).is_absolute() {
+                return Err(Box::new(ServiceError::Other {
+                    msg: "EnvironmentFile= is not an absolute path",
+                }));
+            }
+        }
+        self.exec_ctx.set_environment_files(env_files);
+
+        self.exec_ctx.set_user(service.User.clone());
+        self.exec_ctx.set_group(service.Group.clone());
+        self.exec_ctx
+            .set_supplementary_groups(service.SupplementaryGroups.clone().unwrap_or_default());
+        self.exec_ctx
+            .set_umask(service.UMask.map(Mode::from_bits_truncate));
+        self.exec_ctx
+            .set_working_directory(service.WorkingDirectory.as_ref().map(PathBuf::from));
+        self.exec_ctx.set_dynamic_user(service.DynamicUser);
+        self.exec_ctx.set_stdio(
+            service.StandardInput.clone(),
+            service.StandardOutput.clone(),
+            service.StandardError.clone(),
+        );
+
+        let rlimits = [
+            (Resource::RLIMIT_CPU, service.LimitCPU),
+            (Resource::RLIMIT_FSIZE, service.LimitFSIZE),
+            (Resource::RLIMIT_DATA, service.LimitDATA),
+            (Resource::RLIMIT_STACK, service.LimitSTACK),
+            (Resource::RLIMIT_CORE, service.LimitCORE),
+            (Resource::RLIMIT_RSS, service.LimitRSS),
+            (Resource::RLIMIT_NOFILE, service.LimitNOFILE),
+            (Resource::RLIMIT_AS, service.LimitAS),
+            (Resource::RLIMIT_NPROC, service.LimitNPROC),
+            (Resource::RLIMIT_MEMLOCK, service.LimitMEMLOCK),
+            (Resource::RLIMIT_LOCKS, service.LimitLOCKS),
+            (Resource::RLIMIT_SIGPENDING, service.LimitSIGPENDING),
+            (Resource::RLIMIT_MSGQUEUE, service.LimitMSGQUEUE),
+            (Resource::RLIMIT_NICE, service.LimitNICE),
+            (Resource::RLIMIT_RTPRIO, service.LimitRTPRIO),
+            (Resource::RLIMIT_RTTIME, service.LimitRTTIME),
+        ];
+        let limits = ExecLimits {
+            rlimits: rlimits
+                .iter()
+                .filter_map(|(resource, limit)| limit.map(|l| (*resource, l)))
+                .collect(),
+            nice: service.Nice,
+            cpu_sched_policy: service.CPUSchedulingPolicy,
+            cpu_sched_priority: service.CPUSchedulingPriority,
+            cpu_affinity: service.CPUAffinity.clone().unwrap_or_default(),
+            io_sched_class: service.IOSchedulingClass,
+            io_sched_priority: service.IOSchedulingPriority,
+            oom_score_adjust: service.OOMScoreAdjust,
+        };
+        limits.verify()?;
+        self.exec_ctx.set_limits(limits);
+
+        let sandbox = ExecSandbox {
+            private_tmp: service.PrivateTmp,
+            protect_system: service.ProtectSystem,
+            protect_home: service.ProtectHome,
+            read_only_paths: service.ReadOnlyPaths.clone().unwrap_or_default(),
+            read_write_paths: service.ReadWritePaths.clone().unwrap_or_default(),
+            inaccessible_paths: service.InaccessiblePaths.clone().unwrap_or_default(),
+            private_devices: service.PrivateDevices,
+            private_network: service.PrivateNetwork,
+        };
+        sandbox.verify()?;
+        self.exec_ctx.set_sandbox(sandbox);
+
+        let security = ExecSecurity {
+            capability_bounding_set: service.CapabilityBoundingSet,
+            ambient_capabilities: service.AmbientCapabilities.unwrap_or_default(),
+            no_new_privileges: service.NoNewPrivileges,
+            secure_bits: service.SecureBits.unwrap_or_default(),
+            syscall_filter: service.SystemCallFilter.clone(),
+            syscall_archs: service.SystemCallArchitectures.clone().unwrap_or_default(),
+        };
+        security.verify()?;
+        self.exec_ctx.set_security(security);
+        Ok(())
+    }
+
     fn service_add_extras(&self) -> Result<(), Box<dyn Error>> {
-        if self.config.service_type() == ServiceType::Notify {
+        // the main process is allowed to notify by default if the notification is required
+        let default_main = {
+            let config = self.config.config_data();
+            let service = &config.borrow().Service;
+            service.NotifyAccess.is_none()
+                && (service.Type == ServiceType::Notify
+                    || service.WatchdogUSec.is_some()
+                    || service.FileDescriptorStoreMax > 0)
+        };
+        if default_main {
             self.config.set_notify_access(NotifyAccess::Main);
         }
 
@@ -227,6 +365,18 @@ impl ServiceUnit {
             }));
         }
 
+        if self.config.service_type() == ServiceType::Oneshot
+            && IN_SET!(
+                self.config.config_data().borrow().Service.Restart,
+                ServiceRestart::Always,
+                ServiceRestart::OnSuccess
+            )
+        {
+            return Err(Box::new(ServiceError::Other {
+                msg: "Restart= is always or on-success, which is not allowed for oneshot service",
+            }));
+        }
+
         if self.config.service_type() != ServiceType::Oneshot
             && self.config.get_exec_cmds(ServiceCommand::Start).is_none()
         {
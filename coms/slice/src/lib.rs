@@ -0,0 +1,52 @@
+//! Slice is the entry for the hierarchical cgroup placement of sysmaster. sysmaster refers to systemd, but it is not the same.
+//! A slice unit groups the cgroups of the units placed in it, it manages no process by itself.
+//!
+//! The Slice configuration file contains the [Unit] [Install] sections, a slice can be loaded without configuration file.
+//!
+//! ```toml
+//! [Unit]
+//! Description = "user slice"
+//! ```
+//!
+//! ## Cgroup hierarchy
+//! The name of the slice implies its parent slice, "foo-bar.slice" is placed in "foo.slice", and "foo.slice" is placed in
+//! the root slice "-.slice". The cgroup of "foo-bar.slice" is "foo.slice/foo-bar.slice".
+//!
+//! The service, socket and mount units are placed in the slice configured by Slice= of the [Unit] section, defaults to
+//! "system.slice", the cgroup of "foo.service" is "system.slice/foo.service".
+//!
+//! ## Resource control
+//! The service, socket, mount and slice units can limit the resources of their cgroups in the [ResourceControl] section,
+//! only the unified cgroup hierarchy (cgroup v2) is supported. The required controllers are enabled in cgroup.subtree_control
+//! of the parent cgroups, the limits of a slice apply to all the units placed in it.
+//!
+//! ```toml
+//! [ResourceControl]
+//! CPUWeight = 200
+//! CPUQuota = "20%"
+//! MemoryMax = "512M"
+//! TasksMax = 100
+//! ```
+//!
+//! * CPUWeight, IOWeight: the weight of the cpu time and the io, in the range of [1, 10000].
+//! * CPUQuota: the percentage of the time of one cpu, which can exceed 100%, like "150%".
+//! * MemoryMax, MemoryHigh, MemoryLow, TasksMax: the limits with optional K, M, G, T suffixes (base 1024), or "infinity".
+//! * IODeviceWeight: the weight of the io on a block device, like "/dev/sda 200", multiple entries are separated by ";".
+//! * AllowedCPUs: the cpus which the processes can run on, like "0-3,6".
+//!
+//! The settings are applied when the cgroup is created, and re-applied to the running units on daemon-reload.
+//!
+//! ## Automatic dependency
+//! ### Implicit dependency
+//! The units placed in the slice, including its child slices, require the slice and are ordered after it.
+//! The slice is not stopped until all the units placed in it are gone.
+//! ### Default Dependency
+//! NA
+
+// dependency: slice_base -> slice_rentry -> slice_comm -> {slice_mng -> slice_unit} -> slice_manager
+mod slice_base;
+mod slice_comm;
+mod slice_manager;
+mod slice_mng;
+mod slice_rentry;
+mod slice_unit;
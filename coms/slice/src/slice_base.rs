@@ -0,0 +1,2 @@
+pub(super) const LOG_LEVEL: u32 = 4;
+pub(super) const PLUGIN_NAME: &str = "SliceUnit";
@@ -0,0 +1,166 @@
+//!  Slice association unit object
+//! *  You need to notify the Unit object and change the method
+//! *  Get the attributes of the unit object
+//! *  Call relation: slice_ unit->slice_ mng->slice_ comm
+
+use super::slice_rentry::{SliceRe, SliceState};
+use libsysmaster::manager::{UmIf, Unit};
+use libsysmaster::Reliability;
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, RwLock};
+
+pub(super) struct SliceUnitComm {
+    data: RefCell<SliceUnitCommData>,
+    umcomm: Arc<SliceUmComm>,
+}
+
+impl SliceUnitComm {
+    pub(super) fn new() -> Self {
+        SliceUnitComm {
+            data: RefCell::new(SliceUnitCommData::new()),
+            umcomm: SliceUmComm::get_instance(),
+        }
+    }
+
+    pub(super) fn attach_unit(&self, unit: Rc<Unit>) {
+        self.data.borrow_mut().attach_unit(unit);
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.umcomm.attach_um(um)
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.umcomm.attach_reli(reli)
+    }
+
+    pub(super) fn unit(&self) -> Rc<Unit> {
+        self.data.borrow().unit()
+    }
+
+    pub(super) fn rentry_mng_insert(&self, state: SliceState) {
+        self.rentry().mng_insert(self.unit().id(), state)
+    }
+
+    pub(super) fn rentry_mng_get(&self) -> Option<SliceState> {
+        self.rentry().mng_get(self.unit().id())
+    }
+
+    fn rentry(&self) -> Rc<SliceRe> {
+        self.umcomm.rentry()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        self.umcomm.um()
+    }
+}
+struct SliceUnitCommData {
+    unit: Weak<Unit>,
+}
+
+impl SliceUnitCommData {
+    pub(self) fn new() -> SliceUnitCommData {
+        SliceUnitCommData { unit: Weak::new() }
+    }
+
+    fn attach_unit(&mut self, unit: Rc<Unit>) {
+        self.unit = Rc::downgrade(&unit);
+    }
+
+    pub(self) fn unit(&self) -> Rc<Unit> {
+        self.unit.clone().upgrade().unwrap()
+    }
+}
+
+static SLICE_UM_COMM: Lazy<Arc<SliceUmComm>> = Lazy::new(|| {
+    let comm = SliceUmComm::new();
+    Arc::new(comm)
+});
+
+pub(super) struct SliceUmComm {
+    data: RwLock<SliceUmCommData>,
+}
+
+unsafe impl Send for SliceUmComm {}
+
+unsafe impl Sync for SliceUmComm {}
+
+impl SliceUmComm {
+    pub(super) fn new() -> Self {
+        SliceUmComm {
+            data: RwLock::new(SliceUmCommData::new()),
+        }
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_um(um);
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_reli(reli);
+    }
+
+    pub(super) fn get_instance() -> Arc<SliceUmComm> {
+        SLICE_UM_COMM.clone()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        let rdata = self.data.read().unwrap();
+        rdata.um().unwrap()
+    }
+
+    pub(super) fn rentry(&self) -> Rc<SliceRe> {
+        let rdata = self.data.read().unwrap();
+        rdata.rentry()
+    }
+}
+
+struct SliceUmCommData {
+    // associated objects
+    um: Option<Rc<dyn UmIf>>,
+    reli: Weak<Reliability>,
+    rentry: Option<Rc<SliceRe>>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl SliceUmCommData {
+    pub(self) fn new() -> SliceUmCommData {
+        SliceUmCommData {
+            um: None,
+            reli: Weak::new(),
+            rentry: None,
+        }
+    }
+
+    pub(self) fn attach_um(&mut self, um: Rc<dyn UmIf>) {
+        if self.um.is_none() {
+            log::debug!("SliceUmComm attach_um action.");
+            self.um = Some(um);
+        }
+    }
+
+    pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
+        let old = self.reli.clone().upgrade();
+        if old.is_none() {
+            log::debug!("SliceUmComm attach_reli action.");
+            self.reli = Rc::downgrade(&reli);
+            self.rentry.replace(Rc::new(SliceRe::new(&reli)));
+        }
+    }
+
+    pub(self) fn um(&self) -> Option<Rc<dyn UmIf>> {
+        if let Some(ref um) = self.um {
+            Some(Rc::clone(um))
+        } else {
+            None
+        }
+    }
+
+    pub(self) fn rentry(&self) -> Rc<SliceRe> {
+        self.rentry.as_ref().cloned().unwrap()
+    }
+}
@@ -0,0 +1,52 @@
+use super::slice_base::{LOG_LEVEL, PLUGIN_NAME};
+use super::slice_comm::SliceUmComm;
+use libsysmaster::manager::{UmIf, UnitManagerObj, UnitMngUtil};
+use libsysmaster::{ReStation, Reliability};
+use libutils::logger;
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct SliceManager {
+    comm: Arc<SliceUmComm>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl SliceManager {
+    pub(self) fn new() -> SliceManager {
+        let _comm = SliceUmComm::get_instance();
+        SliceManager {
+            comm: Arc::clone(&_comm),
+        }
+    }
+}
+
+impl UnitManagerObj for SliceManager {
+    // nothing to customize
+}
+
+impl ReStation for SliceManager {
+    // no input, no compensate
+
+    // no data
+
+    // reload: no external connections, no entry
+}
+
+impl UnitMngUtil for SliceManager {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+impl Default for SliceManager {
+    fn default() -> Self {
+        SliceManager::new()
+    }
+}
+
+use libsysmaster::declure_umobj_plugin;
+declure_umobj_plugin!(SliceManager, SliceManager::default, PLUGIN_NAME, LOG_LEVEL);
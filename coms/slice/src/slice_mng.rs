@@ -0,0 +1,245 @@
+//!  The core logic of the slice subclass
+
+use super::slice_comm::SliceUnitComm;
+use super::slice_rentry::SliceState;
+use libevent::{EventState, EventType, Events, Source};
+use libsysmaster::manager::{
+    Unit, UnitActionError, UnitActiveState, UnitNotifyFlags, UnitRelationAtom,
+};
+use libsysmaster::ReStation;
+use libutils::Error;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+impl SliceState {
+    fn slice_state_to_unit_state(&self) -> UnitActiveState {
+        match *self {
+            SliceState::Dead => UnitActiveState::UnitInActive,
+            SliceState::Active => UnitActiveState::UnitActive,
+        }
+    }
+}
+
+pub(super) struct SliceMng {
+    comm: Rc<SliceUnitComm>,
+    state: RefCell<SliceState>,
+    defer: Rc<SliceDefer>,
+}
+
+impl ReStation for SliceMng {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        if let Some(state) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm.rentry_mng_insert(self.state());
+    }
+
+    // reload: no external connections, entry-only
+    fn entry_clear(&self) {
+        self.disable_defer();
+    }
+}
+
+impl SliceMng {
+    pub(super) fn new(_comm: &Rc<SliceUnitComm>) -> Self {
+        SliceMng {
+            comm: Rc::clone(_comm),
+            state: RefCell::new(SliceState::Dead),
+            defer: Rc::new(SliceDefer::new()),
+        }
+    }
+
+    pub(super) fn attach_slice(&self, mngr: &Rc<SliceMng>) {
+        self.defer.attach(Rc::downgrade(mngr));
+    }
+
+    pub(super) fn start_action(&self) {
+        // the cgroup of the slice is created before its members, the cgroup may be not supported
+        if let Err(e) = self.comm.unit().prepare_exec() {
+            log::warn!(
+                "Failed to create the cgroup of {}, ignoring: {}",
+                self.comm.unit().id(),
+                e
+            );
+        }
+
+        self.set_state(SliceState::Active, true);
+    }
+
+    pub(super) fn stop_action(&self) -> Result<(), UnitActionError> {
+        // the stop is retried once the members are gone
+        if self.members_active() {
+            log::debug!(
+                "{} still has active members, waiting for them to stop.",
+                self.comm.unit().id()
+            );
+            return Err(UnitActionError::UnitActionEAgain);
+        }
+
+        self.set_state(SliceState::Dead, true);
+        Ok(())
+    }
+
+    pub(super) fn trigger_notify(&self, _other: Rc<Unit>) {
+        // the state of the member is being dispatched, re-check later
+        if self.state() == SliceState::Active {
+            self.enable_defer();
+        }
+    }
+
+    fn members_active(&self) -> bool {
+        self.comm
+            .um()
+            .get_dependency_list(self.comm.unit().id(), UnitRelationAtom::UnitAtomSliceOf)
+            .iter()
+            .any(|u| {
+                !matches!(
+                    u.current_active_state(),
+                    UnitActiveState::UnitInActive | UnitActiveState::UnitFailed
+                )
+            })
+    }
+
+    fn dispatch_defer(&self) {
+        if self.state() != SliceState::Active {
+            return;
+        }
+
+        if self.comm.um().has_stop_job(self.comm.unit().id()) && !self.members_active() {
+            self.set_state(SliceState::Dead, true);
+        }
+    }
+
+    fn enable_defer(&self) {
+        let events = self.comm.um().events();
+        let defer = self.defer.clone();
+        if let Err(e) = events
+            .add_source(defer.clone())
+            .and_then(|_| events.set_enabled(defer, EventState::OneShot))
+        {
+            log::error!("failed to enable the slice defer event: {}", e);
+        }
+    }
+
+    fn disable_defer(&self) {
+        let events = self.comm.um().events();
+        if let Err(e) = events.set_enabled(self.defer.clone(), EventState::Off) {
+            log::debug!("failed to disable the slice defer event: {}", e);
+        }
+    }
+
+    fn set_state(&self, new_state: SliceState, notify: bool) {
+        let old_state = self.state();
+        self.change_state(new_state);
+
+        if notify {
+            self.state_notify(new_state, old_state);
+        }
+    }
+
+    fn state_notify(&self, new_state: SliceState, old_state: SliceState) {
+        if new_state != old_state {
+            log::debug!(
+                "{} original state[{:?}] -> new state[{:?}]",
+                self.comm.unit().id(),
+                old_state,
+                new_state,
+            );
+        }
+
+        let old_unit_state = old_state.slice_state_to_unit_state();
+        let new_unit_state = new_state.slice_state_to_unit_state();
+        self.comm.unit().notify(
+            old_unit_state,
+            new_unit_state,
+            UnitNotifyFlags::UNIT_NOTIFY_RELOAD_FAILURE,
+        );
+
+        self.db_update();
+    }
+
+    fn change_state(&self, new_state: SliceState) {
+        self.state.replace(new_state);
+    }
+
+    fn state(&self) -> SliceState {
+        *self.state.borrow()
+    }
+
+    pub(super) fn slice_state_to_unit_state(&self) -> UnitActiveState {
+        self.state().slice_state_to_unit_state()
+    }
+
+    pub(super) fn get_state(&self) -> String {
+        self.state().to_string()
+    }
+}
+
+struct SliceDefer {
+    mng: RefCell<Weak<SliceMng>>,
+}
+
+impl SliceDefer {
+    fn new() -> Self {
+        SliceDefer {
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(self) fn attach(&self, mng: Weak<SliceMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    pub(self) fn mng(&self) -> Rc<SliceMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for SliceDefer {
+    fn event_type(&self) -> EventType {
+        EventType::Defer
+    }
+
+    fn epoll_event(&self) -> u32 {
+        0
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn dispatch(&self, _: &Events) -> Result<i32, Error> {
+        self.mng().dispatch_defer();
+        self.mng().db_update();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SliceMng, SliceState, SliceUnitComm};
+    use libsysmaster::manager::UnitActiveState;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_slice_set_state() {
+        let _comm = Rc::new(SliceUnitComm::new());
+        let sm = SliceMng::new(&_comm);
+        assert_eq!(sm.get_state(), "dead");
+        sm.change_state(SliceState::Active);
+        assert_eq!(sm.state(), SliceState::Active);
+        assert_eq!(sm.get_state(), "active");
+        assert_eq!(sm.slice_state_to_unit_state(), UnitActiveState::UnitActive);
+    }
+}
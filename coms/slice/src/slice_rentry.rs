@@ -0,0 +1,74 @@
+use libsysmaster::{ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, Reliability};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use strum::Display;
+
+const RELI_DB_HSLICE_MNG: &str = "slicemng";
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub(super) enum SliceState {
+    Dead,
+    Active,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SliceReMng {
+    state: SliceState,
+}
+
+impl SliceReMng {
+    fn new(state: SliceState) -> SliceReMng {
+        SliceReMng { state }
+    }
+}
+
+struct SliceReDb<K, V>(ReDb<K, V>);
+
+pub(super) struct SliceRe {
+    // database: multi-instance(N)
+    mng: Rc<SliceReDb<String, SliceReMng>>, // RELI_DB_HSLICE_MNG; key: unit_id, data: state;
+}
+
+impl SliceRe {
+    pub(super) fn new(relir: &Reliability) -> SliceRe {
+        let mng = Rc::new(SliceReDb(ReDb::new(relir, RELI_DB_HSLICE_MNG)));
+        let rentry = SliceRe { mng };
+        rentry.register(relir);
+        rentry
+    }
+
+    pub(super) fn mng_insert(&self, unit_id: &str, state: SliceState) {
+        let mng = SliceReMng::new(state);
+        self.mng.0.insert(unit_id.to_string(), mng);
+    }
+
+    pub(super) fn mng_get(&self, unit_id: &String) -> Option<SliceState> {
+        let mng = self.mng.0.get(unit_id);
+        mng.map(|m| m.state)
+    }
+
+    fn register(&self, relir: &Reliability) {
+        // reliability-db: RELI_DB_HSLICE_MNG
+        let db = Rc::clone(&self.mng);
+        relir.history_db_register(RELI_DB_HSLICE_MNG, db);
+    }
+}
+
+impl ReDbTable for SliceReDb<String, SliceReMng> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.0.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.0.set_ignore(ignore);
+    }
+}
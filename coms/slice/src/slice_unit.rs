@@ -0,0 +1,111 @@
+//! slice unit is entry of slice type of unit，need impl
+//! UnitObj,UnitMngUtil, UnitSubClass trait
+
+use super::slice_base::{LOG_LEVEL, PLUGIN_NAME};
+use super::slice_comm::SliceUnitComm;
+use super::slice_mng::SliceMng;
+use libsysmaster::manager::{SubUnit, UmIf, Unit, UnitActionError, UnitActiveState, UnitMngUtil};
+use libsysmaster::{ReStation, Reliability};
+use libutils::logger;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+struct SliceUnit {
+    comm: Rc<SliceUnitComm>,
+    mng: Rc<SliceMng>,
+}
+
+impl ReStation for SliceUnit {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        self.mng.db_map();
+    }
+
+    fn db_insert(&self) {
+        self.mng.db_insert();
+    }
+
+    // reload: no external connections, entry-only
+    fn entry_clear(&self) {
+        self.mng.entry_clear();
+    }
+}
+
+impl SliceUnit {
+    fn new(_um: Rc<dyn UmIf>) -> SliceUnit {
+        let _comm = Rc::new(SliceUnitComm::new());
+        let _mng = Rc::new(SliceMng::new(&_comm));
+        _mng.attach_slice(&_mng);
+        SliceUnit {
+            comm: Rc::clone(&_comm),
+            mng: _mng,
+        }
+    }
+}
+
+impl SubUnit for SliceUnit {
+    // the slice of the slice is implied by its name, which is added by the unit frame
+    fn load(&self, _paths: Vec<PathBuf>) -> libutils::Result<(), Box<dyn std::error::Error>> {
+        let id = self.comm.unit().id().clone();
+        if !id.ends_with(".slice") || id.len() == ".slice".len() {
+            return Err(format!("{} is not a valid slice name", id).into());
+        }
+
+        Ok(())
+    }
+
+    fn current_active_state(&self) -> UnitActiveState {
+        self.mng.slice_state_to_unit_state()
+    }
+
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn attach_unit(&self, unit: Rc<Unit>) {
+        self.comm.attach_unit(unit);
+        self.db_insert();
+    }
+
+    fn trigger_notify(&self, other: Rc<Unit>) {
+        self.mng.trigger_notify(other)
+    }
+
+    fn init(&self) {}
+
+    fn done(&self) {}
+
+    fn dump(&self) {}
+
+    fn start(&self) -> libutils::Result<(), UnitActionError> {
+        self.mng.start_action();
+        Ok(())
+    }
+
+    fn stop(&self, _force: bool) -> libutils::Result<(), UnitActionError> {
+        self.mng.stop_action()
+    }
+
+    fn reload(&self) {}
+
+    fn kill(&self) {}
+
+    fn release_resources(&self) {}
+
+    fn reset_failed(&self) {}
+}
+
+impl UnitMngUtil for SliceUnit {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+use libsysmaster::declure_unitobj_plugin_with_param;
+declure_unitobj_plugin_with_param!(SliceUnit, SliceUnit::new, PLUGIN_NAME, LOG_LEVEL);
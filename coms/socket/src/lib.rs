@@ -64,6 +64,19 @@
 //!  SocketMode
 //!
 //!  Set the access mode when creating a file node, which is applicable to files created when unix sockets are used.
+//!
+//!  Accept
+//!
+//!  Can be set to true or false (default). When it is true, the connection of the stream socket is accepted by sysmaster and passed to the service as the only file descriptor, which can be connected to the standard input and output by StandardInput="socket".
+//!  The service serves one connection at a time, the connections accepted while the service is running are refused. The socket listens again when the service is inactive.
+//!
+//!  FileDescriptorName
+//!
+//!  The name of the file descriptors of the socket, which is passed to the service in LISTEN_FDNAMES. The name is up to 255 printable characters except ":", and is the socket unit name by default. The accepted connection of Accept=true is named "connection".
+//!
+//!  User、Group、SupplementaryGroups、UMask、WorkingDirectory、DynamicUser
+//!
+//!  The identity and the execution environment of the ExecStartPre, ExecStartPost, ExecStopPre and ExecStopPost commands, same as the service.
 
 // dependency:
 // socket_base -> service_rentry -> {socket_comm | socket_config}
@@ -22,6 +22,9 @@ use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::path::PathBuf;
 use std::rc::Rc;
 
+// the max length of the name in LISTEN_FDNAMES
+const FD_NAME_MAX: usize = 255;
+
 pub struct SocketConfig {
     // associated objects
     comm: Rc<SocketUnitComm>,
@@ -98,9 +101,10 @@ impl SocketConfig {
         // parse and record processed configuration
         let ret1 = self.parse_service();
         let ret2 = self.parse_port();
-        if ret1.is_err() || ret2.is_err() {
+        let ret3 = self.parse_fd_name();
+        if ret1.is_err() || ret2.is_err() || ret3.is_err() {
             self.reset(); // fallback
-            return ret1.and(ret2);
+            return ret1.and(ret2).and(ret3);
         }
 
         if update {
@@ -118,6 +122,10 @@ impl SocketConfig {
         self.data.borrow().get_exec_cmds(cmd_type)
     }
 
+    pub(super) fn fd_name(&self) -> Option<String> {
+        self.data.borrow().Socket.FileDescriptorName.clone()
+    }
+
     pub(super) fn set_unit_ref(&self, service: String) -> Result<(), Box<dyn Error>> {
         if !self.comm.um().load_unit_success(&service) {
             return Err(format!("failed to load unit {}", service).into());
@@ -151,6 +159,20 @@ impl SocketConfig {
         Ok(())
     }
 
+    // the names are joined by ":" in LISTEN_FDNAMES
+    fn parse_fd_name(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(name) = self.fd_name() {
+            if name.is_empty()
+                || name.len() > FD_NAME_MAX
+                || !name.chars().all(|c| c.is_ascii_graphic() && c != ':')
+            {
+                return Err(format!("invalid FileDescriptorName: {}", name).into());
+            }
+        }
+
+        Ok(())
+    }
+
     fn parse_port(&self) -> Result<(), Box<dyn Error>> {
         log::debug!("begin to parse socket section");
 
@@ -527,4 +549,24 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_socket_parse_fd_name() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/fdname.socket.toml");
+
+        let comm = Rc::new(SocketUnitComm::new());
+        let config = SocketConfig::new(&comm);
+        assert!(config.load(vec![file_path], false).is_ok());
+        assert_eq!(config.fd_name(), Some("web".to_string()));
+
+        // load() falls back to reset() on the invalid names, which needs the reliability
+        let config = SocketConfig::new(&comm);
+        for name in ["", "web:admin", "web admin", &"a".repeat(256)] {
+            config.data.borrow_mut().Socket.FileDescriptorName = Some(name.to_string());
+            assert!(config.parse_fd_name().is_err());
+        }
+        config.data.borrow_mut().Socket.FileDescriptorName = None;
+        assert!(config.parse_fd_name().is_ok());
+    }
 }
@@ -3,42 +3,68 @@
 
 use crate::socket_comm::SocketUnitComm;
 use crate::socket_config::SocketConfig;
-use crate::socket_rentry::PortType;
-use libsysmaster::manager::{UnitRelations, UnitType};
-use std::path::Path;
+use libsysmaster::manager::{ExecContext, UnitRelations, UnitType};
+use nix::sys::stat::Mode;
+use std::path::{Path, PathBuf};
 use std::{error::Error, rc::Rc};
 
 pub(super) struct SocketLoad {
     config: Rc<SocketConfig>,
     comm: Rc<SocketUnitComm>,
+    exec_ctx: Rc<ExecContext>,
 }
 
 impl SocketLoad {
-    pub(super) fn new(configr: &Rc<SocketConfig>, commr: &Rc<SocketUnitComm>) -> Self {
+    pub(super) fn new(
+        configr: &Rc<SocketConfig>,
+        commr: &Rc<SocketUnitComm>,
+        exec_ctx: &Rc<ExecContext>,
+    ) -> Self {
         SocketLoad {
             config: configr.clone(),
             comm: commr.clone(),
+            exec_ctx: exec_ctx.clone(),
         }
     }
 
+    pub(super) fn parse(&self) {
+        let config = self.config.config_data();
+        let data = config.borrow();
+        let socket = &data.Socket;
+        self.exec_ctx.set_user(socket.User.clone());
+        self.exec_ctx.set_group(socket.Group.clone());
+        self.exec_ctx
+            .set_supplementary_groups(socket.SupplementaryGroups.clone().unwrap_or_default());
+        self.exec_ctx
+            .set_umask(socket.UMask.map(Mode::from_bits_truncate));
+        self.exec_ctx
+            .set_working_directory(socket.WorkingDirectory.as_ref().map(PathBuf::from));
+        self.exec_ctx.set_dynamic_user(socket.DynamicUser);
+    }
+
     pub(super) fn socket_add_extras(&self) -> Result<(), Box<dyn Error>> {
         log::debug!("socket add extras");
-        if self.can_accept() {
-            if self.config.unit_ref_target().is_none() {
-                self.load_related_unit(UnitType::UnitService)?;
-            }
-
-            self.comm.unit().insert_two_deps(
-                UnitRelations::UnitBefore,
-                UnitRelations::UnitTriggers,
-                self.config.unit_ref_target().unwrap(),
-            );
+        // the connections accepted by the Accept= socket are also handed to the service
+        if self.config.unit_ref_target().is_none() {
+            self.load_related_unit(UnitType::UnitService)?;
         }
 
+        self.comm.unit().insert_two_deps(
+            UnitRelations::UnitBefore,
+            UnitRelations::UnitTriggers,
+            self.config.unit_ref_target().unwrap(),
+        );
+
         Ok(())
     }
 
     pub(super) fn socket_verify(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = &self.config.config_data().borrow().Socket.WorkingDirectory {
+            if !Path::new(dir).is_absolute() {
+                return Err(format!("WorkingDirectory={} is not an absolute path", dir).into());
+            }
+        }
+
         Ok(())
     }
 
@@ -55,28 +81,6 @@ impl SocketLoad {
 
         Ok(())
     }
-
-    fn can_accept(&self) -> bool {
-        if !self.config.config_data().borrow().Socket.Accept {
-            return true;
-        };
-
-        self.no_accept_socket()
-    }
-
-    fn no_accept_socket(&self) -> bool {
-        for port in self.config.ports().iter() {
-            if port.p_type() != PortType::Socket {
-                return true;
-            }
-
-            if !port.sa().can_accept() {
-                return true;
-            }
-        }
-
-        false
-    }
 }
 
 #[cfg(test)]
@@ -11,7 +11,7 @@ use super::{
 use libevent::EventState;
 use libevent::{EventType, Events, Source};
 use libsysmaster::manager::{
-    ExecCommand, ExecContext, KillOperation, ReliLastFrame, UnitActionError, UnitActiveState,
+    ExecCommand, ExecContext, KillOperation, ReliLastFrame, Unit, UnitActionError, UnitActiveState,
     UnitNotifyFlags, UnitType,
 };
 use libsysmaster::{ReStation, Reliability};
@@ -19,6 +19,7 @@ use libutils::Error;
 use libutils::IN_SET;
 use nix::errno::Errno;
 use nix::libc::{self};
+use nix::unistd;
 use nix::{sys::signal::Signal, unistd::Pid};
 use std::cell::RefCell;
 use std::os::unix::prelude::RawFd;
@@ -122,10 +123,23 @@ impl SocketMng {
         self.data.current_active_state()
     }
 
-    pub(super) fn collect_fds(&self) -> Vec<i32> {
+    pub(super) fn get_state(&self) -> String {
+        self.data.state().to_string()
+    }
+
+    pub(super) fn get_result(&self) -> String {
+        self.data.result().to_string()
+    }
+
+    pub(super) fn collect_fds(&self) -> Vec<(i32, String)> {
         self.data.collect_fds()
     }
 
+    pub(super) fn trigger_notify(&self, other: Rc<Unit>) {
+        self.data.trigger_notify(other);
+        self.db_update();
+    }
+
     pub(super) fn build_ports(&self) {
         self.data.build_ports(&self.data)
     }
@@ -145,6 +159,8 @@ struct SocketMngData {
     control_cmd_type: RefCell<Option<SocketCommand>>,
     control_command: RefCell<Vec<ExecCommand>>,
     refused: RefCell<i32>,
+    // the connection accepted by the Accept= socket, which is handed to the service
+    accepted: RefCell<RawFd>,
 }
 
 // the declaration "pub(self)" is for identification only.
@@ -166,6 +182,7 @@ impl SocketMngData {
             control_cmd_type: RefCell::new(None),
             control_command: RefCell::new(Vec::new()),
             refused: RefCell::new(0),
+            accepted: RefCell::new(-1),
         });
 
         mng
@@ -274,11 +291,21 @@ impl SocketMngData {
         self.ports.borrow_mut().clear();
     }
 
-    pub(self) fn collect_fds(&self) -> Vec<i32> {
+    pub(self) fn collect_fds(&self) -> Vec<(i32, String)> {
+        let accepted = *self.accepted.borrow();
+        if accepted >= 0 {
+            return vec![(accepted, "connection".to_string())];
+        }
+
+        // all the fds of the socket share the same name, which is the unit name by default
+        let name = self
+            .config
+            .fd_name()
+            .unwrap_or_else(|| self.comm.unit().id().to_string());
         let mut fds = Vec::new();
         for port in self.ports().iter() {
             if port.fd() >= 0 {
-                fds.push(port.fd() as i32);
+                fds.push((port.fd() as i32, name.clone()));
             }
         }
 
@@ -359,6 +386,7 @@ impl SocketMngData {
         if self.comm.um().has_stop_job(self.comm.unit().id()) {
             if fd >= 0 {
                 *self.refused.borrow_mut() += 1;
+                close_fd(fd);
                 return;
             }
 
@@ -366,32 +394,66 @@ impl SocketMngData {
             return;
         }
 
-        if fd < 0 {
-            if !self
-                .comm
-                .um()
-                .relation_active_or_pending(self.comm.unit().id())
-            {
-                if self.config.unit_ref_target().is_none() {
-                    self.enter_stop_pre(SocketResult::FailureResources);
-                    return;
-                }
-                let service = self.config.unit_ref_target().unwrap();
-
-                // start corresponding *.service
-                self.rentry().set_last_frame(SocketReFrame::FdListen(false)); // protect 'start_unit'
-                let ret = self.comm.um().start_unit(&service);
-                self.rentry().set_last_frame(SocketReFrame::FdListen(true));
-                if ret.is_err() {
-                    self.enter_stop_pre(SocketResult::FailureResources);
-                    return;
-                }
+        let active = self
+            .comm
+            .um()
+            .relation_active_or_pending(self.comm.unit().id());
+        if fd >= 0 {
+            // the service serves one connection at a time without the template support
+            if active {
+                log::warn!(
+                    "{} refused the connection, the service is still running.",
+                    self.comm.unit().id()
+                );
+                *self.refused.borrow_mut() += 1;
+                close_fd(fd);
+                return;
             }
+            *self.accepted.borrow_mut() = fd;
+        }
 
-            self.set_state(SocketState::Running);
-        } else {
-            // template support
-            todo!()
+        if fd >= 0 || !active {
+            if self.config.unit_ref_target().is_none() {
+                self.close_accepted();
+                self.enter_stop_pre(SocketResult::FailureResources);
+                return;
+            }
+            let service = self.config.unit_ref_target().unwrap();
+
+            // start corresponding *.service
+            self.rentry().set_last_frame(SocketReFrame::FdListen(false)); // protect 'start_unit'
+            let ret = self.comm.um().start_unit(&service);
+            self.rentry().set_last_frame(SocketReFrame::FdListen(true));
+            if ret.is_err() {
+                self.close_accepted();
+                self.enter_stop_pre(SocketResult::FailureResources);
+                return;
+            }
+        }
+
+        self.set_state(SocketState::Running);
+    }
+
+    pub(self) fn trigger_notify(&self, other: Rc<Unit>) {
+        if self.state() != SocketState::Running {
+            return;
+        }
+
+        // the service is finished, listen again for the next activation
+        if IN_SET!(
+            other.current_active_state(),
+            UnitActiveState::UnitInActive,
+            UnitActiveState::UnitFailed
+        ) {
+            self.close_accepted();
+            self.enter_listening();
+        }
+    }
+
+    fn close_accepted(&self) {
+        let fd = self.accepted.replace(-1);
+        if fd >= 0 {
+            close_fd(fd);
         }
     }
 
@@ -544,6 +606,7 @@ impl SocketMngData {
         for port in self.ports().iter() {
             port.close(true);
         }
+        self.close_accepted();
     }
 
     fn watch_fds(&self) {
@@ -632,6 +695,7 @@ impl SocketMngData {
     }
 
     fn control_command_fill(&self, cmd_type: SocketCommand) {
+        *self.control_cmd_type.borrow_mut() = Some(cmd_type);
         if let Some(cmds) = self.config.get_exec_cmds(cmd_type) {
             *self.control_command.borrow_mut() = cmds
         }
@@ -641,6 +705,19 @@ impl SocketMngData {
         self.control_command.borrow_mut().pop()
     }
 
+    // the running command is the last popped one, whose failure is ignored with the "-" prefix
+    fn control_command_ignore_failure(&self) -> bool {
+        let cmd_type = match *self.control_cmd_type.borrow() {
+            Some(cmd_type) => cmd_type,
+            None => return false,
+        };
+        let len = self.control_command.borrow().len();
+        self.config
+            .get_exec_cmds(cmd_type)
+            .and_then(|cmds| cmds.get(len).map(|cmd| cmd.ignore_failure()))
+            .unwrap_or(false)
+    }
+
     fn control_command_update(&self, cmd_type: Option<SocketCommand>, len: usize) {
         if let Some(c_type) = cmd_type {
             self.control_command.borrow_mut().clear();
@@ -729,13 +806,21 @@ impl SocketMngData {
 // the declaration "pub(self)" is for identification only.
 impl SocketMngData {
     pub(self) fn sigchld_event(&self, _pid: Pid, code: i32, status: Signal) {
-        let res: SocketResult;
+        let mut res: SocketResult;
         if code == 0 {
             res = SocketResult::Success;
         } else if status != Signal::SIGCHLD {
             res = SocketResult::FailureSignal;
         } else {
-            res = SocketResult::Success
+            res = SocketResult::FailureExitCode;
+        }
+
+        if res != SocketResult::Success && self.control_command_ignore_failure() {
+            log::debug!(
+                "ignore the failure of the control command of {}",
+                self.comm.unit().id()
+            );
+            res = SocketResult::Success;
         }
 
         if !self.control_command.borrow().is_empty() && res == SocketResult::Success {
@@ -838,7 +923,7 @@ impl SocketMngPort {
     }
 
     fn dispatch_io(&self) -> Result<i32, Error> {
-        let afd: i32 = -1;
+        let mut afd: i32 = -1;
 
         if self.mng().state() != SocketState::Listening {
             return Ok(0);
@@ -848,7 +933,7 @@ impl SocketMngPort {
             && self.port.p_type() == PortType::Socket
             && self.port.sa().can_accept()
         {
-            let afd = self
+            afd = self
                 .port
                 .accept()
                 .map_err(|_e| Error::Other { msg: "accept err" })?;
@@ -875,6 +960,12 @@ impl SocketMngPort {
     }
 }
 
+fn close_fd(fd: RawFd) {
+    if let Err(e) = unistd::close(fd) {
+        log::warn!("failed to close the accepted connection {}: {}", fd, e);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
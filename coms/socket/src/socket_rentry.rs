@@ -1,11 +1,12 @@
 #![allow(non_snake_case)]
 use confique::Config;
-use libsysmaster::manager::{DeserializeWith, ExecCommand, KillMode};
+use libsysmaster::manager::{deserialize_umask, DeserializeWith, ExecCommand, KillMode};
 use libsysmaster::{ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, Reliability};
 use nix::unistd::Pid;
 use serde::{Deserialize, Serialize};
 use std::os::unix::prelude::RawFd;
 use std::rc::Rc;
+use strum::Display;
 
 struct SocketReDb<K, V>(ReDb<K, V>);
 
@@ -36,6 +37,7 @@ pub(super) struct SectionSocket {
     pub PassPacketInfo: Option<bool>,
     #[config(default = false)]
     pub Accept: bool,
+    pub FileDescriptorName: Option<String>,
     pub Service: Option<String>,
     pub ReceiveBuffer: Option<u64>,
     pub SendBuffer: Option<u64>,
@@ -47,6 +49,15 @@ pub(super) struct SectionSocket {
     #[config(deserialize_with = KillMode::deserialize_with)]
     #[config(default = "none")]
     pub kill_mode: KillMode,
+    pub User: Option<String>,
+    pub Group: Option<String>,
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub SupplementaryGroups: Option<Vec<String>>,
+    #[config(deserialize_with = deserialize_umask)]
+    pub UMask: Option<u32>,
+    pub WorkingDirectory: Option<String>,
+    #[config(default = false)]
+    pub DynamicUser: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -64,7 +75,8 @@ impl SocketReConf {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "kebab-case")]
 pub(super) enum SocketState {
     Dead,
     StartPre,
@@ -84,17 +96,27 @@ pub(super) enum SocketState {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Display)]
 pub(super) enum SocketResult {
+    #[strum(serialize = "success")]
     Success,
+    #[strum(serialize = "resources")]
     FailureResources,
+    #[strum(serialize = "timeout")]
     FailureTimeout,
+    #[strum(serialize = "exit-code")]
     FailureExitCode,
+    #[strum(serialize = "signal")]
     FailureSignal,
+    #[strum(serialize = "core-dump")]
     FailureCoreDump,
+    #[strum(serialize = "start-limit-hit")]
     FailureStartLimitHit,
+    #[strum(serialize = "trigger-limit-hit")]
     FailureTriggerLimitHit,
+    #[strum(serialize = "service-start-limit-hit")]
     FailureServiceStartLimitHit,
+    #[strum(serialize = "invalid")]
     ResultInvalid,
 }
 
@@ -59,6 +59,8 @@ impl SubUnit for SocketUnit {
         log::debug!("socket begin to load conf file");
         self.config.load(paths, true)?;
 
+        self.load.parse();
+
         let ret = self.load.socket_add_extras();
         if ret.is_err() {
             self.config.reset();
@@ -107,10 +109,22 @@ impl SubUnit for SocketUnit {
         self.mng.current_active_state()
     }
 
-    fn collect_fds(&self) -> Vec<i32> {
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_subunit_result(&self) -> String {
+        self.mng.get_result()
+    }
+
+    fn collect_fds(&self) -> Vec<(i32, String)> {
         self.mng.collect_fds()
     }
 
+    fn trigger_notify(&self, other: Rc<Unit>) {
+        self.mng.trigger_notify(other)
+    }
+
     fn attach_unit(&self, unit: Rc<Unit>) {
         self.comm.attach_unit(unit);
         self.db_insert();
@@ -137,7 +151,7 @@ impl SocketUnit {
             comm: Rc::clone(&_comm),
             config: Rc::clone(&_config),
             mng: SocketMng::new(&_comm, &_config, &context),
-            load: SocketLoad::new(&_config, &_comm),
+            load: SocketLoad::new(&_config, &_comm, &context),
         }
     }
 }
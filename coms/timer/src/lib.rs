@@ -0,0 +1,61 @@
+//! Timer is the entry for the timer based activation of sysmaster. sysmaster refers to systemd, but it is not the same.
+//! A timer unit starts the unit it triggers when it elapses, the calendar events are based on the realtime clock and
+//! the others are based on the monotonic clock.
+//!
+//! The Timer configuration file contains the [Unit] [Timer] [Install] sections.
+//!
+//! ```toml
+//! [Timer]
+//! OnCalendar = "Mon..Fri *-*-* 02:00:00; Sat,Sun 04:00"
+//! OnBootSec = "15min"
+//! OnUnitActiveSec = "1h"
+//! OnUnitInactiveSec = "30min"
+//! Persistent = true
+//! AccuracySec = "1min"
+//! RandomizedDelaySec = "30s"
+//! Unit = "backup.service"
+//! ```
+//!
+//! ### OnCalendar
+//! The calendar events separated by ";", in the format "DayOfWeek Year-Month-Day Hour:Minute:Second [UTC]",
+//! or one of the shorthands like "daily". The timer elapses at every matched time.
+//!
+//! ### OnBootSec
+//! The time span relative to the boot of the system, it elapses once since boot.
+//!
+//! ### OnUnitActiveSec
+//! The time span relative to the last time the triggered unit was activated.
+//!
+//! ### OnUnitInactiveSec
+//! The time span relative to the last time the triggered unit was deactivated.
+//!
+//! ### Persistent
+//! true or false(default). The last trigger time is stored on disk, if OnCalendar= elapsed while the timer was
+//! not running, such as the system was powered off, the unit is triggered immediately when the timer starts.
+//!
+//! ### AccuracySec
+//! The elapse may be delayed by up to this time span to coalesce the wake-ups, defaults to 1min.
+//!
+//! ### RandomizedDelaySec
+//! The elapse is delayed by a random time between 0 and this time span, defaults to 0.
+//!
+//! ### Unit
+//! The unit to trigger, defaults to the service with the same name as the timer, like "backup.timer" with
+//! "backup.service". It can not be a timer unit.
+//!
+//! ## Automatic dependency
+//! The timer unit is ordered before the unit it triggers.
+//! ### Implicit dependency
+//! NA
+//! ### Default Dependency
+//! NA
+
+// dependency: timer_base -> timer_calendar -> timer_rentry -> timer_comm -> timer_config -> {timer_mng -> timer_unit} -> timer_manager
+mod timer_base;
+mod timer_calendar;
+mod timer_comm;
+mod timer_config;
+mod timer_manager;
+mod timer_mng;
+mod timer_rentry;
+mod timer_unit;
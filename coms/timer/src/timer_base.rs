@@ -0,0 +1,79 @@
+use libutils::time_util::{self, USEC_PER_SEC};
+use nix::sys::stat::utimes;
+use nix::sys::time::{TimeVal, TimeValLike};
+use nix::time::{clock_gettime, ClockId};
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+pub(super) const LOG_LEVEL: u32 = 4;
+pub(super) const PLUGIN_NAME: &str = "TimerUnit";
+// the last trigger time of the persistent timers is kept on disk, so it survives the reboot
+pub(super) const TIMER_STAMP_DIR: &str = "/var/lib/sysmaster/timers";
+
+/// the current time of the clock in microseconds
+pub(super) fn now_usec(clock: ClockId) -> u64 {
+    match clock_gettime(clock) {
+        Ok(ts) => ts.tv_sec() as u64 * USEC_PER_SEC + ts.tv_nsec() as u64 / 1000,
+        Err(_) => 0,
+    }
+}
+
+/// a random value in [0, max], used to spread the timers with RandomizedDelaySec=
+pub(super) fn random_usec(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+
+    let mut buf = [0u8; 8];
+    let ret = unsafe { libc::getrandom(buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+    if ret != buf.len() as isize {
+        log::debug!("Failed to get random data, the randomized delay is ignored.");
+        return 0;
+    }
+
+    u64::from_ne_bytes(buf) % max.saturating_add(1)
+}
+
+pub(super) fn stamp_path(unit_id: &str) -> PathBuf {
+    PathBuf::from(TIMER_STAMP_DIR).join(format!("stamp-{}", unit_id))
+}
+
+/// the modification time of the stamp file is the realtime of the last trigger
+pub(super) fn stamp_read(unit_id: &str) -> Option<u64> {
+    let mtime = fs::metadata(stamp_path(unit_id)).ok()?.modified().ok()?;
+    if mtime < UNIX_EPOCH {
+        return None;
+    }
+    Some(time_util::timespec_load(mtime) as u64)
+}
+
+pub(super) fn stamp_touch(unit_id: &str, realtime: u64) {
+    let path = stamp_path(unit_id);
+    if let Err(e) = fs::create_dir_all(TIMER_STAMP_DIR).and_then(|_| File::create(&path)) {
+        log::warn!("Failed to create the stamp file {:?}: {}", path, e);
+        return;
+    }
+
+    let tv = TimeVal::microseconds(realtime as i64);
+    if let Err(e) = utimes(&path, &tv, &tv) {
+        log::warn!("Failed to update the stamp file {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{now_usec, random_usec, stamp_path};
+    use nix::time::ClockId;
+
+    #[test]
+    fn test_timer_base() {
+        assert!(now_usec(ClockId::CLOCK_REALTIME) > now_usec(ClockId::CLOCK_MONOTONIC));
+        assert_eq!(random_usec(0), 0);
+        assert!(random_usec(10) <= 10);
+        assert_eq!(
+            stamp_path("foo.timer").to_str(),
+            Some("/var/lib/sysmaster/timers/stamp-foo.timer")
+        );
+    }
+}
@@ -0,0 +1,438 @@
+//! The calendar event of OnCalendar=, the format is "DayOfWeek Year-Month-Day Hour:Minute:Second [UTC]", such as
+//! "Mon..Fri *-*-* 09:00:00". Every component accepts "*", a value, a list "a,b", a range "a..b" and a
+//! repetition "a/step". The weekday, the date or the time can be omitted, the date defaults to "*-*-*" and the
+//! time defaults to "00:00:00". The shorthands minutely, hourly, daily, weekly, monthly, quarterly, semiannually
+//! and yearly are supported too. The time is local time unless "UTC" is appended.
+
+use libutils::time_util::USEC_PER_SEC;
+use std::mem::MaybeUninit;
+
+const YEAR_MIN: u32 = 1970;
+const YEAR_MAX: u32 = 2199;
+// enough to find the next elapse of any valid spec, like "*-02-29" which only matches in leap years
+const MAX_ITERATIONS: usize = 1000;
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CalendarComponent {
+    // None matches any value, the list is sorted
+    values: Option<Vec<u32>>,
+}
+
+impl CalendarComponent {
+    fn parse(s: &str, min: u32, max: u32) -> Result<CalendarComponent, String> {
+        if s == "*" {
+            return Ok(CalendarComponent { values: None });
+        }
+
+        let mut values = Vec::new();
+        for item in s.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((r, st)) => (r, Some(parse_number(st)?)),
+                None => (item, None),
+            };
+            if step == Some(0) {
+                return Err(format!("invalid repetition: {}", item));
+            }
+
+            let (low, high) = if range == "*" {
+                (min, max)
+            } else if let Some((l, h)) = range.split_once("..") {
+                (parse_number(l)?, parse_number(h)?)
+            } else {
+                let v = parse_number(range)?;
+                (v, if step.is_some() { max } else { v })
+            };
+            if low < min || high > max || low > high {
+                return Err(format!("value out of range: {}", item));
+            }
+
+            values.extend((low..=high).step_by(step.unwrap_or(1) as usize));
+        }
+
+        values.sort_unstable();
+        values.dedup();
+        Ok(CalendarComponent {
+            values: Some(values),
+        })
+    }
+
+    /// the smallest matched value which is not less than v
+    fn next(&self, v: u32) -> Option<u32> {
+        match &self.values {
+            None => Some(v),
+            Some(values) => values.iter().find(|x| **x >= v).copied(),
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<u32, String> {
+    s.parse::<u32>()
+        .map_err(|_| format!("invalid number: {}", s))
+}
+
+fn parse_weekday(s: &str) -> Result<usize, String> {
+    let lower = s.to_lowercase();
+    WEEKDAYS
+        .iter()
+        .position(|d| lower.len() >= 3 && lower.starts_with(d))
+        .ok_or_else(|| format!("invalid weekday: {}", s))
+}
+
+/// the parsed calendar event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CalendarSpec {
+    // bit n is set if tm_wday n matches, 0 is Sunday
+    weekdays: u8,
+    year: CalendarComponent,
+    month: CalendarComponent,
+    day: CalendarComponent,
+    hour: CalendarComponent,
+    minute: CalendarComponent,
+    second: CalendarComponent,
+    utc: bool,
+}
+
+impl CalendarSpec {
+    pub(super) fn parse(s: &str) -> Result<CalendarSpec, String> {
+        let mut tokens: Vec<&str> = s.split_whitespace().collect();
+        let utc = tokens.last() == Some(&"UTC");
+        if utc {
+            tokens.pop();
+        }
+        if tokens.is_empty() {
+            return Err(format!("invalid calendar event: {}", s));
+        }
+
+        if tokens.len() == 1 {
+            if let Some(expanded) = expand_shorthand(tokens[0]) {
+                let mut spec = CalendarSpec::parse(expanded)?;
+                spec.utc = utc;
+                return Ok(spec);
+            }
+        }
+
+        let mut tokens = tokens.into_iter().peekable();
+        let weekdays = match tokens.peek() {
+            Some(t) if t.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                parse_weekdays(tokens.next().unwrap())?
+            }
+            _ => 0x7f,
+        };
+
+        let mut date = None;
+        let mut time = None;
+        for token in tokens {
+            if token.contains(':') && time.is_none() {
+                time = Some(token);
+            } else if token.contains('-') && date.is_none() && time.is_none() {
+                date = Some(token);
+            } else {
+                return Err(format!("invalid calendar event: {}", s));
+            }
+        }
+
+        let date: Vec<&str> = date.unwrap_or("*-*-*").split('-').collect();
+        let (year, month, day) = match date.len() {
+            2 => ("*", date[0], date[1]),
+            3 => (date[0], date[1], date[2]),
+            _ => return Err(format!("invalid date in calendar event: {}", s)),
+        };
+
+        let time: Vec<&str> = time.unwrap_or("00:00:00").split(':').collect();
+        let (hour, minute, second) = match time.len() {
+            2 => (time[0], time[1], "00"),
+            3 => (time[0], time[1], time[2]),
+            _ => return Err(format!("invalid time in calendar event: {}", s)),
+        };
+
+        Ok(CalendarSpec {
+            weekdays,
+            year: CalendarComponent::parse(year, YEAR_MIN, YEAR_MAX)?,
+            month: CalendarComponent::parse(month, 1, 12)?,
+            day: CalendarComponent::parse(day, 1, 31)?,
+            hour: CalendarComponent::parse(hour, 0, 23)?,
+            minute: CalendarComponent::parse(minute, 0, 59)?,
+            second: CalendarComponent::parse(second, 0, 59)?,
+            utc,
+        })
+    }
+
+    /// the first realtime in microseconds strictly after the base which matches the spec,
+    /// None if there is no such time
+    pub(super) fn next_elapse(&self, base: u64) -> Option<u64> {
+        let mut tm = self.to_tm((base / USEC_PER_SEC + 1) as libc::time_t)?;
+
+        for _ in 0..MAX_ITERATIONS {
+            let year = (tm.tm_year + 1900) as u32;
+            match self.year.next(year) {
+                None => return None,
+                Some(y) if y != year => {
+                    tm.tm_year = y as i32 - 1900;
+                    tm.tm_mon = 0;
+                    tm.tm_mday = 1;
+                    reset_time(&mut tm, 0);
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let month = (tm.tm_mon + 1) as u32;
+            match self.month.next(month) {
+                None => {
+                    tm.tm_year += 1;
+                    tm.tm_mon = 0;
+                    tm.tm_mday = 1;
+                    reset_time(&mut tm, 0);
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                Some(m) if m != month => {
+                    tm.tm_mon = m as i32 - 1;
+                    tm.tm_mday = 1;
+                    reset_time(&mut tm, 0);
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let day = tm.tm_mday as u32;
+            match self.day.next(day) {
+                Some(d) if d == day => {}
+                Some(d) if d <= days_in_month(tm.tm_year + 1900, tm.tm_mon) => {
+                    tm.tm_mday = d as i32;
+                    reset_time(&mut tm, 0);
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                _ => {
+                    tm.tm_mon += 1;
+                    tm.tm_mday = 1;
+                    reset_time(&mut tm, 0);
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+            }
+
+            if self.weekdays & (1 << tm.tm_wday) == 0 {
+                tm.tm_mday += 1;
+                reset_time(&mut tm, 0);
+                self.normalize(&mut tm)?;
+                continue;
+            }
+
+            let hour = tm.tm_hour as u32;
+            match self.hour.next(hour) {
+                None => {
+                    tm.tm_mday += 1;
+                    reset_time(&mut tm, 0);
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                Some(h) if h != hour => {
+                    reset_time(&mut tm, h as i32);
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let minute = tm.tm_min as u32;
+            match self.minute.next(minute) {
+                None => {
+                    tm.tm_hour += 1;
+                    tm.tm_min = 0;
+                    tm.tm_sec = 0;
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                Some(m) if m != minute => {
+                    tm.tm_min = m as i32;
+                    tm.tm_sec = 0;
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let second = tm.tm_sec as u32;
+            match self.second.next(second) {
+                None => {
+                    tm.tm_min += 1;
+                    tm.tm_sec = 0;
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                Some(s) if s != second => {
+                    tm.tm_sec = s as i32;
+                    self.normalize(&mut tm)?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let t = self.normalize(&mut tm)?;
+            return Some(t as u64 * USEC_PER_SEC);
+        }
+
+        None
+    }
+
+    fn to_tm(&self, t: libc::time_t) -> Option<libc::tm> {
+        let mut tm = unsafe { MaybeUninit::<libc::tm>::zeroed().assume_init() };
+        let ret = unsafe {
+            if self.utc {
+                libc::gmtime_r(&t, &mut tm)
+            } else {
+                libc::localtime_r(&t, &mut tm)
+            }
+        };
+        if ret.is_null() {
+            return None;
+        }
+        Some(tm)
+    }
+
+    // fold the overflowed fields, and fill in the weekday
+    fn normalize(&self, tm: &mut libc::tm) -> Option<libc::time_t> {
+        tm.tm_isdst = -1;
+        let t = unsafe {
+            if self.utc {
+                libc::timegm(tm)
+            } else {
+                libc::mktime(tm)
+            }
+        };
+        if t < 0 {
+            return None;
+        }
+        *tm = self.to_tm(t)?;
+        Some(t)
+    }
+}
+
+fn reset_time(tm: &mut libc::tm, hour: i32) {
+    tm.tm_hour = hour;
+    tm.tm_min = 0;
+    tm.tm_sec = 0;
+}
+
+// month is 0-based like tm_mon
+fn days_in_month(year: i32, month: i32) -> u32 {
+    match month {
+        1 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        1 => 28,
+        3 | 5 | 8 | 10 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_weekdays(s: &str) -> Result<u8, String> {
+    let mut weekdays = 0u8;
+    for item in s.split(',') {
+        let (low, high) = match item.split_once("..").or_else(|| item.split_once('-')) {
+            Some((l, h)) => (parse_weekday(l)?, parse_weekday(h)?),
+            None => {
+                let d = parse_weekday(item)?;
+                (d, d)
+            }
+        };
+        if low > high {
+            return Err(format!("invalid weekday range: {}", item));
+        }
+
+        // Monday first, tm_wday counts from Sunday
+        for d in low..=high {
+            weekdays |= 1 << ((d + 1) % 7);
+        }
+    }
+    Ok(weekdays)
+}
+
+fn expand_shorthand(s: &str) -> Option<&'static str> {
+    let expanded = match s {
+        "minutely" => "*-*-* *:*:00",
+        "hourly" => "*-*-* *:00:00",
+        "daily" => "*-*-* 00:00:00",
+        "weekly" => "Mon *-*-* 00:00:00",
+        "monthly" => "*-*-01 00:00:00",
+        "quarterly" => "*-01,04,07,10-01 00:00:00",
+        "semiannually" => "*-01,07-01 00:00:00",
+        "yearly" | "annually" => "*-01-01 00:00:00",
+        _ => return None,
+    };
+    Some(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CalendarSpec;
+    use libutils::time_util::USEC_PER_SEC;
+
+    // 2023-01-01 00:00:00 UTC, Sunday
+    const JAN_1_2023: u64 = 1672531200 * USEC_PER_SEC;
+    const DAY: u64 = 86400 * USEC_PER_SEC;
+    const HOUR: u64 = 3600 * USEC_PER_SEC;
+
+    fn next(spec: &str, base: u64) -> Option<u64> {
+        CalendarSpec::parse(spec).unwrap().next_elapse(base)
+    }
+
+    #[test]
+    fn test_calendar_parse() {
+        assert_eq!(
+            CalendarSpec::parse("daily UTC").unwrap(),
+            CalendarSpec::parse("*-*-* 00:00:00 UTC").unwrap()
+        );
+        assert_eq!(
+            CalendarSpec::parse("weekly").unwrap(),
+            CalendarSpec::parse("Monday 00:00").unwrap()
+        );
+        assert_eq!(
+            CalendarSpec::parse("Sat,Sun").unwrap(),
+            CalendarSpec::parse("Sat..Sun *-*-* 00:00:00").unwrap()
+        );
+        assert!(CalendarSpec::parse("Mon-Fri 9:30").is_ok());
+        assert!(CalendarSpec::parse("2023-*-01/2 *:0/15:00").is_ok());
+
+        assert!(CalendarSpec::parse("").is_err());
+        assert!(CalendarSpec::parse("Foo *-*-*").is_err());
+        assert!(CalendarSpec::parse("*-13-01").is_err());
+        assert!(CalendarSpec::parse("25:00").is_err());
+        assert!(CalendarSpec::parse("*:*/0").is_err());
+        assert!(CalendarSpec::parse("*-*-* 00:00 00:00").is_err());
+    }
+
+    #[test]
+    fn test_calendar_next_elapse() {
+        // the next elapse is strictly after the base
+        assert_eq!(next("daily UTC", JAN_1_2023), Some(JAN_1_2023 + DAY));
+        assert_eq!(next("hourly UTC", JAN_1_2023 + 1), Some(JAN_1_2023 + HOUR));
+        assert_eq!(
+            next("*:0/15 UTC", JAN_1_2023 + 16 * 60 * USEC_PER_SEC),
+            Some(JAN_1_2023 + 30 * 60 * USEC_PER_SEC)
+        );
+
+        // from Saturday 2023-01-07 to Monday 2023-01-09 09:00
+        assert_eq!(
+            next("Mon..Fri 09:00 UTC", JAN_1_2023 + 6 * DAY),
+            Some(JAN_1_2023 + 8 * DAY + 9 * HOUR)
+        );
+
+        // 2023-02-15, the 30th is skipped in February
+        assert_eq!(
+            next("*-*-01,30 UTC", JAN_1_2023 + 45 * DAY),
+            Some(JAN_1_2023 + 59 * DAY)
+        );
+
+        // from 2023-03-01 to 2024-02-29
+        assert_eq!(
+            next("*-02-29 UTC", JAN_1_2023 + 59 * DAY),
+            Some(JAN_1_2023 + 424 * DAY)
+        );
+
+        assert_eq!(next("2022-*-* UTC", JAN_1_2023), None);
+    }
+}
@@ -0,0 +1,180 @@
+//!  Timer association unit object
+//! *  You need to notify the Unit object and change the method
+//! *  Get the attributes of the unit object
+//! *  Call relation: timer_ unit->timer_ mng->timer_ comm
+
+use super::timer_rentry::{SectionTimer, TimerRe, TimerResult, TimerState};
+use libsysmaster::manager::{UmIf, Unit};
+use libsysmaster::Reliability;
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, RwLock};
+
+pub(super) struct TimerUnitComm {
+    data: RefCell<TimerUnitCommData>,
+    umcomm: Arc<TimerUmComm>,
+}
+
+impl TimerUnitComm {
+    pub(super) fn new() -> Self {
+        TimerUnitComm {
+            data: RefCell::new(TimerUnitCommData::new()),
+            umcomm: TimerUmComm::get_instance(),
+        }
+    }
+
+    pub(super) fn attach_unit(&self, unit: Rc<Unit>) {
+        self.data.borrow_mut().attach_unit(unit);
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.umcomm.attach_um(um)
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.umcomm.attach_reli(reli)
+    }
+
+    pub(super) fn unit(&self) -> Rc<Unit> {
+        self.data.borrow().unit()
+    }
+
+    pub(super) fn rentry_conf_insert(&self, timer: &SectionTimer, unit: Option<String>) {
+        self.rentry().conf_insert(self.unit().id(), timer, unit)
+    }
+
+    pub(super) fn rentry_conf_get(&self) -> Option<(SectionTimer, Option<String>)> {
+        self.rentry().conf_get(self.unit().id())
+    }
+
+    pub(super) fn rentry_mng_insert(
+        &self,
+        state: TimerState,
+        result: TimerResult,
+        last_trigger: (u64, u64),
+    ) {
+        self.rentry()
+            .mng_insert(self.unit().id(), state, result, last_trigger)
+    }
+
+    pub(super) fn rentry_mng_get(&self) -> Option<(TimerState, TimerResult, (u64, u64))> {
+        self.rentry().mng_get(self.unit().id())
+    }
+
+    fn rentry(&self) -> Rc<TimerRe> {
+        self.umcomm.rentry()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        self.umcomm.um()
+    }
+}
+struct TimerUnitCommData {
+    unit: Weak<Unit>,
+}
+
+impl TimerUnitCommData {
+    pub(self) fn new() -> TimerUnitCommData {
+        TimerUnitCommData { unit: Weak::new() }
+    }
+
+    fn attach_unit(&mut self, unit: Rc<Unit>) {
+        self.unit = Rc::downgrade(&unit);
+    }
+
+    pub(self) fn unit(&self) -> Rc<Unit> {
+        self.unit.clone().upgrade().unwrap()
+    }
+}
+
+static TIMER_UM_COMM: Lazy<Arc<TimerUmComm>> = Lazy::new(|| {
+    let comm = TimerUmComm::new();
+    Arc::new(comm)
+});
+
+pub(super) struct TimerUmComm {
+    data: RwLock<TimerUmCommData>,
+}
+
+unsafe impl Send for TimerUmComm {}
+
+unsafe impl Sync for TimerUmComm {}
+
+impl TimerUmComm {
+    pub(super) fn new() -> Self {
+        TimerUmComm {
+            data: RwLock::new(TimerUmCommData::new()),
+        }
+    }
+
+    pub(super) fn attach_um(&self, um: Rc<dyn UmIf>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_um(um);
+    }
+
+    pub(super) fn attach_reli(&self, reli: Rc<Reliability>) {
+        let mut wdata = self.data.write().unwrap();
+        wdata.attach_reli(reli);
+    }
+
+    pub(super) fn get_instance() -> Arc<TimerUmComm> {
+        TIMER_UM_COMM.clone()
+    }
+
+    pub(super) fn um(&self) -> Rc<dyn UmIf> {
+        let rdata = self.data.read().unwrap();
+        rdata.um().unwrap()
+    }
+
+    pub(super) fn rentry(&self) -> Rc<TimerRe> {
+        let rdata = self.data.read().unwrap();
+        rdata.rentry()
+    }
+}
+
+struct TimerUmCommData {
+    // associated objects
+    um: Option<Rc<dyn UmIf>>,
+    reli: Weak<Reliability>,
+    rentry: Option<Rc<TimerRe>>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl TimerUmCommData {
+    pub(self) fn new() -> TimerUmCommData {
+        TimerUmCommData {
+            um: None,
+            reli: Weak::new(),
+            rentry: None,
+        }
+    }
+
+    pub(self) fn attach_um(&mut self, um: Rc<dyn UmIf>) {
+        if self.um.is_none() {
+            log::debug!("TimerUmComm attach_um action.");
+            self.um = Some(um);
+        }
+    }
+
+    pub(self) fn attach_reli(&mut self, reli: Rc<Reliability>) {
+        let old = self.reli.clone().upgrade();
+        if old.is_none() {
+            log::debug!("TimerUmComm attach_reli action.");
+            self.reli = Rc::downgrade(&reli);
+            self.rentry.replace(Rc::new(TimerRe::new(&reli)));
+        }
+    }
+
+    pub(self) fn um(&self) -> Option<Rc<dyn UmIf>> {
+        if let Some(ref um) = self.um {
+            Some(Rc::clone(um))
+        } else {
+            None
+        }
+    }
+
+    pub(self) fn rentry(&self) -> Rc<TimerRe> {
+        self.rentry.as_ref().cloned().unwrap()
+    }
+}
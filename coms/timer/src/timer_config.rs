@@ -0,0 +1,222 @@
+//! timer_config mod load the conf file list and convert it to structure which is defined in this mod.
+//!
+#![allow(non_snake_case)]
+use super::timer_calendar::CalendarSpec;
+use super::timer_comm::TimerUnitComm;
+use super::timer_rentry::SectionTimer;
+use confique::Config;
+use libsysmaster::manager::UnitRef;
+use libsysmaster::ReStation;
+use libutils::time_util::USEC_PER_MINUTE;
+use std::cell::RefCell;
+use std::error::Error;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+pub(super) struct TimerConfig {
+    // associated objects
+    comm: Rc<TimerUnitComm>,
+
+    // owned objects
+    /* original */
+    data: Rc<RefCell<TimerConfigData>>,
+    /* processed */
+    unit: RefCell<UnitRef>,
+    calendars: RefCell<Vec<CalendarSpec>>,
+}
+
+impl ReStation for TimerConfig {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        if let Some((timer, unit)) = self.comm.rentry_conf_get() {
+            self.data.replace(TimerConfigData::new(timer));
+
+            // UnitRef
+            if let Some(u) = unit {
+                self.set_unit_ref(u).unwrap();
+            }
+
+            // CalendarSpec
+            self.parse_calendars().unwrap();
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm
+            .rentry_conf_insert(&self.data.borrow().Timer, self.unit_ref_target());
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl TimerConfig {
+    pub(super) fn new(commr: &Rc<TimerUnitComm>) -> Self {
+        TimerConfig {
+            comm: Rc::clone(commr),
+            data: Rc::new(RefCell::new(TimerConfigData::default())),
+            unit: RefCell::new(UnitRef::new()),
+            calendars: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(super) fn load(&self, paths: Vec<PathBuf>, update: bool) -> Result<(), Box<dyn Error>> {
+        let mut builder = TimerConfigData::builder().env();
+
+        log::debug!("timer load path: {:?}", paths);
+        // fragment
+        for v in paths {
+            builder = builder.file(&v);
+        }
+
+        *self.data.borrow_mut() = builder.load()?;
+        self.parse_calendars()?;
+
+        if update {
+            self.db_update();
+        }
+
+        Ok(())
+    }
+
+    /// a timer without any elapse setting never elapses
+    pub(super) fn verify(&self) -> Result<(), Box<dyn Error>> {
+        let data = self.data.borrow();
+        if self.calendars.borrow().is_empty()
+            && data.Timer.OnBootSec.is_none()
+            && data.Timer.OnUnitActiveSec.is_none()
+            && data.Timer.OnUnitInactiveSec.is_none()
+        {
+            return Err("the timer has no OnCalendar=, OnBootSec=, OnUnitActiveSec= or OnUnitInactiveSec= setting".into());
+        }
+
+        Ok(())
+    }
+
+    /// the unit to trigger, the service with the same name if Unit= is not configured
+    pub(super) fn unit_name(&self, unit_id: &str) -> String {
+        match &self.data.borrow().Timer.Unit {
+            Some(unit) => unit.clone(),
+            None => {
+                let stem = unit_id.trim_end_matches(".timer");
+                format!("{}.service", stem)
+            }
+        }
+    }
+
+    pub(super) fn set_unit_ref(&self, unit: String) -> Result<(), Box<dyn Error>> {
+        if unit.ends_with(".timer") {
+            return Err(format!("timer can not trigger the timer unit {}", unit).into());
+        }
+
+        if !self.comm.um().load_unit_success(&unit) {
+            return Err(format!("failed to load unit {}", unit).into());
+        }
+
+        let source = self.comm.unit().id().to_string();
+        self.unit.borrow_mut().set_ref(source, unit);
+        self.db_update();
+
+        Ok(())
+    }
+
+    pub(super) fn unit_ref_target(&self) -> Option<String> {
+        self.unit.borrow().target().map(|v| v.to_string())
+    }
+
+    pub(super) fn calendars(&self) -> Vec<CalendarSpec> {
+        self.calendars.borrow().clone()
+    }
+
+    pub(super) fn on_boot_sec(&self) -> Option<u64> {
+        self.data.borrow().Timer.OnBootSec
+    }
+
+    pub(super) fn on_unit_active_sec(&self) -> Option<u64> {
+        self.data.borrow().Timer.OnUnitActiveSec
+    }
+
+    pub(super) fn on_unit_inactive_sec(&self) -> Option<u64> {
+        self.data.borrow().Timer.OnUnitInactiveSec
+    }
+
+    pub(super) fn persistent(&self) -> bool {
+        self.data.borrow().Timer.Persistent
+    }
+
+    /// the elapse may be delayed by up to AccuracySec= to coalesce the wake-ups, defaults to 1min
+    pub(super) fn accuracy_usec(&self) -> u64 {
+        self.data
+            .borrow()
+            .Timer
+            .AccuracySec
+            .unwrap_or(USEC_PER_MINUTE)
+    }
+
+    pub(super) fn randomized_delay_usec(&self) -> u64 {
+        self.data.borrow().Timer.RandomizedDelaySec.unwrap_or(0)
+    }
+
+    fn parse_calendars(&self) -> Result<(), Box<dyn Error>> {
+        let mut calendars = Vec::new();
+        if let Some(specs) = &self.data.borrow().Timer.OnCalendar {
+            for spec in specs.iter().filter(|s| !s.is_empty()) {
+                calendars.push(CalendarSpec::parse(spec)?);
+            }
+        }
+        self.calendars.replace(calendars);
+        Ok(())
+    }
+}
+
+#[derive(Config, Default, Debug)]
+pub(super) struct TimerConfigData {
+    #[config(nested)]
+    pub Timer: SectionTimer,
+}
+
+impl TimerConfigData {
+    pub(self) fn new(Timer: SectionTimer) -> TimerConfigData {
+        TimerConfigData { Timer }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timer_comm::TimerUnitComm;
+    use crate::timer_config::TimerConfig;
+    use libtests::get_project_root;
+    use libutils::time_util::{USEC_PER_HOUR, USEC_PER_MINUTE, USEC_PER_SEC};
+    use std::rc::Rc;
+
+    #[test]
+    fn test_timer_parse() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/backup.timer.toml");
+        let paths = vec![file_path];
+
+        let comm = Rc::new(TimerUnitComm::new());
+        let config = TimerConfig::new(&comm);
+        assert!(config.load(paths, false).is_ok());
+        assert!(config.verify().is_ok());
+
+        assert_eq!(config.calendars().len(), 2);
+        assert_eq!(config.on_boot_sec(), Some(15 * USEC_PER_MINUTE));
+        assert_eq!(config.on_unit_active_sec(), Some(USEC_PER_HOUR));
+        assert_eq!(config.on_unit_inactive_sec(), None);
+        assert!(config.persistent());
+        assert_eq!(config.accuracy_usec(), USEC_PER_SEC);
+        assert_eq!(config.randomized_delay_usec(), 30 * USEC_PER_SEC);
+        assert_eq!(config.unit_name("backup.timer"), "backup.service");
+    }
+
+    #[test]
+    fn test_timer_verify() {
+        let comm = Rc::new(TimerUnitComm::new());
+        let config = TimerConfig::new(&comm);
+        assert!(config.verify().is_err());
+        assert_eq!(config.accuracy_usec(), USEC_PER_MINUTE);
+        assert_eq!(config.unit_name("foo.timer"), "foo.service");
+    }
+}
@@ -0,0 +1,52 @@
+use super::timer_base::{LOG_LEVEL, PLUGIN_NAME};
+use super::timer_comm::TimerUmComm;
+use libsysmaster::manager::{UmIf, UnitManagerObj, UnitMngUtil};
+use libsysmaster::{ReStation, Reliability};
+use libutils::logger;
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct TimerManager {
+    comm: Arc<TimerUmComm>,
+}
+
+// the declaration "pub(self)" is for identification only.
+impl TimerManager {
+    pub(self) fn new() -> TimerManager {
+        let _comm = TimerUmComm::get_instance();
+        TimerManager {
+            comm: Arc::clone(&_comm),
+        }
+    }
+}
+
+impl UnitManagerObj for TimerManager {
+    // nothing to customize
+}
+
+impl ReStation for TimerManager {
+    // no input, no compensate
+
+    // no data
+
+    // reload: no external connections, no entry
+}
+
+impl UnitMngUtil for TimerManager {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+impl Default for TimerManager {
+    fn default() -> Self {
+        TimerManager::new()
+    }
+}
+
+use libsysmaster::declure_umobj_plugin;
+declure_umobj_plugin!(TimerManager, TimerManager::default, PLUGIN_NAME, LOG_LEVEL);
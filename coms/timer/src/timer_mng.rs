@@ -0,0 +1,550 @@
+//!  The core logic of the timer subclass
+
+use super::timer_base::{now_usec, random_usec, stamp_read, stamp_touch};
+use super::timer_comm::TimerUnitComm;
+use super::timer_config::TimerConfig;
+use super::timer_rentry::{TimerResult, TimerState};
+use libevent::{EventState, EventType, Events, Source};
+use libsysmaster::manager::{Unit, UnitActionError, UnitActiveState, UnitNotifyFlags};
+use libsysmaster::ReStation;
+use libutils::Error;
+use nix::time::ClockId;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+impl TimerState {
+    fn timer_state_to_unit_state(&self) -> UnitActiveState {
+        match *self {
+            TimerState::Dead => UnitActiveState::UnitInActive,
+            TimerState::Waiting | TimerState::Running | TimerState::Elapsed => {
+                UnitActiveState::UnitActive
+            }
+            TimerState::Failed => UnitActiveState::UnitFailed,
+        }
+    }
+}
+
+pub(super) struct TimerMng {
+    comm: Rc<TimerUnitComm>,
+    config: Rc<TimerConfig>,
+    state: RefCell<TimerState>,
+    result: RefCell<TimerResult>,
+    // (realtime, monotonic) of the last trigger, 0 if it is not triggered
+    last_trigger: RefCell<(u64, u64)>,
+    realtime: Rc<TimerSource>,
+    monotonic: Rc<TimerSource>,
+    defer: Rc<TimerDefer>,
+}
+
+impl ReStation for TimerMng {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        if let Some((state, result, last_trigger)) = self.comm.rentry_mng_get() {
+            *self.state.borrow_mut() = state;
+            *self.result.borrow_mut() = result;
+            *self.last_trigger.borrow_mut() = last_trigger;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.comm
+            .rentry_mng_insert(self.state(), self.result(), self.last_trigger());
+    }
+
+    // reload: no external connections, entry-only
+    fn entry_coldplug(&self) {
+        if self.state() == TimerState::Waiting {
+            self.enter_waiting(false);
+        }
+    }
+
+    fn entry_clear(&self) {
+        self.disable_timer(self.realtime.clone());
+        self.disable_timer(self.monotonic.clone());
+    }
+}
+
+impl TimerMng {
+    pub(super) fn new(_comm: &Rc<TimerUnitComm>, configr: &Rc<TimerConfig>) -> Self {
+        TimerMng {
+            comm: Rc::clone(_comm),
+            config: Rc::clone(configr),
+            state: RefCell::new(TimerState::Dead),
+            result: RefCell::new(TimerResult::Success),
+            last_trigger: RefCell::new((0, 0)),
+            realtime: Rc::new(TimerSource::new(true)),
+            monotonic: Rc::new(TimerSource::new(false)),
+            defer: Rc::new(TimerDefer::new()),
+        }
+    }
+
+    pub(super) fn attach_timer(&self, mngr: &Rc<TimerMng>) {
+        self.realtime.attach(Rc::downgrade(mngr));
+        self.monotonic.attach(Rc::downgrade(mngr));
+        self.defer.attach(Rc::downgrade(mngr));
+    }
+
+    pub(super) fn start_check(&self) -> Result<(), UnitActionError> {
+        if self.config.unit_ref_target().is_none() {
+            return Err(UnitActionError::UnitActionENoent);
+        }
+
+        if !self.comm.unit().test_start_limit() {
+            self.set_result(TimerResult::FailureStartLimitHit);
+            self.enter_dead(true);
+            return Err(UnitActionError::UnitActionECanceled);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn start_action(&self) {
+        self.result.replace(TimerResult::Success);
+
+        // The last trigger of the previous run is forgotten, except the persistent one. The stamp file
+        // survives the reboot, so the elapses missed while the system was down are caught up at once.
+        let mut last_trigger = (0, 0);
+        if self.config.persistent() {
+            let now_real = now_usec(ClockId::CLOCK_REALTIME);
+            let stamp = stamp_read(self.comm.unit().id()).unwrap_or(0);
+            let realtime = stamp.max(self.last_trigger().0);
+            if realtime < now_real {
+                last_trigger.0 = realtime;
+            }
+        }
+        self.last_trigger.replace(last_trigger);
+
+        self.enter_waiting(true);
+    }
+
+    pub(super) fn stop_action(&self) {
+        self.enter_dead(true);
+    }
+
+    pub(super) fn trigger_notify(&self, _other: Rc<Unit>) {
+        // the state of the triggered unit is being dispatched, re-calculate later
+        let events = self.comm.um().events();
+        let defer = self.defer.clone();
+        if let Err(e) = events
+            .add_source(defer.clone())
+            .and_then(|_| events.set_enabled(defer, EventState::OneShot))
+        {
+            log::error!("failed to enable the timer defer event: {}", e);
+        }
+    }
+
+    fn enter_dead(&self, notify: bool) {
+        if self.result() == TimerResult::Success {
+            self.set_state(TimerState::Dead, notify);
+        } else {
+            self.set_state(TimerState::Failed, notify);
+        }
+    }
+
+    fn enter_elapsed(&self, notify: bool) {
+        self.set_state(TimerState::Elapsed, notify);
+    }
+
+    fn enter_waiting(&self, notify: bool) {
+        let now_real = now_usec(ClockId::CLOCK_REALTIME);
+        let now_mono = now_usec(ClockId::CLOCK_MONOTONIC);
+        let (last_real, last_mono) = self.last_trigger();
+        let triggered = last_real > 0 || last_mono > 0;
+
+        let mut next_mono: Option<u64> = None;
+        let mut next_real: Option<u64> = None;
+
+        // OnBootSec= elapses only once since boot
+        if let Some(v) = self.config.on_boot_sec() {
+            if !(triggered && v < now_mono) {
+                next_mono = min_elapse(next_mono, v);
+            }
+        }
+
+        if let Some(unit) = self.trigger_unit() {
+            if let Some(v) = self.config.on_unit_active_sec() {
+                let base =
+                    realtime_to_monotonic(unit.inactive_exit_timestamp(), now_real, now_mono);
+                if let Some(b) = base {
+                    next_mono = min_elapse(next_mono, b.saturating_add(v));
+                }
+            }
+
+            if let Some(v) = self.config.on_unit_inactive_sec() {
+                let base =
+                    realtime_to_monotonic(unit.inactive_enter_timestamp(), now_real, now_mono);
+                if let Some(b) = base {
+                    next_mono = min_elapse(next_mono, b.saturating_add(v));
+                }
+            }
+        }
+
+        let base = if last_real > 0 { last_real } else { now_real };
+        for spec in self.config.calendars() {
+            if let Some(v) = spec.next_elapse(base) {
+                next_real = min_elapse(next_real, v);
+            }
+        }
+
+        if next_mono.is_none() && next_real.is_none() {
+            log::debug!("{} has no more elapse.", self.comm.unit().id());
+            self.disable_timer(self.realtime.clone());
+            self.disable_timer(self.monotonic.clone());
+            self.enter_elapsed(notify);
+            return;
+        }
+
+        // the same delay is applied to both clocks
+        let delay = random_usec(self.config.randomized_delay_usec());
+        let accuracy = self.config.accuracy_usec();
+        for (next, source) in [(next_mono, &self.monotonic), (next_real, &self.realtime)] {
+            match next {
+                Some(v) => {
+                    source.set_time(round_up(v.saturating_add(delay), accuracy));
+                    self.enable_timer(source.clone());
+                }
+                None => self.disable_timer(source.clone()),
+            }
+        }
+
+        self.set_state(TimerState::Waiting, notify);
+    }
+
+    fn enter_running(&self) {
+        let unit = match self.config.unit_ref_target() {
+            Some(u) => u,
+            None => {
+                self.set_result(TimerResult::FailureResources);
+                self.enter_dead(true);
+                return;
+            }
+        };
+
+        if let Err(e) = self.comm.um().start_unit(&unit) {
+            log::error!(
+                "Failed to start {} triggered by {}: {:?}",
+                unit,
+                self.comm.unit().id(),
+                e
+            );
+            self.set_result(TimerResult::FailureResources);
+            self.enter_dead(true);
+            return;
+        }
+
+        let last_trigger = (
+            now_usec(ClockId::CLOCK_REALTIME),
+            now_usec(ClockId::CLOCK_MONOTONIC),
+        );
+        self.last_trigger.replace(last_trigger);
+        if self.config.persistent() {
+            stamp_touch(self.comm.unit().id(), last_trigger.0);
+        }
+
+        self.set_state(TimerState::Running, true);
+    }
+
+    fn trigger_unit(&self) -> Option<Rc<Unit>> {
+        let name = self.config.unit_ref_target()?;
+        self.comm.um().units_get(&name)
+    }
+
+    fn enable_timer(&self, source: Rc<TimerSource>) {
+        let events = self.comm.um().events();
+        if let Err(e) = events
+            .add_source(source.clone())
+            .and_then(|_| events.set_enabled(source, EventState::On))
+        {
+            log::error!("failed to arm the timer: {}", e);
+        }
+    }
+
+    fn disable_timer(&self, source: Rc<TimerSource>) {
+        let events = self.comm.um().events();
+        if let Err(e) = events.set_enabled(source, EventState::Off) {
+            log::debug!("failed to disable the timer: {}", e);
+        }
+    }
+
+    fn dispatch_timer(&self) {
+        if self.state() != TimerState::Waiting {
+            return;
+        }
+
+        log::debug!("{} elapsed.", self.comm.unit().id());
+        self.enter_running();
+    }
+
+    fn dispatch_trigger(&self) {
+        match self.state() {
+            TimerState::Waiting | TimerState::Elapsed => self.enter_waiting(true),
+            TimerState::Running => {
+                // wait for the next elapse once the triggered unit is finished
+                if let Some(unit) = self.trigger_unit() {
+                    if matches!(
+                        unit.current_active_state(),
+                        UnitActiveState::UnitInActive | UnitActiveState::UnitFailed
+                    ) {
+                        self.enter_waiting(true);
+                    }
+                }
+            }
+            TimerState::Dead | TimerState::Failed => {}
+        }
+    }
+
+    fn set_state(&self, new_state: TimerState, notify: bool) {
+        let old_state = self.state();
+        self.change_state(new_state);
+
+        if new_state != TimerState::Waiting {
+            self.disable_timer(self.realtime.clone());
+            self.disable_timer(self.monotonic.clone());
+        }
+
+        if notify {
+            self.state_notify(new_state, old_state);
+        }
+    }
+
+    fn state_notify(&self, new_state: TimerState, old_state: TimerState) {
+        if new_state != old_state {
+            log::debug!(
+                "{} original state[{:?}] -> new state[{:?}]",
+                self.comm.unit().id(),
+                old_state,
+                new_state,
+            );
+        }
+
+        let old_unit_state = old_state.timer_state_to_unit_state();
+        let new_unit_state = new_state.timer_state_to_unit_state();
+        self.comm.unit().notify(
+            old_unit_state,
+            new_unit_state,
+            UnitNotifyFlags::UNIT_NOTIFY_RELOAD_FAILURE,
+        );
+
+        self.db_update();
+    }
+
+    fn change_state(&self, new_state: TimerState) {
+        self.state.replace(new_state);
+    }
+
+    fn state(&self) -> TimerState {
+        *self.state.borrow()
+    }
+
+    // keep the first failure
+    fn set_result(&self, result: TimerResult) {
+        if self.result() == TimerResult::Success {
+            self.result.replace(result);
+        }
+    }
+
+    fn result(&self) -> TimerResult {
+        *self.result.borrow()
+    }
+
+    fn last_trigger(&self) -> (u64, u64) {
+        *self.last_trigger.borrow()
+    }
+
+    pub(super) fn timer_state_to_unit_state(&self) -> UnitActiveState {
+        self.state().timer_state_to_unit_state()
+    }
+
+    pub(super) fn get_state(&self) -> String {
+        self.state().to_string()
+    }
+
+    pub(super) fn get_result(&self) -> String {
+        self.result().to_string()
+    }
+}
+
+fn min_elapse(cur: Option<u64>, v: u64) -> Option<u64> {
+    Some(cur.map_or(v, |c| c.min(v)))
+}
+
+// the unit timestamps are recorded in realtime, shift it to the monotonic clock
+fn realtime_to_monotonic(realtime: u64, now_real: u64, now_mono: u64) -> Option<u64> {
+    if realtime == 0 {
+        return None;
+    }
+    Some(now_mono.saturating_sub(now_real.saturating_sub(realtime)))
+}
+
+// delay the elapse to the next multiple of the accuracy, so the timers elapse together
+fn round_up(usec: u64, accuracy: u64) -> u64 {
+    if accuracy <= 1 {
+        return usec;
+    }
+    match usec % accuracy {
+        0 => usec,
+        r => usec.saturating_add(accuracy - r),
+    }
+}
+
+struct TimerSource {
+    // realtime for OnCalendar=, monotonic for the others
+    realtime: bool,
+    usec: RefCell<u64>,
+    mng: RefCell<Weak<TimerMng>>,
+}
+
+impl TimerSource {
+    fn new(realtime: bool) -> Self {
+        TimerSource {
+            realtime,
+            usec: RefCell::new(0),
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(self) fn attach(&self, mng: Weak<TimerMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    pub(self) fn set_time(&self, usec: u64) {
+        *self.usec.borrow_mut() = usec;
+    }
+
+    pub(self) fn mng(&self) -> Rc<TimerMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for TimerSource {
+    fn event_type(&self) -> EventType {
+        if self.realtime {
+            EventType::TimerRealtime
+        } else {
+            EventType::TimerMonotonic
+        }
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    // the elapse is an absolute time of the clock
+    fn time(&self) -> u64 {
+        *self.usec.borrow()
+    }
+
+    fn dispatch(&self, _: &Events) -> Result<i32, Error> {
+        self.mng().dispatch_timer();
+        self.mng().db_update();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+struct TimerDefer {
+    mng: RefCell<Weak<TimerMng>>,
+}
+
+impl TimerDefer {
+    fn new() -> Self {
+        TimerDefer {
+            mng: RefCell::new(Weak::new()),
+        }
+    }
+
+    pub(self) fn attach(&self, mng: Weak<TimerMng>) {
+        *self.mng.borrow_mut() = mng;
+    }
+
+    pub(self) fn mng(&self) -> Rc<TimerMng> {
+        self.mng.borrow().clone().upgrade().unwrap()
+    }
+}
+
+impl Source for TimerDefer {
+    fn event_type(&self) -> EventType {
+        EventType::Defer
+    }
+
+    fn epoll_event(&self) -> u32 {
+        0
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn dispatch(&self, _: &Events) -> Result<i32, Error> {
+        self.mng().dispatch_trigger();
+        self.mng().db_update();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{realtime_to_monotonic, round_up, TimerConfig, TimerMng, TimerState};
+    use super::{TimerResult, TimerUnitComm};
+    use libsysmaster::manager::UnitActiveState;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_timer_set_state() {
+        let _comm = Rc::new(TimerUnitComm::new());
+        let _config = Rc::new(TimerConfig::new(&_comm));
+        let tm = TimerMng::new(&_comm, &_config);
+        tm.change_state(TimerState::Elapsed);
+        assert_eq!(tm.state(), TimerState::Elapsed);
+        assert_eq!(tm.get_state(), "elapsed");
+
+        tm.set_result(TimerResult::FailureStartLimitHit);
+        tm.set_result(TimerResult::FailureResources);
+        assert_eq!(tm.get_result(), "start-limit-hit");
+    }
+
+    #[test]
+    fn test_timer_state_to_unit_state() {
+        assert_eq!(
+            TimerState::Dead.timer_state_to_unit_state(),
+            UnitActiveState::UnitInActive
+        );
+        assert_eq!(
+            TimerState::Running.timer_state_to_unit_state(),
+            UnitActiveState::UnitActive
+        );
+        assert_eq!(
+            TimerState::Elapsed.timer_state_to_unit_state(),
+            UnitActiveState::UnitActive
+        );
+        assert_eq!(
+            TimerState::Failed.timer_state_to_unit_state(),
+            UnitActiveState::UnitFailed
+        );
+    }
+
+    #[test]
+    fn test_timer_elapse_helpers() {
+        assert_eq!(round_up(61, 60), 120);
+        assert_eq!(round_up(120, 60), 120);
+        assert_eq!(round_up(61, 0), 61);
+        assert_eq!(realtime_to_monotonic(0, 1000, 100), None);
+        assert_eq!(realtime_to_monotonic(990, 1000, 100), Some(90));
+        assert_eq!(realtime_to_monotonic(10, 1000, 100), Some(0));
+    }
+}
@@ -0,0 +1,176 @@
+#![allow(non_snake_case)]
+use confique::Config;
+use libsysmaster::manager::{deserialize_timespan, DeserializeWith};
+use libsysmaster::{ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, Reliability};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use strum::Display;
+
+const RELI_DB_HTIMER_CONF: &str = "timerconf";
+const RELI_DB_HTIMER_MNG: &str = "timermng";
+
+#[derive(Config, Default, Clone, Debug, Serialize, Deserialize)]
+pub(super) struct SectionTimer {
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub OnCalendar: Option<Vec<String>>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub OnBootSec: Option<u64>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub OnUnitActiveSec: Option<u64>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub OnUnitInactiveSec: Option<u64>,
+    #[config(default = false)]
+    pub Persistent: bool,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub AccuracySec: Option<u64>,
+    #[config(deserialize_with = deserialize_timespan)]
+    pub RandomizedDelaySec: Option<u64>,
+    pub Unit: Option<String>,
+}
+
+// Timer waits in Waiting until it elapses, then it stays in Running while the triggered unit is running.
+// Elapsed means that no more elapse is left, Waiting, Running and Elapsed are all active.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub(super) enum TimerState {
+    Dead,
+    Waiting,
+    Running,
+    Elapsed,
+    Failed,
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, Display)]
+pub(super) enum TimerResult {
+    #[strum(serialize = "success")]
+    Success,
+    #[strum(serialize = "resources")]
+    FailureResources,
+    #[strum(serialize = "start-limit-hit")]
+    FailureStartLimitHit,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TimerReConf {
+    timer: SectionTimer,
+    unit: Option<String>,
+}
+
+impl TimerReConf {
+    fn new(timerr: &SectionTimer, unit: Option<String>) -> TimerReConf {
+        TimerReConf {
+            timer: timerr.clone(),
+            unit,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TimerReMng {
+    state: TimerState,
+    result: TimerResult,
+    last_trigger: (u64, u64), // (realtime, monotonic), in microseconds
+}
+
+impl TimerReMng {
+    fn new(state: TimerState, result: TimerResult, last_trigger: (u64, u64)) -> TimerReMng {
+        TimerReMng {
+            state,
+            result,
+            last_trigger,
+        }
+    }
+}
+
+struct TimerReDb<K, V>(ReDb<K, V>);
+
+pub(super) struct TimerRe {
+    // database: multi-instance(N)
+    conf: Rc<TimerReDb<String, TimerReConf>>, // RELI_DB_HTIMER_CONF; key: unit_id, data: config+unit;
+    mng: Rc<TimerReDb<String, TimerReMng>>, // RELI_DB_HTIMER_MNG; key: unit_id, data: state+last_trigger;
+}
+
+impl TimerRe {
+    pub(super) fn new(relir: &Reliability) -> TimerRe {
+        let conf = Rc::new(TimerReDb(ReDb::new(relir, RELI_DB_HTIMER_CONF)));
+        let mng = Rc::new(TimerReDb(ReDb::new(relir, RELI_DB_HTIMER_MNG)));
+        let rentry = TimerRe { conf, mng };
+        rentry.register(relir);
+        rentry
+    }
+
+    pub(super) fn conf_insert(&self, unit_id: &str, timer: &SectionTimer, unit: Option<String>) {
+        let conf = TimerReConf::new(timer, unit);
+        self.conf.0.insert(unit_id.to_string(), conf);
+    }
+
+    pub(super) fn conf_get(&self, unit_id: &String) -> Option<(SectionTimer, Option<String>)> {
+        let conf = self.conf.0.get(unit_id);
+        conf.map(|c| (c.timer, c.unit))
+    }
+
+    pub(super) fn mng_insert(
+        &self,
+        unit_id: &str,
+        state: TimerState,
+        result: TimerResult,
+        last_trigger: (u64, u64),
+    ) {
+        let mng = TimerReMng::new(state, result, last_trigger);
+        self.mng.0.insert(unit_id.to_string(), mng);
+    }
+
+    pub(super) fn mng_get(
+        &self,
+        unit_id: &String,
+    ) -> Option<(TimerState, TimerResult, (u64, u64))> {
+        let mng = self.mng.0.get(unit_id);
+        mng.map(|m| (m.state, m.result, m.last_trigger))
+    }
+
+    fn register(&self, relir: &Reliability) {
+        // reliability-db: RELI_DB_HTIMER_CONF
+        let db = Rc::clone(&self.conf);
+        relir.history_db_register(RELI_DB_HTIMER_CONF, db);
+
+        // reliability-db: RELI_DB_HTIMER_MNG
+        let db = Rc::clone(&self.mng);
+        relir.history_db_register(RELI_DB_HTIMER_MNG, db);
+    }
+}
+
+impl ReDbTable for TimerReDb<String, TimerReConf> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.0.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.0.set_ignore(ignore);
+    }
+}
+
+impl ReDbTable for TimerReDb<String, TimerReMng> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.0.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.0.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.0.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.0.set_ignore(ignore);
+    }
+}
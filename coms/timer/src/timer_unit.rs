@@ -0,0 +1,140 @@
+//! timer unit is entry of timer type of unit，need impl
+//! UnitObj,UnitMngUtil, UnitSubClass trait
+
+use super::timer_base::{LOG_LEVEL, PLUGIN_NAME};
+use super::timer_comm::TimerUnitComm;
+use super::timer_config::TimerConfig;
+use super::timer_mng::TimerMng;
+use libsysmaster::manager::{
+    SubUnit, UmIf, Unit, UnitActionError, UnitActiveState, UnitMngUtil, UnitRelations,
+};
+use libsysmaster::{ReStation, Reliability};
+use libutils::logger;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+struct TimerUnit {
+    comm: Rc<TimerUnitComm>,
+    config: Rc<TimerConfig>,
+    mng: Rc<TimerMng>,
+}
+
+impl ReStation for TimerUnit {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        self.config.db_map();
+        self.mng.db_map();
+    }
+
+    fn db_insert(&self) {
+        self.config.db_insert();
+        self.mng.db_insert();
+    }
+
+    // reload: no external connections, entry-only
+    fn entry_coldplug(&self) {
+        // rebuild external connections, like: timer, ...
+        self.mng.entry_coldplug();
+    }
+
+    fn entry_clear(&self) {
+        // release external connection, like: timer, ...
+        self.mng.entry_clear();
+    }
+}
+
+impl TimerUnit {
+    fn new(_um: Rc<dyn UmIf>) -> TimerUnit {
+        let _comm = Rc::new(TimerUnitComm::new());
+        let _config = Rc::new(TimerConfig::new(&_comm));
+        let _mng = Rc::new(TimerMng::new(&_comm, &_config));
+        _mng.attach_timer(&_mng);
+        TimerUnit {
+            comm: Rc::clone(&_comm),
+            config: Rc::clone(&_config),
+            mng: _mng,
+        }
+    }
+
+    // the timer is ordered before the unit it triggers
+    fn add_extras(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let unit_name = self.config.unit_name(self.comm.unit().id());
+        self.config.set_unit_ref(unit_name.clone())?;
+
+        self.comm.unit().insert_two_deps(
+            UnitRelations::UnitBefore,
+            UnitRelations::UnitTriggers,
+            unit_name,
+        );
+        Ok(())
+    }
+}
+
+impl SubUnit for TimerUnit {
+    fn load(&self, paths: Vec<PathBuf>) -> libutils::Result<(), Box<dyn std::error::Error>> {
+        self.config.load(paths, true)?;
+        self.config.verify()?;
+        self.add_extras()
+    }
+
+    fn current_active_state(&self) -> UnitActiveState {
+        self.mng.timer_state_to_unit_state()
+    }
+
+    fn get_subunit_state(&self) -> String {
+        self.mng.get_state()
+    }
+
+    fn get_subunit_result(&self) -> String {
+        self.mng.get_result()
+    }
+
+    fn attach_unit(&self, unit: Rc<Unit>) {
+        self.comm.attach_unit(unit);
+        self.db_insert();
+    }
+
+    fn trigger_notify(&self, other: Rc<Unit>) {
+        self.mng.trigger_notify(other)
+    }
+
+    fn init(&self) {}
+
+    fn done(&self) {}
+
+    fn dump(&self) {}
+
+    fn start(&self) -> libutils::Result<(), UnitActionError> {
+        self.mng.start_check()?;
+        self.mng.start_action();
+        Ok(())
+    }
+
+    fn stop(&self, _force: bool) -> libutils::Result<(), UnitActionError> {
+        self.mng.stop_action();
+        Ok(())
+    }
+
+    fn reload(&self) {}
+
+    fn kill(&self) {}
+
+    fn release_resources(&self) {}
+
+    fn reset_failed(&self) {}
+}
+
+impl UnitMngUtil for TimerUnit {
+    fn attach_um(&self, um: Rc<dyn UmIf>) {
+        self.comm.attach_um(um);
+    }
+
+    fn attach_reli(&self, reli: Rc<Reliability>) {
+        self.comm.attach_reli(reli);
+    }
+}
+
+use libsysmaster::declure_unitobj_plugin_with_param;
+declure_unitobj_plugin_with_param!(TimerUnit, TimerUnit::new, PLUGIN_NAME, LOG_LEVEL);
@@ -76,7 +76,7 @@ fn cgtype_to_path(cg_type: CgType) -> &'static str {
     }
 }
 
-fn cg_abs_path(cg_path: &PathBuf, suffix: &PathBuf) -> Result<PathBuf, CgroupErr> {
+pub(crate) fn cg_abs_path(cg_path: &PathBuf, suffix: &PathBuf) -> Result<PathBuf, CgroupErr> {
     let cg_type = cg_type()?;
     let base_path = cgtype_to_path(cg_type);
     let path_buf: PathBuf = PathBuf::from(base_path);
@@ -0,0 +1,203 @@
+//! the attributes of the controllers of the unified cgroup hierarchy
+
+use crate::cgroup::{cg_abs_path, cg_type};
+use crate::{CgController, CgType, CgroupErr};
+use nix::sys::stat::{self, SFlag};
+use std::fs;
+use std::io::{Error as IOError, ErrorKind};
+use std::path::{Path, PathBuf};
+
+// the value which means no limit
+const CG_MAX: &str = "max";
+
+fn cg_write_attr(cg_path: &PathBuf, attr: &str, value: &str) -> Result<(), CgroupErr> {
+    // the attributes of the legacy hierarchy are different, which are not supported
+    if cg_type()? != CgType::UnifiedV2 {
+        return Err(CgroupErr::NotSupported);
+    }
+
+    log::debug!("cgroup {:?} set {} to {}", cg_path, attr, value);
+    let path = cg_abs_path(cg_path, &PathBuf::from(attr))?;
+    fs::write(path, value).map_err(CgroupErr::IoError)
+}
+
+fn controller_names(controllers: CgController) -> Vec<&'static str> {
+    [
+        (CgController::CPU, "cpu"),
+        (CgController::CPUSET, "cpuset"),
+        (CgController::IO, "io"),
+        (CgController::MEMORY, "memory"),
+        (CgController::PIDS, "pids"),
+    ]
+    .iter()
+    .filter(|(c, _)| controllers.contains(*c))
+    .map(|(_, name)| *name)
+    .collect()
+}
+
+fn parse_controllers(content: &str) -> CgController {
+    let mut controllers = CgController::empty();
+    for name in content.split_whitespace() {
+        controllers |= match name {
+            "cpu" => CgController::CPU,
+            "cpuset" => CgController::CPUSET,
+            "io" => CgController::IO,
+            "memory" => CgController::MEMORY,
+            "pids" => CgController::PIDS,
+            _ => CgController::empty(),
+        };
+    }
+    controllers
+}
+
+fn limit_value(limit: Option<u64>) -> String {
+    match limit {
+        Some(v) => v.to_string(),
+        None => CG_MAX.to_string(),
+    }
+}
+
+/// enable the controllers for the cg_path, by writing them to cgroup.subtree_control of all its
+/// ancestors from the root. The controllers which are not available are skipped.
+pub fn cg_enable_controllers(cg_path: &Path, controllers: CgController) -> Result<(), CgroupErr> {
+    if cg_type()? != CgType::UnifiedV2 {
+        return Err(CgroupErr::NotSupported);
+    }
+
+    let mut ancestors: Vec<&Path> = cg_path.ancestors().skip(1).collect();
+    ancestors.reverse();
+    for ancestor in ancestors {
+        for name in controller_names(controllers) {
+            let value = format!("+{}", name);
+            if let Err(e) = cg_write_attr(&ancestor.to_path_buf(), "cgroup.subtree_control", &value)
+            {
+                log::debug!(
+                    "failed to enable controller {} in {:?}, ignoring: {}",
+                    name,
+                    ancestor,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// return the controllers which are enabled for the cg_path, read from its cgroup.controllers,
+/// the controllers which are not known are skipped.
+pub fn cg_enabled_controllers(cg_path: &PathBuf) -> Result<CgController, CgroupErr> {
+    if cg_type()? != CgType::UnifiedV2 {
+        return Err(CgroupErr::NotSupported);
+    }
+
+    let path = cg_abs_path(cg_path, &PathBuf::from("cgroup.controllers"))?;
+    let content = fs::read_to_string(path).map_err(CgroupErr::IoError)?;
+    Ok(parse_controllers(&content))
+}
+
+/// set the weight of the cpu time, written to cpu.weight, the valid range is [1, 10000]
+pub fn cg_set_cpu_weight(cg_path: &PathBuf, weight: u64) -> Result<(), CgroupErr> {
+    cg_write_attr(cg_path, "cpu.weight", &weight.to_string())
+}
+
+/// set the cpu time which is allowed in each period, both are in microseconds, written to cpu.max,
+/// None means no limit
+pub fn cg_set_cpu_quota(
+    cg_path: &PathBuf,
+    quota: Option<u64>,
+    period: u64,
+) -> Result<(), CgroupErr> {
+    let value = format!("{} {}", limit_value(quota), period);
+    cg_write_attr(cg_path, "cpu.max", &value)
+}
+
+/// set the hard limit of the memory in bytes, written to memory.max, None means no limit
+pub fn cg_set_memory_max(cg_path: &PathBuf, limit: Option<u64>) -> Result<(), CgroupErr> {
+    cg_write_attr(cg_path, "memory.max", &limit_value(limit))
+}
+
+/// set the throttle limit of the memory in bytes, written to memory.high, None means no limit
+pub fn cg_set_memory_high(cg_path: &PathBuf, limit: Option<u64>) -> Result<(), CgroupErr> {
+    cg_write_attr(cg_path, "memory.high", &limit_value(limit))
+}
+
+/// set the memory in bytes which is protected from reclaiming, written to memory.low
+pub fn cg_set_memory_low(cg_path: &PathBuf, protection: u64) -> Result<(), CgroupErr> {
+    cg_write_attr(cg_path, "memory.low", &protection.to_string())
+}
+
+/// set the max number of the tasks, written to pids.max, None means no limit
+pub fn cg_set_pids_max(cg_path: &PathBuf, limit: Option<u64>) -> Result<(), CgroupErr> {
+    cg_write_attr(cg_path, "pids.max", &limit_value(limit))
+}
+
+/// set the default weight of the io, written to io.weight, the valid range is [1, 10000]
+pub fn cg_set_io_weight(cg_path: &PathBuf, weight: u64) -> Result<(), CgroupErr> {
+    cg_write_attr(cg_path, "io.weight", &format!("default {}", weight))
+}
+
+/// set the weight of the io on the block device, written to io.weight, the device is the path
+/// of the device node, like "/dev/sda"
+pub fn cg_set_io_device_weight(
+    cg_path: &PathBuf,
+    device: &Path,
+    weight: u64,
+) -> Result<(), CgroupErr> {
+    let st = stat::stat(device).map_err(|e| CgroupErr::IoError(IOError::from(e)))?;
+    if !SFlag::from_bits_truncate(st.st_mode).contains(SFlag::S_IFBLK) {
+        return Err(CgroupErr::IoError(IOError::new(
+            ErrorKind::InvalidInput,
+            format!("{:?} is not a block device", device),
+        )));
+    }
+
+    let value = format!(
+        "{}:{} {}",
+        stat::major(st.st_rdev),
+        stat::minor(st.st_rdev),
+        weight
+    );
+    cg_write_attr(cg_path, "io.weight", &value)
+}
+
+/// set the cpus which the tasks can run on, like "0-3,6", written to cpuset.cpus, an empty
+/// string means the cpus of the parent
+pub fn cg_set_cpuset_cpus(cg_path: &PathBuf, cpus: &str) -> Result<(), CgroupErr> {
+    cg_write_attr(cg_path, "cpuset.cpus", cpus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{controller_names, limit_value, parse_controllers};
+    use crate::CgController;
+
+    #[test]
+    fn test_controller_names() {
+        assert_eq!(
+            controller_names(CgController::CPU | CgController::MEMORY),
+            vec!["cpu", "memory"]
+        );
+        assert_eq!(controller_names(CgController::all()).len(), 5);
+        assert!(controller_names(CgController::empty()).is_empty());
+    }
+
+    #[test]
+    fn test_parse_controllers() {
+        assert_eq!(
+            parse_controllers("cpuset cpu io memory hugetlb pids rdma misc\n"),
+            CgController::all()
+        );
+        assert_eq!(
+            parse_controllers("memory pids"),
+            CgController::MEMORY | CgController::PIDS
+        );
+        assert!(parse_controllers("").is_empty());
+    }
+
+    #[test]
+    fn test_limit_value() {
+        assert_eq!(limit_value(Some(1024)), "1024");
+        assert_eq!(limit_value(None), "max");
+    }
+}
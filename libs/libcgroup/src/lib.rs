@@ -6,6 +6,7 @@ use nix::errno::Errno;
 use std::io::Error;
 
 mod cgroup;
+mod cgroup_attr;
 
 bitflags! {
     /// the flag that operate on the cgroup controller
@@ -19,6 +20,22 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// the controllers of the unified cgroup hierarchy
+    pub struct CgController: u8 {
+        /// the cpu controller, cpu.weight and cpu.max
+        const CPU = 1 << 0;
+        /// the cpuset controller, cpuset.cpus
+        const CPUSET = 1 << 1;
+        /// the io controller, io.weight
+        const IO = 1 << 2;
+        /// the memory controller, memory.max, memory.high and memory.low
+        const MEMORY = 1 << 3;
+        /// the pids controller, pids.max
+        const PIDS = 1 << 4;
+    }
+}
+
 /// the cgroup version of the mounted
 #[derive(Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum CgType {
@@ -62,3 +79,8 @@ pub use crate::cgroup::cg_get_pids;
 pub use crate::cgroup::cg_is_empty_recursive;
 pub use crate::cgroup::cg_kill_recursive;
 pub use crate::cgroup::cg_type;
+pub use crate::cgroup_attr::{
+    cg_enable_controllers, cg_enabled_controllers, cg_set_cpu_quota, cg_set_cpu_weight,
+    cg_set_cpuset_cpus, cg_set_io_device_weight, cg_set_io_weight, cg_set_memory_high,
+    cg_set_memory_low, cg_set_memory_max, cg_set_pids_max,
+};
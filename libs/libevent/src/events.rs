@@ -4,6 +4,7 @@ use crate::{EventState, EventType, Poll, Signals, Source};
 
 use libutils::Error;
 use libutils::Result;
+use nix::errno::Errno;
 use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, InotifyEvent, WatchDescriptor};
 use nix::NixPath;
 
@@ -125,17 +126,22 @@ impl Events {
     }
 
     /// for inotify: add watch point to inotify event
-    pub fn add_watch<P: ?Sized + NixPath>(&self, path: &P, mask: AddWatchFlags) -> WatchDescriptor {
+    pub fn add_watch<P: ?Sized + NixPath>(
+        &self,
+        path: &P,
+        mask: AddWatchFlags,
+    ) -> Result<WatchDescriptor> {
         self.data.borrow_mut().add_watch(path, mask)
     }
 
-    /// for inotify: rm watch point to inotify event
-    pub fn rm_watch(&self, wd: WatchDescriptor) {
-        self.data.borrow_mut().rm_watch(wd);
+    /// for inotify: rm watch point to inotify event, the watch point is removed by the kernel
+    /// when the watched file is deleted, so the failure is returned instead of panicking
+    pub fn rm_watch(&self, wd: WatchDescriptor) -> Result<()> {
+        self.data.borrow_mut().rm_watch(wd)
     }
 
     /// for inotify: read the inotify event when dispatch
-    pub fn read_events(&self) -> Vec<InotifyEvent> {
+    pub fn read_events(&self) -> Result<Vec<InotifyEvent>> {
         self.data.borrow_mut().read_events()
     }
 
@@ -306,8 +312,10 @@ impl EventsData {
             | EventType::TimerBoottime
             | EventType::TimerMonotonic
             | EventType::TimerRealtimeAlarm
-            | EventType::TimerBoottimeAlarm => match self.timerfd.get(&et) {
-                None => {
+            | EventType::TimerBoottimeAlarm => {
+                // the timerfd is shared by all sources of the same clock and stays registered,
+                // it is re-armed or disarmed in prepare() according to the earliest source.
+                if !self.timerfd.contains_key(&et) {
                     let fd = unsafe {
                         libc::timerfd_create(
                             self.timer.clockid(&et),
@@ -315,11 +323,11 @@ impl EventsData {
                         )
                     };
                     self.timerfd.insert(et, fd);
+                    event.events = libc::EPOLLIN as u32;
                     self.poller.register(fd, &mut event)?;
-                    self.timer.push(source.clone());
                 }
-                Some(_) => self.timer.push(source.clone()),
-            },
+                self.timer.push(source.clone());
+            }
             EventType::Defer => {
                 self.pending.push(source.clone());
             }
@@ -361,12 +369,7 @@ impl EventsData {
             | EventType::TimerMonotonic
             | EventType::TimerRealtimeAlarm
             | EventType::TimerBoottimeAlarm => {
-                if self.timer.is_empty(&et) {
-                    let fd = self.timerfd.get(&et);
-                    if let Some(fd) = fd {
-                        self.poller.unregister(fd.as_raw_fd())?
-                    }
-                }
+                self.timer.remove(source);
             }
             EventType::Inotify => {
                 self.poller.unregister(self.inotify.as_raw_fd())?;
@@ -397,16 +400,25 @@ impl EventsData {
         &self,
         path: &P,
         mask: AddWatchFlags,
-    ) -> WatchDescriptor {
-        self.inotify.add_watch(path, mask).unwrap()
+    ) -> Result<WatchDescriptor> {
+        self.inotify
+            .add_watch(path, mask)
+            .map_err(|e| inotify_error("inotify_add_watch", e))
     }
 
-    pub(self) fn rm_watch(&self, wd: WatchDescriptor) {
-        self.inotify.rm_watch(wd).unwrap();
+    pub(self) fn rm_watch(&self, wd: WatchDescriptor) -> Result<()> {
+        self.inotify
+            .rm_watch(wd)
+            .map_err(|e| inotify_error("inotify_rm_watch", e))
     }
 
-    pub(self) fn read_events(&self) -> Vec<InotifyEvent> {
-        self.inotify.read_events().unwrap()
+    pub(self) fn read_events(&self) -> Result<Vec<InotifyEvent>> {
+        match self.inotify.read_events() {
+            Ok(events) => Ok(events),
+            // nothing to read, the inotify fd is non-blocking
+            Err(Errno::EAGAIN) => Ok(Vec::new()),
+            Err(e) => Err(inotify_error("read", e)),
+        }
     }
 
     /// Wait for the event event through poller
@@ -479,6 +491,13 @@ impl EventsData {
                         );
                     }
                 }
+            } else if let Some(fd) = self.timerfd.get(&et) {
+                // nothing left to wait for, disarm the timerfd so that it is not readable any more
+                let new_value = self.timer.timer_stored(0);
+                let mut old_value = MaybeUninit::<libc::itimerspec>::zeroed();
+                unsafe {
+                    libc::timerfd_settime(fd.as_raw_fd(), 0, &new_value, old_value.as_mut_ptr());
+                }
             }
         }
 
@@ -524,3 +543,11 @@ impl EventsData {
         self.timerfd.clear();
     }
 }
+
+fn inotify_error(syscall: &'static str, errno: Errno) -> Error {
+    Error::Syscall {
+        syscall,
+        ret: -1,
+        errno: errno as i32,
+    }
+}
@@ -108,19 +108,15 @@ impl Timer {
             },
             it_value: libc::timespec {
                 tv_sec: (next / USEC_PER_SEC) as i64,
-                tv_nsec: (next % USEC_PER_SEC) as i64,
+                tv_nsec: ((next % USEC_PER_SEC) * NSEC_PER_USEC) as i64,
             },
         }
     }
 
-    pub fn is_empty(&mut self, et: &EventType) -> bool {
-        if let Some(inner) = self.timer_set.get_mut(et) {
-            return inner.data.is_empty();
-        }
-        true
-    }
-
     pub fn push(&mut self, source: Rc<dyn Source>) {
+        // re-arming a source replaces its previous expiration
+        self.remove(&source);
+
         // calc the time
         let mut next = source.time_relative();
         if next != u64::MAX {
@@ -149,6 +145,12 @@ impl Timer {
         };
     }
 
+    pub fn remove(&mut self, source: &Rc<dyn Source>) {
+        if let Some(inner) = self.timer_set.get_mut(&source.event_type()) {
+            inner.remove(source.token());
+        }
+    }
+
     pub fn pop(&mut self, et: &EventType) -> Option<Rc<dyn Source>> {
         let next = self.timerid(et);
         // self.now();
@@ -186,6 +188,14 @@ impl TimerInner {
         self.data.push(source);
     }
 
+    pub fn remove(&mut self, token: u64) {
+        let data = mem::take(&mut self.data);
+        self.data = data
+            .into_iter()
+            .filter(|cd| cd.source.token() != token)
+            .collect();
+    }
+
     pub fn pop(&mut self, next: u64) -> Option<ClockData> {
         match self.data.peek() {
             Some(cd) => {
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod test {
     use libutils::Error;
+    use std::cell::RefCell;
     use std::os::unix::prelude::RawFd;
     use std::rc::Rc;
 
@@ -62,4 +63,69 @@ mod test {
 
         e.del_source(s.clone()).unwrap();
     }
+
+    #[derive(Debug)]
+    struct Delay {
+        usec: u64,
+        count: RefCell<u32>,
+    }
+
+    impl Delay {
+        fn new(usec: u64) -> Delay {
+            Delay {
+                usec,
+                count: RefCell::new(0),
+            }
+        }
+    }
+
+    impl Source for Delay {
+        fn event_type(&self) -> EventType {
+            EventType::TimerMonotonic
+        }
+
+        fn epoll_event(&self) -> u32 {
+            (libc::EPOLLIN) as u32
+        }
+
+        fn time_relative(&self) -> u64 {
+            self.usec
+        }
+
+        fn dispatch(&self, e: &Events) -> Result<i32, Error> {
+            *self.count.borrow_mut() += 1;
+            e.set_exit();
+            Ok(0)
+        }
+
+        fn token(&self) -> u64 {
+            let data: u64 = unsafe { std::mem::transmute(self) };
+            data
+        }
+    }
+
+    #[test]
+    fn test_timer_disarm() {
+        let e = Events::new().unwrap();
+        let early = Rc::new(Delay::new(10000));
+        let late = Rc::new(Delay::new(50000));
+        let s_early: Rc<dyn Source> = early.clone();
+        let s_late: Rc<dyn Source> = late.clone();
+        e.add_source(s_early.clone()).unwrap();
+        e.add_source(s_late.clone()).unwrap();
+
+        // the early timer is armed twice and disarmed, it must never be dispatched
+        e.set_enabled(s_early.clone(), EventState::On).unwrap();
+        e.set_enabled(s_early.clone(), EventState::On).unwrap();
+        e.set_enabled(s_late.clone(), EventState::OneShot).unwrap();
+        e.set_enabled(s_early.clone(), EventState::Off).unwrap();
+
+        e.rloop().unwrap();
+
+        assert_eq!(*early.count.borrow(), 0);
+        assert_eq!(*late.count.borrow(), 1);
+
+        e.del_source(s_early).unwrap();
+        e.del_source(s_late).unwrap();
+    }
 }
@@ -1,8 +1,23 @@
 //!
+use crate::Error;
+use crate::Result;
 use std::time::SystemTime;
 
 const USEC_INFINITY: u128 = u128::MAX;
 
+/// microseconds per millisecond
+pub const USEC_PER_MSEC: u64 = 1000;
+/// microseconds per second
+pub const USEC_PER_SEC: u64 = 1000000;
+/// microseconds per minute
+pub const USEC_PER_MINUTE: u64 = 60 * USEC_PER_SEC;
+/// microseconds per hour
+pub const USEC_PER_HOUR: u64 = 60 * USEC_PER_MINUTE;
+/// microseconds per day
+pub const USEC_PER_DAY: u64 = 24 * USEC_PER_HOUR;
+/// microseconds per week
+pub const USEC_PER_WEEK: u64 = 7 * USEC_PER_DAY;
+
 ///
 pub fn timespec_load(systime: SystemTime) -> u128 {
     match systime.duration_since(SystemTime::UNIX_EPOCH) {
@@ -10,3 +25,83 @@ pub fn timespec_load(systime: SystemTime) -> u128 {
         Err(_) => USEC_INFINITY,
     }
 }
+
+fn timespan_unit(unit: &str) -> Option<u64> {
+    let usec = match unit {
+        "us" | "usec" => 1,
+        "ms" | "msec" => USEC_PER_MSEC,
+        "" | "s" | "sec" | "second" | "seconds" => USEC_PER_SEC,
+        "m" | "min" | "minute" | "minutes" => USEC_PER_MINUTE,
+        "h" | "hr" | "hour" | "hours" => USEC_PER_HOUR,
+        "d" | "day" | "days" => USEC_PER_DAY,
+        "w" | "week" | "weeks" => USEC_PER_WEEK,
+        _ => return None,
+    };
+
+    Some(usec)
+}
+
+/// parse a time span to microseconds, like "5", "100ms" or "1min 30s"
+/// a number without unit is in seconds, "infinity" returns u64::MAX
+pub fn parse_timespan(item: &str) -> Result<u64> {
+    let item = item.trim();
+    if item.is_empty() {
+        return Err(Error::Other {
+            msg: "empty time span",
+        });
+    }
+
+    if item == "infinity" {
+        return Ok(u64::MAX);
+    }
+
+    let mut usec: u64 = 0;
+    let mut rest = item;
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        if num_len == 0 {
+            return Err(Error::Other {
+                msg: "invalid time span",
+            });
+        }
+        let num = rest[..num_len].parse::<f64>().map_err(|_| Error::Other {
+            msg: "invalid number in time span",
+        })?;
+
+        rest = rest[num_len..].trim_start();
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let multiplier = timespan_unit(&rest[..unit_len]).ok_or(Error::Other {
+            msg: "invalid unit in time span",
+        })?;
+
+        usec = usec.saturating_add((num * multiplier as f64) as u64);
+        rest = rest[unit_len..].trim_start();
+    }
+
+    Ok(usec)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_timespan() {
+        assert_eq!(parse_timespan("5").unwrap(), 5 * USEC_PER_SEC);
+        assert_eq!(parse_timespan("100ms").unwrap(), 100 * USEC_PER_MSEC);
+        assert_eq!(parse_timespan("1.5s").unwrap(), 1500 * USEC_PER_MSEC);
+        assert_eq!(
+            parse_timespan("1min 30s").unwrap(),
+            USEC_PER_MINUTE + 30 * USEC_PER_SEC
+        );
+        assert_eq!(parse_timespan("2 h").unwrap(), 2 * USEC_PER_HOUR);
+        assert_eq!(parse_timespan("infinity").unwrap(), u64::MAX);
+        assert!(parse_timespan("").is_err());
+        assert!(parse_timespan("5 parsecs").is_err());
+        assert!(parse_timespan("ms").is_err());
+    }
+}
@@ -6,6 +6,15 @@ fn main() {
     let mut config = prost_build::Config::new();
     config.bytes(["."]);
     config.type_attribute(".", "#[rustfmt::skip]");
+    // the query results are printed as json by pctrl
+    for msg in [
+        ".abi.UnitRow",
+        ".abi.JobInfo",
+        ".abi.UnitStatus",
+        ".abi.LogEntry",
+    ] {
+        config.type_attribute(msg, "#[derive(serde::Serialize)]");
+    }
     config
         .out_dir("src/proto")
         .compile_protos(&["abi.proto"], &["./src/proto"])
@@ -1,11 +1,15 @@
 use super::rentry::ReliLastFrame;
 use crate::proto::execute::ExecuterAction;
-use crate::proto::ProstServerStream;
+use crate::proto::{ProstServerStream, SCTL_SOCKET};
 use crate::reliability::Reliability;
 use libevent::{EventType, Events, Source};
 use libutils::{Error, Result};
-use std::net::{SocketAddr, TcpListener};
+use nix::sys::socket::{self, sockopt};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
 use std::os::unix::io::RawFd;
+use std::os::unix::net::UnixListener;
+use std::path::Path;
 use std::{os::unix::prelude::AsRawFd, rc::Rc};
 
 pub(super) struct Commands<T> {
@@ -14,16 +18,21 @@ pub(super) struct Commands<T> {
     command_action: Rc<T>,
 
     // owned objects
-    fd: TcpListener,
+    fd: UnixListener,
 }
 
 impl<T> Commands<T> {
     pub(super) fn new(relir: &Rc<Reliability>, comm_action: T) -> Self {
-        let addrs = [
-            SocketAddr::from(([127, 0, 0, 1], 9526)),
-            SocketAddr::from(([127, 0, 0, 1], 9527)),
-        ];
-        let fd = TcpListener::bind(&addrs[..]).unwrap();
+        let sock_path = Path::new(SCTL_SOCKET);
+        fs::create_dir_all(sock_path.parent().unwrap()).unwrap();
+        if let Err(e) = nix::unistd::unlink(sock_path) {
+            log::debug!("unlink path failed: {:?}, error: {}", sock_path, e);
+        }
+        let fd = UnixListener::bind(sock_path).unwrap();
+        // everyone can connect, the permission is checked with the peer credential.
+        if let Err(e) = fs::set_permissions(sock_path, fs::Permissions::from_mode(0o666)) {
+            log::warn!("set permissions of {:?} failed: {}", sock_path, e);
+        }
         Commands {
             reli: Rc::clone(relir),
             command_action: Rc::new(comm_action),
@@ -45,15 +54,21 @@ where
     }
 
     fn dispatch(&self, _e: &Events) -> Result<i32, Error> {
-        println!("Dispatching Command!");
+        log::debug!("Dispatching Command!");
 
         self.reli.set_last_frame1(ReliLastFrame::CmdOp as u32);
-        match self.fd.incoming().next() {
-            None => println!("None CommandRequest!"),
-            Some(stream) => {
-                println!("{:?}", stream);
-                let dispatch = ProstServerStream::new(stream.unwrap(), self.command_action.clone());
-                dispatch.process().unwrap();
+        match self.fd.accept() {
+            Err(e) => log::error!("accept command connection failed: {}", e),
+            Ok((stream, _)) => {
+                let cred = socket::getsockopt(stream.as_raw_fd(), sockopt::PeerCredentials)
+                    .map_err(|e| log::warn!("get peer credentials failed: {}", e))
+                    .ok();
+                let dispatch = ProstServerStream::new(stream, self.command_action.clone(), cred);
+                match dispatch.process() {
+                    Err(e) => log::error!("process command request failed: {}", e),
+                    Ok(Some(stream)) => self.command_action.subscribe(stream),
+                    Ok(None) => {}
+                }
             }
         }
         self.reli.clear_last_frame();
@@ -11,14 +11,15 @@ pub struct ManagerConfig {
 
 #[derive(Config, Default, Debug)]
 pub struct SectionManager {
+    /// the default RestartSec= of the services, in milliseconds
     #[config(default = 100)]
     pub DefaultRestartSec: u64,
+    /// the default TimeoutSec= of the units, in seconds
     #[config(default = 90)]
     pub DefaultTimeoutSec: u64,
 }
 
 impl ManagerConfig {
-    #[allow(dead_code)]
     pub fn new(file: Option<&str>) -> ManagerConfig {
         let builder = ManagerConfig::builder().env();
         let manager_config = builder.file(file.unwrap_or(SYSTEM_CONFIG));
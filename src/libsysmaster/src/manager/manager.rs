@@ -1,11 +1,13 @@
 #![allow(clippy::module_inception)]
 use super::commands::Commands;
+use super::config::ManagerConfig;
 use super::pre_install::{Install, PresetMode};
 use super::rentry::{ReliLastFrame, RELI_HISTORY_MAX_DBS};
 use super::signals::{SignalDispatcher, Signals};
-use super::unit::UnitManagerX;
+use super::unit::{JobTimeoutAction, UnitManagerX};
 use super::MngErrno;
 use crate::proto::execute::ExecuterAction;
+use crate::proto::{JobInfo, LogEntry, UnitRow, UnitStatus};
 use crate::reliability::Reliability;
 use libevent::{EventState, Events};
 use libutils::path_lookup::LookupPaths;
@@ -14,12 +16,18 @@ use libutils::Result;
 use nix::sys::reboot::{self, RebootMode};
 use nix::sys::signal::Signal;
 use std::cell::RefCell;
+use std::ffi::CString;
+use std::fs;
 use std::io::Error;
+use std::os::unix::net::UnixStream;
 use std::rc::Rc;
 
 /// maximal size of process's arguments
 pub const MANAGER_ARGS_SIZE_MAX: usize = 5; // 6 - 1
 
+// the system sleeps in the state written to it
+const SYS_POWER_STATE: &str = "/sys/power/state";
+
 struct SignalMgr {
     um: Rc<UnitManagerX>,
 }
@@ -73,37 +81,86 @@ impl SignalDispatcher for SignalMgr {
 
 struct CommandActionMgr {
     um: Rc<UnitManagerX>,
+    state: Rc<RefCell<State>>,
 }
 
 impl CommandActionMgr {
-    fn new(um: Rc<UnitManagerX>) -> Self {
-        CommandActionMgr { um: Rc::clone(&um) }
+    fn new(um: Rc<UnitManagerX>, state: Rc<RefCell<State>>) -> Self {
+        CommandActionMgr {
+            um: Rc::clone(&um),
+            state,
+        }
+    }
+
+    fn set_state(&self, state: State) -> Result<i32> {
+        *self.state.borrow_mut() = state;
+        Ok(0)
     }
 }
 
 impl ExecuterAction for CommandActionMgr {
-    fn start(&self, service_name: &str) -> Result<(), MngErrno> {
-        self.um.start_unit(service_name)
+    fn start(&self, service_name: &str) -> Result<u32, MngErrno> {
+        self.um.start_unit_job(service_name)
     }
 
     fn stop(&self, unit_name: &str) -> Result<(), MngErrno> {
         self.um.stop_unit(unit_name)
     }
 
+    fn restart(&self, unit_name: &str) -> Result<(), MngErrno> {
+        self.um.restart_unit(unit_name)
+    }
+
+    fn reload(&self, unit_name: &str) -> Result<(), MngErrno> {
+        self.um.reload_unit(unit_name)
+    }
+
+    fn kill(&self, unit_name: &str, signal: i32) -> Result<(), MngErrno> {
+        self.um.kill_unit(unit_name, signal)
+    }
+
+    fn isolate(&self, unit_name: &str) -> Result<(), MngErrno> {
+        self.um.isolate_unit(unit_name)
+    }
+
+    fn list_units(&self, all: bool) -> Vec<UnitRow> {
+        self.um.list_units(all)
+    }
+
+    fn list_jobs(&self) -> Vec<JobInfo> {
+        self.um.list_jobs()
+    }
+
+    fn status(&self, unit_name: &str) -> Result<UnitStatus, MngErrno> {
+        self.um.unit_status(unit_name)
+    }
+
+    fn logs(&self, unit_name: &str, since: u64) -> Result<Vec<LogEntry>, MngErrno> {
+        self.um.unit_logs(unit_name, since)
+    }
+
+    fn subscribe(&self, stream: UnixStream) {
+        self.um.subscribe(stream)
+    }
+
+    fn cancel_job(&self, job_id: u32) -> Result<(), MngErrno> {
+        self.um.cancel_job(job_id)
+    }
+
     fn suspend(&self) -> Result<i32> {
-        todo!()
+        self.set_state(State::Suspend)
     }
 
     fn poweroff(&self) -> Result<i32> {
-        todo!()
+        self.set_state(State::PowerOff)
     }
 
     fn reboot(&self) -> Result<i32> {
-        todo!()
+        self.set_state(State::Reboot)
     }
 
     fn halt(&self) -> Result<i32> {
-        todo!()
+        self.set_state(State::Halt)
     }
 
     fn disable(&self, unit_file: &str) -> Result<(), Error> {
@@ -113,6 +170,26 @@ impl ExecuterAction for CommandActionMgr {
     fn enable(&self, unit_file: &str) -> Result<(), Error> {
         self.um.enable_unit(unit_file)
     }
+
+    fn mask(&self, unit_file: &str) -> Result<(), Error> {
+        self.um.mask_unit(unit_file)
+    }
+
+    fn get_default(&self) -> Result<String, Error> {
+        self.um.get_default_target()
+    }
+
+    fn set_default(&self, unit_file: &str) -> Result<(), Error> {
+        self.um.set_default_target(unit_file)
+    }
+
+    fn daemon_reload(&self) -> Result<i32> {
+        self.set_state(State::ReLoad)
+    }
+
+    fn daemon_reexec(&self) -> Result<i32> {
+        self.set_state(State::ReExecute)
+    }
 }
 
 /// Encapsulate manager and expose api to the outside
@@ -123,7 +200,8 @@ pub struct Manager {
     signal: Rc<Signals<SignalMgr>>,
     mode: Mode,
     _action: Action,
-    state: RefCell<State>,
+    state: Rc<RefCell<State>>,
+    reboot_arg: RefCell<String>,
     um: Rc<UnitManagerX>,
     lookup_path: Rc<LookupPaths>,
 }
@@ -145,11 +223,13 @@ impl Manager {
         let mut l_path = LookupPaths::new();
         l_path.init_lookup_paths();
         let lookup_path = Rc::new(l_path);
-        let umx = Rc::new(UnitManagerX::new(&_event, &_reli, &lookup_path));
+        let config = Rc::new(ManagerConfig::new(None));
+        let umx = Rc::new(UnitManagerX::new(&_event, &_reli, &lookup_path, &config));
         let _signal = Rc::new(Signals::new(&_reli, SignalMgr::new(Rc::clone(&umx))));
+        let state = Rc::new(RefCell::new(State::Init));
         let _commands = Rc::new(Commands::new(
             &_reli,
-            CommandActionMgr::new(Rc::clone(&umx)),
+            CommandActionMgr::new(Rc::clone(&umx), Rc::clone(&state)),
         ));
         Manager {
             event: Rc::clone(&_event),
@@ -158,7 +238,8 @@ impl Manager {
             signal: _signal,
             mode,
             _action: action,
-            state: RefCell::new(State::Init),
+            state,
+            reboot_arg: RefCell::new(String::new()),
             um: umx,
             lookup_path,
         }
@@ -183,6 +264,11 @@ impl Manager {
             self.reli.set_last_frame1(ReliLastFrame::OtherEvent as u32);
             self.event.run(-1)?;
             self.reli.clear_last_frame();
+
+            // the action requested by the timed-out job
+            if let Some((action, argument)) = self.um.take_job_timeout_action() {
+                self.job_timeout_action(action, argument);
+            }
         }
 
         Ok(self.state())
@@ -234,7 +320,7 @@ impl Manager {
                 State::PowerOff => self.reboot(RebootMode::RB_POWER_OFF),
                 State::Halt => self.reboot(RebootMode::RB_HALT_SYSTEM),
                 State::KExec => self.reboot(RebootMode::RB_KEXEC),
-                State::Suspend => self.reboot(RebootMode::RB_SW_SUSPEND),
+                State::Suspend => self.do_suspend(),
                 _ => todo!(),
             };
         }
@@ -283,19 +369,45 @@ impl Manager {
         Ok(())
     }
 
+    fn job_timeout_action(&self, action: JobTimeoutAction, argument: String) {
+        match action {
+            JobTimeoutAction::None => {}
+            JobTimeoutAction::Reboot => {
+                *self.reboot_arg.borrow_mut() = argument;
+                self.set_state(State::Reboot);
+            }
+            JobTimeoutAction::Poweroff => self.set_state(State::PowerOff),
+        }
+    }
+
     fn reboot(&self, reboot_mode: RebootMode) {
         // self.start_unit("shutdown.target");
         let mut pids = process_util::kill_all_pids(15);
         pids = process_util::wait_pids(pids, 10000000);
-        if pids.is_empty() {
-            return;
+        if !pids.is_empty() {
+            pids = process_util::kill_all_pids(9);
+            process_util::wait_pids(pids, 10000000);
         }
-        pids = process_util::kill_all_pids(9);
-        process_util::wait_pids(pids, 10000000);
         log::info!("Rebooting...");
+        let argument = self.reboot_arg.borrow().clone();
+        if reboot_mode == RebootMode::RB_AUTOBOOT && !argument.is_empty() {
+            reboot_with_argument(&argument);
+        }
         let _ = reboot::reboot(reboot_mode); // make lint happy
     }
 
+    /// suspend the system to memory without touching the processes, and go on running when
+    /// it resumes
+    fn do_suspend(&self) {
+        log::info!("Suspending...");
+        // the write returns when the system resumes
+        match fs::write(SYS_POWER_STATE, "mem") {
+            Ok(_) => log::info!("Resumed from suspend."),
+            Err(e) => log::error!("Failed to suspend the system: {}", e),
+        }
+        self.set_state(State::Ok);
+    }
+
     fn register_ex(&self) {
         // data
         self.um.register_ex();
@@ -314,6 +426,25 @@ impl Manager {
     }
 }
 
+// pass the argument to the firmware or the boot loader, it returns only on failure
+fn reboot_with_argument(argument: &str) {
+    let argument = match CString::new(argument) {
+        Ok(argument) => argument,
+        Err(_) => return,
+    };
+
+    log::info!("Rebooting with argument: {:?}", argument);
+    unsafe {
+        libc::syscall(
+            libc::SYS_reboot,
+            libc::LINUX_REBOOT_MAGIC1,
+            libc::LINUX_REBOOT_MAGIC2,
+            libc::LINUX_REBOOT_CMD_RESTART2,
+            argument.as_ptr(),
+        );
+    }
+}
+
 /// manager running mode
 #[allow(missing_docs)]
 #[derive(PartialEq, Eq, Debug)]
@@ -2,10 +2,14 @@
 pub use manager::{Action, Manager, Mode, MANAGER_ARGS_SIZE_MAX};
 pub use rentry::ReliLastFrame;
 pub use unit::{
-    DeserializeWith, ExecCmdError, ExecCommand, ExecContext, ExecFlags, ExecParameters,
-    KillContext, KillMode, KillOperation, SubUnit, UmIf, Unit, UnitActionError, UnitActiveState,
-    UnitDependencyMask, UnitManager, UnitManagerObj, UnitMngUtil, UnitNotifyFlags, UnitRef,
-    UnitRelationAtom, UnitRelations, UnitType,
+    deserialize_cpu_affinity, deserialize_rlimit_sec, deserialize_rlimit_usec,
+    deserialize_syscall_archs, deserialize_timespan, deserialize_umask, CapabilitySet,
+    CpuSchedPolicy, DeserializeWith, ExecCmdError, ExecCommand, ExecCommandFlags, ExecContext,
+    ExecFlags, ExecInput, ExecLimits, ExecOutput, ExecParameters, ExecRlimit, ExecSandbox,
+    ExecSecurity, ExecStep, IoSchedClass, KillContext, KillMode, KillOperation, ProtectHome,
+    ProtectSystem, RateLimit, SecureBits, SubUnit, SyscallFilter, UmIf, Unit, UnitActionError,
+    UnitActiveState, UnitDependencyMask, UnitManager, UnitManagerObj, UnitMngUtil, UnitNotifyFlags,
+    UnitRef, UnitRelationAtom, UnitRelations, UnitType,
 };
 
 /// error number of manager
@@ -17,6 +17,9 @@ use std::{
 };
 use walkdir::{DirEntry, WalkDir};
 
+/// the unit started at boot, which links to the real target unit
+const DEFAULT_TARGET: &str = "default.target";
+
 #[derive(PartialEq, Eq)]
 pub(crate) enum PresetMode {
     // All,
@@ -442,6 +445,71 @@ impl Install {
         Ok(())
     }
 
+    /// mask one unit file by linking it to /dev/null
+    pub fn unit_mask_files(&self, file: &str) -> Result<(), Error> {
+        if unit_name_to_type(file) == UnitType::UnitTypeInvalid {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid unit name: {}", file),
+            ));
+        }
+
+        let target = Path::new(&self.lookup_path.persistent_path).join(file);
+        if target.is_file() && !target.is_symlink() {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("unit file {:?} exists, refuse to mask it", target),
+            ));
+        }
+
+        replace_symlink(Path::new("/dev/null"), &target)
+    }
+
+    /// return the unit name that default.target links to
+    pub fn get_default_target(&self) -> Result<String, Error> {
+        // the persistent path has the highest priority
+        for v in self.lookup_path.search_path.iter().rev() {
+            let path = Path::new(v).join(DEFAULT_TARGET);
+            if let Ok(target) = fs::read_link(&path) {
+                if let Some(name) = target.file_name() {
+                    return Ok(name.to_string_lossy().to_string());
+                }
+            }
+        }
+
+        Err(Error::new(
+            ErrorKind::NotFound,
+            format!("{} is not set", DEFAULT_TARGET),
+        ))
+    }
+
+    /// link default.target in the persistent path to the target unit file
+    pub fn set_default_target(&self, file: &str) -> Result<(), Error> {
+        if unit_name_to_type(file) != UnitType::UnitTarget {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a target unit", file),
+            ));
+        }
+
+        let source = self
+            .lookup_path
+            .search_path
+            .iter()
+            .rev()
+            .map(|v| Path::new(v).join(file))
+            .find(|p| p.is_file())
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!("unit file {} is not found", file),
+                )
+            })?;
+
+        let target = Path::new(&self.lookup_path.persistent_path).join(DEFAULT_TARGET);
+        replace_symlink(&source, &target)
+    }
+
     fn preset_one_file(&self, unit: &str, presets: &Presets) -> Result<(), Error> {
         log::debug!("preset one unit file {}", unit);
         if self.installed_unit(unit) {
@@ -721,6 +789,19 @@ impl Install {
     }
 }
 
+/// create the symlink {target} which points to {source}, the old one is replaced
+fn replace_symlink(source: &Path, target: &Path) -> Result<(), Error> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if target.is_symlink() {
+        fs::remove_file(target)?;
+    }
+
+    std::os::unix::fs::symlink(source, target)
+}
+
 #[cfg(test)]
 mod test {
     use super::{Install, PresetAction, PresetMode, PresetRule, Presets};
@@ -778,6 +859,32 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_mask_and_default_target() {
+        let dir = std::env::temp_dir().join(format!("pre_install_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("multi-user.target"), "").unwrap();
+
+        let mut l_path = LookupPaths::new();
+        l_path.search_path.push(dir.to_string_lossy().to_string());
+        l_path.persistent_path = dir.to_string_lossy().to_string();
+        let install = Install::new(PresetMode::Disable, Rc::new(l_path));
+
+        assert!(install.get_default_target().is_err());
+        assert!(install.set_default_target("test.service").is_err());
+        assert!(install.set_default_target("rescue.target").is_err());
+        install.set_default_target("multi-user.target").unwrap();
+        assert_eq!(install.get_default_target().unwrap(), "multi-user.target");
+
+        install.unit_mask_files("test.service").unwrap();
+        let link = std::fs::read_link(dir.join("test.service")).unwrap();
+        assert_eq!(link, std::path::Path::new("/dev/null"));
+        assert!(install.unit_mask_files("multi-user.target").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_preset_all() {
         let mut l_path = LookupPaths::new();
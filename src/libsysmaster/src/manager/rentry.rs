@@ -86,9 +86,11 @@ pub(super) const RELI_DB_HUNIT_BASE: &str = "ubase";
 pub(super) const RELI_DB_HUNIT_LOAD: &str = "uload";
 pub(super) const RELI_DB_HUNIT_CONFIG: &str = "uconfig";
 pub(super) const RELI_DB_HUNIT_CGROUP: &str = "ucgroup";
+pub(super) const RELI_DB_HUNIT_TIMESTAMP: &str = "utimestamp";
 pub(super) const RELI_DB_HUNIT_CHILD: &str = "uchild";
 pub(super) const RELI_DB_HUNIT_PPS: &str = "upps";
 pub(super) const RELI_DB_HUNIT_DEP: &str = "udep";
+pub(super) const RELI_DB_HUNIT_DYNUSER: &str = "udynuser";
 pub(super) const RELI_DB_HUM_NOTIFY: &str = "um-notify";
 /* service */
 #[allow(dead_code)]
@@ -104,14 +106,29 @@ const RELI_DB_HSOCKET_MNG: &str = "sockmng";
 const RELI_DB_HSOCKETM_FRAME: &str = "sockm-frame";
 /* mount */
 #[allow(dead_code)]
+const RELI_DB_HMOUNT_CONF: &str = "mntconf";
+#[allow(dead_code)]
 const RELI_DB_HMOUNT_MNG: &str = "mntmng";
 #[allow(dead_code)]
 const RELI_DB_HMOUNTM_FRAME: &str = "mntm-frame";
 #[allow(dead_code)]
 /* target */
 const RELI_DB_HTARGET_MNG: &str = "tarmng";
+/* timer */
+#[allow(dead_code)]
+const RELI_DB_HTIMER_CONF: &str = "timerconf";
+#[allow(dead_code)]
+const RELI_DB_HTIMER_MNG: &str = "timermng";
+/* path */
+#[allow(dead_code)]
+const RELI_DB_HPATH_CONF: &str = "pathconf";
+#[allow(dead_code)]
+const RELI_DB_HPATH_MNG: &str = "pathmng";
+/* slice */
+#[allow(dead_code)]
+const RELI_DB_HSLICE_MNG: &str = "slicemng";
 
-pub(super) const RELI_HISTORY_MAX_DBS: u32 = 18;
+pub(super) const RELI_HISTORY_MAX_DBS: u32 = 26;
 #[allow(dead_code)]
 static RELI_HISTORY_DB_NAME: [&str; RELI_HISTORY_MAX_DBS as usize] = [
     RELI_DB_HJOB_TRIGGER,
@@ -120,16 +137,24 @@ static RELI_HISTORY_DB_NAME: [&str; RELI_HISTORY_MAX_DBS as usize] = [
     RELI_DB_HUNIT_LOAD,
     RELI_DB_HUNIT_CONFIG,
     RELI_DB_HUNIT_CGROUP,
+    RELI_DB_HUNIT_TIMESTAMP,
     RELI_DB_HUNIT_CHILD,
     RELI_DB_HUNIT_PPS,
     RELI_DB_HUNIT_DEP,
+    RELI_DB_HUNIT_DYNUSER,
     RELI_DB_HUM_NOTIFY,
     RELI_DB_HSERVICE_CONF,
     RELI_DB_HSERVICE_MNG,
     RELI_DB_HSOCKET_CONF,
     RELI_DB_HSOCKET_MNG,
     RELI_DB_HSOCKETM_FRAME,
+    RELI_DB_HMOUNT_CONF,
     RELI_DB_HMOUNT_MNG,
     RELI_DB_HMOUNTM_FRAME,
     RELI_DB_HTARGET_MNG,
+    RELI_DB_HTIMER_CONF,
+    RELI_DB_HTIMER_MNG,
+    RELI_DB_HPATH_CONF,
+    RELI_DB_HPATH_MNG,
+    RELI_DB_HSLICE_MNG,
 ];
@@ -36,6 +36,20 @@ pub enum UnitActiveState {
     UnitMaintenance,
 }
 
+impl From<UnitActiveState> for String {
+    fn from(a_s: UnitActiveState) -> Self {
+        match a_s {
+            UnitActiveState::UnitActive => "active".into(),
+            UnitActiveState::UnitReloading => "reloading".into(),
+            UnitActiveState::UnitInActive => "inactive".into(),
+            UnitActiveState::UnitFailed => "failed".into(),
+            UnitActiveState::UnitActivating => "activating".into(),
+            UnitActiveState::UnitDeActivating => "deactivating".into(),
+            UnitActiveState::UnitMaintenance => "maintenance".into(),
+        }
+    }
+}
+
 bitflags! {
     /// notify unit state to manager
     pub struct UnitNotifyFlags: u8 {
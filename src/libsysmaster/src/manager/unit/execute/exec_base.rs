@@ -1,5 +1,11 @@
+use super::exec_limit::ExecLimits;
+use super::exec_sandbox::ExecSandbox;
+use super::exec_security::ExecSecurity;
+use super::exec_stdio::{ExecInput, ExecOutput};
 use bitflags::bitflags;
-use std::{cell::RefCell, collections::HashMap, ffi::CString, path::PathBuf, rc::Rc};
+use nix::errno::Errno;
+use nix::sys::stat::Mode;
+use std::{cell::RefCell, collections::HashMap, ffi::CString, fmt, path::PathBuf, rc::Rc};
 
 /// the error
 #[derive(Debug)]
@@ -12,12 +18,118 @@ pub enum ExecCmdError {
     SpawnError,
     /// exec error for create cgroup error
     CgroupError(String),
+    /// exec error for resolving the user or the group
+    CredentialError(String),
+    /// exec error for creating the pipe to the log collector
+    LogError(String),
+    /// exec error for reading the environment files
+    EnvironmentError(String),
+    /// exec error for expanding the specifiers in the command line
+    SpecifierError(String),
+    /// exec error for creating the runtime directory of the sandbox
+    SandboxError(String),
+    /// the step before executing the command failed with the errno, in the child or while
+    /// preparing it in the parent, like building the seccomp filter
+    ExecFailed(ExecStep, Errno),
+}
+
+impl fmt::Display for ExecCmdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecCmdError::Timeout => write!(f, "timeout"),
+            ExecCmdError::NoCmdFound => write!(f, "command not found"),
+            ExecCmdError::SpawnError => write!(f, "failed to fork the child"),
+            ExecCmdError::CgroupError(e) => write!(f, "cgroup error: {}", e),
+            ExecCmdError::CredentialError(e) => write!(f, "credential error: {}", e),
+            ExecCmdError::LogError(e) => write!(f, "log error: {}", e),
+            ExecCmdError::EnvironmentError(e) => write!(f, "environment error: {}", e),
+            ExecCmdError::SpecifierError(e) => write!(f, "specifier error: {}", e),
+            ExecCmdError::SandboxError(e) => write!(f, "sandbox error: {}", e),
+            ExecCmdError::ExecFailed(step, errno) => {
+                write!(f, "failed at step {}: {}", step, errno)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExecCmdError {}
+
+/// the steps of the child between forking and executing the command, which is reported to the
+/// parent when it fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecStep {
+    /// attach to the cgroup of the unit
+    Cgroup,
+    /// redirect the standard input, output and error
+    Stdio,
+    /// set up the sandbox
+    Sandbox,
+    /// close the needless fds and pass the sockets
+    Fds,
+    /// set the resource limits and the scheduling
+    Limits,
+    /// drop the capabilities and set the secure bits
+    Capabilities,
+    /// change the user, the group and the working directory
+    Credentials,
+    /// load the seccomp filter
+    Seccomp,
+    /// execute the command
+    Exec,
+}
+
+impl ExecStep {
+    const ALL: [ExecStep; 9] = [
+        ExecStep::Cgroup,
+        ExecStep::Stdio,
+        ExecStep::Sandbox,
+        ExecStep::Fds,
+        ExecStep::Limits,
+        ExecStep::Capabilities,
+        ExecStep::Credentials,
+        ExecStep::Seccomp,
+        ExecStep::Exec,
+    ];
+
+    pub(super) fn from_u32(v: u32) -> Option<ExecStep> {
+        ExecStep::ALL.get(v as usize).copied()
+    }
+}
+
+impl fmt::Display for ExecStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExecStep::Cgroup => "CGROUP",
+            ExecStep::Stdio => "STDIO",
+            ExecStep::Sandbox => "NAMESPACE",
+            ExecStep::Fds => "FDS",
+            ExecStep::Limits => "LIMITS",
+            ExecStep::Capabilities => "CAPABILITIES",
+            ExecStep::Credentials => "USER",
+            ExecStep::Seccomp => "SECCOMP",
+            ExecStep::Exec => "EXEC",
+        };
+        f.write_str(name)
+    }
 }
 
 /// the exec context that was parse from the unit file.
 /// like parsed from Environment field.
 pub struct ExecContext {
     envs: RefCell<HashMap<String, String>>,
+    env_files: RefCell<Vec<String>>,
+    user: RefCell<Option<String>>,
+    group: RefCell<Option<String>>,
+    supplementary_groups: RefCell<Vec<String>>,
+    umask: RefCell<Option<Mode>>,
+    working_directory: RefCell<Option<PathBuf>>,
+    dynamic_user: RefCell<bool>,
+    stdin: RefCell<ExecInput>,
+    stdout: RefCell<ExecOutput>,
+    stderr: RefCell<ExecOutput>,
+    limits: RefCell<ExecLimits>,
+    sandbox: RefCell<ExecSandbox>,
+    security: RefCell<ExecSecurity>,
 }
 
 impl Default for ExecContext {
@@ -31,6 +143,20 @@ impl ExecContext {
     pub fn new() -> ExecContext {
         ExecContext {
             envs: RefCell::new(HashMap::new()),
+            env_files: RefCell::new(Vec::new()),
+            user: RefCell::new(None),
+            group: RefCell::new(None),
+            supplementary_groups: RefCell::new(Vec::new()),
+            umask: RefCell::new(None),
+            working_directory: RefCell::new(None),
+            dynamic_user: RefCell::new(false),
+            stdin: RefCell::new(ExecInput::default()),
+            // the output is collected by default, the error is the same as the output
+            stdout: RefCell::new(ExecOutput::Log),
+            stderr: RefCell::new(ExecOutput::default()),
+            limits: RefCell::new(ExecLimits::default()),
+            sandbox: RefCell::new(ExecSandbox::default()),
+            security: RefCell::new(ExecSecurity::default()),
         }
     }
 
@@ -48,13 +174,132 @@ impl ExecContext {
         }
         tmp
     }
+
+    /// set the files which the environments are read from when the commands start,
+    /// the file prefixed with "-" is ignored if it does not exist
+    pub fn set_environment_files(&self, files: Vec<String>) {
+        *self.env_files.borrow_mut() = files;
+    }
+
+    /// return the environment files
+    pub fn environment_files(&self) -> Vec<String> {
+        self.env_files.borrow().clone()
+    }
+
+    /// set the user name or uid which the commands run as, root if not set
+    pub fn set_user(&self, user: Option<String>) {
+        *self.user.borrow_mut() = user;
+    }
+
+    /// return the user which the commands run as
+    pub fn user(&self) -> Option<String> {
+        self.user.borrow().clone()
+    }
+
+    /// set the group name or gid which the commands run as, the primary group of the user if not set
+    pub fn set_group(&self, group: Option<String>) {
+        *self.group.borrow_mut() = group;
+    }
+
+    /// return the group which the commands run as
+    pub fn group(&self) -> Option<String> {
+        self.group.borrow().clone()
+    }
+
+    /// set the supplementary groups which are added to the groups of the user
+    pub fn set_supplementary_groups(&self, groups: Vec<String>) {
+        *self.supplementary_groups.borrow_mut() = groups;
+    }
+
+    /// return the supplementary groups
+    pub fn supplementary_groups(&self) -> Vec<String> {
+        self.supplementary_groups.borrow().clone()
+    }
+
+    /// set the file mode creation mask of the commands
+    pub fn set_umask(&self, umask: Option<Mode>) {
+        *self.umask.borrow_mut() = umask;
+    }
+
+    /// return the file mode creation mask
+    pub fn umask(&self) -> Option<Mode> {
+        *self.umask.borrow()
+    }
+
+    /// set the working directory of the commands, "/" is kept if not set
+    pub fn set_working_directory(&self, dir: Option<PathBuf>) {
+        *self.working_directory.borrow_mut() = dir;
+    }
+
+    /// return the working directory
+    pub fn working_directory(&self) -> Option<PathBuf> {
+        self.working_directory.borrow().clone()
+    }
+
+    /// set whether the commands run as a transient user allocated when the unit starts
+    pub fn set_dynamic_user(&self, dynamic_user: bool) {
+        *self.dynamic_user.borrow_mut() = dynamic_user;
+    }
+
+    /// return whether the commands run as a transient user
+    pub fn dynamic_user(&self) -> bool {
+        *self.dynamic_user.borrow()
+    }
+
+    /// set the standard input, output and error of the commands
+    pub fn set_stdio(&self, stdin: ExecInput, stdout: ExecOutput, stderr: ExecOutput) {
+        *self.stdin.borrow_mut() = stdin;
+        *self.stdout.borrow_mut() = stdout;
+        *self.stderr.borrow_mut() = stderr;
+    }
+
+    /// return the standard input, output and error of the commands
+    pub fn stdio(&self) -> (ExecInput, ExecOutput, ExecOutput) {
+        (
+            self.stdin.borrow().clone(),
+            self.stdout.borrow().clone(),
+            self.stderr.borrow().clone(),
+        )
+    }
+
+    /// set the resource limits and the scheduling settings of the commands
+    pub fn set_limits(&self, limits: ExecLimits) {
+        *self.limits.borrow_mut() = limits;
+    }
+
+    /// return the resource limits and the scheduling settings
+    pub fn limits(&self) -> ExecLimits {
+        self.limits.borrow().clone()
+    }
+
+    /// set the sandbox which the commands run in
+    pub fn set_sandbox(&self, sandbox: ExecSandbox) {
+        *self.sandbox.borrow_mut() = sandbox;
+    }
+
+    /// return the sandbox settings
+    pub fn sandbox(&self) -> ExecSandbox {
+        self.sandbox.borrow().clone()
+    }
+
+    /// set the capabilities and the seccomp filter of the commands
+    pub fn set_security(&self, security: ExecSecurity) {
+        *self.security.borrow_mut() = security;
+    }
+
+    /// return the capabilities and the seccomp filter
+    pub fn security(&self) -> ExecSecurity {
+        self.security.borrow().clone()
+    }
 }
 
 /// the environment that will be set when start a new command
 pub struct ExecParameters {
     environment: Rc<EnvData>,
     fds: Vec<i32>,
+    fd_names: Vec<String>,
     notify_sock: Option<PathBuf>,
+    watchdog_usec: u64,
 }
 
 struct EnvData {
@@ -99,7 +344,9 @@ impl ExecParameters {
         ExecParameters {
             environment: Rc::new(EnvData::new()),
             fds: Vec::new(),
+            fd_names: Vec::new(),
             notify_sock: None,
+            watchdog_usec: 0,
         }
     }
 
@@ -128,10 +375,30 @@ impl ExecParameters {
         self.fds.to_vec()
     }
 
+    /// insert the names of the fds passed to child, in the same order as the fds
+    pub fn insert_fd_names(&mut self, fd_names: Vec<String>) {
+        self.fd_names = fd_names
+    }
+
+    /// return the names of the fds passed to child
+    pub fn fd_names(&self) -> Vec<String> {
+        self.fd_names.to_vec()
+    }
+
     /// set the NOTIFY_SOCKET value
     pub fn set_notify_sock(&mut self, notify_sock: PathBuf) {
         self.notify_sock = Some(notify_sock)
     }
+
+    /// set the WATCHDOG_USEC value, 0 means the watchdog is disabled
+    pub fn set_watchdog_usec(&mut self, usec: u64) {
+        self.watchdog_usec = usec
+    }
+
+    /// return the WATCHDOG_USEC value
+    pub fn watchdog_usec(&self) -> u64 {
+        self.watchdog_usec
+    }
 }
 
 bitflags! {
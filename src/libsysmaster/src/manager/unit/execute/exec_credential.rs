@@ -0,0 +1,206 @@
+use super::exec_base::{ExecCmdError, ExecContext};
+use nix::sys::stat;
+use nix::unistd::{self, Gid, Group, Uid, User};
+use std::ffi::CString;
+
+/// the range of the uids which are allocated to the units running with DynamicUser=
+pub(in crate::manager::unit) const DYNAMIC_UID_MIN: u32 = 61184;
+pub(in crate::manager::unit) const DYNAMIC_UID_MAX: u32 = 65519;
+
+/// pick the lowest uid of the dynamic range, which is neither used by the other units nor by the
+/// users and groups of the system
+pub(in crate::manager::unit) fn dynamic_uid_pick(used: &[u32]) -> Option<u32> {
+    (DYNAMIC_UID_MIN..=DYNAMIC_UID_MAX).find(|uid| {
+        !used.contains(uid)
+            && !matches!(User::from_uid(Uid::from_raw(*uid)), Ok(Some(_)))
+            && !matches!(Group::from_gid(Gid::from_raw(*uid)), Ok(Some(_)))
+    })
+}
+
+fn lookup_user(name: &str) -> Result<User, ExecCmdError> {
+    let user = match name.parse::<u32>() {
+        Ok(uid) => User::from_uid(Uid::from_raw(uid)),
+        Err(_) => User::from_name(name),
+    };
+    match user {
+        Ok(Some(u)) => Ok(u),
+        Ok(None) => Err(ExecCmdError::CredentialError(format!(
+            "user {} is not found",
+            name
+        ))),
+        Err(e) => Err(ExecCmdError::CredentialError(format!(
+            "failed to look up user {}: {}",
+            name, e
+        ))),
+    }
+}
+
+fn lookup_group(name: &str) -> Result<Gid, ExecCmdError> {
+    let group = match name.parse::<u32>() {
+        Ok(gid) => Group::from_gid(Gid::from_raw(gid)),
+        Err(_) => Group::from_name(name),
+    };
+    match group {
+        Ok(Some(g)) => Ok(g.gid),
+        Ok(None) => Err(ExecCmdError::CredentialError(format!(
+            "group {} is not found",
+            name
+        ))),
+        Err(e) => Err(ExecCmdError::CredentialError(format!(
+            "failed to look up group {}: {}",
+            name, e
+        ))),
+    }
+}
+
+/// the identity which the command runs with, it is resolved in the parent before forking,
+/// because looking up the users in the child is not async-signal-safe.
+#[derive(Default)]
+pub(super) struct ExecCredential {
+    uid: Option<Uid>,
+    gid: Option<Gid>,
+    groups: Vec<Gid>,
+    envs: Vec<(String, String)>,
+}
+
+impl ExecCredential {
+    pub(super) fn new(ctx: &ExecContext, dynamic_uid: Option<u32>) -> Result<Self, ExecCmdError> {
+        let mut cred = ExecCredential::default();
+
+        if let Some(uid) = dynamic_uid {
+            // the transient user has no entry in the user database, its group shares the id
+            cred.uid = Some(Uid::from_raw(uid));
+            cred.gid = Some(Gid::from_raw(uid));
+            if let Some(name) = ctx.user() {
+                cred.set_user_envs(&name, "/");
+            }
+        } else if let Some(name) = ctx.user() {
+            let user = lookup_user(&name)?;
+            let c_name = CString::new(user.name.as_str())
+                .map_err(|e| ExecCmdError::CredentialError(e.to_string()))?;
+            cred.groups = unistd::getgrouplist(&c_name, user.gid).map_err(|e| {
+                ExecCmdError::CredentialError(format!(
+                    "failed to get the groups of user {}: {}",
+                    name, e
+                ))
+            })?;
+            cred.uid = Some(user.uid);
+            cred.gid = Some(user.gid);
+            cred.set_user_envs(&user.name, &user.dir.to_string_lossy());
+        }
+
+        if dynamic_uid.is_none() {
+            if let Some(name) = ctx.group() {
+                cred.gid = Some(lookup_group(&name)?);
+            }
+        }
+
+        for name in ctx.supplementary_groups() {
+            let gid = lookup_group(&name)?;
+            if !cred.groups.contains(&gid) {
+                cred.groups.push(gid);
+            }
+        }
+
+        Ok(cred)
+    }
+
+    fn set_user_envs(&mut self, name: &str, home: &str) {
+        self.envs.push(("USER".to_string(), name.to_string()));
+        self.envs.push(("LOGNAME".to_string(), name.to_string()));
+        self.envs.push(("HOME".to_string(), home.to_string()));
+    }
+
+    /// keep the identity of the manager, for the commands with the "+" or "!" prefix. the
+    /// environments of the user are still set
+    pub(super) fn keep_identity(&mut self) {
+        self.uid = None;
+        self.gid = None;
+        self.groups.clear();
+    }
+
+    /// the environments of the user, like USER and HOME
+    pub(super) fn envs(&self) -> Vec<(String, String)> {
+        self.envs.clone()
+    }
+
+    /// the home directory of the user, which the "%h" specifier is replaced by
+    pub(super) fn home(&self) -> Option<&str> {
+        self.envs
+            .iter()
+            .find(|(key, _)| key == "HOME")
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// change the identity of the child, called before execve.
+    /// the groups must be changed before the user, which drops the privileges.
+    pub(super) fn apply(&self, ctx: &ExecContext) -> nix::Result<()> {
+        if let Some(mask) = ctx.umask() {
+            stat::umask(mask);
+        }
+
+        if self.uid.is_some() || self.gid.is_some() || !self.groups.is_empty() {
+            unistd::setgroups(&self.groups)?;
+        }
+
+        if let Some(gid) = self.gid {
+            unistd::setgid(gid)?;
+        }
+
+        if let Some(uid) = self.uid {
+            unistd::setuid(uid)?;
+        }
+
+        // the directory is checked with the permission of the user
+        if let Some(dir) = ctx.working_directory() {
+            unistd::chdir(&dir)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dynamic_uid_pick, ExecCredential, DYNAMIC_UID_MAX, DYNAMIC_UID_MIN};
+    use crate::manager::unit::execute::ExecContext;
+
+    #[test]
+    fn test_dynamic_uid_pick() {
+        let uid = dynamic_uid_pick(&[]).unwrap();
+        assert!((DYNAMIC_UID_MIN..=DYNAMIC_UID_MAX).contains(&uid));
+
+        let next = dynamic_uid_pick(&[uid]).unwrap();
+        assert_ne!(uid, next);
+    }
+
+    #[test]
+    fn test_credential_dynamic_user() {
+        let ctx = ExecContext::new();
+        ctx.set_user(Some("foo".to_string()));
+        ctx.set_dynamic_user(true);
+
+        let cred = ExecCredential::new(&ctx, Some(DYNAMIC_UID_MIN)).unwrap();
+        assert_eq!(cred.uid.unwrap().as_raw(), DYNAMIC_UID_MIN);
+        assert_eq!(cred.gid.unwrap().as_raw(), DYNAMIC_UID_MIN);
+        assert!(cred
+            .envs()
+            .contains(&("USER".to_string(), "foo".to_string())));
+    }
+
+    #[test]
+    fn test_credential_root() {
+        let ctx = ExecContext::new();
+        ctx.set_user(Some("0".to_string()));
+
+        let cred = ExecCredential::new(&ctx, None).unwrap();
+        assert_eq!(cred.uid.unwrap().as_raw(), 0);
+        assert!(cred
+            .envs()
+            .contains(&("USER".to_string(), "root".to_string())));
+
+        ctx.set_user(None);
+        ctx.set_group(Some("no-such-group-for-test".to_string()));
+        assert!(ExecCredential::new(&ctx, None).is_err());
+    }
+}
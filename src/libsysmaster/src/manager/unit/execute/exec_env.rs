@@ -0,0 +1,363 @@
+use super::exec_base::ExecCmdError;
+use nix::unistd;
+use std::fs;
+use std::path::Path;
+
+const PATH_MACHINE_ID: &str = "/etc/machine-id";
+const RUNTIME_DIR: &str = "/run";
+const ROOT_HOME: &str = "/root";
+
+/// parse the environment file, every line is an assignment like "KEY=VALUE", the value can be
+/// quoted with single or double quotes, and a line ending with a backslash is continued by the
+/// next line. the empty lines and the comments starting with "#" or ";" are ignored.
+pub(super) fn parse_env_file(content: &str) -> Vec<(String, String)> {
+    let mut envs = Vec::new();
+    let mut line = String::new();
+
+    for raw in content.lines() {
+        if let Some(part) = raw.strip_suffix('\\') {
+            line.push_str(part);
+            continue;
+        }
+        line.push_str(raw);
+        let assignment = std::mem::take(&mut line);
+
+        let assignment = assignment.trim();
+        if assignment.is_empty() || assignment.starts_with('#') || assignment.starts_with(';') {
+            continue;
+        }
+
+        match parse_assignment(assignment) {
+            Some(env) => envs.push(env),
+            None => log::warn!("ignore the invalid environment assignment: {}", assignment),
+        }
+    }
+
+    envs
+}
+
+fn parse_assignment(s: &str) -> Option<(String, String)> {
+    let (key, value) = s.split_once('=')?;
+    let key = key.trim();
+    if !is_valid_name(key) {
+        return None;
+    }
+
+    let value = value.trim();
+    let value = match value.chars().next() {
+        Some(q) if (q == '"' || q == '\'') && value.len() > 1 && value.ends_with(q) => {
+            let inner = &value[1..value.len() - 1];
+            if q == '"' {
+                unescape(inner)
+            } else {
+                inner.to_string()
+            }
+        }
+        _ => value.to_string(),
+    };
+
+    Some((key.to_string(), value))
+}
+
+// the escaped characters in the double quotes, like "\"" and "\\"
+fn unescape(s: &str) -> String {
+    let mut value = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(e @ ('"' | '\\' | '$' | '`')) => value.push(e),
+            Some(e) => {
+                value.push('\\');
+                value.push(e);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// load the environment files in order, the file prefixed with "-" is ignored if it does not exist
+pub(super) fn load_env_files(files: &[String]) -> Result<Vec<(String, String)>, ExecCmdError> {
+    let mut envs = Vec::new();
+    for file in files {
+        let (path, optional) = match file.strip_prefix('-') {
+            Some(path) => (path, true),
+            None => (file.as_str(), false),
+        };
+
+        match fs::read_to_string(path) {
+            Ok(content) => envs.append(&mut parse_env_file(&content)),
+            Err(e) if optional && e.kind() == std::io::ErrorKind::NotFound => {
+                log::debug!("the environment file {} does not exist, ignore it", path);
+            }
+            Err(e) => {
+                return Err(ExecCmdError::EnvironmentError(format!(
+                    "failed to read the environment file {}: {}",
+                    path, e
+                )))
+            }
+        }
+    }
+    Ok(envs)
+}
+
+/// expand the variables in the arguments of the command. a word which is exactly "$NAME" is
+/// replaced by the value split at whitespace, which results in no word if the value is empty.
+/// the other references, like "${NAME}" or "--opt=$NAME", are replaced in place without
+/// splitting, the unset variable is replaced by the empty string. "$$" is a literal "$".
+pub(super) fn expand_args<F>(argv: &[String], env: F) -> Vec<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut args = Vec::new();
+    for arg in argv {
+        if let Some(name) = arg.strip_prefix('$') {
+            if is_valid_name(name) {
+                let value = env(name).unwrap_or_default();
+                args.extend(value.split_whitespace().map(|s| s.to_string()));
+                continue;
+            }
+        }
+        args.push(expand_word(arg, &env));
+    }
+    args
+}
+
+fn expand_word<F>(word: &str, env: &F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::new();
+    let mut rest = word;
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+
+        if let Some(r) = rest.strip_prefix('$') {
+            result.push('$');
+            rest = r;
+            continue;
+        }
+
+        if let Some(r) = rest.strip_prefix('{') {
+            if let Some(end) = r.find('}') {
+                if is_valid_name(&r[..end]) {
+                    result.push_str(&env(&r[..end]).unwrap_or_default());
+                    rest = &r[end + 1..];
+                    continue;
+                }
+            }
+            result.push('$');
+            continue;
+        }
+
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if is_valid_name(&rest[..end]) {
+            result.push_str(&env(&rest[..end]).unwrap_or_default());
+            rest = &rest[end..];
+        } else {
+            result.push('$');
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// the values of the unit specifiers, like "%n" for the full unit name
+pub(super) struct Specifiers<'a> {
+    name: &'a str,
+    home: Option<&'a str>,
+}
+
+impl<'a> Specifiers<'a> {
+    /// the home is the directory of the user which the command runs as, root if not set
+    pub(super) fn new(name: &'a str, home: Option<&'a str>) -> Self {
+        Specifiers { name, home }
+    }
+
+    /// replace the specifiers in the word, "%%" is a literal "%"
+    pub(super) fn expand(&self, word: &str) -> Result<String, ExecCmdError> {
+        let mut result = String::new();
+        let mut chars = word.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                result.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('%') => result.push('%'),
+                Some('n') => result.push_str(self.name),
+                Some('N') => result.push_str(self.name_without_type()),
+                Some('p') => result.push_str(self.prefix()),
+                Some('i') => result.push_str(self.instance()),
+                Some('t') => result.push_str(RUNTIME_DIR),
+                Some('h') => result.push_str(self.home.unwrap_or(ROOT_HOME)),
+                Some('H') => result.push_str(&hostname()?),
+                Some('m') => result.push_str(&machine_id()?),
+                Some(s) => {
+                    return Err(ExecCmdError::SpecifierError(format!(
+                        "unknown specifier %{} in {}",
+                        s, word
+                    )))
+                }
+                None => {
+                    return Err(ExecCmdError::SpecifierError(format!(
+                        "incomplete specifier in {}",
+                        word
+                    )))
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    // "foo@bar.service" -> "foo@bar"
+    fn name_without_type(&self) -> &str {
+        Path::new(self.name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(self.name)
+    }
+
+    // "foo@bar.service" -> "foo"
+    fn prefix(&self) -> &str {
+        let name = self.name_without_type();
+        name.split_once('@').map_or(name, |(prefix, _)| prefix)
+    }
+
+    // "foo@bar.service" -> "bar", empty if the unit is not an instance
+    fn instance(&self) -> &str {
+        self.name_without_type()
+            .split_once('@')
+            .map_or("", |(_, instance)| instance)
+    }
+}
+
+fn hostname() -> Result<String, ExecCmdError> {
+    let mut buf = [0u8; 256];
+    unistd::gethostname(&mut buf)
+        .map(|h| h.to_string_lossy().to_string())
+        .map_err(|e| ExecCmdError::SpecifierError(format!("failed to get the hostname: {}", e)))
+}
+
+fn machine_id() -> Result<String, ExecCmdError> {
+    fs::read_to_string(PATH_MACHINE_ID)
+        .map(|id| id.trim().to_string())
+        .map_err(|e| {
+            ExecCmdError::SpecifierError(format!("failed to read {}: {}", PATH_MACHINE_ID, e))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_args, load_env_files, parse_env_file, Specifiers};
+    use std::collections::HashMap;
+
+    fn strings(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = "# comment\n; comment\n\nFOO=bar\n  BAZ = qux quux  \n\
+                       QUOTED=\"a \\\"b\\\" $c\"\nSINGLE='x \\\" y'\nLONG=a\\\nb\n\
+                       1BAD=x\nnot an assignment\nEMPTY=\n";
+        let envs = parse_env_file(content);
+        assert_eq!(
+            envs,
+            vec![
+                ("FOO".to_string(), "bar".to_string()),
+                ("BAZ".to_string(), "qux quux".to_string()),
+                ("QUOTED".to_string(), "a \"b\" $c".to_string()),
+                ("SINGLE".to_string(), "x \\\" y".to_string()),
+                ("LONG".to_string(), "ab".to_string()),
+                ("EMPTY".to_string(), "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_env_files() {
+        let path = std::env::temp_dir().join(format!("sysmaster-env-{}", std::process::id()));
+        std::fs::write(&path, "FOO=bar\n").unwrap();
+        let file = path.to_string_lossy().to_string();
+
+        let envs = load_env_files(&[file.clone(), "-/nonexistent/env".to_string()]).unwrap();
+        assert_eq!(envs, vec![("FOO".to_string(), "bar".to_string())]);
+        assert!(load_env_files(&["/nonexistent/env".to_string()]).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_expand_args() {
+        let mut env = HashMap::new();
+        env.insert("ONE", "1");
+        env.insert("MANY", "a  b c");
+        env.insert("EMPTY", "");
+        let lookup = |name: &str| env.get(name).map(|v| v.to_string());
+
+        let argv = strings(&[
+            "$MANY",
+            "${MANY}",
+            "--opt=$ONE",
+            "x${ONE}y$ONE",
+            "$EMPTY",
+            "${EMPTY}",
+            "$UNSET",
+            "pre$UNSET",
+            "$$ONE",
+            "cost$",
+            "${bad-name}",
+            "$1",
+        ]);
+        assert_eq!(
+            expand_args(&argv, lookup),
+            strings(&[
+                "a",
+                "b",
+                "c",
+                "a  b c",
+                "--opt=1",
+                "x1y1",
+                "",
+                "pre",
+                "$ONE",
+                "cost$",
+                "${bad-name}",
+                "$1"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_specifiers() {
+        let spec = Specifiers::new("foo@bar.service", Some("/home/foo"));
+        assert_eq!(
+            spec.expand("%n %N %p %i %t %h 100%%").unwrap(),
+            "foo@bar.service foo@bar foo bar /run /home/foo 100%"
+        );
+
+        let spec = Specifiers::new("foo.service", None);
+        assert_eq!(spec.expand("%p:%i:%h").unwrap(), "foo::/root");
+        assert!(!spec.expand("%H").unwrap().is_empty());
+        assert!(spec.expand("%x").is_err());
+        assert!(spec.expand("50%").is_err());
+    }
+}
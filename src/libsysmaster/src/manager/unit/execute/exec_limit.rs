@@ -0,0 +1,409 @@
+use crate::manager::DeserializeWith;
+use libutils::conf_parser::{self, Base};
+use libutils::time_util::{self, USEC_PER_SEC};
+use nix::errno::Errno;
+use nix::sched::{self, CpuSet};
+use nix::sys::resource::{self, Resource};
+use nix::unistd::Pid;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::fs;
+use std::str::FromStr;
+
+const PATH_OOM_SCORE_ADJ: &str = "/proc/self/oom_score_adj";
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+// the default priority of the realtime and best-effort classes
+const IOPRIO_DEFAULT: u32 = 4;
+
+/// the soft and hard limits of a resource, u64::MAX is infinity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecRlimit {
+    soft: u64,
+    hard: u64,
+}
+
+impl ExecRlimit {
+    /// "soft:hard" or a single value for both, each value is parsed by parse_value
+    fn parse(s: &str, parse_value: fn(&str) -> Result<u64, String>) -> Result<Self, String> {
+        let (soft, hard) = match s.split_once(':') {
+            Some((soft, hard)) => (parse_value(soft)?, parse_value(hard)?),
+            None => {
+                let value = parse_value(s)?;
+                (value, value)
+            }
+        };
+
+        if soft > hard {
+            return Err(format!("the soft limit exceeds the hard limit: {}", s));
+        }
+        Ok(ExecRlimit { soft, hard })
+    }
+
+    // a number or a size like "1K"
+    fn parse_size(s: &str) -> Result<u64, String> {
+        let s = s.trim();
+        if s == "infinity" {
+            return Ok(u64::MAX);
+        }
+        conf_parser::parse_size(s, Base::Binary)
+            .map_err(|_| format!("invalid resource limit: {}", s))
+    }
+
+    // a time span like "1h", a number without unit is in seconds, rounded up to seconds
+    fn parse_sec(s: &str) -> Result<u64, String> {
+        let usec = time_util::parse_timespan(s)
+            .map_err(|_| format!("invalid resource limit: {}", s.trim()))?;
+        if usec == u64::MAX {
+            return Ok(u64::MAX);
+        }
+        Ok(usec / USEC_PER_SEC + u64::from(usec % USEC_PER_SEC != 0))
+    }
+
+    // a time span like "500ms", a number without unit is in microseconds
+    fn parse_usec(s: &str) -> Result<u64, String> {
+        if let Ok(usec) = s.trim().parse::<u64>() {
+            return Ok(usec);
+        }
+        time_util::parse_timespan(s).map_err(|_| format!("invalid resource limit: {}", s.trim()))
+    }
+
+    // u64::MAX is infinity for setrlimit
+    fn to_rlim(value: u64) -> libc::rlim_t {
+        if value == u64::MAX {
+            libc::RLIM_INFINITY
+        } else {
+            value as libc::rlim_t
+        }
+    }
+}
+
+impl FromStr for ExecRlimit {
+    type Err = String;
+
+    /// "soft:hard" or a single value for both, the value is a number, a size like "1K" or "infinity"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Self::parse_size)
+    }
+}
+
+/// the CPU scheduling policy
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CpuSchedPolicy {
+    Other,
+    Batch,
+    Idle,
+    Fifo,
+    Rr,
+}
+
+impl CpuSchedPolicy {
+    fn to_raw(self) -> libc::c_int {
+        match self {
+            CpuSchedPolicy::Other => libc::SCHED_OTHER,
+            CpuSchedPolicy::Batch => libc::SCHED_BATCH,
+            CpuSchedPolicy::Idle => libc::SCHED_IDLE,
+            CpuSchedPolicy::Fifo => libc::SCHED_FIFO,
+            CpuSchedPolicy::Rr => libc::SCHED_RR,
+        }
+    }
+}
+
+impl FromStr for CpuSchedPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "other" => Ok(CpuSchedPolicy::Other),
+            "batch" => Ok(CpuSchedPolicy::Batch),
+            "idle" => Ok(CpuSchedPolicy::Idle),
+            "fifo" => Ok(CpuSchedPolicy::Fifo),
+            "rr" => Ok(CpuSchedPolicy::Rr),
+            _ => Err(format!("invalid CPU scheduling policy: {}", s)),
+        }
+    }
+}
+
+/// the IO scheduling class
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IoSchedClass {
+    None = 0,
+    Realtime = 1,
+    BestEffort = 2,
+    Idle = 3,
+}
+
+impl FromStr for IoSchedClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "none" => Ok(IoSchedClass::None),
+            "realtime" => Ok(IoSchedClass::Realtime),
+            "best-effort" => Ok(IoSchedClass::BestEffort),
+            "idle" => Ok(IoSchedClass::Idle),
+            _ => Err(format!("invalid IO scheduling class: {}", s)),
+        }
+    }
+}
+
+macro_rules! impl_deserialize_with {
+    ($t:ty) => {
+        impl DeserializeWith for $t {
+            fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s = String::deserialize(de)?;
+                <$t>::from_str(&s).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_deserialize_with!(ExecRlimit);
+impl_deserialize_with!(CpuSchedPolicy);
+impl_deserialize_with!(IoSchedClass);
+
+// the CPUs are separated by whitespace or ",", and a range is like "4-7"
+fn parse_cpu_affinity(s: &str) -> Result<Vec<usize>, String> {
+    let mut cpus = Vec::new();
+    for item in s.split(|c: char| c.is_whitespace() || c == ',') {
+        if item.is_empty() {
+            continue;
+        }
+
+        let invalid = || format!("invalid CPU affinity: {}", item);
+        let (low, high) = match item.split_once('-') {
+            Some((low, high)) => (low, high),
+            None => (item, item),
+        };
+        let low = low.parse::<usize>().map_err(|_| invalid())?;
+        let high = high.parse::<usize>().map_err(|_| invalid())?;
+        if low > high {
+            return Err(invalid());
+        }
+        for cpu in low..=high {
+            if !cpus.contains(&cpu) {
+                cpus.push(cpu);
+            }
+        }
+    }
+    Ok(cpus)
+}
+
+/// deserialize a list of CPU indexes like "0 2 4-7" or "0,1"
+pub fn deserialize_cpu_affinity<'de, D>(de: D) -> Result<Vec<usize>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(de)?;
+    parse_cpu_affinity(&s).map_err(de::Error::custom)
+}
+
+/// deserialize the limit in seconds of LimitCPU=, the value is a time span like "1h" or "infinity"
+pub fn deserialize_rlimit_sec<'de, D>(de: D) -> Result<ExecRlimit, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(de)?;
+    ExecRlimit::parse(&s, ExecRlimit::parse_sec).map_err(de::Error::custom)
+}
+
+/// deserialize the limit in microseconds of LimitRTTIME=, the value is a time span like "500ms"
+/// or "infinity"
+pub fn deserialize_rlimit_usec<'de, D>(de: D) -> Result<ExecRlimit, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(de)?;
+    ExecRlimit::parse(&s, ExecRlimit::parse_usec).map_err(de::Error::custom)
+}
+
+/// the resource limits and the scheduling settings of the spawned process
+#[derive(Debug, Clone, Default)]
+pub struct ExecLimits {
+    /// the limits set by setrlimit
+    pub rlimits: Vec<(Resource, ExecRlimit)>,
+    /// the nice level, from -20 to 19
+    pub nice: Option<i32>,
+    /// the CPU scheduling policy
+    pub cpu_sched_policy: Option<CpuSchedPolicy>,
+    /// the CPU scheduling priority, from 1 to 99 for fifo and rr, 0 for the others
+    pub cpu_sched_priority: Option<i32>,
+    /// the CPUs which the process is bound to, all if empty
+    pub cpu_affinity: Vec<usize>,
+    /// the IO scheduling class
+    pub io_sched_class: Option<IoSchedClass>,
+    /// the IO scheduling priority, from 0 (the highest) to 7
+    pub io_sched_priority: Option<u32>,
+    /// the adjustment of the OOM killer score, from -1000 to 1000
+    pub oom_score_adjust: Option<i32>,
+}
+
+impl ExecLimits {
+    /// check the ranges of the settings
+    pub fn verify(&self) -> Result<(), String> {
+        if self.nice.map_or(false, |n| !(-20..=19).contains(&n)) {
+            return Err("Nice= is out of the range from -20 to 19".to_string());
+        }
+        if self
+            .cpu_sched_priority
+            .map_or(false, |p| !(0..=99).contains(&p))
+        {
+            return Err("CPUSchedulingPriority= is out of the range from 0 to 99".to_string());
+        }
+        if self.io_sched_priority.map_or(false, |p| p > 7) {
+            return Err("IOSchedulingPriority= is out of the range from 0 to 7".to_string());
+        }
+        if self
+            .oom_score_adjust
+            .map_or(false, |s| !(-1000..=1000).contains(&s))
+        {
+            return Err("OOMScoreAdjust= is out of the range from -1000 to 1000".to_string());
+        }
+        Ok(())
+    }
+
+    /// apply the settings to the child, called before the privileges are dropped, because
+    /// raising the limits or the priorities requires them
+    pub(super) fn apply(&self) -> nix::Result<()> {
+        for (resource, limit) in &self.rlimits {
+            resource::setrlimit(
+                *resource,
+                ExecRlimit::to_rlim(limit.soft),
+                ExecRlimit::to_rlim(limit.hard),
+            )?;
+        }
+
+        if let Some(nice) = self.nice {
+            Errno::result(unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) })?;
+        }
+
+        if self.cpu_sched_policy.is_some() || self.cpu_sched_priority.is_some() {
+            let policy = self.cpu_sched_policy.unwrap_or(CpuSchedPolicy::Other);
+            let param = libc::sched_param {
+                sched_priority: self.cpu_sched_priority.unwrap_or(0),
+            };
+            Errno::result(unsafe { libc::sched_setscheduler(0, policy.to_raw(), &param) })?;
+        }
+
+        if !self.cpu_affinity.is_empty() {
+            let mut set = CpuSet::new();
+            for cpu in &self.cpu_affinity {
+                set.set(*cpu)?;
+            }
+            sched::sched_setaffinity(Pid::from_raw(0), &set)?;
+        }
+
+        if self.io_sched_class.is_some() || self.io_sched_priority.is_some() {
+            let class = self.io_sched_class.unwrap_or(IoSchedClass::BestEffort);
+            let priority = match class {
+                IoSchedClass::None | IoSchedClass::Idle => 0,
+                _ => self.io_sched_priority.unwrap_or(IOPRIO_DEFAULT),
+            };
+            let ioprio = ((class as u32) << IOPRIO_CLASS_SHIFT) | priority;
+            Errno::result(unsafe {
+                libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio)
+            })?;
+        }
+
+        if let Some(adjust) = self.oom_score_adjust {
+            fs::write(PATH_OOM_SCORE_ADJ, adjust.to_string())
+                .map_err(|e| Errno::from_i32(e.raw_os_error().unwrap_or(libc::EIO)))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cpu_affinity, CpuSchedPolicy, ExecLimits, ExecRlimit, IoSchedClass};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_exec_rlimit_from_str() {
+        assert_eq!(
+            ExecRlimit::from_str("65536"),
+            Ok(ExecRlimit {
+                soft: 65536,
+                hard: 65536
+            })
+        );
+        assert_eq!(
+            ExecRlimit::from_str("1K:infinity"),
+            Ok(ExecRlimit {
+                soft: 1024,
+                hard: u64::MAX
+            })
+        );
+        assert_eq!(
+            ExecRlimit::from_str("infinity"),
+            Ok(ExecRlimit {
+                soft: u64::MAX,
+                hard: u64::MAX
+            })
+        );
+        assert!(ExecRlimit::from_str("2048:1024").is_err());
+        assert!(ExecRlimit::from_str("-1").is_err());
+        assert!(ExecRlimit::from_str("many").is_err());
+    }
+
+    #[test]
+    fn test_exec_rlimit_time() {
+        assert_eq!(
+            ExecRlimit::parse("1h:infinity", ExecRlimit::parse_sec),
+            Ok(ExecRlimit {
+                soft: 3600,
+                hard: u64::MAX
+            })
+        );
+        assert_eq!(ExecRlimit::parse_sec("30"), Ok(30));
+        assert_eq!(ExecRlimit::parse_sec("1500ms"), Ok(2));
+        assert!(ExecRlimit::parse_sec("1K").is_err());
+
+        assert_eq!(ExecRlimit::parse_usec("500ms"), Ok(500000));
+        assert_eq!(ExecRlimit::parse_usec("1000"), Ok(1000));
+        assert_eq!(ExecRlimit::parse_usec("infinity"), Ok(u64::MAX));
+        assert!(ExecRlimit::parse_usec("1M").is_err());
+    }
+
+    #[test]
+    fn test_sched_from_str() {
+        assert_eq!(CpuSchedPolicy::from_str("fifo"), Ok(CpuSchedPolicy::Fifo));
+        assert!(CpuSchedPolicy::from_str("deadline").is_err());
+        assert_eq!(
+            IoSchedClass::from_str("best-effort"),
+            Ok(IoSchedClass::BestEffort)
+        );
+        assert!(IoSchedClass::from_str("besteffort").is_err());
+    }
+
+    #[test]
+    fn test_parse_cpu_affinity() {
+        assert_eq!(parse_cpu_affinity("0 2,4-6"), Ok(vec![0, 2, 4, 5, 6]));
+        assert_eq!(parse_cpu_affinity("1 1-2"), Ok(vec![1, 2]));
+        assert_eq!(parse_cpu_affinity(""), Ok(vec![]));
+        assert!(parse_cpu_affinity("3-1").is_err());
+        assert!(parse_cpu_affinity("a").is_err());
+    }
+
+    #[test]
+    fn test_exec_limits_verify() {
+        let mut limits = ExecLimits {
+            nice: Some(-5),
+            oom_score_adjust: Some(-1000),
+            ..Default::default()
+        };
+        assert!(limits.verify().is_ok());
+
+        limits.nice = Some(20);
+        assert!(limits.verify().is_err());
+
+        limits.nice = None;
+        limits.io_sched_priority = Some(8);
+        assert!(limits.verify().is_err());
+    }
+}
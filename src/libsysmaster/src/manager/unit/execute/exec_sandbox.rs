@@ -0,0 +1,830 @@
+use crate::manager::DeserializeWith;
+use nix::errno::Errno;
+use nix::fcntl::{self, FlockArg, OFlag};
+use nix::mount::{self, MsFlags};
+use nix::sched::{self, CloneFlags};
+use nix::sys::socket::{self, AddressFamily, ControlMessage, MsgFlags, SockFlag, SockType};
+use nix::sys::stat::{self, Mode};
+use nix::sys::statvfs::{self, FsFlags};
+use nix::unistd;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::ffi::{CString, OsString};
+use std::fs::{self, File};
+use std::io::{IoSlice, IoSliceMut};
+use std::os::unix::ffi::OsStringExt;
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::os::unix::io::RawFd;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const PATH_MOUNTINFO: &str = "/proc/self/mountinfo";
+const PATH_NETNS: &str = "/proc/self/ns/net";
+const RUNTIME_PREFIX: &str = "/tmp/sysmaster-private-";
+// the file systems which are kept writable by ProtectSystem=strict
+const API_PATHS: [&str; 3] = ["/dev", "/proc", "/sys"];
+// the devices which are kept by PrivateDevices=
+const DEVICE_NODES: [&str; 6] = ["null", "zero", "full", "random", "urandom", "tty"];
+const DEVICE_DIRS: [&str; 4] = ["pts", "shm", "mqueue", "hugepages"];
+const DEVICE_LINKS: [(&str, &str); 5] = [
+    ("ptmx", "pts/ptmx"),
+    ("fd", "/proc/self/fd"),
+    ("stdin", "/proc/self/fd/0"),
+    ("stdout", "/proc/self/fd/1"),
+    ("stderr", "/proc/self/fd/2"),
+];
+
+/// whether the operating system directories are mounted read-only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtectSystem {
+    /// not protected
+    No,
+    /// /usr and /boot are read-only
+    Yes,
+    /// /etc is read-only too
+    Full,
+    /// the whole file system is read-only, except /dev, /proc and /sys
+    Strict,
+}
+
+impl Default for ProtectSystem {
+    fn default() -> Self {
+        ProtectSystem::No
+    }
+}
+
+impl FromStr for ProtectSystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "no" | "false" => Ok(ProtectSystem::No),
+            "yes" | "true" => Ok(ProtectSystem::Yes),
+            "full" => Ok(ProtectSystem::Full),
+            "strict" => Ok(ProtectSystem::Strict),
+            _ => Err(format!("invalid ProtectSystem: {}", s)),
+        }
+    }
+}
+
+/// whether the home directories are accessible
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtectHome {
+    /// not protected
+    No,
+    /// /home, /root and /run/user are inaccessible
+    Yes,
+    /// they are read-only
+    ReadOnly,
+    /// they are replaced by empty read-only tmpfs
+    Tmpfs,
+}
+
+impl Default for ProtectHome {
+    fn default() -> Self {
+        ProtectHome::No
+    }
+}
+
+impl FromStr for ProtectHome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "no" | "false" => Ok(ProtectHome::No),
+            "yes" | "true" => Ok(ProtectHome::Yes),
+            "read-only" => Ok(ProtectHome::ReadOnly),
+            "tmpfs" => Ok(ProtectHome::Tmpfs),
+            _ => Err(format!("invalid ProtectHome: {}", s)),
+        }
+    }
+}
+
+impl DeserializeWith for ProtectSystem {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+        ProtectSystem::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl DeserializeWith for ProtectHome {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+        ProtectHome::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+/// the sandbox of the spawned process, which is set up in its own mount namespace.
+/// the paths prefixed with "-" are ignored if they do not exist.
+#[derive(Debug, Clone, Default)]
+pub struct ExecSandbox {
+    /// mount private /tmp and /var/tmp, which are shared with the units joined by JoinsNamespaceOf=
+    pub private_tmp: bool,
+    /// the operating system directories which are read-only
+    pub protect_system: ProtectSystem,
+    /// the home directories which are protected
+    pub protect_home: ProtectHome,
+    /// the paths which are read-only
+    pub read_only_paths: Vec<String>,
+    /// the paths which are writable, even under a read-only path
+    pub read_write_paths: Vec<String>,
+    /// the paths which are replaced by empty inaccessible nodes
+    pub inaccessible_paths: Vec<String>,
+    /// mount a private /dev with only the pseudo devices like /dev/null
+    pub private_devices: bool,
+    /// run in a network namespace with only the loopback device, which is shared with the units
+    /// joined by JoinsNamespaceOf=
+    pub private_network: bool,
+}
+
+impl ExecSandbox {
+    /// check the configured paths are absolute
+    pub fn verify(&self) -> Result<(), String> {
+        let paths = self
+            .read_only_paths
+            .iter()
+            .chain(&self.read_write_paths)
+            .chain(&self.inaccessible_paths);
+        for path in paths {
+            if !Path::new(path.trim_start_matches('-')).is_absolute() {
+                return Err(format!("{} is not an absolute path", path));
+            }
+        }
+        Ok(())
+    }
+
+    fn needs_mount_ns(&self) -> bool {
+        self.private_tmp
+            || self.private_devices
+            || self.protect_system != ProtectSystem::No
+            || self.protect_home != ProtectHome::No
+            || !self.read_only_paths.is_empty()
+            || !self.read_write_paths.is_empty()
+            || !self.inaccessible_paths.is_empty()
+    }
+
+    /// whether the runtime directory or the network namespace is needed
+    pub(super) fn needs_runtime(&self) -> bool {
+        self.needs_mount_ns() || self.private_network
+    }
+
+    fn mount_entries(&self) -> Vec<MountEntry> {
+        let mut entries = Vec::new();
+
+        if self.private_tmp {
+            entries.push(MountEntry::new("/tmp", MountMode::Bind("tmp")));
+            entries.push(MountEntry::new("/var/tmp", MountMode::Bind("var-tmp")));
+        }
+
+        if self.private_devices {
+            entries.push(MountEntry::new("/dev", MountMode::PrivateDev));
+        }
+
+        let system: &[&str] = match self.protect_system {
+            ProtectSystem::No => &[],
+            ProtectSystem::Yes => &["/usr", "-/boot", "-/efi"],
+            ProtectSystem::Full => &["/usr", "-/boot", "-/efi", "/etc"],
+            ProtectSystem::Strict => &["/"],
+        };
+        for path in system {
+            entries.push(MountEntry::new(path, MountMode::ReadOnly));
+        }
+        if self.protect_system == ProtectSystem::Strict {
+            for path in API_PATHS {
+                entries.push(MountEntry::new(&format!("-{}", path), MountMode::Keep));
+            }
+        }
+
+        let home_mode = match self.protect_home {
+            ProtectHome::No => None,
+            ProtectHome::Yes => Some(MountMode::Inaccessible),
+            ProtectHome::ReadOnly => Some(MountMode::ReadOnly),
+            ProtectHome::Tmpfs => Some(MountMode::Tmpfs),
+        };
+        if let Some(mode) = home_mode {
+            for path in ["-/home", "-/root", "-/run/user"] {
+                entries.push(MountEntry::new(path, mode.clone()));
+            }
+        }
+
+        for path in &self.read_only_paths {
+            entries.push(MountEntry::new(path, MountMode::ReadOnly));
+        }
+        for path in &self.read_write_paths {
+            entries.push(MountEntry::new(path, MountMode::ReadWrite));
+        }
+        for path in &self.inaccessible_paths {
+            entries.push(MountEntry::new(path, MountMode::Inaccessible));
+        }
+
+        // the parents are mounted before the children, and only the mode with the highest
+        // priority is kept for a path
+        entries.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then(a.mode.priority().cmp(&b.mode.priority()))
+        });
+        entries.dedup_by(|a, b| a.path == b.path);
+        entries
+    }
+
+    /// set up the sandbox in the child, called before the privileges are dropped
+    pub(super) fn apply(&self, runtime: Option<&ExecRuntime>) -> nix::Result<()> {
+        let runtime = match runtime {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+
+        if self.private_network {
+            runtime.setup_netns()?;
+        }
+
+        if !self.needs_mount_ns() {
+            return Ok(());
+        }
+
+        sched::unshare(CloneFlags::CLONE_NEWNS)?;
+        // the mounts of the sandbox are not propagated to the host
+        mount::mount(
+            None::<&str>,
+            "/",
+            None::<&str>,
+            MsFlags::MS_SLAVE | MsFlags::MS_REC,
+            None::<&str>,
+        )?;
+
+        // the runtime directory may be covered by the mounts, like the private /tmp, so it is
+        // referred to by the file descriptor
+        let dir = fcntl::open(
+            &runtime.dir,
+            OFlag::O_PATH | OFlag::O_DIRECTORY | OFlag::O_CLOEXEC,
+            Mode::empty(),
+        )?;
+        let base = PathBuf::from(format!("/proc/self/fd/{}", dir));
+        let ret = self.apply_mounts(&base);
+        let _ = unistd::close(dir);
+        ret
+    }
+
+    fn apply_mounts(&self, base: &Path) -> nix::Result<()> {
+        let entries = self.mount_entries();
+        for entry in &entries {
+            if entry.optional && !entry.path.exists() {
+                continue;
+            }
+
+            // the children are mounted by their own entries
+            let excluded = entries
+                .iter()
+                .filter(|e| e.path != entry.path && e.path.starts_with(&entry.path))
+                .map(|e| e.path.as_path())
+                .collect::<Vec<_>>();
+            entry.apply(base, &excluded)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MountMode {
+    // bind the directory in the runtime directory
+    Bind(&'static str),
+    PrivateDev,
+    Inaccessible,
+    Tmpfs,
+    ReadOnly,
+    ReadWrite,
+    // leave the mount as is, which keeps the API file systems writable under a read-only root
+    Keep,
+}
+
+impl MountMode {
+    fn priority(&self) -> u8 {
+        match self {
+            MountMode::Bind(_) | MountMode::PrivateDev => 0,
+            MountMode::Inaccessible => 1,
+            MountMode::Tmpfs => 2,
+            MountMode::ReadOnly => 3,
+            MountMode::ReadWrite => 4,
+            MountMode::Keep => 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MountEntry {
+    path: PathBuf,
+    mode: MountMode,
+    optional: bool,
+}
+
+impl MountEntry {
+    fn new(path: &str, mode: MountMode) -> MountEntry {
+        let (path, optional) = match path.strip_prefix('-') {
+            Some(p) => (p, true),
+            None => (path, false),
+        };
+        MountEntry {
+            path: PathBuf::from(path),
+            mode,
+            optional,
+        }
+    }
+
+    fn apply(&self, base: &Path, excluded: &[&Path]) -> nix::Result<()> {
+        let path = self.path.as_path();
+        match &self.mode {
+            MountMode::Bind(dir) => bind(&base.join(dir), path),
+            MountMode::PrivateDev => mount_private_dev(&base.join("dev"), path),
+            MountMode::Inaccessible => {
+                let source = if stat::stat(path)?.st_mode & libc::S_IFMT == libc::S_IFDIR {
+                    base.join("inaccessible/dir")
+                } else {
+                    base.join("inaccessible/reg")
+                };
+                bind(&source, path)?;
+                remount(path, true)
+            }
+            MountMode::Tmpfs => {
+                mount::mount(
+                    Some("tmpfs"),
+                    path,
+                    Some("tmpfs"),
+                    MsFlags::MS_NOSUID | MsFlags::MS_NODEV,
+                    Some("mode=755"),
+                )?;
+                remount(path, true)
+            }
+            MountMode::ReadOnly => {
+                bind(path, path)?;
+                remount(path, true)?;
+                for sub in mount_points()? {
+                    if sub == path
+                        || !sub.starts_with(path)
+                        || excluded.iter().any(|e| sub.starts_with(e))
+                    {
+                        continue;
+                    }
+                    // the submounts which are not accessible are skipped
+                    if let Err(e) = remount(&sub, true) {
+                        log::debug!("failed to remount {:?} read-only: {}", sub, e);
+                    }
+                }
+                Ok(())
+            }
+            MountMode::ReadWrite => {
+                bind(path, path)?;
+                remount(path, false)
+            }
+            MountMode::Keep => Ok(()),
+        }
+    }
+}
+
+fn bind(source: &Path, target: &Path) -> nix::Result<()> {
+    mount::mount(
+        Some(source),
+        target,
+        None::<&str>,
+        MsFlags::MS_BIND | MsFlags::MS_REC,
+        None::<&str>,
+    )
+}
+
+// the flags of the mount are kept, which cannot be changed in a user namespace
+fn remount(path: &Path, read_only: bool) -> nix::Result<()> {
+    let kept = [
+        (FsFlags::ST_NOSUID, MsFlags::MS_NOSUID),
+        (FsFlags::ST_NODEV, MsFlags::MS_NODEV),
+        (FsFlags::ST_NOEXEC, MsFlags::MS_NOEXEC),
+        (FsFlags::ST_NOATIME, MsFlags::MS_NOATIME),
+        (FsFlags::ST_NODIRATIME, MsFlags::MS_NODIRATIME),
+        (FsFlags::ST_RELATIME, MsFlags::MS_RELATIME),
+    ];
+
+    let current = statvfs::statvfs(path)?.flags();
+    let mut flags = MsFlags::MS_BIND | MsFlags::MS_REMOUNT;
+    for (fs_flag, ms_flag) in kept {
+        if current.contains(fs_flag) {
+            flags |= ms_flag;
+        }
+    }
+    if read_only {
+        flags |= MsFlags::MS_RDONLY;
+    }
+    mount::mount(None::<&str>, path, None::<&str>, flags, None::<&str>)
+}
+
+// build the devices in the staging directory, and move it onto /dev
+fn mount_private_dev(staging: &Path, target: &Path) -> nix::Result<()> {
+    let io_errno = |e: std::io::Error| Errno::from_i32(e.raw_os_error().unwrap_or(libc::EIO));
+
+    mount::mount(
+        Some("tmpfs"),
+        staging,
+        Some("tmpfs"),
+        MsFlags::MS_NOSUID | MsFlags::MS_NOEXEC,
+        Some("mode=755"),
+    )?;
+
+    let host = Path::new("/dev");
+    for name in DEVICE_NODES {
+        if !host.join(name).exists() {
+            continue;
+        }
+        File::create(staging.join(name)).map_err(io_errno)?;
+        bind(&host.join(name), &staging.join(name))?;
+    }
+    for name in DEVICE_DIRS {
+        if !host.join(name).is_dir() {
+            continue;
+        }
+        fs::create_dir(staging.join(name)).map_err(io_errno)?;
+        bind(&host.join(name), &staging.join(name))?;
+    }
+    for (name, dest) in DEVICE_LINKS {
+        symlink(dest, staging.join(name)).map_err(io_errno)?;
+    }
+
+    mount::mount(
+        Some(staging),
+        target,
+        None::<&str>,
+        MsFlags::MS_MOVE,
+        None::<&str>,
+    )
+}
+
+fn mount_points() -> nix::Result<Vec<PathBuf>> {
+    let content = fs::read_to_string(PATH_MOUNTINFO)
+        .map_err(|e| Errno::from_i32(e.raw_os_error().unwrap_or(libc::EIO)))?;
+    Ok(parse_mountinfo(&content))
+}
+
+// the mount point is the fifth field, in which the whitespace is escaped like "\040"
+fn parse_mountinfo(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .filter_map(|line| line.split(' ').nth(4))
+        .map(|point| PathBuf::from(unescape_octal(point)))
+        .collect()
+}
+
+fn unescape_octal(s: &str) -> String {
+    let mut result = String::new();
+    let mut rest = s;
+    while let Some(pos) = rest.find('\\') {
+        result.push_str(&rest[..pos]);
+        let code = rest
+            .get(pos + 1..pos + 4)
+            .and_then(|o| u8::from_str_radix(o, 8).ok());
+        match code {
+            Some(c) => {
+                result.push(c as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// the resources of the sandbox which are kept while the unit is active, and shared with the
+/// units joined by JoinsNamespaceOf=: the directory holding the private /tmp and /var/tmp,
+/// and the socket pair storing the network namespace
+pub(in crate::manager::unit) struct ExecRuntime {
+    dir: PathBuf,
+    netns: (RawFd, RawFd),
+}
+
+impl ExecRuntime {
+    pub(super) fn new(unit: &str) -> nix::Result<ExecRuntime> {
+        let io_errno = |e: std::io::Error| Errno::from_i32(e.raw_os_error().unwrap_or(libc::EIO));
+
+        let dir = mkdtemp(&format!("{}{}-XXXXXX", RUNTIME_PREFIX, unit))?;
+        let (reader, writer) = socket::socketpair(
+            AddressFamily::Unix,
+            SockType::Datagram,
+            None,
+            SockFlag::SOCK_CLOEXEC,
+        )?;
+        // dropped on failure, which removes the directory
+        let runtime = ExecRuntime {
+            dir,
+            netns: (reader, writer),
+        };
+
+        for (name, mode) in [("tmp", 0o1777), ("var-tmp", 0o1777), ("dev", 0o755)] {
+            let path = runtime.dir.join(name);
+            fs::create_dir(&path).map_err(io_errno)?;
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).map_err(io_errno)?;
+        }
+
+        let inaccessible = runtime.dir.join("inaccessible");
+        fs::create_dir(&inaccessible).map_err(io_errno)?;
+        fs::create_dir(inaccessible.join("dir")).map_err(io_errno)?;
+        File::create(inaccessible.join("reg")).map_err(io_errno)?;
+        for name in ["dir", "reg"] {
+            fs::set_permissions(inaccessible.join(name), fs::Permissions::from_mode(0o000))
+                .map_err(io_errno)?;
+        }
+
+        Ok(runtime)
+    }
+
+    // take the network namespace stored by the first process, or create it if there is none,
+    // and store it back for the next process
+    fn setup_netns(&self) -> nix::Result<()> {
+        // the processes of the joined units may be spawned at the same time
+        let lock = fcntl::open(
+            &self.dir,
+            OFlag::O_RDONLY | OFlag::O_DIRECTORY | OFlag::O_CLOEXEC,
+            Mode::empty(),
+        )?;
+        let ret = fcntl::flock(lock, FlockArg::LockExclusive).and_then(|_| self.join_netns());
+        let _ = unistd::close(lock);
+        ret
+    }
+
+    fn join_netns(&self) -> nix::Result<()> {
+        let fd = match recv_fd(self.netns.0) {
+            Ok(fd) => {
+                if let Err(e) = sched::setns(fd, CloneFlags::CLONE_NEWNET) {
+                    let _ = unistd::close(fd);
+                    return Err(e);
+                }
+                fd
+            }
+            Err(Errno::EAGAIN) => {
+                sched::unshare(CloneFlags::CLONE_NEWNET)?;
+                fcntl::open(
+                    PATH_NETNS,
+                    OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+                    Mode::empty(),
+                )?
+            }
+            Err(e) => return Err(e),
+        };
+
+        let ret = send_fd(self.netns.1, fd);
+        let _ = unistd::close(fd);
+        ret
+    }
+}
+
+impl Drop for ExecRuntime {
+    fn drop(&mut self) {
+        let _ = unistd::close(self.netns.0);
+        let _ = unistd::close(self.netns.1);
+
+        // the inaccessible directory cannot be read when removing it
+        let _ = fs::set_permissions(
+            self.dir.join("inaccessible/dir"),
+            fs::Permissions::from_mode(0o700),
+        );
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            log::warn!(
+                "failed to remove the runtime directory {:?}: {}",
+                self.dir,
+                e
+            );
+        }
+    }
+}
+
+// nix does not wrap mkdtemp(3)
+fn mkdtemp(template: &str) -> nix::Result<PathBuf> {
+    let template = CString::new(template).map_err(|_| Errno::EINVAL)?;
+    let ptr = template.into_raw();
+    let ret = unsafe { libc::mkdtemp(ptr) };
+    let path = unsafe { CString::from_raw(ptr) };
+    if ret.is_null() {
+        return Err(Errno::last());
+    }
+    Ok(PathBuf::from(OsString::from_vec(path.into_bytes())))
+}
+
+fn recv_fd(socket: RawFd) -> nix::Result<RawFd> {
+    let mut buffer = [0u8; 1];
+    let mut iov = [IoSliceMut::new(&mut buffer)];
+    let mut space = nix::cmsg_space!(RawFd);
+    let msg = socket::recvmsg::<()>(socket, &mut iov, Some(&mut space), MsgFlags::MSG_DONTWAIT)?;
+    for cmsg in msg.cmsgs() {
+        if let socket::ControlMessageOwned::ScmRights(fds) = cmsg {
+            if let Some(fd) = fds.first() {
+                return Ok(*fd);
+            }
+        }
+    }
+    Err(Errno::EBADMSG)
+}
+
+fn send_fd(socket: RawFd, fd: RawFd) -> nix::Result<()> {
+    let iov = [IoSlice::new(b"x")];
+    let fds = [fd];
+    socket::sendmsg::<()>(
+        socket,
+        &iov,
+        &[ControlMessage::ScmRights(&fds)],
+        MsgFlags::MSG_DONTWAIT,
+        None,
+    )
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_mountinfo, ExecRuntime, ExecSandbox, MountMode, ProtectHome, ProtectSystem};
+    use nix::errno::Errno;
+    use nix::sched::{self, CloneFlags};
+    use nix::sys::wait::{self, WaitStatus};
+    use nix::unistd::{self, ForkResult};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+
+    // the exit code of the child when the user namespace is not supported
+    const EXIT_SKIP: i32 = 77;
+
+    #[test]
+    fn test_protect_from_str() {
+        assert_eq!(ProtectSystem::from_str("strict"), Ok(ProtectSystem::Strict));
+        assert_eq!(ProtectSystem::from_str("true"), Ok(ProtectSystem::Yes));
+        assert!(ProtectSystem::from_str("all").is_err());
+        assert_eq!(
+            ProtectHome::from_str("read-only"),
+            Ok(ProtectHome::ReadOnly)
+        );
+        assert!(ProtectHome::from_str("readonly").is_err());
+    }
+
+    #[test]
+    fn test_mount_entries() {
+        let sandbox = ExecSandbox {
+            private_tmp: true,
+            protect_system: ProtectSystem::Strict,
+            read_write_paths: vec!["/var/lib/foo".to_string(), "/tmp".to_string()],
+            inaccessible_paths: vec!["-/srv".to_string()],
+            ..Default::default()
+        };
+        let entries = sandbox.mount_entries();
+        let paths = entries
+            .iter()
+            .map(|e| e.path.to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "/",
+                "/dev",
+                "/proc",
+                "/srv",
+                "/sys",
+                "/tmp",
+                "/var/lib/foo",
+                "/var/tmp"
+            ]
+        );
+        assert_eq!(entries[0].mode, MountMode::ReadOnly);
+        assert_eq!(entries[1].mode, MountMode::Keep);
+        assert!(entries[3].optional);
+        // the private /tmp is not replaced by the writable path
+        assert_eq!(entries[5].mode, MountMode::Bind("tmp"));
+        assert_eq!(entries[6].mode, MountMode::ReadWrite);
+
+        assert!(sandbox.verify().is_ok());
+        let sandbox = ExecSandbox {
+            read_only_paths: vec!["-var".to_string()],
+            ..Default::default()
+        };
+        assert!(sandbox.verify().is_err());
+    }
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let content = "22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+                       40 22 0:35 / /mnt/my\\040disk rw - tmpfs tmpfs rw\n";
+        assert_eq!(
+            parse_mountinfo(content),
+            vec![PathBuf::from("/"), PathBuf::from("/mnt/my disk")]
+        );
+    }
+
+    // run the function in a child which is root in a new user namespace
+    fn run_in_userns<F: FnOnce() -> i32>(f: F) -> Option<i32> {
+        let uid = unistd::getuid();
+        let gid = unistd::getgid();
+        match unsafe { unistd::fork() }.unwrap() {
+            ForkResult::Child => {
+                if sched::unshare(CloneFlags::CLONE_NEWUSER).is_err()
+                    || fs::write("/proc/self/uid_map", format!("0 {} 1", uid)).is_err()
+                    || fs::write("/proc/self/setgroups", "deny").is_err()
+                    || fs::write("/proc/self/gid_map", format!("0 {} 1", gid)).is_err()
+                {
+                    std::process::exit(EXIT_SKIP);
+                }
+                std::process::exit(f());
+            }
+            ForkResult::Parent { child } => match wait::waitpid(child, None).unwrap() {
+                WaitStatus::Exited(_, EXIT_SKIP) => {
+                    println!("the user namespace is not supported, skipping.");
+                    None
+                }
+                WaitStatus::Exited(_, code) => Some(code),
+                status => panic!("the child is not exited: {:?}", status),
+            },
+        }
+    }
+
+    #[test]
+    fn test_sandbox_private_tmp() {
+        let runtime = ExecRuntime::new("test.service").unwrap();
+        let marker = format!("sysmaster-sandbox-{}", std::process::id());
+        let sandbox = ExecSandbox {
+            private_tmp: true,
+            ..Default::default()
+        };
+
+        let code = run_in_userns(|| {
+            if let Err(e) = sandbox.apply(Some(&runtime)) {
+                println!("failed to apply the sandbox: {}", e);
+                return 1;
+            }
+            // the private /tmp is empty, and the file is created in the runtime directory
+            if fs::read_dir("/tmp").map_or(true, |d| d.count() != 0) {
+                return 2;
+            }
+            if fs::write(Path::new("/tmp").join(&marker), "").is_err() {
+                return 3;
+            }
+            0
+        });
+
+        if let Some(code) = code {
+            assert_eq!(code, 0);
+            assert!(!Path::new("/tmp").join(&marker).exists());
+            assert!(runtime.dir.join("tmp").join(&marker).exists());
+        }
+        let dir = runtime.dir.clone();
+        drop(runtime);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_sandbox_paths() {
+        let root = std::env::temp_dir().join(format!("sysmaster-paths-{}", std::process::id()));
+        let (ro, rw, hidden) = (root.join("ro"), root.join("ro/rw"), root.join("hidden"));
+        fs::create_dir_all(&rw).unwrap();
+        fs::create_dir_all(&hidden).unwrap();
+        fs::write(hidden.join("secret"), "").unwrap();
+
+        let runtime = ExecRuntime::new("test.service").unwrap();
+        let path = |p: &PathBuf| p.to_string_lossy().to_string();
+        let sandbox = ExecSandbox {
+            read_only_paths: vec![path(&ro), "-/nonexistent".to_string()],
+            read_write_paths: vec![path(&rw)],
+            inaccessible_paths: vec![path(&hidden)],
+            private_network: true,
+            ..Default::default()
+        };
+
+        let code = run_in_userns(|| {
+            if let Err(e) = sandbox.apply(Some(&runtime)) {
+                println!("failed to apply the sandbox: {}", e);
+                return 1;
+            }
+            let err = fs::write(ro.join("file"), "").map_err(|e| e.raw_os_error());
+            if err != Err(Some(Errno::EROFS as i32)) {
+                return 2;
+            }
+            if fs::write(rw.join("file"), "").is_err() {
+                return 3;
+            }
+            if fs::read_dir(&hidden).map_or(false, |d| d.count() != 0) {
+                return 4;
+            }
+            // only the loopback device is in the private network namespace, the devices are listed
+            // after the two header lines
+            match fs::read_to_string("/proc/self/net/dev").map(|c| c.lines().count()) {
+                Ok(count) if count > 3 => 5,
+                _ => 0,
+            }
+        });
+
+        if let Some(code) = code {
+            assert_eq!(code, 0);
+            assert!(rw.join("file").exists());
+            assert!(hidden.join("secret").exists());
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,1193 @@
+use crate::manager::DeserializeWith;
+use nix::errno::Errno;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+// the offsets of the fields in struct seccomp_data
+const SECCOMP_DATA_NR: u32 = 0;
+const SECCOMP_DATA_ARCH: u32 = 4;
+// the max number of the instructions of a filter
+const BPF_MAXINSNS: usize = 4096;
+
+const AUDIT_ARCH_X86_64: u32 = 0xc000_003e;
+const AUDIT_ARCH_I386: u32 = 0x4000_0003;
+const AUDIT_ARCH_AARCH64: u32 = 0xc000_00b7;
+const AUDIT_ARCH_ARM: u32 = 0x4000_0028;
+const AUDIT_ARCH_RISCV64: u32 = 0xc000_00f3;
+const AUDIT_ARCH_LOONGARCH64: u32 = 0xc000_0102;
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_X86_64);
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_AARCH64);
+#[cfg(target_arch = "riscv64")]
+const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_RISCV64);
+#[cfg(target_arch = "loongarch64")]
+const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_LOONGARCH64);
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    target_arch = "riscv64",
+    target_arch = "loongarch64"
+)))]
+const AUDIT_ARCH_NATIVE: Option<u32> = None;
+
+// the x32 calls share the architecture of x86-64, and are told apart by this bit of the number
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+// the syscalls which are always allowed by an allow list, so the command can be executed
+const SYSCALLS_IMPLIED: [&str; 2] = ["@default", "execve"];
+
+// the groups of syscalls referred to by "@name", the names missing on the architecture are ignored
+const SYSCALL_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "@default",
+        &[
+            "arch_prctl",
+            "brk",
+            "clock_getres",
+            "clock_gettime",
+            "clock_nanosleep",
+            "exit",
+            "exit_group",
+            "futex",
+            "get_robust_list",
+            "get_thread_area",
+            "getegid",
+            "geteuid",
+            "getgid",
+            "getgroups",
+            "getpgid",
+            "getpgrp",
+            "getpid",
+            "getppid",
+            "getrandom",
+            "getresgid",
+            "getresuid",
+            "getrlimit",
+            "getsid",
+            "gettid",
+            "gettimeofday",
+            "getuid",
+            "membarrier",
+            "mmap",
+            "mprotect",
+            "munmap",
+            "nanosleep",
+            "pause",
+            "prlimit64",
+            "restart_syscall",
+            "rseq",
+            "rt_sigreturn",
+            "sched_getaffinity",
+            "sched_yield",
+            "set_robust_list",
+            "set_thread_area",
+            "set_tid_address",
+            "time",
+        ],
+    ),
+    (
+        "@aio",
+        &[
+            "io_cancel",
+            "io_destroy",
+            "io_getevents",
+            "io_setup",
+            "io_submit",
+        ],
+    ),
+    (
+        "@basic-io",
+        &[
+            "close",
+            "close_range",
+            "dup",
+            "dup2",
+            "dup3",
+            "lseek",
+            "pread64",
+            "preadv",
+            "preadv2",
+            "pwrite64",
+            "pwritev",
+            "pwritev2",
+            "read",
+            "readv",
+            "write",
+            "writev",
+        ],
+    ),
+    ("@chown", &["chown", "fchown", "fchownat", "lchown"]),
+    (
+        "@clock",
+        &["adjtimex", "clock_adjtime", "clock_settime", "settimeofday"],
+    ),
+    (
+        "@debug",
+        &[
+            "lookup_dcookie",
+            "perf_event_open",
+            "pidfd_getfd",
+            "process_vm_readv",
+            "process_vm_writev",
+            "ptrace",
+        ],
+    ),
+    (
+        "@file-system",
+        &[
+            "access",
+            "chdir",
+            "chmod",
+            "close",
+            "creat",
+            "faccessat",
+            "faccessat2",
+            "fallocate",
+            "fchdir",
+            "fchmod",
+            "fchmodat",
+            "fcntl",
+            "fgetxattr",
+            "flistxattr",
+            "fremovexattr",
+            "fsetxattr",
+            "fstat",
+            "fstatfs",
+            "ftruncate",
+            "getcwd",
+            "getdents",
+            "getdents64",
+            "getxattr",
+            "inotify_add_watch",
+            "inotify_init",
+            "inotify_init1",
+            "inotify_rm_watch",
+            "lgetxattr",
+            "link",
+            "linkat",
+            "listxattr",
+            "llistxattr",
+            "lremovexattr",
+            "lsetxattr",
+            "lstat",
+            "mkdir",
+            "mkdirat",
+            "mknod",
+            "mknodat",
+            "mmap",
+            "munmap",
+            "newfstatat",
+            "open",
+            "openat",
+            "openat2",
+            "readlink",
+            "readlinkat",
+            "removexattr",
+            "rename",
+            "renameat",
+            "renameat2",
+            "rmdir",
+            "setxattr",
+            "stat",
+            "statfs",
+            "statx",
+            "symlink",
+            "symlinkat",
+            "truncate",
+            "unlink",
+            "unlinkat",
+            "utime",
+            "utimensat",
+            "utimes",
+        ],
+    ),
+    (
+        "@io-event",
+        &[
+            "epoll_create",
+            "epoll_create1",
+            "epoll_ctl",
+            "epoll_pwait",
+            "epoll_pwait2",
+            "epoll_wait",
+            "eventfd",
+            "eventfd2",
+            "poll",
+            "ppoll",
+            "pselect6",
+            "select",
+        ],
+    ),
+    (
+        "@ipc",
+        &[
+            "memfd_create",
+            "mq_getsetattr",
+            "mq_notify",
+            "mq_open",
+            "mq_timedreceive",
+            "mq_timedsend",
+            "mq_unlink",
+            "msgctl",
+            "msgget",
+            "msgrcv",
+            "msgsnd",
+            "pipe",
+            "pipe2",
+            "process_vm_readv",
+            "process_vm_writev",
+            "semctl",
+            "semget",
+            "semop",
+            "semtimedop",
+            "shmat",
+            "shmctl",
+            "shmdt",
+            "shmget",
+        ],
+    ),
+    ("@keyring", &["add_key", "keyctl", "request_key"]),
+    (
+        "@memlock",
+        &["mlock", "mlock2", "mlockall", "munlock", "munlockall"],
+    ),
+    ("@module", &["delete_module", "finit_module", "init_module"]),
+    (
+        "@mount",
+        &[
+            "chroot",
+            "fsconfig",
+            "fsmount",
+            "fsopen",
+            "fspick",
+            "mount",
+            "mount_setattr",
+            "move_mount",
+            "open_tree",
+            "pivot_root",
+            "umount2",
+        ],
+    ),
+    (
+        "@network-io",
+        &[
+            "accept",
+            "accept4",
+            "bind",
+            "connect",
+            "getpeername",
+            "getsockname",
+            "getsockopt",
+            "listen",
+            "recvfrom",
+            "recvmmsg",
+            "recvmsg",
+            "sendmmsg",
+            "sendmsg",
+            "sendto",
+            "setsockopt",
+            "shutdown",
+            "socket",
+            "socketpair",
+        ],
+    ),
+    (
+        "@privileged",
+        &[
+            "@chown",
+            "@clock",
+            "@module",
+            "@raw-io",
+            "@reboot",
+            "@swap",
+            "acct",
+            "bpf",
+            "capset",
+            "chroot",
+            "fanotify_init",
+            "fanotify_mark",
+            "nfsservctl",
+            "open_by_handle_at",
+            "pivot_root",
+            "quotactl",
+            "setdomainname",
+            "setfsgid",
+            "setfsuid",
+            "setgid",
+            "setgroups",
+            "sethostname",
+            "setregid",
+            "setresgid",
+            "setresuid",
+            "setreuid",
+            "setuid",
+            "vhangup",
+        ],
+    ),
+    (
+        "@process",
+        &[
+            "capget",
+            "clone",
+            "clone3",
+            "execveat",
+            "fork",
+            "getrusage",
+            "kill",
+            "pidfd_open",
+            "pidfd_send_signal",
+            "prctl",
+            "rt_sigqueueinfo",
+            "rt_tgsigqueueinfo",
+            "setns",
+            "tgkill",
+            "times",
+            "tkill",
+            "unshare",
+            "vfork",
+            "wait4",
+            "waitid",
+        ],
+    ),
+    ("@raw-io", &["ioperm", "iopl"]),
+    ("@reboot", &["kexec_file_load", "kexec_load", "reboot"]),
+    (
+        "@resources",
+        &[
+            "ioprio_set",
+            "mbind",
+            "migrate_pages",
+            "move_pages",
+            "sched_setaffinity",
+            "sched_setattr",
+            "sched_setparam",
+            "sched_setscheduler",
+            "set_mempolicy",
+            "setpriority",
+            "setrlimit",
+        ],
+    ),
+    (
+        "@setuid",
+        &[
+            "setgid",
+            "setgroups",
+            "setregid",
+            "setresgid",
+            "setresuid",
+            "setreuid",
+            "setuid",
+        ],
+    ),
+    (
+        "@signal",
+        &[
+            "rt_sigaction",
+            "rt_sigpending",
+            "rt_sigprocmask",
+            "rt_sigsuspend",
+            "rt_sigtimedwait",
+            "sigaltstack",
+            "signalfd",
+            "signalfd4",
+        ],
+    ),
+    ("@swap", &["swapoff", "swapon"]),
+    (
+        "@sync",
+        &[
+            "fdatasync",
+            "fsync",
+            "msync",
+            "sync",
+            "sync_file_range",
+            "syncfs",
+        ],
+    ),
+    (
+        "@timer",
+        &[
+            "alarm",
+            "getitimer",
+            "setitimer",
+            "timer_create",
+            "timer_delete",
+            "timer_getoverrun",
+            "timer_gettime",
+            "timer_settime",
+            "timerfd_create",
+            "timerfd_gettime",
+            "timerfd_settime",
+            "times",
+        ],
+    ),
+    (
+        "@system-service",
+        &[
+            "@aio",
+            "@basic-io",
+            "@chown",
+            "@default",
+            "@file-system",
+            "@io-event",
+            "@ipc",
+            "@keyring",
+            "@memlock",
+            "@network-io",
+            "@process",
+            "@resources",
+            "@setuid",
+            "@signal",
+            "@sync",
+            "@timer",
+            "capget",
+            "capset",
+            "copy_file_range",
+            "fadvise64",
+            "flock",
+            "get_mempolicy",
+            "getcpu",
+            "getpriority",
+            "ioctl",
+            "ioprio_get",
+            "kcmp",
+            "madvise",
+            "mincore",
+            "mremap",
+            "name_to_handle_at",
+            "personality",
+            "readahead",
+            "sched_get_priority_max",
+            "sched_get_priority_min",
+            "sched_getattr",
+            "sched_getparam",
+            "sched_getscheduler",
+            "sched_rr_get_interval",
+            "sendfile",
+            "setfsgid",
+            "setfsuid",
+            "setpgid",
+            "setsid",
+            "splice",
+            "sysinfo",
+            "tee",
+            "umask",
+            "uname",
+            "userfaultfd",
+            "vmsplice",
+        ],
+    ),
+];
+
+fn syscall_group(name: &str) -> Option<&'static [&'static str]> {
+    SYSCALL_GROUPS
+        .iter()
+        .find(|(group, _)| *group == name)
+        .map(|(_, syscalls)| *syscalls)
+}
+
+/// the syscalls which are allowed or denied by seccomp, like "@system-service" or "~@mount reboot".
+/// the syscall groups are prefixed with "@", and the list prefixed with "~" is a deny list.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SyscallFilter {
+    deny: bool,
+    names: Vec<String>,
+}
+
+impl SyscallFilter {
+    /// whether the listed syscalls are denied instead of allowed
+    pub fn is_deny_list(&self) -> bool {
+        self.deny
+    }
+
+    // expand the groups into the numbers of the syscalls, the allow list contains the syscalls
+    // needed to execute the command
+    fn numbers(&self) -> BTreeSet<u32> {
+        let mut numbers = BTreeSet::new();
+        let mut names = self.names.iter().map(|n| n.as_str()).collect::<Vec<_>>();
+        if !self.deny {
+            names.extend(SYSCALLS_IMPLIED);
+        }
+
+        let mut expanded = Vec::new();
+        while let Some(name) = names.pop() {
+            if expanded.contains(&name) {
+                continue;
+            }
+            expanded.push(name);
+            match syscall_group(name) {
+                Some(group) => names.extend(group),
+                None => {
+                    if let Some(nr) = syscall_nr(name) {
+                        numbers.insert(nr as u32);
+                    }
+                }
+            }
+        }
+        numbers
+    }
+}
+
+impl FromStr for SyscallFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (deny, list) = match s.strip_prefix('~') {
+            Some(list) => (true, list),
+            None => (false, s),
+        };
+
+        let mut names = Vec::new();
+        for name in list.split_whitespace() {
+            let known = if name.starts_with('@') {
+                syscall_group(name).is_some()
+            } else {
+                syscall_nr(name).is_some()
+            };
+            if !known {
+                return Err(format!("unknown syscall or syscall group: {}", name));
+            }
+            names.push(name.to_string());
+        }
+        Ok(SyscallFilter { deny, names })
+    }
+}
+
+impl DeserializeWith for SyscallFilter {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+        SyscallFilter::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+fn parse_syscall_arch(name: &str) -> Result<u32, String> {
+    match name {
+        "native" => {
+            AUDIT_ARCH_NATIVE.ok_or_else(|| "the native architecture is not supported".to_string())
+        }
+        "x86-64" => Ok(AUDIT_ARCH_X86_64),
+        "x86" => Ok(AUDIT_ARCH_I386),
+        "arm64" => Ok(AUDIT_ARCH_AARCH64),
+        "arm" => Ok(AUDIT_ARCH_ARM),
+        "riscv64" => Ok(AUDIT_ARCH_RISCV64),
+        "loongarch64" => Ok(AUDIT_ARCH_LOONGARCH64),
+        _ => Err(format!("invalid syscall architecture: {}", name)),
+    }
+}
+
+/// deserialize the architectures whose syscalls are allowed, like "native x86"
+pub fn deserialize_syscall_archs<'de, D>(de: D) -> Result<Vec<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(de)?;
+    let mut archs = Vec::new();
+    for name in s.split_whitespace() {
+        let arch = parse_syscall_arch(name).map_err(de::Error::custom)?;
+        if !archs.contains(&arch) {
+            archs.push(arch);
+        }
+    }
+    Ok(archs)
+}
+
+fn bpf_stmt(code: u32, k: u32) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+fn bpf_jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt,
+        jf,
+        k,
+    }
+}
+
+/// the seccomp-bpf program, which is built in the parent and loaded by the child before execve
+pub(super) struct SeccompProgram {
+    filter: Vec<libc::sock_filter>,
+}
+
+impl SeccompProgram {
+    /// build the program from the filter and the allowed architectures, the calls which are
+    /// not allowed kill the process. the syscall names only apply to the native architecture,
+    /// so the calls of the other architectures are killed if the filter is set.
+    pub(super) fn new(
+        filter: Option<&SyscallFilter>,
+        archs: &[u32],
+    ) -> Result<Option<SeccompProgram>, String> {
+        if filter.is_none() && archs.is_empty() {
+            return Ok(None);
+        }
+
+        let mut prog = vec![bpf_stmt(
+            libc::BPF_LD | libc::BPF_W | libc::BPF_ABS,
+            SECCOMP_DATA_ARCH,
+        )];
+
+        let filter = match filter {
+            Some(f) => f,
+            None => {
+                for arch in archs {
+                    prog.push(bpf_jump(
+                        libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+                        *arch,
+                        0,
+                        1,
+                    ));
+                    prog.push(bpf_stmt(
+                        libc::BPF_RET | libc::BPF_K,
+                        libc::SECCOMP_RET_ALLOW,
+                    ));
+                }
+                prog.push(bpf_stmt(
+                    libc::BPF_RET | libc::BPF_K,
+                    libc::SECCOMP_RET_KILL_PROCESS,
+                ));
+                return Ok(Some(SeccompProgram { filter: prog }));
+            }
+        };
+
+        let native = AUDIT_ARCH_NATIVE
+            .ok_or_else(|| "the native architecture is not supported".to_string())?;
+        if !archs.is_empty() && !archs.contains(&native) {
+            return Err(
+                "SystemCallFilter= needs the native architecture to be allowed".to_string(),
+            );
+        }
+        prog.push(bpf_jump(
+            libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+            native,
+            1,
+            0,
+        ));
+        prog.push(bpf_stmt(
+            libc::BPF_RET | libc::BPF_K,
+            libc::SECCOMP_RET_KILL_PROCESS,
+        ));
+        prog.push(bpf_stmt(
+            libc::BPF_LD | libc::BPF_W | libc::BPF_ABS,
+            SECCOMP_DATA_NR,
+        ));
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            prog.push(bpf_jump(
+                libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+                X32_SYSCALL_BIT,
+                0,
+                1,
+            ));
+            prog.push(bpf_stmt(
+                libc::BPF_RET | libc::BPF_K,
+                libc::SECCOMP_RET_KILL_PROCESS,
+            ));
+        }
+
+        let (matched, unmatched) = if filter.deny {
+            (libc::SECCOMP_RET_KILL_PROCESS, libc::SECCOMP_RET_ALLOW)
+        } else {
+            (libc::SECCOMP_RET_ALLOW, libc::SECCOMP_RET_KILL_PROCESS)
+        };
+        for nr in filter.numbers() {
+            prog.push(bpf_jump(
+                libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+                nr,
+                0,
+                1,
+            ));
+            prog.push(bpf_stmt(libc::BPF_RET | libc::BPF_K, matched));
+        }
+        prog.push(bpf_stmt(libc::BPF_RET | libc::BPF_K, unmatched));
+
+        if prog.len() > BPF_MAXINSNS {
+            return Err(format!(
+                "the seccomp filter is too long: {} instructions",
+                prog.len()
+            ));
+        }
+        Ok(Some(SeccompProgram { filter: prog }))
+    }
+
+    /// load the program into the child, which needs NoNewPrivileges or CAP_SYS_ADMIN
+    pub(super) fn load(&self) -> nix::Result<()> {
+        let fprog = libc::sock_fprog {
+            len: self.filter.len() as libc::c_ushort,
+            filter: self.filter.as_ptr() as *mut libc::sock_filter,
+        };
+        let ret = unsafe {
+            libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER as libc::c_ulong,
+                &fprog as *const libc::sock_fprog as libc::c_ulong,
+            )
+        };
+        Errno::result(ret).map(drop)
+    }
+}
+
+// the syscall numbers of the names, which are the same on the architectures based on
+// asm-generic, and those only on some architectures are looked up by syscall_nr_arch
+fn syscall_nr(name: &str) -> Option<libc::c_long> {
+    let nr = match name {
+        "accept" => libc::SYS_accept,
+        "accept4" => libc::SYS_accept4,
+        "acct" => libc::SYS_acct,
+        "add_key" => libc::SYS_add_key,
+        "adjtimex" => libc::SYS_adjtimex,
+        "bind" => libc::SYS_bind,
+        "bpf" => libc::SYS_bpf,
+        "brk" => libc::SYS_brk,
+        "capget" => libc::SYS_capget,
+        "capset" => libc::SYS_capset,
+        "chdir" => libc::SYS_chdir,
+        "chroot" => libc::SYS_chroot,
+        "clock_adjtime" => libc::SYS_clock_adjtime,
+        "clock_getres" => libc::SYS_clock_getres,
+        "clock_gettime" => libc::SYS_clock_gettime,
+        "clock_nanosleep" => libc::SYS_clock_nanosleep,
+        "clock_settime" => libc::SYS_clock_settime,
+        "clone" => libc::SYS_clone,
+        "clone3" => libc::SYS_clone3,
+        "close" => libc::SYS_close,
+        "close_range" => libc::SYS_close_range,
+        "connect" => libc::SYS_connect,
+        "copy_file_range" => libc::SYS_copy_file_range,
+        "delete_module" => libc::SYS_delete_module,
+        "dup" => libc::SYS_dup,
+        "dup3" => libc::SYS_dup3,
+        "epoll_create1" => libc::SYS_epoll_create1,
+        "epoll_ctl" => libc::SYS_epoll_ctl,
+        "epoll_pwait" => libc::SYS_epoll_pwait,
+        "epoll_pwait2" => libc::SYS_epoll_pwait2,
+        "eventfd2" => libc::SYS_eventfd2,
+        "execve" => libc::SYS_execve,
+        "execveat" => libc::SYS_execveat,
+        "exit" => libc::SYS_exit,
+        "exit_group" => libc::SYS_exit_group,
+        "faccessat" => libc::SYS_faccessat,
+        "faccessat2" => libc::SYS_faccessat2,
+        "fadvise64" => libc::SYS_fadvise64,
+        "fallocate" => libc::SYS_fallocate,
+        "fanotify_init" => libc::SYS_fanotify_init,
+        "fanotify_mark" => libc::SYS_fanotify_mark,
+        "fchdir" => libc::SYS_fchdir,
+        "fchmod" => libc::SYS_fchmod,
+        "fchmodat" => libc::SYS_fchmodat,
+        "fchown" => libc::SYS_fchown,
+        "fchownat" => libc::SYS_fchownat,
+        "fcntl" => libc::SYS_fcntl,
+        "fdatasync" => libc::SYS_fdatasync,
+        "fgetxattr" => libc::SYS_fgetxattr,
+        "finit_module" => libc::SYS_finit_module,
+        "flistxattr" => libc::SYS_flistxattr,
+        "flock" => libc::SYS_flock,
+        "fremovexattr" => libc::SYS_fremovexattr,
+        "fsconfig" => libc::SYS_fsconfig,
+        "fsetxattr" => libc::SYS_fsetxattr,
+        "fsmount" => libc::SYS_fsmount,
+        "fsopen" => libc::SYS_fsopen,
+        "fspick" => libc::SYS_fspick,
+        "fstatfs" => libc::SYS_fstatfs,
+        "fsync" => libc::SYS_fsync,
+        "ftruncate" => libc::SYS_ftruncate,
+        "futex" => libc::SYS_futex,
+        "futex_waitv" => libc::SYS_futex_waitv,
+        "get_mempolicy" => libc::SYS_get_mempolicy,
+        "get_robust_list" => libc::SYS_get_robust_list,
+        "getcpu" => libc::SYS_getcpu,
+        "getcwd" => libc::SYS_getcwd,
+        "getdents64" => libc::SYS_getdents64,
+        "getegid" => libc::SYS_getegid,
+        "geteuid" => libc::SYS_geteuid,
+        "getgid" => libc::SYS_getgid,
+        "getgroups" => libc::SYS_getgroups,
+        "getitimer" => libc::SYS_getitimer,
+        "getpeername" => libc::SYS_getpeername,
+        "getpgid" => libc::SYS_getpgid,
+        "getpid" => libc::SYS_getpid,
+        "getppid" => libc::SYS_getppid,
+        "getpriority" => libc::SYS_getpriority,
+        "getrandom" => libc::SYS_getrandom,
+        "getresgid" => libc::SYS_getresgid,
+        "getresuid" => libc::SYS_getresuid,
+        "getrusage" => libc::SYS_getrusage,
+        "getsid" => libc::SYS_getsid,
+        "getsockname" => libc::SYS_getsockname,
+        "getsockopt" => libc::SYS_getsockopt,
+        "gettid" => libc::SYS_gettid,
+        "gettimeofday" => libc::SYS_gettimeofday,
+        "getuid" => libc::SYS_getuid,
+        "getxattr" => libc::SYS_getxattr,
+        "init_module" => libc::SYS_init_module,
+        "inotify_add_watch" => libc::SYS_inotify_add_watch,
+        "inotify_init1" => libc::SYS_inotify_init1,
+        "inotify_rm_watch" => libc::SYS_inotify_rm_watch,
+        "io_cancel" => libc::SYS_io_cancel,
+        "io_destroy" => libc::SYS_io_destroy,
+        "io_getevents" => libc::SYS_io_getevents,
+        "io_setup" => libc::SYS_io_setup,
+        "io_submit" => libc::SYS_io_submit,
+        "io_uring_enter" => libc::SYS_io_uring_enter,
+        "io_uring_register" => libc::SYS_io_uring_register,
+        "io_uring_setup" => libc::SYS_io_uring_setup,
+        "ioctl" => libc::SYS_ioctl,
+        "ioprio_get" => libc::SYS_ioprio_get,
+        "ioprio_set" => libc::SYS_ioprio_set,
+        "kcmp" => libc::SYS_kcmp,
+        "kexec_load" => libc::SYS_kexec_load,
+        "keyctl" => libc::SYS_keyctl,
+        "kill" => libc::SYS_kill,
+        "landlock_add_rule" => libc::SYS_landlock_add_rule,
+        "landlock_create_ruleset" => libc::SYS_landlock_create_ruleset,
+        "landlock_restrict_self" => libc::SYS_landlock_restrict_self,
+        "lgetxattr" => libc::SYS_lgetxattr,
+        "linkat" => libc::SYS_linkat,
+        "listen" => libc::SYS_listen,
+        "listxattr" => libc::SYS_listxattr,
+        "llistxattr" => libc::SYS_llistxattr,
+        "lookup_dcookie" => libc::SYS_lookup_dcookie,
+        "lremovexattr" => libc::SYS_lremovexattr,
+        "lseek" => libc::SYS_lseek,
+        "lsetxattr" => libc::SYS_lsetxattr,
+        "madvise" => libc::SYS_madvise,
+        "mbind" => libc::SYS_mbind,
+        "membarrier" => libc::SYS_membarrier,
+        "memfd_create" => libc::SYS_memfd_create,
+        "migrate_pages" => libc::SYS_migrate_pages,
+        "mincore" => libc::SYS_mincore,
+        "mkdirat" => libc::SYS_mkdirat,
+        "mknodat" => libc::SYS_mknodat,
+        "mlock" => libc::SYS_mlock,
+        "mlock2" => libc::SYS_mlock2,
+        "mlockall" => libc::SYS_mlockall,
+        "mmap" => libc::SYS_mmap,
+        "mount" => libc::SYS_mount,
+        "mount_setattr" => libc::SYS_mount_setattr,
+        "move_mount" => libc::SYS_move_mount,
+        "move_pages" => libc::SYS_move_pages,
+        "mprotect" => libc::SYS_mprotect,
+        "mq_getsetattr" => libc::SYS_mq_getsetattr,
+        "mq_notify" => libc::SYS_mq_notify,
+        "mq_open" => libc::SYS_mq_open,
+        "mq_timedreceive" => libc::SYS_mq_timedreceive,
+        "mq_timedsend" => libc::SYS_mq_timedsend,
+        "mq_unlink" => libc::SYS_mq_unlink,
+        "mremap" => libc::SYS_mremap,
+        "msgctl" => libc::SYS_msgctl,
+        "msgget" => libc::SYS_msgget,
+        "msgrcv" => libc::SYS_msgrcv,
+        "msgsnd" => libc::SYS_msgsnd,
+        "msync" => libc::SYS_msync,
+        "munlock" => libc::SYS_munlock,
+        "munlockall" => libc::SYS_munlockall,
+        "munmap" => libc::SYS_munmap,
+        "name_to_handle_at" => libc::SYS_name_to_handle_at,
+        "nanosleep" => libc::SYS_nanosleep,
+        "nfsservctl" => libc::SYS_nfsservctl,
+        "open_by_handle_at" => libc::SYS_open_by_handle_at,
+        "open_tree" => libc::SYS_open_tree,
+        "openat" => libc::SYS_openat,
+        "openat2" => libc::SYS_openat2,
+        "perf_event_open" => libc::SYS_perf_event_open,
+        "personality" => libc::SYS_personality,
+        "pidfd_getfd" => libc::SYS_pidfd_getfd,
+        "pidfd_open" => libc::SYS_pidfd_open,
+        "pidfd_send_signal" => libc::SYS_pidfd_send_signal,
+        "pipe2" => libc::SYS_pipe2,
+        "pivot_root" => libc::SYS_pivot_root,
+        "pkey_alloc" => libc::SYS_pkey_alloc,
+        "pkey_free" => libc::SYS_pkey_free,
+        "pkey_mprotect" => libc::SYS_pkey_mprotect,
+        "ppoll" => libc::SYS_ppoll,
+        "prctl" => libc::SYS_prctl,
+        "pread64" => libc::SYS_pread64,
+        "preadv" => libc::SYS_preadv,
+        "preadv2" => libc::SYS_preadv2,
+        "prlimit64" => libc::SYS_prlimit64,
+        "process_madvise" => libc::SYS_process_madvise,
+        "process_mrelease" => libc::SYS_process_mrelease,
+        "process_vm_readv" => libc::SYS_process_vm_readv,
+        "process_vm_writev" => libc::SYS_process_vm_writev,
+        "pselect6" => libc::SYS_pselect6,
+        "ptrace" => libc::SYS_ptrace,
+        "pwrite64" => libc::SYS_pwrite64,
+        "pwritev" => libc::SYS_pwritev,
+        "pwritev2" => libc::SYS_pwritev2,
+        "quotactl" => libc::SYS_quotactl,
+        "quotactl_fd" => libc::SYS_quotactl_fd,
+        "read" => libc::SYS_read,
+        "readahead" => libc::SYS_readahead,
+        "readlinkat" => libc::SYS_readlinkat,
+        "readv" => libc::SYS_readv,
+        "reboot" => libc::SYS_reboot,
+        "recvfrom" => libc::SYS_recvfrom,
+        "recvmmsg" => libc::SYS_recvmmsg,
+        "recvmsg" => libc::SYS_recvmsg,
+        "remap_file_pages" => libc::SYS_remap_file_pages,
+        "removexattr" => libc::SYS_removexattr,
+        "renameat2" => libc::SYS_renameat2,
+        "request_key" => libc::SYS_request_key,
+        "restart_syscall" => libc::SYS_restart_syscall,
+        "rseq" => libc::SYS_rseq,
+        "rt_sigaction" => libc::SYS_rt_sigaction,
+        "rt_sigpending" => libc::SYS_rt_sigpending,
+        "rt_sigprocmask" => libc::SYS_rt_sigprocmask,
+        "rt_sigqueueinfo" => libc::SYS_rt_sigqueueinfo,
+        "rt_sigreturn" => libc::SYS_rt_sigreturn,
+        "rt_sigsuspend" => libc::SYS_rt_sigsuspend,
+        "rt_sigtimedwait" => libc::SYS_rt_sigtimedwait,
+        "rt_tgsigqueueinfo" => libc::SYS_rt_tgsigqueueinfo,
+        "sched_get_priority_max" => libc::SYS_sched_get_priority_max,
+        "sched_get_priority_min" => libc::SYS_sched_get_priority_min,
+        "sched_getaffinity" => libc::SYS_sched_getaffinity,
+        "sched_getattr" => libc::SYS_sched_getattr,
+        "sched_getparam" => libc::SYS_sched_getparam,
+        "sched_getscheduler" => libc::SYS_sched_getscheduler,
+        "sched_rr_get_interval" => libc::SYS_sched_rr_get_interval,
+        "sched_setaffinity" => libc::SYS_sched_setaffinity,
+        "sched_setattr" => libc::SYS_sched_setattr,
+        "sched_setparam" => libc::SYS_sched_setparam,
+        "sched_setscheduler" => libc::SYS_sched_setscheduler,
+        "sched_yield" => libc::SYS_sched_yield,
+        "seccomp" => libc::SYS_seccomp,
+        "semctl" => libc::SYS_semctl,
+        "semget" => libc::SYS_semget,
+        "semop" => libc::SYS_semop,
+        "semtimedop" => libc::SYS_semtimedop,
+        "sendfile" => libc::SYS_sendfile,
+        "sendmmsg" => libc::SYS_sendmmsg,
+        "sendmsg" => libc::SYS_sendmsg,
+        "sendto" => libc::SYS_sendto,
+        "set_mempolicy" => libc::SYS_set_mempolicy,
+        "set_mempolicy_home_node" => libc::SYS_set_mempolicy_home_node,
+        "set_robust_list" => libc::SYS_set_robust_list,
+        "set_tid_address" => libc::SYS_set_tid_address,
+        "setdomainname" => libc::SYS_setdomainname,
+        "setfsgid" => libc::SYS_setfsgid,
+        "setfsuid" => libc::SYS_setfsuid,
+        "setgid" => libc::SYS_setgid,
+        "setgroups" => libc::SYS_setgroups,
+        "sethostname" => libc::SYS_sethostname,
+        "setitimer" => libc::SYS_setitimer,
+        "setns" => libc::SYS_setns,
+        "setpgid" => libc::SYS_setpgid,
+        "setpriority" => libc::SYS_setpriority,
+        "setregid" => libc::SYS_setregid,
+        "setresgid" => libc::SYS_setresgid,
+        "setresuid" => libc::SYS_setresuid,
+        "setreuid" => libc::SYS_setreuid,
+        "setsid" => libc::SYS_setsid,
+        "setsockopt" => libc::SYS_setsockopt,
+        "settimeofday" => libc::SYS_settimeofday,
+        "setuid" => libc::SYS_setuid,
+        "setxattr" => libc::SYS_setxattr,
+        "shmat" => libc::SYS_shmat,
+        "shmctl" => libc::SYS_shmctl,
+        "shmdt" => libc::SYS_shmdt,
+        "shmget" => libc::SYS_shmget,
+        "shutdown" => libc::SYS_shutdown,
+        "sigaltstack" => libc::SYS_sigaltstack,
+        "signalfd4" => libc::SYS_signalfd4,
+        "socket" => libc::SYS_socket,
+        "socketpair" => libc::SYS_socketpair,
+        "splice" => libc::SYS_splice,
+        "statfs" => libc::SYS_statfs,
+        "statx" => libc::SYS_statx,
+        "swapoff" => libc::SYS_swapoff,
+        "swapon" => libc::SYS_swapon,
+        "symlinkat" => libc::SYS_symlinkat,
+        "sync" => libc::SYS_sync,
+        "syncfs" => libc::SYS_syncfs,
+        "sysinfo" => libc::SYS_sysinfo,
+        "syslog" => libc::SYS_syslog,
+        "tee" => libc::SYS_tee,
+        "tgkill" => libc::SYS_tgkill,
+        "timer_create" => libc::SYS_timer_create,
+        "timer_delete" => libc::SYS_timer_delete,
+        "timer_getoverrun" => libc::SYS_timer_getoverrun,
+        "timer_gettime" => libc::SYS_timer_gettime,
+        "timer_settime" => libc::SYS_timer_settime,
+        "timerfd_create" => libc::SYS_timerfd_create,
+        "timerfd_gettime" => libc::SYS_timerfd_gettime,
+        "timerfd_settime" => libc::SYS_timerfd_settime,
+        "times" => libc::SYS_times,
+        "tkill" => libc::SYS_tkill,
+        "truncate" => libc::SYS_truncate,
+        "umask" => libc::SYS_umask,
+        "umount2" => libc::SYS_umount2,
+        "uname" => libc::SYS_uname,
+        "unlinkat" => libc::SYS_unlinkat,
+        "unshare" => libc::SYS_unshare,
+        "userfaultfd" => libc::SYS_userfaultfd,
+        "utimensat" => libc::SYS_utimensat,
+        "vhangup" => libc::SYS_vhangup,
+        "vmsplice" => libc::SYS_vmsplice,
+        "wait4" => libc::SYS_wait4,
+        "waitid" => libc::SYS_waitid,
+        "write" => libc::SYS_write,
+        "writev" => libc::SYS_writev,
+        _ => return syscall_nr_arch(name),
+    };
+    Some(nr)
+}
+
+#[cfg(target_arch = "x86_64")]
+fn syscall_nr_arch(name: &str) -> Option<libc::c_long> {
+    let nr = match name {
+        "access" => libc::SYS_access,
+        "alarm" => libc::SYS_alarm,
+        "arch_prctl" => libc::SYS_arch_prctl,
+        "chmod" => libc::SYS_chmod,
+        "chown" => libc::SYS_chown,
+        "creat" => libc::SYS_creat,
+        "dup2" => libc::SYS_dup2,
+        "epoll_create" => libc::SYS_epoll_create,
+        "epoll_wait" => libc::SYS_epoll_wait,
+        "eventfd" => libc::SYS_eventfd,
+        "fchmodat2" => libc::SYS_fchmodat2,
+        "fork" => libc::SYS_fork,
+        "fstat" => libc::SYS_fstat,
+        "futimesat" => libc::SYS_futimesat,
+        "get_thread_area" => libc::SYS_get_thread_area,
+        "getdents" => libc::SYS_getdents,
+        "getpgrp" => libc::SYS_getpgrp,
+        "getrlimit" => libc::SYS_getrlimit,
+        "inotify_init" => libc::SYS_inotify_init,
+        "ioperm" => libc::SYS_ioperm,
+        "iopl" => libc::SYS_iopl,
+        "kexec_file_load" => libc::SYS_kexec_file_load,
+        "lchown" => libc::SYS_lchown,
+        "link" => libc::SYS_link,
+        "lstat" => libc::SYS_lstat,
+        "memfd_secret" => libc::SYS_memfd_secret,
+        "mkdir" => libc::SYS_mkdir,
+        "mknod" => libc::SYS_mknod,
+        "modify_ldt" => libc::SYS_modify_ldt,
+        "mseal" => libc::SYS_mseal,
+        "newfstatat" => libc::SYS_newfstatat,
+        "open" => libc::SYS_open,
+        "pause" => libc::SYS_pause,
+        "pipe" => libc::SYS_pipe,
+        "poll" => libc::SYS_poll,
+        "readlink" => libc::SYS_readlink,
+        "rename" => libc::SYS_rename,
+        "renameat" => libc::SYS_renameat,
+        "rmdir" => libc::SYS_rmdir,
+        "select" => libc::SYS_select,
+        "set_thread_area" => libc::SYS_set_thread_area,
+        "setrlimit" => libc::SYS_setrlimit,
+        "signalfd" => libc::SYS_signalfd,
+        "stat" => libc::SYS_stat,
+        "symlink" => libc::SYS_symlink,
+        "sync_file_range" => libc::SYS_sync_file_range,
+        "sysfs" => libc::SYS_sysfs,
+        "time" => libc::SYS_time,
+        "unlink" => libc::SYS_unlink,
+        "uselib" => libc::SYS_uselib,
+        "ustat" => libc::SYS_ustat,
+        "utime" => libc::SYS_utime,
+        "utimes" => libc::SYS_utimes,
+        "vfork" => libc::SYS_vfork,
+        _ => return None,
+    };
+    Some(nr)
+}
+
+#[cfg(target_arch = "aarch64")]
+fn syscall_nr_arch(name: &str) -> Option<libc::c_long> {
+    let nr = match name {
+        "fstat" => libc::SYS_fstat,
+        "kexec_file_load" => libc::SYS_kexec_file_load,
+        "memfd_secret" => libc::SYS_memfd_secret,
+        "mseal" => libc::SYS_mseal,
+        "newfstatat" => libc::SYS_newfstatat,
+        _ => return None,
+    };
+    Some(nr)
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn syscall_nr_arch(_name: &str) -> Option<libc::c_long> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{syscall_nr, SeccompProgram, SyscallFilter, AUDIT_ARCH_I386, AUDIT_ARCH_NATIVE};
+    use nix::sys::signal::Signal;
+    use nix::sys::wait::{self, WaitStatus};
+    use nix::unistd::{self, ForkResult};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_syscall_filter_from_str() {
+        let filter = SyscallFilter::from_str("~@mount reboot").unwrap();
+        assert!(filter.is_deny_list());
+        let numbers = filter.numbers();
+        assert!(numbers.contains(&(syscall_nr("mount").unwrap() as u32)));
+        assert!(numbers.contains(&(syscall_nr("reboot").unwrap() as u32)));
+        assert!(!numbers.contains(&(syscall_nr("execve").unwrap() as u32)));
+
+        // the allow list contains the syscalls needed to start the command
+        let filter = SyscallFilter::from_str("@basic-io").unwrap();
+        assert!(!filter.is_deny_list());
+        let numbers = filter.numbers();
+        assert!(numbers.contains(&(syscall_nr("read").unwrap() as u32)));
+        assert!(numbers.contains(&(syscall_nr("execve").unwrap() as u32)));
+        assert!(numbers.contains(&(syscall_nr("exit_group").unwrap() as u32)));
+        assert!(!numbers.contains(&(syscall_nr("mount").unwrap() as u32)));
+
+        assert!(SyscallFilter::from_str("@no-such-group").is_err());
+        assert!(SyscallFilter::from_str("no_such_syscall").is_err());
+    }
+
+    #[test]
+    fn test_seccomp_program() {
+        assert!(SeccompProgram::new(None, &[]).unwrap().is_none());
+
+        let filter = SyscallFilter::from_str("@system-service").unwrap();
+        let prog = SeccompProgram::new(Some(&filter), &[]).unwrap().unwrap();
+        assert!(prog.filter.len() > 2 * filter.numbers().len());
+
+        // the syscall names only apply to the native architecture
+        if AUDIT_ARCH_NATIVE != Some(AUDIT_ARCH_I386) {
+            assert!(SeccompProgram::new(Some(&filter), &[AUDIT_ARCH_I386]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_seccomp_load() {
+        let filter = SyscallFilter::from_str("~getcwd").unwrap();
+        let prog = SeccompProgram::new(Some(&filter), &[]).unwrap().unwrap();
+
+        match unsafe { unistd::fork() }.unwrap() {
+            ForkResult::Child => {
+                let ret = unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+                if ret < 0 || prog.load().is_err() {
+                    std::process::exit(1);
+                }
+                let _ = unistd::getcwd();
+                std::process::exit(0);
+            }
+            ForkResult::Parent { child } => {
+                let status = wait::waitpid(child, None).unwrap();
+                assert!(matches!(status, WaitStatus::Signaled(_, Signal::SIGSYS, _)));
+            }
+        }
+    }
+}
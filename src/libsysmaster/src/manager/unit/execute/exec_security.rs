@@ -0,0 +1,398 @@
+use super::exec_base::{ExecCmdError, ExecStep};
+use super::exec_seccomp::{SeccompProgram, SyscallFilter};
+use crate::manager::DeserializeWith;
+use nix::errno::Errno;
+use nix::unistd;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
+
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+// the capabilities indexed by their numbers
+const CAPABILITIES: [&str; 41] = [
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_DAC_READ_SEARCH",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETPCAP",
+    "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_BROADCAST",
+    "CAP_NET_ADMIN",
+    "CAP_NET_RAW",
+    "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER",
+    "CAP_SYS_MODULE",
+    "CAP_SYS_RAWIO",
+    "CAP_SYS_CHROOT",
+    "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT",
+    "CAP_SYS_ADMIN",
+    "CAP_SYS_BOOT",
+    "CAP_SYS_NICE",
+    "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME",
+    "CAP_SYS_TTY_CONFIG",
+    "CAP_MKNOD",
+    "CAP_LEASE",
+    "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL",
+    "CAP_SETFCAP",
+    "CAP_MAC_OVERRIDE",
+    "CAP_MAC_ADMIN",
+    "CAP_SYSLOG",
+    "CAP_WAKE_ALARM",
+    "CAP_BLOCK_SUSPEND",
+    "CAP_AUDIT_READ",
+    "CAP_PERFMON",
+    "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+// the secure bits indexed by their bits
+const SECURE_BITS: [&str; 6] = [
+    "noroot",
+    "noroot-locked",
+    "no-setuid-fixup",
+    "no-setuid-fixup-locked",
+    "keep-caps",
+    "keep-caps-locked",
+];
+const SECBIT_KEEP_CAPS: u32 = 1 << 4;
+
+/// a set of capabilities, like "CAP_NET_BIND_SERVICE CAP_NET_RAW", the set prefixed with "~"
+/// contains all the capabilities except the listed ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CapabilitySet(u64);
+
+impl CapabilitySet {
+    /// the set of all the capabilities
+    pub fn all() -> CapabilitySet {
+        CapabilitySet((1 << CAPABILITIES.len()) - 1)
+    }
+
+    /// whether the set contains no capability
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// whether the capability numbered cap is in the set
+    pub fn contains(&self, cap: usize) -> bool {
+        self.0 & (1 << cap) != 0
+    }
+
+    fn caps(self) -> impl Iterator<Item = usize> {
+        (0..CAPABILITIES.len()).filter(move |cap| self.contains(*cap))
+    }
+
+    // the lower and the upper halves of the set, as the capability data of capset(2)
+    fn halves(self) -> [u32; 2] {
+        [self.0 as u32, (self.0 >> 32) as u32]
+    }
+}
+
+impl FromStr for CapabilitySet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (invert, list) = match s.strip_prefix('~') {
+            Some(list) => (true, list),
+            None => (false, s),
+        };
+
+        let mut set = 0;
+        for name in list.split_whitespace() {
+            let name = name.to_uppercase();
+            let cap = CAPABILITIES
+                .iter()
+                .position(|c| *c == name)
+                .ok_or_else(|| format!("invalid capability: {}", name))?;
+            set |= 1 << cap;
+        }
+
+        if invert {
+            set = CapabilitySet::all().0 & !set;
+        }
+        Ok(CapabilitySet(set))
+    }
+}
+
+/// the secure bits of the process, like "keep-caps noroot-locked"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SecureBits(u32);
+
+impl FromStr for SecureBits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bits = 0;
+        for name in s.split_whitespace() {
+            let bit = SECURE_BITS
+                .iter()
+                .position(|b| *b == name)
+                .ok_or_else(|| format!("invalid secure bit: {}", name))?;
+            bits |= 1 << bit;
+        }
+        Ok(SecureBits(bits))
+    }
+}
+
+macro_rules! impl_deserialize_with {
+    ($t:ty) => {
+        impl DeserializeWith for $t {
+            fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s = String::deserialize(de)?;
+                <$t>::from_str(&s).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_deserialize_with!(CapabilitySet);
+impl_deserialize_with!(SecureBits);
+
+#[repr(C)]
+struct CapUserHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct CapUserData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+fn prctl(option: libc::c_int, arg: libc::c_ulong) -> nix::Result<()> {
+    let ret = unsafe { libc::prctl(option, arg, 0, 0, 0) };
+    Errno::result(ret).map(drop)
+}
+
+/// whether the kernel supports the ambient capabilities, which are added in linux 4.3
+pub(super) fn ambient_supported() -> bool {
+    prctl(
+        libc::PR_CAP_AMBIENT,
+        libc::PR_CAP_AMBIENT_IS_SET as libc::c_ulong,
+    )
+    .is_ok()
+}
+
+fn capget() -> nix::Result<[CapUserData; 2]> {
+    let mut header = CapUserHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let mut data = [CapUserData::default(); 2];
+    let ret = unsafe { libc::syscall(libc::SYS_capget, &mut header, data.as_mut_ptr()) };
+    Errno::result(ret).map(|_| data)
+}
+
+fn capset(data: &[CapUserData; 2]) -> nix::Result<()> {
+    let mut header = CapUserHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let ret = unsafe { libc::syscall(libc::SYS_capset, &mut header, data.as_ptr()) };
+    Errno::result(ret).map(drop)
+}
+
+/// the privileges of the spawned process: the capabilities, the secure bits and the seccomp filter
+#[derive(Debug, Clone, Default)]
+pub struct ExecSecurity {
+    /// the capabilities kept in the bounding set, all if None
+    pub capability_bounding_set: Option<CapabilitySet>,
+    /// the capabilities passed to the command as the ambient set, which are kept for the user
+    pub ambient_capabilities: CapabilitySet,
+    /// the process and its children never gain privileges by execve, like the setuid programs
+    pub no_new_privileges: bool,
+    /// the secure bits
+    pub secure_bits: SecureBits,
+    /// the syscalls which are allowed or denied
+    pub syscall_filter: Option<SyscallFilter>,
+    /// the architectures whose syscalls are allowed, all if empty
+    pub syscall_archs: Vec<u32>,
+}
+
+impl ExecSecurity {
+    /// check the ambient capabilities are in the bounding set, and build the seccomp filter
+    pub fn verify(&self) -> Result<(), String> {
+        if let Some(bounding) = self.capability_bounding_set {
+            if self.ambient_capabilities.0 & !bounding.0 != 0 {
+                return Err("AmbientCapabilities= is not in CapabilityBoundingSet=".to_string());
+            }
+        }
+
+        SeccompProgram::new(self.syscall_filter.as_ref(), &self.syscall_archs).map(drop)
+    }
+
+    /// build the seccomp program in the parent, which is loaded by the child
+    pub(super) fn seccomp_program(&self) -> Result<Option<SeccompProgram>, ExecCmdError> {
+        SeccompProgram::new(self.syscall_filter.as_ref(), &self.syscall_archs).map_err(|e| {
+            log::error!("failed to build the seccomp filter: {}", e);
+            ExecCmdError::ExecFailed(ExecStep::Seccomp, Errno::EINVAL)
+        })
+    }
+
+    /// drop the capabilities from the bounding set and set the secure bits, called before the
+    /// credentials are changed, which needs CAP_SETPCAP
+    pub(super) fn apply_privileged(&self) -> nix::Result<()> {
+        if let Some(bounding) = self.capability_bounding_set {
+            for cap in 0..CAPABILITIES.len() {
+                if bounding.contains(cap) {
+                    continue;
+                }
+                match prctl(libc::PR_CAPBSET_DROP, cap as libc::c_ulong) {
+                    // the capabilities are unknown to the kernel
+                    Err(Errno::EINVAL) => break,
+                    ret => ret?,
+                }
+            }
+        }
+
+        // the permitted capabilities are kept when changing the user, and then raised as ambient
+        let mut bits = self.secure_bits.0;
+        if !self.ambient_capabilities.is_empty() {
+            bits |= SECBIT_KEEP_CAPS;
+        }
+        if bits != 0 {
+            prctl(libc::PR_SET_SECUREBITS, bits as libc::c_ulong)?;
+        }
+        Ok(())
+    }
+
+    /// raise the ambient capabilities and load the seccomp filter, called after the credentials
+    /// are changed and right before execve
+    pub(super) fn apply_unprivileged(&self, seccomp: Option<&SeccompProgram>) -> nix::Result<()> {
+        if !self.ambient_capabilities.is_empty() {
+            // the ambient capabilities must be permitted and inheritable
+            let mut data = capget()?;
+            for (half, bits) in self.ambient_capabilities.halves().iter().enumerate() {
+                data[half].inheritable |= bits;
+            }
+            capset(&data)?;
+            for cap in self.ambient_capabilities.caps() {
+                let ret = unsafe {
+                    libc::prctl(
+                        libc::PR_CAP_AMBIENT,
+                        libc::PR_CAP_AMBIENT_RAISE as libc::c_ulong,
+                        cap as libc::c_ulong,
+                        0,
+                        0,
+                    )
+                };
+                Errno::result(ret)?;
+            }
+        }
+
+        // the filter can only be loaded by the unprivileged user with no new privileges
+        let implied = seccomp.is_some() && !unistd::geteuid().is_root();
+        if self.no_new_privileges || implied {
+            prctl(libc::PR_SET_NO_NEW_PRIVS, 1)?;
+        }
+
+        if let Some(prog) = seccomp {
+            prog.load()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{capget, CapabilitySet, ExecSecurity, SecureBits, CAPABILITIES};
+    use crate::manager::unit::execute::exec_seccomp::SyscallFilter;
+    use nix::sys::wait::{self, WaitStatus};
+    use nix::unistd::{self, ForkResult};
+    use std::fs;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_capability_set_from_str() {
+        let set = CapabilitySet::from_str("CAP_CHOWN cap_net_raw").unwrap();
+        assert!(set.contains(0));
+        assert!(set.contains(13));
+        assert_eq!(set.caps().collect::<Vec<_>>(), vec![0, 13]);
+
+        let set = CapabilitySet::from_str("~CAP_SYS_ADMIN").unwrap();
+        assert!(!set.contains(21));
+        assert_eq!(set.caps().count(), CAPABILITIES.len() - 1);
+
+        assert!(CapabilitySet::from_str("").unwrap().is_empty());
+        assert!(CapabilitySet::from_str("CAP_NO_SUCH").is_err());
+
+        assert_eq!(
+            SecureBits::from_str("noroot keep-caps-locked"),
+            Ok(SecureBits(0b100001))
+        );
+        assert!(SecureBits::from_str("keepcaps").is_err());
+    }
+
+    #[test]
+    fn test_security_verify() {
+        let security = ExecSecurity {
+            capability_bounding_set: Some(CapabilitySet::from_str("CAP_CHOWN").unwrap()),
+            ambient_capabilities: CapabilitySet::from_str("CAP_NET_RAW").unwrap(),
+            ..Default::default()
+        };
+        assert!(security.verify().is_err());
+
+        let security = ExecSecurity {
+            syscall_filter: Some(SyscallFilter::from_str("@system-service").unwrap()),
+            ..Default::default()
+        };
+        assert!(security.verify().is_ok());
+    }
+
+    // the capability is dropped from the bounding set without privileges only if it is not held,
+    // so the test drops the capabilities in a child and checks the status
+    #[test]
+    fn test_security_apply() {
+        let security = ExecSecurity {
+            capability_bounding_set: Some(CapabilitySet::from_str("~CAP_SYS_BOOT").unwrap()),
+            no_new_privileges: true,
+            ..Default::default()
+        };
+        let is_root = unistd::geteuid().is_root();
+
+        match unsafe { unistd::fork() }.unwrap() {
+            ForkResult::Child => {
+                if is_root && security.apply_privileged().is_err() {
+                    std::process::exit(1);
+                }
+                if security.apply_unprivileged(None).is_err() {
+                    std::process::exit(2);
+                }
+                let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+                if !status.lines().any(|l| l == "NoNewPrivs:\t1") {
+                    std::process::exit(3);
+                }
+                // CAP_SYS_BOOT is the bit 22
+                let bounding = status
+                    .lines()
+                    .find_map(|l| l.strip_prefix("CapBnd:\t"))
+                    .and_then(|v| u64::from_str_radix(v, 16).ok());
+                if is_root && bounding.map_or(true, |b| b & (1 << 22) != 0) {
+                    std::process::exit(4);
+                }
+                if capget().is_err() {
+                    std::process::exit(5);
+                }
+                std::process::exit(0);
+            }
+            ForkResult::Parent { child } => {
+                let status = wait::waitpid(child, None).unwrap();
+                assert_eq!(status, WaitStatus::Exited(child, 0));
+            }
+        }
+    }
+}
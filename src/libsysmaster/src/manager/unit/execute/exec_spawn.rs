@@ -1,63 +1,268 @@
-use super::exec_base::{ExecCmdError, ExecParameters};
-use super::ExecContext;
+use super::exec_base::{ExecCmdError, ExecParameters, ExecStep};
+use super::exec_credential::ExecCredential;
+use super::exec_env::{expand_args, load_env_files, Specifiers};
+use super::exec_sandbox::{ExecRuntime, ExecSandbox};
+use super::exec_seccomp::SeccompProgram;
+use super::exec_security::{ambient_supported, ExecSecurity};
+use super::exec_stdio::setup_stdio;
+use super::{ExecContext, ExecOutput};
+use crate::manager::unit::logs::{LogCollector, LogPipe};
 use crate::manager::unit::unit_entry::Unit;
-use crate::manager::unit::unit_rentry::ExecCommand;
-use libcgroup;
+use crate::manager::unit::unit_rentry::{ExecCommand, ExecCommandFlags};
+use libcgroup::{self, CgroupErr};
 use log;
-use nix::fcntl::FcntlArg;
+use nix::errno::Errno;
+use nix::fcntl::{self, FcntlArg, OFlag};
+use nix::sys::wait;
 use nix::unistd::{self, ForkResult, Pid};
-use regex::Regex;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::unix::prelude::RawFd;
 use std::path::PathBuf;
 use std::process;
 use std::rc::Rc;
-use std::thread;
-use std::time::Duration;
 use walkdir::DirEntry;
 use walkdir::WalkDir;
 
 use libutils::fd_util;
 
-pub(in crate::manager::unit) struct ExecSpawn;
+pub(in crate::manager::unit) struct ExecSpawn {
+    // associated objects
+    logs: Rc<LogCollector>,
+
+    // owned objects
+    // the runtimes of the sandboxes, keyed by the units using them
+    runtimes: RefCell<HashMap<String, Rc<ExecRuntime>>>,
+}
 
 impl ExecSpawn {
-    pub(in crate::manager::unit) fn new() -> ExecSpawn {
-        ExecSpawn
+    pub(in crate::manager::unit) fn new(logsr: &Rc<LogCollector>) -> ExecSpawn {
+        ExecSpawn {
+            logs: Rc::clone(logsr),
+            runtimes: RefCell::new(HashMap::new()),
+        }
     }
 
+    /// spawn the command, the units in joined are the ones the unit joins the namespace of
     pub(in crate::manager::unit) fn spawn(
         &self,
         unit: &Unit,
         cmdline: &ExecCommand,
         params: &ExecParameters,
         ctx: Rc<ExecContext>,
+        joined: &[String],
     ) -> Result<Pid, ExecCmdError> {
+        let dynamic_uid = if ctx.dynamic_user() {
+            let uid = unit.alloc_dynamic_uid().ok_or_else(|| {
+                ExecCmdError::CredentialError("no free uid for the dynamic user".to_string())
+            })?;
+            Some(uid)
+        } else {
+            None
+        };
+        let flags = cmdline.flags();
+        let privileged = flags.contains(ExecCommandFlags::FULLY_PRIVILEGED);
+        let mut cred = ExecCredential::new(&ctx, dynamic_uid)?;
+        // "!!" is the same as "!" only if the ambient capabilities are not supported
+        if privileged
+            || flags.contains(ExecCommandFlags::NO_SETUID)
+            || (flags.contains(ExecCommandFlags::AMBIENT_MAGIC) && !ambient_supported())
+        {
+            cred.keep_identity();
+        }
+        // the files are read and the specifiers are expanded before forking, so the errors
+        // fail the command instead of the child
+        let env_files = load_env_files(&ctx.environment_files())?;
+        let specifiers = Specifiers::new(unit.id(), cred.home());
+        let argv = cmdline
+            .argv()
+            .iter()
+            .map(|arg| specifiers.expand(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        // the command with the "+" prefix is not restricted by the security settings or the sandbox
+        let (security, sandbox) = if privileged {
+            (ExecSecurity::default(), ExecSandbox::default())
+        } else {
+            (ctx.security(), ctx.sandbox())
+        };
+        let seccomp = security.seccomp_program()?;
+        let runtime = self.runtime_acquire(unit.id(), joined, &sandbox)?;
+        let log_pipe = self.open_log_pipe(unit, &ctx)?;
+        // the child reports the failed step through the pipe, which is closed by execve on success
+        let (status_reader, status_writer) = match unistd::pipe2(OFlag::O_CLOEXEC) {
+            Ok(pipe) => pipe,
+            Err(_e) => {
+                if let Some(pipe) = &log_pipe {
+                    self.logs.close(pipe);
+                }
+                return Err(ExecCmdError::SpawnError);
+            }
+        };
+
         let ret = unsafe { unistd::fork() };
 
         match ret {
             Ok(ForkResult::Parent { child }) => {
                 log::debug!("child pid is :{}", child);
-                libcgroup::cg_attach(child, &unit.cg_path())
-                    .map_err(|e| ExecCmdError::CgroupError(e.to_string()))?;
-                Ok(child)
+                fd_util::close(status_writer);
+                let status = exec_status_read(status_reader);
+                fd_util::close(status_reader);
+                if let Some(pipe) = &log_pipe {
+                    if status.is_some() {
+                        self.logs.close(pipe);
+                    } else {
+                        pipe.attach(child);
+                    }
+                }
+
+                match status {
+                    None => Ok(child),
+                    Some((step, errno)) => {
+                        // the child has exited, reap it here as nobody is watching it
+                        let _ = wait::waitpid(child, None);
+                        log::error!(
+                            "failed to execute {} of {} at step {}: {}",
+                            cmdline.path(),
+                            unit.id(),
+                            step,
+                            errno
+                        );
+                        Err(ExecCmdError::ExecFailed(step, errno))
+                    }
+                }
             }
             Ok(ForkResult::Child) => {
-                thread::sleep(Duration::from_secs(2));
-                exec_child(unit, cmdline, params, ctx);
-                process::exit(0);
+                fd_util::close(status_reader);
+                let log_fd = log_pipe.as_ref().map(|p| p.writer());
+                let child = ExecChild {
+                    argv: &argv,
+                    env_files: &env_files,
+                    cred: &cred,
+                    log_fd,
+                    sandbox: &sandbox,
+                    runtime: runtime.as_deref(),
+                    security: &security,
+                    seccomp: seccomp.as_ref(),
+                };
+                let mut status_fd = status_writer;
+                let (step, errno) = exec_child(unit, cmdline, params, ctx, &child, &mut status_fd);
+                exec_status_write(status_fd, step, errno);
+                process::exit(1);
             }
-            Err(_e) => Err(ExecCmdError::SpawnError),
+            Err(_e) => {
+                fd_util::close(status_reader);
+                fd_util::close(status_writer);
+                if let Some(pipe) = &log_pipe {
+                    self.logs.close(pipe);
+                }
+                Err(ExecCmdError::SpawnError)
+            }
+        }
+    }
+
+    /// release the runtime of the sandbox of the unit, which is removed when it is not used by
+    /// the joined units either
+    pub(in crate::manager::unit) fn runtime_release(&self, unit: &str) {
+        if self.runtimes.borrow_mut().remove(unit).is_some() {
+            log::debug!("release the sandbox runtime of {}", unit);
         }
     }
+
+    // the runtime is created at the first time, or taken from the joined units, and kept until
+    // the unit is inactive
+    fn runtime_acquire(
+        &self,
+        unit: &str,
+        joined: &[String],
+        sandbox: &ExecSandbox,
+    ) -> Result<Option<Rc<ExecRuntime>>, ExecCmdError> {
+        if !sandbox.needs_runtime() {
+            return Ok(None);
+        }
+
+        let mut runtimes = self.runtimes.borrow_mut();
+        if let Some(runtime) = runtimes.get(unit) {
+            return Ok(Some(Rc::clone(runtime)));
+        }
+
+        let runtime = match joined.iter().find_map(|u| runtimes.get(u)) {
+            Some(runtime) => Rc::clone(runtime),
+            None => Rc::new(
+                ExecRuntime::new(unit).map_err(|e| ExecCmdError::SandboxError(e.to_string()))?,
+            ),
+        };
+        runtimes.insert(unit.to_string(), Rc::clone(&runtime));
+        Ok(Some(runtime))
+    }
+
+    // the pipe is needed only if the output or the error is collected
+    fn open_log_pipe(
+        &self,
+        unit: &Unit,
+        ctx: &ExecContext,
+    ) -> Result<Option<Rc<LogPipe>>, ExecCmdError> {
+        let (_, stdout, stderr) = ctx.stdio();
+        if stdout != ExecOutput::Log && stderr != ExecOutput::Log {
+            return Ok(None);
+        }
+
+        self.logs
+            .open(unit.id())
+            .map(Some)
+            .map_err(|e| ExecCmdError::LogError(e.to_string()))
+    }
+}
+
+// the data prepared in the parent for the child
+struct ExecChild<'a> {
+    // the arguments whose specifiers are expanded
+    argv: &'a [String],
+    env_files: &'a [(String, String)],
+    cred: &'a ExecCredential,
+    log_fd: Option<RawFd>,
+    sandbox: &'a ExecSandbox,
+    runtime: Option<&'a ExecRuntime>,
+    security: &'a ExecSecurity,
+    // the seccomp filter built in the parent
+    seccomp: Option<&'a SeccompProgram>,
 }
 
-fn exec_child(unit: &Unit, cmdline: &ExecCommand, params: &ExecParameters, ctx: Rc<ExecContext>) {
+// returns the failed step and the errno, it does not return if the command is executed. the
+// status fd may be moved, and the new one is set back
+fn exec_child(
+    unit: &Unit,
+    cmdline: &ExecCommand,
+    params: &ExecParameters,
+    ctx: Rc<ExecContext>,
+    child: &ExecChild,
+    status_fd: &mut RawFd,
+) -> (ExecStep, Errno) {
+    // attach to the cgroup before anything else, so no process escapes from it
+    if let Err(e) = libcgroup::cg_attach(unistd::getpid(), &unit.cg_path()) {
+        log::error!("failed to attach the child to the cgroup: {}", e);
+        return (ExecStep::Cgroup, cgroup_errno(&e));
+    }
+
     log::debug!("exec context params: {:?}", ctx.envs());
+    let cred = child.cred;
 
     for (key, value) in ctx.envs() {
         params.add_env(&key, value.to_string());
     }
 
-    let (cmd, args) = build_run_args(unit, cmdline, params);
+    // the environment files override the Environment= settings
+    for (key, value) in child.env_files {
+        params.add_env(key, value.to_string());
+    }
+
+    // the environments configured explicitly take precedence
+    for (key, value) in cred.envs() {
+        if params.get_env(&key).is_none() {
+            params.add_env(&key, value);
+        }
+    }
+
+    let (cmd, args) = build_run_args(unit, cmdline, child.argv, params);
     let cstr_args = args
         .iter()
         .map(|cstring| cstring.as_c_str())
@@ -69,7 +274,7 @@ fn exec_child(unit: &Unit, cmdline: &ExecCommand, params: &ExecParameters, ctx:
         args
     );
 
-    let mut envs = build_environment(unit, params.fds().len());
+    let mut envs = build_environment(unit, params);
     envs.append(&mut params.envs());
 
     log::debug!("exec child env env is: {:?}", envs);
@@ -77,82 +282,172 @@ fn exec_child(unit: &Unit, cmdline: &ExecCommand, params: &ExecParameters, ctx:
     let envs_cstr = envs.iter().map(|v| v.as_c_str()).collect::<Vec<_>>();
     let mut keep_fds = params.fds();
 
-    let ret = close_all_fds(params.fds());
-    if !ret {
+    let (stdin, stdout, stderr) = ctx.stdio();
+    if let Err(e) = setup_stdio(&stdin, &stdout, &stderr, &keep_fds, child.log_fd) {
+        log::error!("failed to set up the standard input and output: {:?}", e);
+        return (ExecStep::Stdio, e);
+    }
+
+    // the sockets of the runtime are closed with the needless fds
+    if let Err(e) = child.sandbox.apply(child.runtime) {
+        log::error!("failed to set up the sandbox of the child: {:?}", e);
+        return (ExecStep::Sandbox, e);
+    }
+
+    // the status fd is moved above the passed fds, so it is not in the way of shifting them
+    match fcntl::fcntl(
+        *status_fd,
+        FcntlArg::F_DUPFD_CLOEXEC(keep_fds.len() as RawFd + 3),
+    ) {
+        Ok(fd) => {
+            fd_util::close(*status_fd);
+            *status_fd = fd;
+        }
+        Err(e) => return (ExecStep::Fds, e),
+    }
+
+    let mut fds = params.fds();
+    fds.push(*status_fd);
+    if !close_all_fds(fds) {
         log::error!("close all needless fds failed");
-        return;
+        return (ExecStep::Fds, Errno::EBADF);
     }
 
     if !shift_fds(&mut keep_fds) {
         log::error!("shift all fds error");
-        return;
+        return (ExecStep::Fds, Errno::last());
     }
 
     if !flags_fds(&mut keep_fds) {
         log::error!("flags set all fds error");
-        return;
+        return (ExecStep::Fds, Errno::last());
+    }
+
+    if let Err(e) = ctx.limits().apply() {
+        log::error!("failed to set the limits of the child: {:?}", e);
+        return (ExecStep::Limits, e);
+    }
+
+    if let Err(e) = child.security.apply_privileged() {
+        log::error!("failed to set the capabilities of the child: {:?}", e);
+        return (ExecStep::Capabilities, e);
+    }
+
+    if let Err(e) = cred.apply(&ctx) {
+        log::error!("failed to set the credentials of the child: {:?}", e);
+        return (ExecStep::Credentials, e);
+    }
+
+    // the filter is loaded at last, so the syscalls above are not filtered
+    if let Err(e) = child.security.apply_unprivileged(child.seccomp) {
+        log::error!("failed to set the seccomp filter of the child: {:?}", e);
+        return (ExecStep::Seccomp, e);
     }
 
     log::debug!("exec child envs to execve is: {:?}", envs_cstr);
-    match unistd::execve(&cmd, &cstr_args, &envs_cstr) {
-        Ok(_) => {
-            log::debug!("execv returned Ok()");
-        }
-        Err(e) => {
-            log::error!("exec child failed: {:?}", e);
-            std::process::exit(1);
+    let e = match unistd::execve(&cmd, &cstr_args, &envs_cstr) {
+        Ok(_) => unreachable!(),
+        Err(e) => e,
+    };
+    log::error!("exec child failed: {:?}", e);
+    (ExecStep::Exec, e)
+}
+
+fn cgroup_errno(e: &CgroupErr) -> Errno {
+    match e {
+        CgroupErr::IoError(e) => match e.raw_os_error() {
+            Some(errno) => Errno::from_i32(errno),
+            None if e.kind() == std::io::ErrorKind::NotFound => Errno::ENOENT,
+            None => Errno::EIO,
+        },
+        CgroupErr::KillError(errno) => *errno,
+        CgroupErr::NotSupported => Errno::EOPNOTSUPP,
+    }
+}
+
+// the record is the step and the errno, in the native byte order
+const EXEC_STATUS_SIZE: usize = 8;
+
+fn exec_status_write(fd: RawFd, step: ExecStep, errno: Errno) {
+    let mut buf = [0u8; EXEC_STATUS_SIZE];
+    buf[..4].copy_from_slice(&(step as u32).to_ne_bytes());
+    buf[4..].copy_from_slice(&(errno as i32).to_ne_bytes());
+    // nothing more can be done if the parent is not reading
+    let _ = unistd::write(fd, &buf);
+}
+
+// returns None when the pipe is closed without a record, which means the command is executed
+fn exec_status_read(fd: RawFd) -> Option<(ExecStep, Errno)> {
+    let mut buf = [0u8; EXEC_STATUS_SIZE];
+    let mut len = 0;
+    while len < EXEC_STATUS_SIZE {
+        match unistd::read(fd, &mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(Errno::EINTR) => continue,
+            Err(e) => {
+                log::error!("failed to read the exec status of the child: {}", e);
+                return None;
+            }
         }
     }
+
+    if len < EXEC_STATUS_SIZE {
+        return None;
+    }
+
+    let mut step = [0u8; 4];
+    let mut errno = [0u8; 4];
+    step.copy_from_slice(&buf[..4]);
+    errno.copy_from_slice(&buf[4..]);
+    let step = ExecStep::from_u32(u32::from_ne_bytes(step))?;
+    Some((step, Errno::from_i32(i32::from_ne_bytes(errno))))
 }
 
 // contrast: build_environment
 fn build_run_args(
     _unit: &Unit,
     cmdline: &ExecCommand,
+    argv: &[String],
     env: &ExecParameters,
 ) -> (std::ffi::CString, Vec<std::ffi::CString>) {
     let cmd = std::ffi::CString::new(cmdline.path().clone()).unwrap();
-    let exec_name = std::ffi::CString::new(cmdline.path().clone()).unwrap();
+    let exec_name = std::ffi::CString::new(cmdline.argv0().clone()).unwrap();
 
     let mut args = Vec::new();
     args.push(exec_name);
 
-    let var_regex = Regex::new(r"(\$[A-Z_]+)|(\$\{[A-Z_]+\})").unwrap();
-    for arg in cmdline.argv() {
-        let cap = var_regex.captures(arg);
-        if let Some(cap) = cap {
-            let match_result = {
-                if let Some(mat) = cap.get(1) {
-                    Some(mat.as_str())
-                } else {
-                    cap.get(2).map(|mat| mat.as_str())
-                }
-            };
-
-            if let Some(val) = match_result {
-                let v = val.trim_matches('$').trim_matches('{').trim_matches('}');
-                if let Some(target) = env.get_env(v) {
-                    args.push(
-                        std::ffi::CString::new(var_regex.replace(arg, target).to_string()).unwrap(),
-                    );
-                };
-            }
-            continue;
-        }
-
-        args.push(std::ffi::CString::new(arg.as_str()).unwrap())
+    for arg in expand_args(argv, |name| env.get_env(name)) {
+        args.push(std::ffi::CString::new(arg).unwrap())
     }
 
     (cmd, args)
 }
 
-fn build_environment(_unit: &Unit, fds: usize) -> Vec<std::ffi::CString> {
+fn build_environment(_unit: &Unit, params: &ExecParameters) -> Vec<std::ffi::CString> {
     let mut envs = Vec::new();
 
+    let fds = params.fds().len();
     if fds > 0 {
         envs.push(std::ffi::CString::new(format!("LISTEN_PID={}", nix::unistd::getpid())).unwrap());
 
         envs.push(std::ffi::CString::new(format!("LISTEN_FDS={}", fds)).unwrap());
+
+        let names = params.fd_names();
+        if names.len() == fds {
+            envs.push(
+                std::ffi::CString::new(format!("LISTEN_FDNAMES={}", names.join(":"))).unwrap(),
+            );
+        }
+    }
+
+    // the pid is the child itself, like LISTEN_PID
+    let watchdog_usec = params.watchdog_usec();
+    if watchdog_usec > 0 {
+        envs.push(
+            std::ffi::CString::new(format!("WATCHDOG_PID={}", nix::unistd::getpid())).unwrap(),
+        );
+        envs.push(std::ffi::CString::new(format!("WATCHDOG_USEC={}", watchdog_usec)).unwrap());
     }
     envs
 }
@@ -255,3 +550,30 @@ fn flags_fds(fds: &mut Vec<i32>) -> bool {
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{exec_status_read, exec_status_write, ExecStep};
+    use libutils::fd_util;
+    use nix::errno::Errno;
+    use nix::fcntl::OFlag;
+    use nix::unistd;
+
+    #[test]
+    fn test_exec_status() {
+        let (reader, writer) = unistd::pipe2(OFlag::O_CLOEXEC).unwrap();
+        exec_status_write(writer, ExecStep::Credentials, Errno::EPERM);
+        fd_util::close(writer);
+        assert_eq!(
+            exec_status_read(reader),
+            Some((ExecStep::Credentials, Errno::EPERM))
+        );
+        fd_util::close(reader);
+
+        // closed by execve without a record
+        let (reader, writer) = unistd::pipe2(OFlag::O_CLOEXEC).unwrap();
+        fd_util::close(writer);
+        assert_eq!(exec_status_read(reader), None);
+        fd_util::close(reader);
+    }
+}
@@ -0,0 +1,266 @@
+use crate::manager::DeserializeWith;
+use nix::errno::Errno;
+use nix::fcntl::{self, OFlag};
+use nix::sys::stat::{self, Mode, SFlag};
+use nix::unistd;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::os::unix::io::RawFd;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const PATH_DEV_NULL: &str = "/dev/null";
+const PATH_DEV_CONSOLE: &str = "/dev/console";
+
+/// the standard input of the spawned process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecInput {
+    /// connect to /dev/null
+    Null,
+    /// connect to the console
+    Tty,
+    /// read from the file
+    File(PathBuf),
+    /// connect to the socket passed by the socket unit, which is the accepted connection for Accept= socket
+    Socket,
+    /// connect to the first file descriptor passed by the socket unit
+    Fd,
+}
+
+impl Default for ExecInput {
+    fn default() -> Self {
+        ExecInput::Null
+    }
+}
+
+/// the standard output or error of the spawned process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecOutput {
+    /// the output is the same as the input, the error is the same as the output
+    Inherit,
+    /// collected by sysmaster and stored in the logs of the unit
+    Log,
+    /// connect to /dev/null
+    Null,
+    /// connect to the console
+    Tty,
+    /// write to the file from its beginning without truncating it
+    File(PathBuf),
+    /// append to the file
+    Append(PathBuf),
+    /// truncate the file and write to it
+    Truncate(PathBuf),
+    /// connect to the socket passed by the socket unit, which is the accepted connection for Accept= socket
+    Socket,
+    /// connect to the first file descriptor passed by the socket unit
+    Fd,
+}
+
+impl Default for ExecOutput {
+    fn default() -> Self {
+        ExecOutput::Inherit
+    }
+}
+
+fn parse_path(s: &str, prefix: &str) -> Option<Result<PathBuf, String>> {
+    let path = PathBuf::from(s.strip_prefix(prefix)?);
+    if !path.is_absolute() {
+        return Some(Err(format!("{} is not an absolute path", path.display())));
+    }
+    Some(Ok(path))
+}
+
+impl FromStr for ExecInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(path) = parse_path(s, "file:") {
+            return path.map(ExecInput::File);
+        }
+
+        match s {
+            "null" => Ok(ExecInput::Null),
+            "tty" => Ok(ExecInput::Tty),
+            "socket" => Ok(ExecInput::Socket),
+            "fd" => Ok(ExecInput::Fd),
+            _ => Err(format!("invalid standard input: {}", s)),
+        }
+    }
+}
+
+impl FromStr for ExecOutput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(path) = parse_path(s, "file:") {
+            return path.map(ExecOutput::File);
+        }
+        if let Some(path) = parse_path(s, "append:") {
+            return path.map(ExecOutput::Append);
+        }
+        if let Some(path) = parse_path(s, "truncate:") {
+            return path.map(ExecOutput::Truncate);
+        }
+
+        match s {
+            "inherit" => Ok(ExecOutput::Inherit),
+            // compatible with the units written for systemd
+            "log" | "journal" => Ok(ExecOutput::Log),
+            "null" => Ok(ExecOutput::Null),
+            "tty" => Ok(ExecOutput::Tty),
+            "socket" => Ok(ExecOutput::Socket),
+            "fd" => Ok(ExecOutput::Fd),
+            _ => Err(format!("invalid standard output: {}", s)),
+        }
+    }
+}
+
+impl DeserializeWith for ExecInput {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+        ExecInput::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl DeserializeWith for ExecOutput {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+        ExecOutput::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+fn open_as(path: &str, flags: OFlag, target: RawFd) -> nix::Result<()> {
+    let fd = fcntl::open(
+        path,
+        flags | OFlag::O_NOCTTY | OFlag::O_CLOEXEC,
+        Mode::from_bits_truncate(0o644),
+    )?;
+    if fd == target {
+        return fcntl::fcntl(fd, fcntl::FcntlArg::F_SETFD(fcntl::FdFlag::empty())).map(|_| ());
+    }
+
+    let ret = unistd::dup2(fd, target);
+    unistd::close(fd)?;
+    ret.map(|_| ())
+}
+
+fn dup_as(fd: RawFd, target: RawFd) -> nix::Result<()> {
+    if fd == target {
+        return Ok(());
+    }
+    unistd::dup2(fd, target).map(|_| ())
+}
+
+// the file descriptor passed by the socket unit, the socket mode requires exactly one socket
+fn passed_fd(fds: &[RawFd], socket: bool) -> nix::Result<RawFd> {
+    let fd = *fds.first().ok_or(Errno::EBADF)?;
+    if socket {
+        let st = stat::fstat(fd)?;
+        if fds.len() != 1 || !SFlag::from_bits_truncate(st.st_mode).contains(SFlag::S_IFSOCK) {
+            return Err(Errno::ENOTSOCK);
+        }
+    }
+    Ok(fd)
+}
+
+fn setup_input(input: &ExecInput, fds: &[RawFd]) -> nix::Result<()> {
+    let target = libc::STDIN_FILENO;
+    match input {
+        ExecInput::Null => open_as(PATH_DEV_NULL, OFlag::O_RDONLY, target),
+        ExecInput::Tty => open_as(PATH_DEV_CONSOLE, OFlag::O_RDWR, target),
+        ExecInput::File(path) => open_as(&path.to_string_lossy(), OFlag::O_RDONLY, target),
+        ExecInput::Socket => dup_as(passed_fd(fds, true)?, target),
+        ExecInput::Fd => dup_as(passed_fd(fds, false)?, target),
+    }
+}
+
+fn setup_output(
+    output: &ExecOutput,
+    input: &ExecInput,
+    fds: &[RawFd],
+    log_fd: Option<RawFd>,
+    target: RawFd,
+) -> nix::Result<()> {
+    let flags = OFlag::O_WRONLY | OFlag::O_CREAT;
+    match output {
+        ExecOutput::Inherit => {
+            if target == libc::STDERR_FILENO {
+                return dup_as(libc::STDOUT_FILENO, target);
+            }
+            // the output of sysmaster is kept when the input is not connected to anything
+            if *input == ExecInput::Null {
+                return Ok(());
+            }
+            dup_as(libc::STDIN_FILENO, target)
+        }
+        ExecOutput::Log => dup_as(log_fd.ok_or(Errno::EBADF)?, target),
+        ExecOutput::Null => open_as(PATH_DEV_NULL, OFlag::O_WRONLY, target),
+        ExecOutput::Tty => open_as(PATH_DEV_CONSOLE, OFlag::O_WRONLY, target),
+        ExecOutput::File(path) => open_as(&path.to_string_lossy(), flags, target),
+        ExecOutput::Append(path) => {
+            open_as(&path.to_string_lossy(), flags | OFlag::O_APPEND, target)
+        }
+        ExecOutput::Truncate(path) => {
+            open_as(&path.to_string_lossy(), flags | OFlag::O_TRUNC, target)
+        }
+        ExecOutput::Socket => dup_as(passed_fd(fds, true)?, target),
+        ExecOutput::Fd => dup_as(passed_fd(fds, false)?, target),
+    }
+}
+
+/// redirect the standard input, output and error of the child, called before the passed fds are
+/// shifted. the output is set up after the input, and the error after the output, which may be
+/// duplicated from the former. log_fd is the write end of the pipe to the log collector.
+pub(super) fn setup_stdio(
+    input: &ExecInput,
+    output: &ExecOutput,
+    error: &ExecOutput,
+    fds: &[RawFd],
+    log_fd: Option<RawFd>,
+) -> nix::Result<()> {
+    setup_input(input, fds)?;
+    setup_output(output, input, fds, log_fd, libc::STDOUT_FILENO)?;
+    setup_output(error, input, fds, log_fd, libc::STDERR_FILENO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExecInput, ExecOutput};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_exec_input_from_str() {
+        assert_eq!(ExecInput::from_str("null"), Ok(ExecInput::Null));
+        assert_eq!(ExecInput::from_str("socket"), Ok(ExecInput::Socket));
+        assert_eq!(
+            ExecInput::from_str("file:/tmp/input"),
+            Ok(ExecInput::File(PathBuf::from("/tmp/input")))
+        );
+        assert!(ExecInput::from_str("file:tmp/input").is_err());
+        assert!(ExecInput::from_str("append:/tmp/input").is_err());
+    }
+
+    #[test]
+    fn test_exec_output_from_str() {
+        assert_eq!(ExecOutput::from_str("inherit"), Ok(ExecOutput::Inherit));
+        assert_eq!(ExecOutput::from_str("tty"), Ok(ExecOutput::Tty));
+        assert_eq!(
+            ExecOutput::from_str("append:/var/log/foo.log"),
+            Ok(ExecOutput::Append(PathBuf::from("/var/log/foo.log")))
+        );
+        assert_eq!(
+            ExecOutput::from_str("truncate:/var/log/foo.log"),
+            Ok(ExecOutput::Truncate(PathBuf::from("/var/log/foo.log")))
+        );
+        assert_eq!(ExecOutput::from_str("journal"), Ok(ExecOutput::Log));
+        assert!(ExecOutput::from_str("syslog").is_err());
+    }
+}
@@ -1,5 +1,21 @@
-pub use exec_base::{ExecCmdError, ExecContext, ExecFlags, ExecParameters};
+pub use exec_base::{ExecCmdError, ExecContext, ExecFlags, ExecParameters, ExecStep};
+pub(super) use exec_credential::dynamic_uid_pick;
+pub use exec_limit::{
+    deserialize_cpu_affinity, deserialize_rlimit_sec, deserialize_rlimit_usec, CpuSchedPolicy,
+    ExecLimits, ExecRlimit, IoSchedClass,
+};
+pub use exec_sandbox::{ExecSandbox, ProtectHome, ProtectSystem};
+pub use exec_seccomp::{deserialize_syscall_archs, SyscallFilter};
+pub use exec_security::{CapabilitySet, ExecSecurity, SecureBits};
 pub(super) use exec_spawn::ExecSpawn;
+pub use exec_stdio::{ExecInput, ExecOutput};
 
 mod exec_base;
+mod exec_credential;
+mod exec_env;
+mod exec_limit;
+mod exec_sandbox;
+mod exec_seccomp;
+mod exec_security;
 mod exec_spawn;
+mod exec_stdio;
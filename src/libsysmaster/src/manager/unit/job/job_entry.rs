@@ -25,6 +25,25 @@ pub(in crate::manager) enum JobResult {
     Merged,
 }
 
+impl From<JobResult> for String {
+    fn from(result: JobResult) -> Self {
+        match result {
+            JobResult::Done => "done".into(),
+            JobResult::Cancelled => "canceled".into(),
+            JobResult::TimeOut => "timeout".into(),
+            JobResult::Failed => "failed".into(),
+            JobResult::Dependency => "dependency".into(),
+            JobResult::Skipped => "skipped".into(),
+            JobResult::Invalid => "invalid".into(),
+            JobResult::Assert => "assert".into(),
+            JobResult::UnSupported => "unsupported".into(),
+            JobResult::Collected => "collected".into(),
+            JobResult::Once => "once".into(),
+            JobResult::Merged => "merged".into(),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub(in crate::manager) enum JobStage {
     Init,
@@ -33,6 +52,17 @@ pub(in crate::manager) enum JobStage {
     End(JobResult),
 }
 
+impl From<JobStage> for String {
+    fn from(stage: JobStage) -> Self {
+        match stage {
+            JobStage::Init => "init".into(),
+            JobStage::Wait => "waiting".into(),
+            JobStage::Running => "running".into(),
+            JobStage::End(_) => "end".into(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(in crate::manager::unit) struct JobConf {
     unit: Rc<UnitX>,
@@ -1,5 +1,5 @@
 use super::job_alloc::JobAlloc;
-use super::job_entry::{self, Job, JobConf, JobInfo, JobResult};
+use super::job_entry::{self, Job, JobConf, JobInfo, JobResult, JobStage};
 use super::job_notify::{self};
 use super::job_rentry::{JobAttr, JobKind, JobRe};
 use super::job_stat::JobStat;
@@ -10,15 +10,21 @@ use super::JobErrno;
 use crate::manager::rentry::ReliLastFrame;
 use crate::manager::table::{TableOp, TableSubscribe};
 use crate::manager::unit::data::{UnitActiveState, UnitNotifyFlags};
+use crate::manager::unit::subscribe::Subscribers;
 use crate::manager::unit::unit_base::UnitRelationAtom;
 use crate::manager::unit::unit_datastore::UnitDb;
 use crate::manager::unit::unit_entry::UnitX;
-use crate::manager::unit::unit_rentry::JobMode;
+use crate::manager::unit::unit_rentry::{JobMode, JobTimeoutAction};
+use crate::proto::event::EventData;
+use crate::proto::{JobNew, JobRemoved};
 use crate::reliability::{ReStation, Reliability};
 use libevent::{EventState, EventType, Events, Source};
+use libutils::time_util::USEC_PER_SEC;
 use libutils::{Error, Result};
+use nix::time::{clock_gettime, ClockId};
 use std::cell::RefCell;
-use std::rc::Rc;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
 
 #[derive(Debug)]
 pub(in crate::manager::unit) struct JobAffect {
@@ -126,11 +132,12 @@ impl JobManager {
         eventr: &Rc<Events>,
         relir: &Rc<Reliability>,
         dbr: &Rc<UnitDb>,
+        subscribersr: &Rc<Subscribers>,
     ) -> JobManager {
         let jm = JobManager {
             event: Rc::clone(eventr),
             sub_name: String::from("JobManager"),
-            data: Rc::new(JobManagerData::new(relir, dbr)),
+            data: Rc::new(JobManagerData::new(eventr, relir, dbr, subscribersr)),
         };
         jm.register(eventr, dbr);
         jm
@@ -185,7 +192,6 @@ impl JobManager {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub(in crate::manager::unit) fn remove(&self, id: u32) -> Result<(), JobErrno> {
         self.data.remove(id)?;
         self.try_enable();
@@ -197,35 +203,34 @@ impl JobManager {
         self.data.get_jobinfo(id)
     }
 
+    pub(in crate::manager::unit) fn get_all_jobinfos(&self) -> Vec<JobInfo> {
+        self.data.get_all_jobinfos()
+    }
+
     pub(in crate::manager::unit) fn has_stop_job(&self, unit: &Rc<UnitX>) -> bool {
         self.data.get_suspends(unit).is_some()
     }
 
-    fn try_enable(&self) {
-        // prepare for async-running
-        if self.data.calc_jobs_ready() && !self.data.up_ready() {
-            // somethings new comes in, it should be enabled again.
-            self.enable(&self.event);
-        }
+    pub(in crate::manager::unit) fn take_timeout_action(
+        &self,
+    ) -> Option<(JobTimeoutAction, String)> {
+        self.data.take_timeout_action()
+    }
 
-        // update up_ready
-        self.data.update_up_ready();
+    fn try_enable(&self) {
+        self.data.try_enable();
     }
 
     fn register(&self, eventr: &Rc<Events>, dbr: &Rc<UnitDb>) {
         // event
         let source = Rc::clone(&self.data);
         eventr.add_source(source).unwrap();
+        self.data.attach(Rc::downgrade(&self.data));
 
         // db
         let subscriber = Rc::clone(&self.data);
         dbr.units_register(&self.sub_name, subscriber);
     }
-
-    fn enable(&self, eventr: &Rc<Events>) {
-        let source = Rc::clone(&self.data);
-        eventr.set_enabled(source, EventState::OneShot).unwrap();
-    }
 }
 
 impl Source for JobManagerData {
@@ -269,11 +274,55 @@ impl TableSubscribe<String, Rc<UnitX>> for JobManagerData {
     }
 }
 
+struct JobTimer {
+    id: u32,   // job-id
+    usec: u64, // the deadline in monotonic time
+    mng: Weak<JobManagerData>,
+}
+
+impl JobTimer {
+    fn new(id: u32, usec: u64, mng: Weak<JobManagerData>) -> JobTimer {
+        JobTimer { id, usec, mng }
+    }
+}
+
+impl Source for JobTimer {
+    fn event_type(&self) -> EventType {
+        EventType::TimerMonotonic
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn time(&self) -> u64 {
+        self.usec
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+
+    fn dispatch(&self, _event: &Events) -> Result<i32, Error> {
+        if let Some(mng) = self.mng.upgrade() {
+            mng.dispatch_timer(self.id);
+        }
+        Ok(0)
+    }
+}
+
 //#[derive(Debug)]
 struct JobManagerData {
     // associated objects
+    event: Rc<Events>,
     reli: Rc<Reliability>,
     db: Rc<UnitDb>,
+    subscribers: Rc<Subscribers>,
 
     // owned objects
     // control
@@ -284,9 +333,13 @@ struct JobManagerData {
     /* job */
     jobs: JobTable,  // (relative) stable
     stage: JobTable, // temporary
+    /* timer */
+    timers: RefCell<HashMap<u32, Rc<JobTimer>>>, // key: job-id
+    me: RefCell<Weak<JobManagerData>>,
 
     // status
     running: RefCell<bool>,
+    timeout_action: RefCell<Option<(JobTimeoutAction, String)>>, // (action, reboot-argument)
     #[allow(clippy::type_complexity)]
     text: RefCell<Option<(Rc<UnitX>, UnitActiveState, UnitActiveState, UnitNotifyFlags)>>, // (unit, os, ns, flags) for synchronous finish
 
@@ -296,30 +349,49 @@ struct JobManagerData {
 
 // the declaration "pub(self)" is for identification only.
 impl JobManagerData {
-    pub(self) fn new(relir: &Rc<Reliability>, dbr: &Rc<UnitDb>) -> JobManagerData {
+    pub(self) fn new(
+        eventr: &Rc<Events>,
+        relir: &Rc<Reliability>,
+        dbr: &Rc<UnitDb>,
+        subscribersr: &Rc<Subscribers>,
+    ) -> JobManagerData {
         let _rentry = Rc::new(JobRe::new(relir));
         JobManagerData {
+            event: Rc::clone(eventr),
             reli: Rc::clone(relir),
             db: Rc::clone(dbr),
+            subscribers: Rc::clone(subscribersr),
 
             rentry: Rc::clone(&_rentry),
             ja: JobAlloc::new(relir, &_rentry),
 
             jobs: JobTable::new(dbr),
             stage: JobTable::new(dbr),
+            timers: RefCell::new(HashMap::new()),
+            me: RefCell::new(Weak::new()),
 
             running: RefCell::new(false),
+            timeout_action: RefCell::new(None),
             text: RefCell::new(None),
 
             stat: JobStat::new(),
         }
     }
 
+    pub(self) fn attach(&self, me: Weak<JobManagerData>) {
+        *self.me.borrow_mut() = me;
+    }
+
     pub(self) fn entry_clear(&self) {
         self.jobs.clear();
         self.stage.clear();
         self.ja.clear();
+        let ids = self.timers.borrow().keys().copied().collect::<Vec<_>>();
+        for id in ids.iter() {
+            self.timer_disarm(*id);
+        }
         *self.running.borrow_mut() = false;
+        *self.timeout_action.borrow_mut() = None;
         *self.text.borrow_mut() = None;
         self.stat.clear();
     }
@@ -389,6 +461,15 @@ impl JobManagerData {
 
         // suspends
         self.jobs.coldplug_suspend(unit);
+
+        // timer: the timeouts restart from now
+        if let Some((trigger, _)) = self.jobs.get_trigger_info(unit) {
+            self.timer_arm(&trigger, false);
+            self.timer_arm(&trigger, true);
+        }
+        for suspend in self.jobs.get_suspends(unit).iter() {
+            self.timer_arm(suspend, false);
+        }
     }
 
     pub(self) fn exec(
@@ -404,7 +485,7 @@ impl JobManagerData {
         // build changes in stage
         job_transaction::job_trans_expand(&self.stage, &self.ja, &self.db, config, mode)?;
         job_transaction::job_trans_affect(&self.stage, &self.ja, &self.db, config, mode)?;
-        job_transaction::job_trans_verify(&self.stage, &self.jobs, mode)?;
+        job_transaction::job_trans_verify(&self.stage, &self.jobs, &self.db, config, mode)?;
 
         // commit stage to jobs
         let (add_jobs, del_jobs, update_jobs) = self.jobs.commit(&self.stage, mode)?;
@@ -412,6 +493,17 @@ impl JobManagerData {
         // clear stage
         self.stage.clear();
 
+        // update timers and subscribers
+        self.jobs_removed(&del_jobs);
+        for job_info in jobs_2_jobinfo(&add_jobs).iter() {
+            self.timer_arm(job_info, false);
+            self.subscribers.publish(EventData::JobNew(JobNew {
+                id: job_info.id,
+                unitname: job_info.unit.id().clone(),
+                kind: job_info.kind.into(),
+            }));
+        }
+
         // update statistics
         self.stat
             .update_changes(&(&add_jobs, &del_jobs, &update_jobs));
@@ -451,8 +543,10 @@ impl JobManagerData {
 
                 // update statistics
                 self.stat.update_change(&(&None, &merge_trigger, &None));
+                self.jobs_removed(merge_trigger.iter());
                 if trigger_info.is_some() {
                     let t_jinfo = trigger_info.as_ref().cloned().unwrap().0;
+                    self.timer_arm(&t_jinfo, true); // running timeout
                     let not_retrigger = t_jinfo.kind == t_jinfo.run_kind;
                     self.stat.update_stage_wait(not_retrigger.into(), false); // trigger-non-retrigger[wait->run]: decrease 'wait'
                     self.stat.update_stage_run(not_retrigger.into(), true); // trigger-non-retrigger[wait->run]: increase 'run'
@@ -509,7 +603,6 @@ impl JobManagerData {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub(self) fn remove(&self, id: u32) -> Result<(), JobErrno> {
         assert!(!*self.running.borrow());
 
@@ -534,6 +627,65 @@ impl JobManagerData {
         Ok(())
     }
 
+    pub(self) fn try_enable(&self) {
+        // prepare for async-running
+        if self.calc_jobs_ready() && !self.up_ready() {
+            // somethings new comes in, it should be enabled again.
+            self.enable();
+        }
+
+        // update up_ready
+        self.update_up_ready();
+    }
+
+    pub(self) fn dispatch_timer(&self, id: u32) {
+        self.timer_disarm(id);
+
+        let job_info = match self.jobs.get(id) {
+            Some(job_info) => job_info,
+            None => return, // the job has been finished already
+        };
+        let unit = Rc::clone(&job_info.unit);
+        log::warn!(
+            "job {} {:?} of unit {} timed out.",
+            id,
+            job_info.kind,
+            unit.id()
+        );
+
+        self.reli.set_last_frame1(ReliLastFrame::JobManager as u32);
+        self.do_remove(&job_info, JobResult::TimeOut, true);
+        self.reli.clear_last_frame();
+        self.try_enable();
+
+        // the action of the manager
+        let action = unit
+            .get_config()
+            .config_data()
+            .borrow()
+            .Unit
+            .JobTimeoutAction;
+        if action != JobTimeoutAction::None {
+            let argument = unit
+                .get_config()
+                .config_data()
+                .borrow()
+                .Unit
+                .JobTimeoutRebootArgument
+                .clone();
+            log::error!(
+                "job of unit {} timed out, taking the action: {:?}.",
+                unit.id(),
+                action
+            );
+            *self.timeout_action.borrow_mut() = Some((action, argument));
+        }
+    }
+
+    pub(self) fn take_timeout_action(&self) -> Option<(JobTimeoutAction, String)> {
+        self.timeout_action.take()
+    }
+
     pub(self) fn update_up_ready(&self) {
         self.jobs.update_up_ready();
     }
@@ -543,6 +695,10 @@ impl JobManagerData {
         self.jobs.get(id)
     }
 
+    pub(self) fn get_all_jobinfos(&self) -> Vec<JobInfo> {
+        self.jobs.get_all()
+    }
+
     pub(self) fn up_ready(&self) -> bool {
         self.jobs.up_ready()
     }
@@ -559,9 +715,75 @@ impl JobManagerData {
         self.jobs.get_suspend(unit, JobKind::Stop)
     }
 
+    fn enable(&self) {
+        let source = self.me.borrow().upgrade().unwrap();
+        self.event.set_enabled(source, EventState::OneShot).unwrap();
+    }
+
+    fn timer_arm(&self, job_info: &JobInfo, running: bool) {
+        let usec = match job_timeout_usec(&job_info.unit, running) {
+            Some(usec) => usec,
+            None => return, // no limit
+        };
+
+        // the earlier deadline prevails when both of the timeouts are set
+        let deadline = now_monotonic().saturating_add(usec);
+        if let Some(timer) = self.timers.borrow().get(&job_info.id) {
+            if timer.usec <= deadline {
+                return;
+            }
+        }
+
+        self.timer_disarm(job_info.id);
+        let timer = Rc::new(JobTimer::new(
+            job_info.id,
+            deadline,
+            self.me.borrow().clone(),
+        ));
+        let source = Rc::clone(&timer);
+        if let Err(e) = self.event.add_source(source) {
+            log::error!("failed to add the timer of job {}: {}", job_info.id, e);
+            return;
+        }
+        let source = Rc::clone(&timer);
+        if let Err(e) = self.event.set_enabled(source, EventState::On) {
+            log::error!("failed to arm the timer of job {}: {}", job_info.id, e);
+            return;
+        }
+        self.timers.borrow_mut().insert(job_info.id, timer);
+    }
+
+    fn timer_disarm(&self, id: u32) {
+        let timer = self.timers.borrow_mut().remove(&id);
+        if let Some(source) = timer {
+            if let Err(e) = self.event.del_source(source) {
+                log::debug!("failed to delete the timer of job {}: {}", id, e);
+            }
+        }
+    }
+
+    /// the jobs are deleted from the job table: disarm their timers and tell the subscribers
+    fn jobs_removed<'a>(&self, jobs: impl IntoIterator<Item = &'a Rc<Job>>) {
+        for job in jobs {
+            self.timer_disarm(job.get_id());
+            let result = match job.get_stage() {
+                JobStage::End(result) => result,
+                _ => JobResult::Cancelled,
+            };
+            self.subscribers.publish(EventData::JobRemoved(JobRemoved {
+                id: job.get_id(),
+                unitname: job.unit().id().clone(),
+                kind: job.kind().into(),
+                result: result.into(),
+            }));
+        }
+    }
+
     fn remove_unit(&self, unit: &UnitX) {
         // delete related jobs
         let (del_trigger, del_suspends) = self.jobs.remove_unit(unit);
+        self.jobs_removed(del_trigger.iter());
+        self.jobs_removed(&del_suspends);
 
         // update statistics
         self.stat.update_change(&(&None, &del_trigger, &None));
@@ -614,6 +836,12 @@ impl JobManagerData {
                 other.trigger(unit);
             }
         }
+
+        // the slice waits for its members to be gone before it stops
+        let atom = UnitRelationAtom::UnitAtomInSlice;
+        for slice in self.db.dep_gets_atom(unit, atom) {
+            slice.trigger(unit);
+        }
     }
 
     fn do_remove(&self, job_info: &JobInfo, result: JobResult, inside: bool) -> bool {
@@ -646,6 +874,8 @@ impl JobManagerData {
             del_suspend = del_s.pop();
         }
         let del_one = del_trigger.is_some() || del_suspend.is_some();
+        self.jobs_removed(del_trigger.iter());
+        self.jobs_removed(del_suspend.iter());
 
         // simulate and notify unit events, which are not generated by the unit.
         if del_one {
@@ -671,6 +901,7 @@ impl JobManagerData {
         let result_rel = JobResult::Dependency;
         let del_rel =
             job_transaction::job_trans_fallback(&self.jobs, &self.db, unit, run_kind, result_rel);
+        self.jobs_removed(&del_rel);
 
         // simulate and notify unit events, which are not generated by the unit.
         for u in job_table::jobs_2_units(&del_rel).iter() {
@@ -796,6 +1027,26 @@ fn jobs_2_jobinfo(jobs: &[Rc<Job>]) -> Vec<JobInfo> {
     jobs.iter().map(|jr| JobInfo::map(jr)).collect::<Vec<_>>()
 }
 
+// the time limit of the job, in microseconds
+fn job_timeout_usec(unit: &UnitX, running: bool) -> Option<u64> {
+    let config = unit.get_config();
+    let data = config.config_data();
+    let usec = match running {
+        true => data.borrow().Unit.JobRunningTimeoutSec,
+        false => data.borrow().Unit.JobTimeoutSec,
+    };
+
+    // 0 and "infinity" mean no limit
+    usec.filter(|usec| *usec != 0 && *usec != u64::MAX)
+}
+
+fn now_monotonic() -> u64 {
+    match clock_gettime(ClockId::CLOCK_MONOTONIC) {
+        Ok(ts) => ts.tv_sec() as u64 * USEC_PER_SEC + ts.tv_nsec() as u64 / 1000,
+        Err(_) => 0,
+    }
+}
+
 fn job_trans_check_input(config: &JobConf, mode: JobMode) -> Result<(), JobErrno> {
     let kind = config.get_kind();
     let unit = config.get_unit();
@@ -835,7 +1086,7 @@ mod tests {
         let event = Rc::new(Events::new().unwrap());
         let rentry = Rc::new(UnitRe::new(&reli));
         let db = Rc::new(UnitDb::new(&rentry));
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         log::debug!("job_reli, reli:{}.", Rc::strong_count(&reli)); // 3
         log::debug!("job_reli, event:{}.", Rc::strong_count(&event)); // 2
@@ -862,7 +1113,7 @@ mod tests {
     #[test]
     fn job_exec_input_check() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
         let mut affect = JobAffect::new(true);
 
         let conf = JobConf::new(&unit_test1, JobKind::Stop);
@@ -877,7 +1128,7 @@ mod tests {
     #[test]
     fn job_exec_single() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         let mut affect = JobAffect::new(true);
         let conf = JobConf::new(&unit_test1, JobKind::Start);
@@ -898,7 +1149,7 @@ mod tests {
     fn job_exec_multi() {
         let relation = Some(UnitRelations::UnitRequires);
         let (event, reli, db, unit_test1, unit_test2) = prepare_unit_multi(relation);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         let mut affect = JobAffect::new(true);
         let conf = JobConf::new(&unit_test1, JobKind::Start);
@@ -927,7 +1178,7 @@ mod tests {
     #[test]
     fn job_notify() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         let conf = JobConf::new(&unit_test1, JobKind::Start);
         let ret = jm.notify(&conf, JobMode::Replace);
@@ -941,7 +1192,7 @@ mod tests {
     #[test]
     fn job_try_finish_async() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
         let os = UnitActiveState::UnitInActive;
         let ns = UnitActiveState::UnitActive;
         let flags = UnitNotifyFlags::empty();
@@ -953,7 +1204,7 @@ mod tests {
     #[test]
     fn job_try_finish_sync() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
         let os = UnitActiveState::UnitInActive;
         let ns = UnitActiveState::UnitActive;
         let flags = UnitNotifyFlags::empty();
@@ -974,7 +1225,7 @@ mod tests {
     #[test]
     fn job_run_finish_single() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         let conf = JobConf::new(&unit_test1, JobKind::Nop);
         jm.exec(&conf, JobMode::Replace, &mut JobAffect::new(false))
@@ -990,7 +1241,7 @@ mod tests {
     #[test]
     fn job_run_finish_multi() {
         let (event, reli, db, unit_test1, unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         let conf1 = JobConf::new(&unit_test1, JobKind::Nop);
         jm.exec(&conf1, JobMode::Replace, &mut JobAffect::new(false))
@@ -1009,7 +1260,7 @@ mod tests {
     #[test]
     fn job_run_unit_finish_single() {
         let (event, reli, db, unit_test1, unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         let conf = JobConf::new(&unit_test1, JobKind::Nop);
         jm.exec(&conf, JobMode::Replace, &mut JobAffect::new(false))
@@ -1029,7 +1280,7 @@ mod tests {
     #[test]
     fn job_run_unit_finish_multi() {
         let (event, reli, db, unit_test1, unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
 
         let conf1 = JobConf::new(&unit_test1, JobKind::Nop);
         jm.exec(&conf1, JobMode::Replace, &mut JobAffect::new(false))
@@ -1052,7 +1303,7 @@ mod tests {
     #[test]
     fn job_remove() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
         let mut affect = JobAffect::new(true);
 
         // nothing exists
@@ -1073,7 +1324,7 @@ mod tests {
     #[test]
     fn job_get_jobinfo() {
         let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
         let mut affect = JobAffect::new(true);
 
         // nothing exists
@@ -1100,7 +1351,7 @@ mod tests {
     #[test]
     fn job_has_stop_job() {
         let (event, reli, db, unit_test1, unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
         let mut affect = JobAffect::new(true);
 
         // nothing exists
@@ -1129,7 +1380,7 @@ mod tests {
     #[test]
     fn job_remove_unit() {
         let (event, reli, db, unit_test1, unit_test2) = prepare_unit_multi(None);
-        let jm = JobManager::new(&event, &reli, &db);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
         let mut affect = JobAffect::new(true);
 
         let conf = JobConf::new(&unit_test1, JobKind::Start);
@@ -1147,6 +1398,32 @@ mod tests {
         assert_eq!(jm.data.jobs.len(), 0);
     }
 
+    #[test]
+    fn job_timeout() {
+        let (event, reli, db, unit_test1, _unit_test2) = prepare_unit_multi(None);
+        let jm = JobManager::new(&event, &reli, &db, &Rc::new(Subscribers::new()));
+        let mut affect = JobAffect::new(true);
+        {
+            let config = unit_test1.get_config().config_data();
+            let mut data = config.borrow_mut();
+            data.Unit.JobTimeoutSec = Some(USEC_PER_SEC);
+            data.Unit.JobTimeoutAction = JobTimeoutAction::Reboot;
+        }
+
+        let conf = JobConf::new(&unit_test1, JobKind::Start);
+        let ret = jm.exec(&conf, JobMode::Replace, &mut affect);
+        assert!(ret.is_ok());
+        let job_info = affect.adds.pop().unwrap();
+        assert!(jm.data.timers.borrow().contains_key(&job_info.id));
+
+        jm.data.dispatch_timer(job_info.id);
+        assert_eq!(jm.data.jobs.len(), 0);
+        assert!(jm.data.timers.borrow().is_empty());
+        let action = jm.take_timeout_action();
+        assert_eq!(action, Some((JobTimeoutAction::Reboot, String::new())));
+        assert_eq!(jm.take_timeout_action(), None);
+    }
+
     #[allow(clippy::type_complexity)]
     fn prepare_unit_multi(
         relation: Option<UnitRelations>,
@@ -24,6 +24,22 @@ pub(in crate::manager) enum JobKind {
     ReloadOrStart,
 }
 
+impl From<JobKind> for String {
+    fn from(kind: JobKind) -> Self {
+        match kind {
+            JobKind::Start => "start".into(),
+            JobKind::Stop => "stop".into(),
+            JobKind::Reload => "reload".into(),
+            JobKind::Restart => "restart".into(),
+            JobKind::Verify => "verify".into(),
+            JobKind::Nop => "nop".into(),
+            JobKind::TryReload => "try-reload".into(),
+            JobKind::TryRestart => "try-restart".into(),
+            JobKind::ReloadOrStart => "reload-or-start".into(),
+        }
+    }
+}
+
 pub(super) fn job_is_basic_op(kind: JobKind) -> bool {
     match kind {
         JobKind::Start | JobKind::Stop | JobKind::Reload | JobKind::Restart => true,
@@ -246,6 +246,31 @@ impl JobTable {
         self.t_id.borrow().get(&id).map(|job| JobInfo::map(job))
     }
 
+    pub(super) fn get_all(&self) -> Vec<JobInfo> {
+        let mut jobs = self
+            .t_id
+            .borrow()
+            .values()
+            .map(|job| JobInfo::map(job))
+            .collect::<Vec<_>>();
+        jobs.sort_by_key(|job| job.id);
+        jobs
+    }
+
+    /// the units whose jobs are ordered with the others, sorted by the unit id
+    pub(super) fn get_order_units(&self) -> Vec<Rc<UnitX>> {
+        let mut units = self
+            .t_id
+            .borrow()
+            .values()
+            .filter(|job| !job.attr().ignore_order)
+            .map(|job| Rc::clone(job.unit()))
+            .collect::<Vec<_>>();
+        units.sort_by(|a, b| a.id().cmp(b.id()));
+        units.dedup();
+        units
+    }
+
     pub(super) fn get_suspend(&self, unit: &UnitX, kind: JobKind) -> Option<JobInfo> {
         self.t_unit
             .borrow()
@@ -253,6 +278,15 @@ impl JobTable {
             .map(|job| JobInfo::map(&job))
     }
 
+    pub(super) fn get_suspends(&self, unit: &UnitX) -> Vec<JobInfo> {
+        self.t_unit
+            .borrow()
+            .get_suspends(unit)
+            .into_iter()
+            .map(|job| JobInfo::map(&job))
+            .collect::<Vec<_>>()
+    }
+
     pub(super) fn get_trigger_info(&self, unit: &UnitX) -> Option<(JobInfo, bool)> {
         self.t_unit
             .borrow()
@@ -7,6 +7,7 @@ use crate::manager::unit::unit_base::{UnitActionError, UnitRelationAtom};
 use crate::manager::unit::unit_datastore::UnitDb;
 use crate::manager::unit::unit_entry::UnitX;
 use crate::manager::unit::unit_rentry::JobMode;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 pub(super) fn job_trans_expand(
@@ -59,11 +60,12 @@ pub(super) fn job_trans_affect(
 pub(super) fn job_trans_verify(
     stage: &JobTable,
     jobs: &JobTable,
+    db: &UnitDb,
+    config: &JobConf,
     mode: JobMode,
 ) -> Result<(), JobErrno> {
     // job-list + unit-list(from db) -> job-list' => stage
-    // todo!(); transaction_activate: the other parts is waiting for future support
-
+    trans_verify_order(stage, db, config)?;
     trans_verify_is_conflict(stage)?;
     trans_verify_is_destructive(stage, jobs, mode)?;
 
@@ -304,6 +306,172 @@ fn trans_affect_trigger(
     // the jobs expanded do not need to be reverted separately, which are reverted in the up-level caller 'JobManagerData->exec()' uniformly.
 }
 
+fn trans_verify_order(stage: &JobTable, db: &UnitDb, config: &JobConf) -> Result<(), JobErrno> {
+    let essentials = trans_pulled_units(stage, db, config, &TRANS_REQUIRED_ATOMS);
+    let pulled = trans_pulled_units(stage, db, config, &TRANS_PULL_ATOMS);
+    while let Some(cycle) = trans_find_order_cycle(stage, db) {
+        let path = cycle
+            .iter()
+            .map(|u| u.id().as_str())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        // the first unit is repeated at the end of the cycle
+        let victim = cycle[1..].iter().find(|u| !essentials.contains(*u));
+        match victim {
+            Some(unit) => {
+                log::warn!(
+                    "found ordering cycle: {}, breaking it by deleting the job of {}",
+                    path,
+                    unit.id()
+                );
+                trans_delete_unit(stage, unit);
+                trans_collect_garbage(stage, db, config, &pulled);
+            }
+            None => {
+                log::error!(
+                    "found ordering cycle on the required jobs: {}, the transaction fails",
+                    path
+                );
+                return Err(JobErrno::Cycle);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// the atoms through which the jobs are pulled in by trans_expand_*
+const TRANS_PULL_ATOMS: [UnitRelationAtom; 8] = [
+    UnitRelationAtom::UnitAtomPullInStart,
+    UnitRelationAtom::UnitAtomPullInStartIgnored,
+    UnitRelationAtom::UnitAtomPullInVerify,
+    UnitRelationAtom::UnitAtomPullInStop,
+    UnitRelationAtom::UnitAtomPullInStopIgnored,
+    UnitRelationAtom::UnitAtomPropagateStop,
+    UnitRelationAtom::UnitAtomPropagateRestart,
+    UnitRelationAtom::UnitAtomPropagatesReloadTo,
+];
+
+// the atoms of the required dependencies, the jobs pulled in through them can not be deleted to
+// break the ordering cycles. the ones pulled in by Wants are not essential.
+const TRANS_REQUIRED_ATOMS: [UnitRelationAtom; 5] = [
+    UnitRelationAtom::UnitAtomPullInStart,
+    UnitRelationAtom::UnitAtomPullInVerify,
+    UnitRelationAtom::UnitAtomPullInStop,
+    UnitRelationAtom::UnitAtomPropagateStop,
+    UnitRelationAtom::UnitAtomPropagateRestart,
+];
+
+fn trans_delete_unit(stage: &JobTable, unit: &UnitX) {
+    let (_, del_jobs) = stage.remove_unit(unit);
+    for job in del_jobs.iter() {
+        job.clear();
+    }
+}
+
+// delete the jobs which were pulled in by the anchor, but are not reachable from it any more,
+// since the jobs pulling them in have been deleted. the jobs added by the isolation or the
+// trigger are not pulled in, which are kept.
+fn trans_collect_garbage(
+    stage: &JobTable,
+    db: &UnitDb,
+    config: &JobConf,
+    pulled: &HashSet<Rc<UnitX>>,
+) {
+    let reachable = trans_pulled_units(stage, db, config, &TRANS_PULL_ATOMS);
+    for unit in pulled.iter() {
+        if reachable.contains(unit) || stage.get_suspends(unit).is_empty() {
+            continue;
+        }
+
+        log::debug!(
+            "deleting the job of {}, which is not pulled in any more",
+            unit.id()
+        );
+        trans_delete_unit(stage, unit);
+    }
+}
+
+// the units pulled in by the anchor through the atoms, including the anchor, only the units
+// with jobs in the stage are walked through
+fn trans_pulled_units(
+    stage: &JobTable,
+    db: &UnitDb,
+    config: &JobConf,
+    atoms: &[UnitRelationAtom],
+) -> HashSet<Rc<UnitX>> {
+    let mut units = HashSet::new();
+    let mut pending = vec![Rc::clone(config.get_unit())];
+    while let Some(unit) = pending.pop() {
+        if !units.insert(Rc::clone(&unit)) {
+            continue;
+        }
+
+        for atom in atoms.iter() {
+            for other in db.dep_gets_atom(&unit, *atom) {
+                if !stage.get_suspends(&other).is_empty() && !units.contains(&other) {
+                    pending.push(other);
+                }
+            }
+        }
+    }
+
+    units
+}
+
+// the cycle is returned as the path of the units, which ends with the first unit
+fn trans_find_order_cycle(stage: &JobTable, db: &UnitDb) -> Option<Vec<Rc<UnitX>>> {
+    let units = stage.get_order_units();
+    let nodes = units.iter().cloned().collect::<HashSet<_>>();
+    let mut done = HashSet::new();
+    let mut path = Vec::new();
+    for unit in units.iter() {
+        let cycle = trans_order_visit(db, &nodes, unit, &mut done, &mut path);
+        if cycle.is_some() {
+            return cycle;
+        }
+    }
+
+    None
+}
+
+// depth-first search along the After atom, the path holds the units being visited
+fn trans_order_visit(
+    db: &UnitDb,
+    nodes: &HashSet<Rc<UnitX>>,
+    unit: &Rc<UnitX>,
+    done: &mut HashSet<Rc<UnitX>>,
+    path: &mut Vec<Rc<UnitX>>,
+) -> Option<Vec<Rc<UnitX>>> {
+    if done.contains(unit) {
+        return None;
+    }
+
+    if let Some(pos) = path.iter().position(|u| u == unit) {
+        let mut cycle = path[pos..].to_vec();
+        cycle.push(Rc::clone(unit));
+        return Some(cycle);
+    }
+
+    path.push(Rc::clone(unit));
+    let atom = UnitRelationAtom::UnitAtomAfter;
+    for other in db.dep_gets_atom(unit, atom).iter() {
+        if !nodes.contains(other) {
+            continue;
+        }
+
+        let cycle = trans_order_visit(db, nodes, other, done, path);
+        if cycle.is_some() {
+            return cycle;
+        }
+    }
+    path.pop();
+    done.insert(Rc::clone(unit));
+
+    None
+}
+
 fn trans_verify_is_conflict(stage: &JobTable) -> Result<(), JobErrno> {
     if stage.is_suspends_conflict() {
         return Err(JobErrno::Conflict);
@@ -613,6 +781,104 @@ mod tests {
         assert_eq!(ret.len(), 0);
     }
 
+    #[test]
+    fn jt_api_verify_order_cycle_break() {
+        let relation = UnitRelations::UnitWants;
+        let (reli, db, unit_test1, unit_test2) = prepare_unit_multi(relation);
+        prepare_order_cycle(&db, &unit_test1, &unit_test2);
+        let rentry = Rc::new(JobRe::new(&reli));
+        let jobs = JobTable::new(&db);
+        let stage = JobTable::new(&db);
+        let ja = JobAlloc::new(&reli, &rentry);
+        let mode = JobMode::Replace;
+
+        let conf = JobConf::new(&unit_test1, JobKind::Start);
+        let ret = job_trans_expand(&stage, &ja, &db, &conf, mode);
+        assert!(ret.is_ok());
+        assert_eq!(stage.len(), 2);
+        let ret = job_trans_verify(&stage, &jobs, &db, &conf, mode);
+        assert!(ret.is_ok());
+        assert_eq!(stage.len(), 1);
+        assert!(stage.get_suspends(&unit_test2).is_empty());
+    }
+
+    #[test]
+    fn jt_api_verify_order_cycle_required() {
+        let relation = UnitRelations::UnitRequires;
+        let (reli, db, unit_test1, unit_test2) = prepare_unit_multi(relation);
+        prepare_order_cycle(&db, &unit_test1, &unit_test2);
+        let rentry = Rc::new(JobRe::new(&reli));
+        let jobs = JobTable::new(&db);
+        let stage = JobTable::new(&db);
+        let ja = JobAlloc::new(&reli, &rentry);
+        let mode = JobMode::Replace;
+
+        let conf = JobConf::new(&unit_test1, JobKind::Start);
+        let ret = job_trans_expand(&stage, &ja, &db, &conf, mode);
+        assert!(ret.is_ok());
+        let ret = job_trans_verify(&stage, &jobs, &db, &conf, mode);
+        assert_eq!(ret.err(), Some(JobErrno::Cycle));
+        assert_eq!(stage.len(), 2);
+    }
+
+    #[test]
+    fn jt_api_verify_order_cycle_garbage() {
+        let dm = Rc::new(DataManager::new());
+        let reli = Rc::new(Reliability::new(RELI_HISTORY_MAX_DBS));
+        let rentry = Rc::new(UnitRe::new(&reli));
+        let db = Rc::new(UnitDb::new(&rentry));
+        let mut units = Vec::new();
+        for name in ["test1.service", "test2.service", "test3.service"] {
+            let unit = create_unit(&dm, &reli, &rentry, name);
+            db.units_insert(name.to_string(), Rc::clone(&unit));
+            units.push(unit);
+        }
+        let (unit_test1, unit_test2, unit_test3) = (&units[0], &units[1], &units[2]);
+        let relation = UnitRelations::UnitWants;
+        db.dep_insert(
+            Rc::clone(unit_test1),
+            relation,
+            Rc::clone(unit_test2),
+            true,
+            0,
+        )
+        .unwrap();
+        let relation = UnitRelations::UnitRequires;
+        db.dep_insert(
+            Rc::clone(unit_test2),
+            relation,
+            Rc::clone(unit_test3),
+            true,
+            0,
+        )
+        .unwrap();
+        prepare_order_cycle(&db, unit_test1, unit_test2);
+        let rentry = Rc::new(JobRe::new(&reli));
+        let jobs = JobTable::new(&db);
+        let stage = JobTable::new(&db);
+        let ja = JobAlloc::new(&reli, &rentry);
+        let mode = JobMode::Replace;
+
+        // the job of test3 is pulled in only by the job of test2, which breaks the cycle
+        let conf = JobConf::new(unit_test1, JobKind::Start);
+        let ret = job_trans_expand(&stage, &ja, &db, &conf, mode);
+        assert!(ret.is_ok());
+        assert_eq!(stage.len(), 3);
+        let ret = job_trans_verify(&stage, &jobs, &db, &conf, mode);
+        assert!(ret.is_ok());
+        assert_eq!(stage.len(), 1);
+        assert!(stage.get_suspends(unit_test3).is_empty());
+    }
+
+    fn prepare_order_cycle(db: &Rc<UnitDb>, unit_test1: &Rc<UnitX>, unit_test2: &Rc<UnitX>) {
+        let u1 = Rc::clone(unit_test1);
+        let u2 = Rc::clone(unit_test2);
+        let relation = UnitRelations::UnitAfter;
+        db.dep_insert(Rc::clone(&u1), relation, Rc::clone(&u2), true, 0)
+            .unwrap();
+        db.dep_insert(u2, relation, u1, true, 0).unwrap();
+    }
+
     fn prepare_unit_multi(
         relation: UnitRelations,
     ) -> (Rc<Reliability>, Rc<UnitDb>, Rc<UnitX>, Rc<UnitX>) {
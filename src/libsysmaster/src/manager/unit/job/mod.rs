@@ -13,6 +13,7 @@ pub(super) enum JobErrno {
     Internal,
     NotSupported,
     BadRequest,
+    Cycle,
 }
 
 use crate::manager::MngErrno;
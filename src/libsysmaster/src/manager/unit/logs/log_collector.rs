@@ -0,0 +1,265 @@
+use super::log_store::{LogStore, LOG_DIR};
+use crate::manager::MngErrno;
+use crate::proto::LogEntry;
+use libevent::{EventState, EventType, Events, Source};
+use libutils::{fd_util, Error};
+use nix::errno::Errno;
+use nix::fcntl::{self, FcntlArg, OFlag};
+use nix::unistd::{self, Pid};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::os::unix::prelude::RawFd;
+use std::path::Path;
+use std::rc::{Rc, Weak};
+
+/// the maximal length of a log line, the longer line is split
+const LOG_LINE_MAX: usize = 2048;
+/// the maximal number of reads in a dispatch, the pipe is level-triggered, so the other
+/// events are not starved by a process writing continuously
+const LOG_READ_MAX: usize = 16;
+const LOG_INVALID_FD: RawFd = -1;
+
+/// collect the standard output and error of the spawned processes through pipes, and store the
+/// lines in the log store
+pub(in crate::manager::unit) struct LogCollector {
+    // associated objects
+    events: Rc<Events>,
+
+    // owned objects
+    store: LogStore,
+    pipes: RefCell<HashMap<RawFd, Rc<LogPipe>>>,
+}
+
+impl LogCollector {
+    pub(in crate::manager::unit) fn new(eventr: &Rc<Events>) -> LogCollector {
+        LogCollector {
+            events: Rc::clone(eventr),
+            store: LogStore::new(Path::new(LOG_DIR)),
+            pipes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// create the pipe for the process of the unit, the read end is watched by the collector,
+    /// and the write end is duplicated to the standard output or error of the process
+    pub(in crate::manager::unit) fn open(
+        self: &Rc<Self>,
+        unit: &str,
+    ) -> Result<Rc<LogPipe>, Errno> {
+        let (reader, writer) = unistd::pipe2(OFlag::O_CLOEXEC)?;
+        let pipe = Rc::new(LogPipe::new(self, unit, reader, writer));
+        // the write end is blocking, the process waits when the pipe is full
+        fcntl::fcntl(reader, FcntlArg::F_SETFL(OFlag::O_NONBLOCK))?;
+
+        let source = Rc::clone(&pipe);
+        self.events
+            .add_source(source)
+            .and_then(|_| self.events.set_enabled(pipe.clone(), EventState::On))
+            .map_err(|e| {
+                log::error!("failed to watch the log pipe of {}: {}", unit, e);
+                Errno::EINVAL
+            })?;
+        self.pipes.borrow_mut().insert(reader, Rc::clone(&pipe));
+        Ok(pipe)
+    }
+
+    /// stop watching the pipe, it is closed when dropped
+    pub(in crate::manager::unit) fn close(&self, pipe: &LogPipe) {
+        if let Some(source) = self.pipes.borrow_mut().remove(&pipe.reader) {
+            if let Err(e) = self.events.del_source(source) {
+                log::warn!("failed to unwatch the log pipe of {}: {}", pipe.unit, e);
+            }
+        }
+    }
+
+    /// get the logs of the unit collected at or after the realtime since
+    pub(in crate::manager::unit) fn logs(
+        &self,
+        unit: &str,
+        since: u64,
+    ) -> Result<Vec<LogEntry>, MngErrno> {
+        self.store.read(unit, since).map_err(|e| {
+            log::error!("failed to read the logs of {}: {}", unit, e);
+            MngErrno::Internal
+        })
+    }
+
+    /// whether any log of the unit has been collected
+    pub(in crate::manager::unit) fn has_logs(&self, unit: &str) -> bool {
+        self.store.exists(unit)
+    }
+
+    fn store(&self, unit: &str, pid: u32, lines: &[String]) {
+        if let Err(e) = self.store.append(unit, pid, lines) {
+            log::error!("failed to store the logs of {}: {}", unit, e);
+        }
+    }
+}
+
+/// the pipe connected to the standard output and error of a process
+pub(in crate::manager::unit) struct LogPipe {
+    // associated objects
+    collector: Weak<LogCollector>,
+
+    // owned objects
+    unit: String,
+    pid: Cell<u32>,
+    reader: RawFd,
+    writer: Cell<RawFd>,
+    // the incomplete line
+    buffer: RefCell<Vec<u8>>,
+}
+
+impl Drop for LogPipe {
+    fn drop(&mut self) {
+        fd_util::close(self.reader);
+        self.close_writer();
+    }
+}
+
+impl LogPipe {
+    fn new(collector: &Rc<LogCollector>, unit: &str, reader: RawFd, writer: RawFd) -> LogPipe {
+        LogPipe {
+            collector: Rc::downgrade(collector),
+            unit: unit.to_string(),
+            pid: Cell::new(0),
+            reader,
+            writer: Cell::new(writer),
+            buffer: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// the write end, which is duplicated in the child
+    pub(in crate::manager::unit) fn writer(&self) -> RawFd {
+        self.writer.get()
+    }
+
+    /// the child is forked, tag the lines with its pid and close the write end in the parent,
+    /// so the end of the file is read when all the processes of the child close it
+    pub(in crate::manager::unit) fn attach(&self, pid: Pid) {
+        self.pid.set(pid.as_raw() as u32);
+        self.close_writer();
+    }
+
+    fn close_writer(&self) {
+        let writer = self.writer.replace(LOG_INVALID_FD);
+        if writer >= 0 {
+            fd_util::close(writer);
+        }
+    }
+
+    fn dispatch_io(&self) {
+        let mut buf = [0u8; 4096];
+        let mut eof = false;
+        for _ in 0..LOG_READ_MAX {
+            match unistd::read(self.reader, &mut buf) {
+                Ok(0) => {
+                    eof = true;
+                    break;
+                }
+                Ok(n) => self.buffer.borrow_mut().extend_from_slice(&buf[..n]),
+                Err(Errno::EINTR) => continue,
+                Err(Errno::EAGAIN) => break,
+                Err(e) => {
+                    log::error!("failed to read the log pipe of {}: {}", self.unit, e);
+                    eof = true;
+                    break;
+                }
+            }
+        }
+
+        let lines = split_lines(&mut self.buffer.borrow_mut(), eof);
+        let collector = match self.collector.upgrade() {
+            Some(c) => c,
+            None => return,
+        };
+        collector.store(&self.unit, self.pid.get(), &lines);
+        if eof {
+            collector.close(self);
+        }
+    }
+}
+
+impl Source for LogPipe {
+    fn fd(&self) -> RawFd {
+        self.reader
+    }
+
+    fn event_type(&self) -> EventType {
+        EventType::Io
+    }
+
+    fn epoll_event(&self) -> u32 {
+        (libc::EPOLLIN) as u32
+    }
+
+    fn priority(&self) -> i8 {
+        0i8
+    }
+
+    fn dispatch(&self, _e: &Events) -> Result<i32, Error> {
+        self.dispatch_io();
+        Ok(0)
+    }
+
+    fn token(&self) -> u64 {
+        let data: u64 = unsafe { std::mem::transmute(self) };
+        data
+    }
+}
+
+/// take the complete lines out of the buffer, the incomplete line is taken too at the end of
+/// the file or when it is too long
+fn split_lines(buffer: &mut Vec<u8>, eof: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while let Some(pos) = buffer[start..].iter().position(|b| *b == b'\n') {
+        push_line(&mut lines, &buffer[start..start + pos]);
+        start += pos + 1;
+    }
+
+    while buffer.len() - start >= LOG_LINE_MAX {
+        push_line(&mut lines, &buffer[start..start + LOG_LINE_MAX]);
+        start += LOG_LINE_MAX;
+    }
+
+    if eof && start < buffer.len() {
+        push_line(&mut lines, &buffer[start..]);
+        start = buffer.len();
+    }
+
+    buffer.drain(..start);
+    lines
+}
+
+fn push_line(lines: &mut Vec<String>, line: &[u8]) {
+    if line.is_empty() {
+        lines.push(String::new());
+    }
+    for chunk in line.chunks(LOG_LINE_MAX) {
+        let chunk = String::from_utf8_lossy(chunk);
+        lines.push(chunk.trim_end_matches('\r').to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_lines, LOG_LINE_MAX};
+
+    #[test]
+    fn test_split_lines() {
+        let mut buffer = b"hello\r\nworld\npart".to_vec();
+        assert_eq!(split_lines(&mut buffer, false), vec!["hello", "world"]);
+        assert_eq!(buffer, b"part".to_vec());
+
+        buffer.extend_from_slice(b"ial\n\nend");
+        assert_eq!(split_lines(&mut buffer, false), vec!["partial", ""]);
+        assert_eq!(split_lines(&mut buffer, true), vec!["end"]);
+        assert!(buffer.is_empty());
+
+        let mut buffer = vec![b'x'; LOG_LINE_MAX + 10];
+        let lines = split_lines(&mut buffer, false);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), LOG_LINE_MAX);
+        assert_eq!(buffer.len(), 10);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::proto::LogEntry;
+use libutils::time_util;
+use std::cell::Cell;
+use std::fs::{self, DirBuilder, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// the directory which the logs of the units are stored in
+pub(in crate::manager::unit) const LOG_DIR: &str = "/var/log/sysmaster";
+/// the size of the log file of a unit, it is rotated when the size is exceeded
+const LOG_FILE_MAX: u64 = 8 << 20;
+/// the number of the rotated log files of a unit, the oldest one is removed when exceeded
+const LOG_ROTATE_MAX: usize = 3;
+/// the maximal number of the entries returned by a query, keep the response in a frame
+pub(super) const LOG_QUERY_MAX: usize = 256;
+
+/// the on-disk store of the collected logs, each unit has its own file named by the unit id,
+/// like "foo.service.log", and the rotated files "foo.service.log.1" to "foo.service.log.3".
+/// every line of the file is a log entry: "<timestamp> <pid> <unit> <message>".
+pub(super) struct LogStore {
+    dir: PathBuf,
+    // the timestamp of the last entry, the timestamps are strictly increasing
+    last: Cell<u64>,
+}
+
+impl LogStore {
+    pub(super) fn new(dir: &Path) -> LogStore {
+        LogStore {
+            dir: dir.to_path_buf(),
+            last: Cell::new(0),
+        }
+    }
+
+    /// append the lines collected from the process pid of the unit
+    pub(super) fn append(&self, unit: &str, pid: u32, lines: &[String]) -> io::Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        DirBuilder::new()
+            .recursive(true)
+            .mode(0o750)
+            .create(&self.dir)?;
+        let path = self.path(unit, 0);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o640)
+            .open(&path)?;
+
+        let mut buf = String::new();
+        for line in lines {
+            buf.push_str(&format!("{} {} {} {}\n", self.stamp(), pid, unit, line));
+        }
+        file.write_all(buf.as_bytes())?;
+
+        if file.metadata()?.len() >= LOG_FILE_MAX {
+            self.rotate(unit)?;
+        }
+        Ok(())
+    }
+
+    /// read the entries of the unit collected at or after the realtime since, from the oldest
+    /// to the newest, at most LOG_QUERY_MAX entries are returned
+    pub(super) fn read(&self, unit: &str, since: u64) -> io::Result<Vec<LogEntry>> {
+        // the entries of a rotated file are older than the first entry of the newer file,
+        // start from the newest file whose first entry is not after since
+        let mut files = Vec::new();
+        for index in 0..=LOG_ROTATE_MAX {
+            let file = match File::open(self.path(unit, index)) {
+                Ok(f) => f,
+                // the live file is missing right after the rotation, go on with the rotated ones
+                Err(e) if e.kind() == io::ErrorKind::NotFound && index == 0 => continue,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+                Err(e) => return Err(e),
+            };
+            let first = BufReader::new(&file)
+                .lines()
+                .next()
+                .transpose()?
+                .and_then(|l| parse_entry(&l));
+            files.push(file);
+            if first.map_or(false, |e| e.timestamp <= since) {
+                break;
+            }
+        }
+
+        let mut entries = Vec::new();
+        for mut file in files.into_iter().rev() {
+            file.seek(SeekFrom::Start(0))?;
+            for line in BufReader::new(file).lines() {
+                let entry = match parse_entry(&line?) {
+                    Some(e) => e,
+                    None => continue,
+                };
+                if entry.timestamp < since {
+                    continue;
+                }
+                entries.push(entry);
+                if entries.len() >= LOG_QUERY_MAX {
+                    return Ok(entries);
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// whether there are logs of the unit
+    pub(super) fn exists(&self, unit: &str) -> bool {
+        (0..=LOG_ROTATE_MAX).any(|index| self.path(unit, index).exists())
+    }
+
+    fn rotate(&self, unit: &str) -> io::Result<()> {
+        for index in (1..LOG_ROTATE_MAX).rev() {
+            let from = self.path(unit, index);
+            if from.exists() {
+                fs::rename(&from, self.path(unit, index + 1))?;
+            }
+        }
+        fs::rename(self.path(unit, 0), self.path(unit, 1))
+    }
+
+    fn path(&self, unit: &str, index: usize) -> PathBuf {
+        if index == 0 {
+            self.dir.join(format!("{}.log", unit))
+        } else {
+            self.dir.join(format!("{}.log.{}", unit, index))
+        }
+    }
+
+    fn stamp(&self) -> u64 {
+        let now = time_util::timespec_load(SystemTime::now()) as u64;
+        let timestamp = now.max(self.last.get() + 1);
+        self.last.set(timestamp);
+        timestamp
+    }
+}
+
+fn parse_entry(line: &str) -> Option<LogEntry> {
+    let mut fields = line.splitn(4, ' ');
+    let timestamp = fields.next()?.parse::<u64>().ok()?;
+    let pid = fields.next()?.parse::<u32>().ok()?;
+    let unitname = fields.next()?.to_string();
+    let message = fields.next().unwrap_or_default().to_string();
+    Some(LogEntry {
+        timestamp,
+        pid,
+        unitname,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_entry, LogStore, LOG_FILE_MAX, LOG_QUERY_MAX};
+    use std::fs;
+
+    #[test]
+    fn test_parse_entry() {
+        let entry = parse_entry("1000 12 foo.service hello  world").unwrap();
+        assert_eq!(entry.timestamp, 1000);
+        assert_eq!(entry.pid, 12);
+        assert_eq!(entry.unitname, "foo.service");
+        assert_eq!(entry.message, "hello  world");
+
+        assert_eq!(parse_entry("1000 12 foo.service").unwrap().message, "");
+        assert!(parse_entry("garbage").is_none());
+    }
+
+    #[test]
+    fn test_log_store() {
+        let dir = std::env::temp_dir().join(format!("sysmaster-logs-{}", std::process::id()));
+        let store = LogStore::new(&dir);
+        assert!(!store.exists("foo.service"));
+
+        let lines = vec!["first".to_string(), "second".to_string()];
+        store.append("foo.service", 10, &lines).unwrap();
+        store
+            .append("foo.service", 11, &["third".to_string()])
+            .unwrap();
+        assert!(store.exists("foo.service"));
+
+        let entries = store.read("foo.service", 0).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].message, "first");
+        assert_eq!(entries[2].pid, 11);
+        assert!(entries[0].timestamp < entries[1].timestamp);
+        assert!(entries[1].timestamp < entries[2].timestamp);
+
+        let entries = store.read("foo.service", entries[2].timestamp).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "third");
+
+        // the entries are kept in the rotated file
+        let long = vec!["x".repeat(1 << 16); (LOG_FILE_MAX >> 16) as usize];
+        store.append("foo.service", 12, &long).unwrap();
+        assert!(dir.join("foo.service.log.1").exists());
+        let entries = store.read("foo.service", 0).unwrap();
+        assert_eq!(entries.len(), 3 + long.len());
+        assert_eq!(entries[0].message, "first");
+
+        // the query is paged by the timestamp of the last entry
+        let lines = (0..300).map(|i| i.to_string()).collect::<Vec<_>>();
+        store.append("bar.service", 10, &lines).unwrap();
+        let entries = store.read("bar.service", 0).unwrap();
+        assert_eq!(entries.len(), LOG_QUERY_MAX);
+        let last = entries.last().unwrap().timestamp;
+        let entries = store.read("bar.service", last + 1).unwrap();
+        assert_eq!(entries.len(), 300 - LOG_QUERY_MAX);
+        assert_eq!(entries[0].message, LOG_QUERY_MAX.to_string());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
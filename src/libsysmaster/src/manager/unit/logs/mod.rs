@@ -0,0 +1,4 @@
+pub(super) use log_collector::{LogCollector, LogPipe};
+
+mod log_collector;
+mod log_store;
@@ -2,6 +2,7 @@
 //!  The module contains:
 //!  [execute]: unit Object data structure definition to be executed.
 //!  [job]: The scheduling execution entity corresponding to the unit. After each unit is started, it will be driven by the job.
+//!  [subscribe]: Stream the events of the jobs and units to the subscribers of the command socket.
 //!  [uload_util]: Attribute definitions related to each unit configuration file.
 //!  [unit_base]: Definition of basic attributes of unit related objects, such as enumeration of unit type and definition of unit dependency
 //!  [unit_datastore]: the unit object storage module is responsible for storing the unit module status.
@@ -10,18 +11,24 @@
 //!  [um_interface]: Share api of unit_manager for subunit
 
 pub use data::{UnitActiveState, UnitNotifyFlags};
-pub use execute::{ExecCmdError, ExecContext, ExecFlags, ExecParameters};
+pub use execute::{
+    deserialize_cpu_affinity, deserialize_rlimit_sec, deserialize_rlimit_usec,
+    deserialize_syscall_archs, CapabilitySet, CpuSchedPolicy, ExecCmdError, ExecContext, ExecFlags,
+    ExecInput, ExecLimits, ExecOutput, ExecParameters, ExecRlimit, ExecSandbox, ExecSecurity,
+    ExecStep, IoSchedClass, ProtectHome, ProtectSystem, SecureBits, SyscallFilter,
+};
 
 pub use um_interface::UmIf;
 pub use unit_base::{
-    DeserializeWith, KillOperation, UnitActionError, UnitDependencyMask, UnitRef, UnitRelationAtom,
+    deserialize_timespan, deserialize_umask, DeserializeWith, KillOperation, UnitActionError,
+    UnitDependencyMask, UnitRef, UnitRelationAtom,
 };
-pub use unit_entry::{KillContext, KillMode};
+pub use unit_entry::{KillContext, KillMode, RateLimit};
 pub use unit_entry::{SubUnit, Unit};
 pub(super) use unit_manager::UnitManagerX;
 pub use unit_manager::{UnitManager, UnitManagerObj, UnitMngUtil};
-pub(crate) use unit_rentry::unit_name_to_type;
-pub use unit_rentry::{ExecCommand, UnitRelations, UnitType};
+pub(crate) use unit_rentry::{unit_name_to_type, JobTimeoutAction};
+pub use unit_rentry::{ExecCommand, ExecCommandFlags, UnitRelations, UnitType};
 
 pub use unit_rentry::UeConfigInstall;
 
@@ -41,14 +48,16 @@ pub enum UnitErrno {
 
 // dependency:
 // unit_rentry -> data -> unit_base -> {uload_util} ->
-// unit_entry -> {unit_datastore -> unit_runtime} -> job ->
+// unit_entry -> {unit_datastore -> unit_runtime} -> subscribe -> job ->
 // {execute | sigchld | notify} -> unit_manager -> um_interface
 
 mod data;
 mod execute;
 mod job;
+mod logs;
 mod notify;
 mod sigchld;
+mod subscribe;
 #[cfg(test)]
 mod test;
 mod uload_util;
@@ -20,6 +20,8 @@ use std::{
 };
 
 const NOTIFY_SOCKET: &str = "/run/sysmaster/notify";
+/// the maximal number of fds carried by a notify message, such as FDSTORE=1
+const NOTIFY_FD_MAX: usize = 768;
 
 pub(super) struct NotifyManager {
     // associated objects
@@ -180,8 +182,13 @@ impl Notify {
         })?;
 
         //  record + pop + action
+        // the sender may be not watched, such as a child of the main process with NotifyAccess=all
+        let pid_unit = self
+            .db
+            .get_unit_by_pid(Pid::from_raw(pid))
+            .or_else(|| self.db.get_unit_by_cgroup_pid(Pid::from_raw(pid)));
         let ret;
-        if let Some(unit) = self.db.get_unit_by_pid(Pid::from_raw(pid)) {
+        if let Some(unit) = pid_unit {
             self.reli.set_last_unit(unit.id());
             ret = self.notify_recv_dispatch(flags, pid, Some(unit));
             self.reli.clear_last_unit();
@@ -200,7 +207,7 @@ impl Notify {
     ) -> Result<i32, Error> {
         let mut buffer = [0u8; 4096];
         let mut iov = [IoSliceMut::new(&mut buffer)];
-        let mut space = cmsg_space!(libc::ucred, RawFd);
+        let mut space = cmsg_space!(libc::ucred, [RawFd; NOTIFY_FD_MAX]);
 
         // pop
         let msgs = socket::recvmsg::<()>(self.rawfd(), &mut iov, Some(&mut space), flags).map_err(
@@ -210,9 +217,11 @@ impl Notify {
         )?;
 
         // check: peek == pop
+        let len = msgs.bytes;
         let (received_cred, received_fds) = notify_trans_recvmsg(&msgs);
         if get_pid_from_cred(&received_cred) != pid {
             log::error!("the received notify message has been destroyed");
+            notify_close_fds(&received_fds);
             return Err(Error::Other {
                 msg: "the received notify message has been destroyed",
             });
@@ -220,25 +229,26 @@ impl Notify {
 
         // build input
         let ucred = received_cred.unwrap();
-        let contents = String::from_utf8(buffer.to_vec()).map_err(Error::from)?;
-        let mut messages = HashMap::new();
-        for line in contents.lines() {
-            let content: Vec<&str> = line
-                .split('=')
-                .map(|s| s.trim_end_matches(char::from(0)))
-                .collect();
-            if content.len() != 2 {
-                continue;
+        let contents = match String::from_utf8(buffer[..len.min(buffer.len())].to_vec()) {
+            Ok(contents) => contents,
+            Err(e) => {
+                notify_close_fds(&received_fds);
+                return Err(Error::from(e));
             }
-
-            messages.insert(content[0], content[1].trim_end());
-        }
+        };
+        let messages = notify_parse_messages(&contents);
         log::debug!("[notify] ucred: {:?}, messages: {:?}", &ucred, messages,);
 
-        // action
-        if let Some(u) = unit {
-            log::debug!("[notify] unit: {:?}", u.id());
-            u.notify_message(&ucred, &messages, received_fds)?;
+        // action: the fds are owned by the unit from now on
+        match unit {
+            Some(u) => {
+                log::debug!("[notify] unit: {:?}", u.id());
+                u.notify_message(&ucred, &messages, received_fds)?;
+            }
+            None => {
+                log::debug!("[notify] no unit for the sender {}, ignoring", pid);
+                notify_close_fds(&received_fds);
+            }
         }
 
         Ok(0)
@@ -257,7 +267,7 @@ impl Notify {
 fn notify_peek_pid(fd: RawFd, flags: MsgFlags) -> Result<libc::pid_t, Error> {
     let mut buffer = [0u8; 4096];
     let mut iov = [IoSliceMut::new(&mut buffer)];
-    let mut space = cmsg_space!(libc::ucred, RawFd);
+    let mut space = cmsg_space!(libc::ucred, [RawFd; NOTIFY_FD_MAX]);
 
     // peek
     let peek_flags = flags | MsgFlags::MSG_PEEK;
@@ -269,9 +279,7 @@ fn notify_peek_pid(fd: RawFd, flags: MsgFlags) -> Result<libc::pid_t, Error> {
 
     // get message information
     let (received_cred, received_fds) = notify_trans_recvmsg(&msgs);
-    for fd in received_fds.iter() {
-        fd_util::close(*fd);
-    }
+    notify_close_fds(&received_fds);
 
     // check
     let pid = get_pid_from_cred(&received_cred);
@@ -306,6 +314,26 @@ fn notify_trans_recvmsg(msgs: &RecvMsg<()>) -> (Option<UnixCredentials>, Vec<i32
     (received_cred, received_fds)
 }
 
+/// parse the "KEY=VALUE" lines, the value may contain '=', the invalid lines are ignored
+fn notify_parse_messages(contents: &str) -> HashMap<&str, &str> {
+    let mut messages = HashMap::new();
+    for line in contents.trim_end_matches(char::from(0)).lines() {
+        if let Some((key, value)) = line.split_once('=') {
+            if !key.is_empty() {
+                messages.insert(key, value.trim_end());
+            }
+        }
+    }
+
+    messages
+}
+
+fn notify_close_fds(fds: &[i32]) {
+    for fd in fds.iter() {
+        fd_util::close(*fd);
+    }
+}
+
 fn get_pid_from_cred(cred: &Option<UnixCredentials>) -> libc::pid_t {
     match cred {
         Some(c) => c.pid(),
@@ -383,3 +411,16 @@ impl NotifConfigData {
         self.notify_sock.as_ref().cloned()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::notify_parse_messages;
+
+    #[test]
+    fn test_notify_parse_messages() {
+        let messages = notify_parse_messages("READY=1\nSTATUS=a=b \ninvalid\n=1\n\0\0");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages.get("READY"), Some(&"1"));
+        assert_eq!(messages.get("STATUS"), Some(&"a=b"));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::proto::event::EventData;
+use crate::proto::frame::FrameCoder;
+use crate::proto::{CommandResponse, Event, StatusCode};
+use nix::sys::socket::{self, MsgFlags};
+use prost::bytes::BytesMut;
+use std::cell::RefCell;
+use std::os::unix::net::UnixStream;
+use std::os::unix::prelude::AsRawFd;
+
+/// the streams subscribing the events of the jobs and units, each event is sent as a
+/// CommandResponse frame
+pub(super) struct Subscribers {
+    streams: RefCell<Vec<UnixStream>>,
+}
+
+impl Subscribers {
+    pub(super) fn new() -> Subscribers {
+        Subscribers {
+            streams: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(super) fn add(&self, stream: UnixStream) {
+        log::debug!("add the event subscriber: {:?}", stream);
+        self.streams.borrow_mut().push(stream);
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.streams.borrow().is_empty()
+    }
+
+    /// send the event to all the subscribers, the manager never waits for a subscriber, so the
+    /// subscriber which is closed or does not read in time is dropped
+    pub(super) fn publish(&self, data: EventData) {
+        if self.is_empty() {
+            return;
+        }
+
+        let res = CommandResponse {
+            status: StatusCode::OK.as_u16() as _,
+            event: Some(Event {
+                event_data: Some(data),
+            }),
+            ..Default::default()
+        };
+        let mut buf = BytesMut::new();
+        if let Err(e) = res.encode_frame(&mut buf) {
+            log::error!("failed to encode the event: {}", e);
+            return;
+        }
+
+        let flags = MsgFlags::MSG_DONTWAIT | MsgFlags::MSG_NOSIGNAL;
+        self.streams.borrow_mut().retain(|stream| {
+            match socket::send(stream.as_raw_fd(), &buf[..], flags) {
+                Ok(len) if len == buf.len() => true,
+                Ok(_) => {
+                    log::warn!("event subscriber {:?} is too slow, dropped.", stream);
+                    false
+                }
+                Err(e) => {
+                    log::debug!("event subscriber {:?} is gone: {}", stream, e);
+                    false
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::frame::read_frame;
+    use crate::proto::JobNew;
+
+    #[test]
+    fn subscribe_publish() {
+        let subs = Subscribers::new();
+        let (mut client, server) = UnixStream::pair().unwrap();
+        let (closed, gone) = UnixStream::pair().unwrap();
+        subs.add(server);
+        subs.add(gone);
+        drop(closed);
+
+        subs.publish(EventData::JobNew(JobNew {
+            id: 1,
+            unitname: "test.service".to_string(),
+            kind: "start".to_string(),
+        }));
+        assert_eq!(subs.streams.borrow().len(), 1);
+
+        let mut buf = BytesMut::new();
+        read_frame(&mut client, &mut buf).unwrap();
+        let res = CommandResponse::decode_frame(&mut buf).unwrap();
+        match res.event.unwrap().event_data {
+            Some(EventData::JobNew(job)) => assert_eq!(job.unitname, "test.service"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        drop(client);
+        subs.publish(EventData::JobNew(JobNew::default()));
+        assert!(subs.is_empty());
+    }
+}
@@ -2,7 +2,7 @@ use libutils::path_lookup::LookupPaths;
 use libutils::time_util;
 use siphasher::sip::SipHasher24;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::hash::Hasher;
 use std::path::{Path, PathBuf};
@@ -31,6 +31,10 @@ impl UnitFile {
         self.data.borrow().get_unit_id_dropin_wants(name)
     }
 
+    pub fn is_masked(&self, name: &str) -> bool {
+        self.data.borrow().unit_id_masked.contains(name)
+    }
+
     pub fn get_unit_id_dropin_requires(&self, name: &String) -> Vec<PathBuf> {
         self.data.borrow().get_unit_id_dropin_requires(name)
     }
@@ -41,6 +45,7 @@ struct UnitFileData {
     pub unit_id_fragment: HashMap<String, Vec<PathBuf>>,
     pub unit_id_dropin_wants: HashMap<String, Vec<PathBuf>>,
     pub unit_id_dropin_requires: HashMap<String, Vec<PathBuf>>,
+    pub unit_id_masked: HashSet<String>,
     _unit_name_map: HashMap<String, String>,
     last_updated_timestamp_hash: u64,
     lookup_path: Rc<LookupPaths>,
@@ -53,6 +58,7 @@ impl UnitFileData {
             unit_id_fragment: HashMap::new(),
             unit_id_dropin_wants: HashMap::new(),
             unit_id_dropin_requires: HashMap::new(),
+            unit_id_masked: HashSet::new(),
             _unit_name_map: HashMap::new(),
             lookup_path: lookup_path.clone(),
             last_updated_timestamp_hash: 0,
@@ -91,6 +97,7 @@ impl UnitFileData {
 
     fn build_id_fragment(&mut self, name: &String) {
         let mut pathbuf_fragment = Vec::new();
+        self.unit_id_masked.remove(name);
         for v in &self.lookup_path.search_path {
             if let Err(_e) = fs::metadata(v) {
                 continue;
@@ -121,6 +128,11 @@ impl UnitFileData {
                 format!("{}/{}", v, name)
             };
             let tmp = Path::new(&path);
+            // the unit is masked by linking the unit file to /dev/null
+            if matches!(fs::read_link(tmp), Ok(t) if t == Path::new("/dev/null")) {
+                self.unit_id_masked.insert(name.to_string());
+                continue;
+            }
             if tmp.exists() && !tmp.is_symlink() {
                 let path = format!("{}.toml", tmp.to_string_lossy());
                 if let Err(e) = std::fs::copy(tmp, &path) {
@@ -131,6 +143,9 @@ impl UnitFileData {
             }
         }
 
+        if self.unit_id_masked.contains(name) {
+            pathbuf_fragment.clear();
+        }
         self.unit_id_fragment
             .insert(name.to_string(), pathbuf_fragment);
     }
@@ -108,13 +108,23 @@ pub trait UmIf {
     fn notify_socket(&self) -> Option<PathBuf> {
         None
     }
+
+    /// return the default restart interval of the services in microseconds
+    fn default_restart_usec(&self) -> u64 {
+        0
+    }
+
+    /// return the default start and stop timeout of the units in microseconds, 0 means no timeout
+    fn default_timeout_usec(&self) -> u64 {
+        0
+    }
     /// check the pid corresponding unit is the same with the unit
     fn same_unit_with_pid(&self, _unit: &str, _pid: Pid) -> bool {
         false
     }
 
-    /// return the fds that trigger the unit {name};
-    fn collect_socket_fds(&self, _name: &str) -> Vec<i32> {
+    /// return the fds that trigger the unit {name}, with the names of the fds;
+    fn collect_socket_fds(&self, _name: &str) -> Vec<(i32, String)> {
         Vec::new()
     }
 
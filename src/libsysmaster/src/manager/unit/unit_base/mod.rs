@@ -1,5 +1,9 @@
-pub use ub_basic::{DeserializeWith, KillOperation, UnitActionError, UnitDependencyMask, UnitRef};
+pub use ub_basic::{
+    deserialize_timespan, deserialize_umask, DeserializeWith, KillOperation, UnitActionError,
+    UnitDependencyMask, UnitRef,
+};
 
+pub(super) use ub_basic::{deserialize_limit, deserialize_percent, split_command_line};
 pub(super) use ub_relation::unit_relation_to_inverse;
 pub(super) use ub_relation_atom::unit_relation_from_unique_atom;
 pub use ub_relation_atom::UnitRelationAtom;
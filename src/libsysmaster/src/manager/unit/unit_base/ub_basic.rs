@@ -1,5 +1,8 @@
+use libutils::time_util;
 use nix::sys::signal::Signal;
+use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer};
+use std::fmt;
 
 #[allow(missing_docs)]
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
@@ -84,6 +87,199 @@ impl DeserializeWith for Vec<String> {
     }
 }
 
+struct TimespanVisitor;
+
+impl<'de> Visitor<'de> for TimespanVisitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number of seconds or a time span string")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u64, E> {
+        Ok(v.saturating_mul(time_util::USEC_PER_SEC))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u64, E> {
+        if v < 0 {
+            return Err(E::invalid_value(de::Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<u64, E> {
+        time_util::parse_timespan(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+/// deserialize a time span to microseconds, the value is either a number of seconds or a
+/// string like "100ms" or "1min 30s"
+pub fn deserialize_timespan<'de, D>(de: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    de.deserialize_any(TimespanVisitor)
+}
+
+struct LimitVisitor;
+
+impl<'de> Visitor<'de> for LimitVisitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number, a size string like \"512M\" or \"infinity\"")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u64, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u64, E> {
+        if v < 0 {
+            return Err(E::invalid_value(de::Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<u64, E> {
+        parse_limit(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+// the size is in bytes with an optional base-1024 suffix of K, M, G or T
+fn parse_limit(s: &str) -> Option<u64> {
+    let s = s.trim();
+    if s == "infinity" {
+        return Some(u64::MAX);
+    }
+
+    let (num, unit) = match s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((pos, _)) => s.split_at(pos),
+        None => (s, ""),
+    };
+    let factor: u64 = match unit.trim() {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return None,
+    };
+    num.parse::<u64>().ok()?.checked_mul(factor)
+}
+
+/// deserialize a resource limit, like a memory size in bytes or a number of tasks, the value is either
+/// a number, a size string like "512M", or "infinity" which is deserialized to u64::MAX
+pub(in crate::manager::unit) fn deserialize_limit<'de, D>(de: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    de.deserialize_any(LimitVisitor)
+}
+
+/// deserialize a percentage string like "20%" to the number, which can exceed 100
+pub(in crate::manager::unit) fn deserialize_percent<'de, D>(de: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(de)?;
+    s.trim()
+        .strip_suffix('%')
+        .and_then(|p| p.trim().parse::<u64>().ok())
+        .ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Str(&s), &"a percentage like \"20%\"")
+        })
+}
+
+struct UmaskVisitor;
+
+impl<'de> Visitor<'de> for UmaskVisitor {
+    type Value = u32;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an octal file mode mask like \"0022\"")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u32, E> {
+        if v > 0o777 {
+            return Err(E::invalid_value(de::Unexpected::Unsigned(v), &self));
+        }
+        Ok(v as u32)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u32, E> {
+        if v < 0 {
+            return Err(E::invalid_value(de::Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<u32, E> {
+        u32::from_str_radix(v.trim(), 8)
+            .ok()
+            .filter(|m| *m <= 0o777)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+/// deserialize a file mode creation mask, the value is either an octal string like "0022" or an
+/// octal number like 0o022
+pub fn deserialize_umask<'de, D>(de: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    de.deserialize_any(UmaskVisitor)
+}
+
+/// split the command lines separated by ";" into words, the words are separated by whitespace.
+/// the single or double quotes group a word containing whitespace or ";", and the backslash
+/// escapes the next character, like "\\n" for a newline. the variables are expanded when the
+/// command is executed, "$" is kept as is.
+pub(in crate::manager::unit) fn split_command_line(s: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut cmds = Vec::new();
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some(e) => e,
+                    None => return Err(format!("trailing backslash in {}", s)),
+                };
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            '"' | '\'' if quote.is_none() => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            _ if quote == Some(c) => quote = None,
+            _ if quote.is_some() => word.get_or_insert_with(String::new).push(c),
+            ';' => {
+                words.extend(word.take());
+                if !words.is_empty() {
+                    cmds.push(std::mem::take(&mut words));
+                }
+            }
+            _ if c.is_whitespace() => words.extend(word.take()),
+            _ => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(format!("unbalanced quotes in {}", s));
+    }
+    words.extend(word.take());
+    if !words.is_empty() {
+        cmds.push(words);
+    }
+    Ok(cmds)
+}
+
 ///
 #[derive(Default)]
 pub struct UnitRef {
@@ -117,3 +313,40 @@ impl UnitRef {
         self.target.as_ref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_limit, split_command_line};
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit("1024"), Some(1024));
+        assert_eq!(parse_limit("512K"), Some(512 * 1024));
+        assert_eq!(parse_limit("2G"), Some(2 << 30));
+        assert_eq!(parse_limit("infinity"), Some(u64::MAX));
+        assert_eq!(parse_limit("1X"), None);
+        assert_eq!(parse_limit("M"), None);
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line("/bin/echo a  b;/bin/true").unwrap(),
+            vec![vec!["/bin/echo", "a", "b"], vec!["/bin/true"]]
+        );
+        assert_eq!(
+            split_command_line(r#"/bin/echo "a b" 'c;d' e\ f "" $FOO"#).unwrap(),
+            vec![vec!["/bin/echo", "a b", "c;d", "e f", "", "$FOO"]]
+        );
+        assert_eq!(
+            split_command_line(r#"/bin/echo "say \"hi\"" a\;b x\ty"#).unwrap(),
+            vec![vec!["/bin/echo", "say \"hi\"", "a;b", "x\ty"]]
+        );
+        assert_eq!(
+            split_command_line(" ; /bin/true ;").unwrap(),
+            vec![vec!["/bin/true"]]
+        );
+        assert!(split_command_line("/bin/echo \"a").is_err());
+        assert!(split_command_line("/bin/echo a\\").is_err());
+    }
+}
@@ -153,6 +153,14 @@ impl UnitDb {
         self.child.get_unit_by_pid(pid)
     }
 
+    /// find the unit whose cgroup contains the pid, which is not required to be watched
+    pub(super) fn get_unit_by_cgroup_pid(&self, pid: Pid) -> Option<Rc<UnitX>> {
+        self.units_get_all(None).into_iter().find(|u| {
+            let cg_path = u.cg_path();
+            !cg_path.is_empty() && libcgroup::cg_get_pids(&cg_path).contains(&pid)
+        })
+    }
+
     // repeating protection
     pub(super) fn clear(&self) {
         self.child.entry_clear();
@@ -24,17 +24,19 @@
 //!  uu_load: Encapsulates Unitload Status
 //!  uu_child: The child maintains the parent and child processes associated with the unit. The child services associated with the unit may start the child processes. Therefore, it is necessary to maintain the processes associated with the unit.
 //!  uu_cgroup: cgroup related configurations
+//!  uu_timestamp: the time when the active state of the unit changes
 //!  uu_config is the configuration of unit
 //!
 pub use u_entry::Unit;
 pub use u_interface::SubUnit;
 pub(in crate::manager) use uf_interface::UnitX;
 pub use uu_kill::{KillContext, KillMode};
+pub use uu_ratelimit::RateLimit;
 // pub(super) use uu_config::UnitConfigItem;
 
 // dependency:
 // uu_condition ->
-// uu_base -> {uu_config | uu_cgroup} -> {uu_load | uu_child} ->
+// uu_base -> {uu_config | uu_cgroup | uu_timestamp} -> {uu_load | uu_child} ->
 // u_entry -> {uf_interface | u_interface}
 mod u_entry;
 mod u_interface;
@@ -47,3 +49,4 @@ mod uu_config;
 mod uu_kill;
 mod uu_load;
 mod uu_ratelimit;
+mod uu_timestamp;
@@ -1,5 +1,5 @@
 use super::uu_base::UeBase;
-use super::uu_cgroup::UeCgroup;
+use super::uu_cgroup::{slice_parent, UeCgroup, SPECIAL_SYSTEM_SLICE};
 use super::uu_child::UeChild;
 use super::uu_condition::{
     UeCondition, ASSERT_PATH_EXISTS, CONDITION_FILE_NOT_EMPTY, CONDITION_NEEDS_UPDATE,
@@ -8,12 +8,14 @@ use super::uu_condition::{
 use super::uu_config::UeConfig;
 use super::uu_load::UeLoad;
 use super::uu_ratelimit::StartLimit;
+use super::uu_timestamp::UeTimestamp;
 use super::SubUnit;
 use super::{KillContext, KillMode};
 use crate::manager::unit::data::{DataManager, UnitActiveState, UnitDepConf, UnitState};
+use crate::manager::unit::execute::dynamic_uid_pick;
 use crate::manager::unit::uload_util::UnitFile;
 use crate::manager::unit::unit_base::{KillOperation, UnitActionError};
-use crate::manager::unit::unit_rentry::{UnitLoadState, UnitRe, UnitType};
+use crate::manager::unit::unit_rentry::{UnitLoadState, UnitRe, UnitTimestamp, UnitType};
 use crate::manager::{UnitNotifyFlags, UnitRelations};
 use crate::reliability::ReStation;
 use libcgroup::{self, CgFlags};
@@ -43,6 +45,7 @@ pub struct Unit {
     load: UeLoad,
     child: UeChild,
     cgroup: UeCgroup,
+    timestamp: UeTimestamp,
     conditions: Rc<UeCondition>,
     start_limit: StartLimit,
     sub: Box<dyn SubUnit>,
@@ -82,6 +85,7 @@ impl ReStation for Unit {
         self.base.db_map();
         self.config.db_map();
         self.cgroup.db_map();
+        self.timestamp.db_map();
         self.load.db_map();
         self.child.db_map();
 
@@ -91,7 +95,8 @@ impl ReStation for Unit {
     // reload: entry-only
     fn entry_coldplug(&self) {
         // rebuild external connections, like: timer, ...
-        // unit-frame: do nothing now
+        // unit-frame: re-read the resource control settings and apply them to the running cgroup
+        self.reload_resource();
 
         // sub
         self.sub.entry_coldplug();
@@ -123,6 +128,7 @@ impl Unit {
             load: UeLoad::new(dmr, filer, &_base, &_config),
             child: UeChild::new(&_base),
             cgroup: UeCgroup::new(&_base),
+            timestamp: UeTimestamp::new(&_base),
             conditions: Rc::new(UeCondition::new()),
             sub,
             start_limit: StartLimit::new(),
@@ -206,6 +212,16 @@ impl Unit {
                 original_state,
                 new_state
             );
+            self.timestamp.update(original_state, new_state);
+
+            // the transient user is released when the unit has no process
+            if matches!(
+                new_state,
+                UnitActiveState::UnitInActive | UnitActiveState::UnitFailed
+            ) && self.base.rentry_dynuser_get().is_some()
+            {
+                self.base.rentry_dynuser_remove();
+            }
         }
         let u_state = UnitState::new(original_state, new_state, flags);
         self.dm.insert_unit_state(self.id().clone(), u_state);
@@ -219,9 +235,55 @@ impl Unit {
     ///
     pub fn prepare_exec(&self) -> Result<()> {
         log::debug!("prepare exec cgroup");
-        self.cgroup.setup_cg_path();
+        self.cgroup.setup_cg_path(self.slice().as_deref());
+
+        self.cgroup.prepare_cg_exec()?;
+        self.cgroup
+            .apply_resource(&self.config.config_data().borrow().ResourceControl);
+        Ok(())
+    }
+
+    fn reload_resource(&self) {
+        if self.slice().is_none() || self.load_state() != UnitLoadState::UnitLoaded {
+            return;
+        }
+
+        if self.load.get_unit_id_fragment_pathbuf().is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.load.reload_resource() {
+            log::warn!(
+                "{} failed to reload the resource control settings: {}",
+                self.id(),
+                e
+            );
+            return;
+        }
 
-        self.cgroup.prepare_cg_exec()
+        // the cgroup is only there when the unit is running
+        let inactive = matches!(
+            self.current_active_state(),
+            UnitActiveState::UnitInActive | UnitActiveState::UnitFailed
+        );
+        if self.cg_path().is_empty() || inactive {
+            return;
+        }
+        self.cgroup
+            .apply_resource(&self.config.config_data().borrow().ResourceControl);
+    }
+
+    /// return the uid of the transient user of the unit running with DynamicUser=, which is
+    /// allocated at the first time and kept until the unit is inactive
+    pub(in crate::manager::unit) fn alloc_dynamic_uid(&self) -> Option<u32> {
+        if let Some(uid) = self.base.rentry_dynuser_get() {
+            return Some(uid);
+        }
+
+        let uid = dynamic_uid_pick(&self.base.rentry_dynuser_uids())?;
+        log::debug!("allocate dynamic uid {} for {}", uid, self.id());
+        self.base.rentry_dynuser_insert(uid);
+        Some(uid)
     }
 
     /// return the cgroup name of the unit
@@ -229,6 +291,24 @@ impl Unit {
         self.cgroup.cg_path()
     }
 
+    /// return the slice which the unit is placed in, None if the unit has no cgroup or is the root slice
+    ///
+    /// the slice of a slice unit is implied by its name, "foo-bar.slice" is placed in "foo.slice"
+    pub fn slice(&self) -> Option<String> {
+        match self.unit_type() {
+            UnitType::UnitSlice => slice_parent(self.id()),
+            UnitType::UnitService | UnitType::UnitSocket | UnitType::UnitMount => {
+                let slice = self.get_config().config_data().borrow().Unit.Slice.clone();
+                if slice.is_empty() {
+                    Some(SPECIAL_SYSTEM_SLICE.to_string())
+                } else {
+                    Some(slice)
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// kill the process belongs to the unit
     pub fn kill_context(
         &self,
@@ -237,7 +317,10 @@ impl Unit {
         c_pid: Option<Pid>,
         ko: KillOperation,
     ) -> Result<(), Box<dyn Error>> {
-        let sig = ko.to_signal();
+        let sig = match ko {
+            KillOperation::KillWatchdog => k_context.watchdog_signal(),
+            _ => ko.to_signal(),
+        };
         if let Some(pid) = m_pid {
             match nix::sys::signal::kill(pid, sig) {
                 Ok(_) => {
@@ -417,32 +500,62 @@ impl Unit {
 
     pub(super) fn load_unit(&self) -> Result<(), Box<dyn Error>> {
         self.set_in_load_queue(false);
-        // Mount unit doesn't have config file, set its loadstate to
-        // UnitLoaded directly.
-        if self.unit_type() == UnitType::UnitMount {
-            self.load.set_load_state(UnitLoadState::UnitLoaded);
-            return Ok(());
-        }
-        match self.load.load_unit_confs() {
-            Ok(_) => {
-                {
-                    let paths = self.load.get_unit_id_fragment_pathbuf();
-                    log::debug!("begin exec sub class load");
-                    let ret = self.sub.load(paths);
-
-                    if let Err(e) = ret {
-                        return Err(format!("load Unit {} failed, error: {}", self.id(), e).into());
-                    }
-
-                    self.load.set_load_state(UnitLoadState::UnitLoaded);
-                };
-                Ok(())
+        let paths = match self.load.load_unit_confs() {
+            Ok(_) => self.load.get_unit_id_fragment_pathbuf(),
+            Err(e) if self.load.masked() => {
+                self.load.set_load_state(UnitLoadState::UnitMasked);
+                return Err(e);
             }
-            Err(e) => {
+            // Mount unit may not have config file when it is found in /proc/self/mountinfo,
+            // and the parent slices are implied by the names of their children,
+            // load them without config file, but a config file failing to parse is an error.
+            Err(_)
+                if matches!(self.unit_type(), UnitType::UnitMount | UnitType::UnitSlice)
+                    && self.load.get_unit_id_fragment_pathbuf().is_empty() =>
+            {
+                Vec::new()
+            }
+            Err(e) if self.load.get_unit_id_fragment_pathbuf().is_empty() => {
                 self.load.set_load_state(UnitLoadState::UnitNotFound);
-                Err(e)
+                return Err(e);
             }
+            Err(e) => {
+                self.load.set_load_state(UnitLoadState::UnitError);
+                return Err(e);
+            }
+        };
+
+        log::debug!("begin exec sub class load");
+        if let Err(e) = self.sub.load(paths) {
+            return Err(format!("load Unit {} failed, error: {}", self.id(), e).into());
         }
+
+        if let Err(e) = self.add_slice_dependencies() {
+            return Err(format!("load Unit {} failed, error: {}", self.id(), e).into());
+        }
+
+        self.load.set_load_state(UnitLoadState::UnitLoaded);
+        Ok(())
+    }
+
+    // the unit requires its slice, and it is started after the slice and stopped before it
+    fn add_slice_dependencies(&self) -> Result<(), Box<dyn Error>> {
+        let slice = match self.slice() {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+
+        if !slice.ends_with(".slice") || slice.len() == ".slice".len() {
+            return Err(format!("Slice={} is not a slice unit", slice).into());
+        }
+
+        self.insert_two_deps(
+            UnitRelations::UnitInSlice,
+            UnitRelations::UnitRequires,
+            slice.clone(),
+        );
+        self.insert_dep(UnitRelations::UnitAfter, slice);
+        Ok(())
     }
 
     ///
@@ -493,6 +606,106 @@ impl Unit {
         self.sub.stop(force)
     }
 
+    /// reload the unit, only the active unit can be reloaded
+    pub fn reload(&self) -> Result<(), UnitActionError> {
+        let active_state = self.current_active_state();
+        if active_state == UnitActiveState::UnitReloading {
+            return Err(UnitActionError::UnitActionEAgain);
+        }
+
+        if active_state != UnitActiveState::UnitActive {
+            return Err(UnitActionError::UnitActionEBadR);
+        }
+
+        self.sub.reload();
+        Ok(())
+    }
+
+    /// send the signal to all the processes of the unit
+    pub fn kill(&self, signal: Signal) -> Result<(), UnitActionError> {
+        let cg_path = self.cg_path();
+        if cg_path.is_empty() {
+            return Err(UnitActionError::UnitActionENoent);
+        }
+
+        if let Err(e) =
+            libcgroup::cg_kill_recursive(&cg_path, signal, CgFlags::SIGCONT, HashSet::new())
+        {
+            log::error!("failed to kill the processes of unit {}: {}", self.id(), e);
+            return Err(UnitActionError::UnitActionEFailed);
+        }
+
+        Ok(())
+    }
+
+    /// return the state of the sub unit, the active state is returned if the sub unit has no own state
+    pub fn sub_state(&self) -> String {
+        let state = self.sub.get_subunit_state();
+        if state.is_empty() {
+            return String::from(self.current_active_state());
+        }
+        state
+    }
+
+    /// return the description of the unit
+    pub fn description(&self) -> String {
+        self.get_config()
+            .config_data()
+            .borrow()
+            .Unit
+            .Description
+            .clone()
+    }
+
+    /// return the time when the active state of the unit changes
+    pub(in crate::manager::unit) fn timestamp(&self) -> UnitTimestamp {
+        self.timestamp.timestamp()
+    }
+
+    /// return the result of the last run of the unit, such as "exit-code"
+    pub fn result(&self) -> String {
+        self.sub.get_subunit_result()
+    }
+
+    /// return the main process of the unit
+    pub fn main_pid(&self) -> Option<Pid> {
+        self.sub.get_main_pid()
+    }
+
+    /// return the exit code and the signal of the last main process of the unit
+    pub fn main_exit_status(&self) -> Option<(i32, Signal)> {
+        self.sub.get_main_exit_status()
+    }
+
+    /// return the status text reported by the processes of the unit
+    pub fn status_text(&self) -> String {
+        self.sub.get_status_text()
+    }
+
+    /// return the path of the unit file which the unit is loaded from, the drop-ins are skipped
+    pub fn fragment_path(&self) -> Option<PathBuf> {
+        // the fragments are the toml copies of the unit files
+        self.load
+            .get_unit_id_fragment_pathbuf()
+            .into_iter()
+            .map(|p| p.with_extension(""))
+            .find(|p| matches!(p.file_name(), Some(n) if n == self.id().as_str()))
+    }
+
+    /// return the realtime when the unit left the inactive state last time, 0 if never
+    pub fn inactive_exit_timestamp(&self) -> u64 {
+        self.timestamp.timestamp().inactive_exit
+    }
+
+    /// return the realtime when the unit entered the inactive state last time, 0 if never
+    pub fn inactive_enter_timestamp(&self) -> u64 {
+        self.timestamp.timestamp().inactive_enter
+    }
+
+    pub(super) fn trigger(&self, other: &Rc<Unit>) {
+        self.sub.trigger_notify(Rc::clone(other))
+    }
+
     pub(super) fn sigchld_events(&self, pid: Pid, code: i32, signal: Signal) {
         self.sub.sigchld_events(pid, code, signal)
     }
@@ -513,7 +726,7 @@ impl Unit {
         self.base.unit_type()
     }
 
-    pub(super) fn collect_fds(&self) -> Vec<i32> {
+    pub(super) fn collect_fds(&self) -> Vec<(i32, String)> {
         self.sub.collect_fds()
     }
 
@@ -59,8 +59,8 @@ pub trait SubUnit: ReStation + UnitMngUtil {
     ///
     fn reset_failed(&self) {}
 
-    ///
-    fn collect_fds(&self) -> Vec<i32> {
+    /// return the fds passed to the triggered unit, with the names of the fds
+    fn collect_fds(&self) -> Vec<(i32, String)> {
         Vec::new()
     }
 
@@ -70,9 +70,39 @@ pub trait SubUnit: ReStation + UnitMngUtil {
     ///
     fn current_active_state(&self) -> UnitActiveState;
 
+    /// Get the state of the sub unit, such as "running" of service
+    fn get_subunit_state(&self) -> String {
+        String::new()
+    }
+
+    /// Get the result of the last run of the sub unit, such as "exit-code" of service
+    fn get_subunit_result(&self) -> String {
+        String::new()
+    }
+
+    /// Get the main process of the sub unit, such as the main pid of service
+    fn get_main_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    /// Get the exit code and the signal of the last main process
+    ///
+    /// like [`SubUnit::sigchld_events`], the signal is SIGCHLD if the process exited by itself
+    fn get_main_exit_status(&self) -> Option<(i32, Signal)> {
+        None
+    }
+
+    /// Get the status text reported by the sub unit, such as STATUS= of service
+    fn get_status_text(&self) -> String {
+        String::new()
+    }
+
     ///
     fn attach_unit(&self, unit: Rc<Unit>);
 
+    /// Notify the sub unit that the state of the unit triggered by it has changed, such as the service of a timer
+    fn trigger_notify(&self, _other: Rc<Unit>) {}
+
     ///
     fn notify_message(
         &self,
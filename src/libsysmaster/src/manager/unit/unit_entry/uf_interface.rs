@@ -74,7 +74,7 @@ impl UnitX {
         self.0.stop(force)
     }
     pub(in crate::manager::unit) fn reload(&self) -> Result<(), UnitActionError> {
-        todo!();
+        self.0.reload()
     }
 
     #[allow(dead_code)]
@@ -86,7 +86,10 @@ impl UnitX {
     }
     #[allow(dead_code)]
     pub(in crate::manager::unit) fn reset_failed(&self) {}
-    pub(in crate::manager::unit) fn trigger(&self, _other: &Self) {}
+    pub(in crate::manager::unit) fn trigger(&self, other: &Self) {
+        self.0.trigger(&other.0)
+    }
+
     pub(in crate::manager::unit) fn in_load_queue(&self) -> bool {
         self.0.in_load_queue()
     }
@@ -186,7 +189,7 @@ impl UnitX {
         self.0.unit_type()
     }
 
-    pub(in crate::manager::unit) fn collect_fds(&self) -> Vec<i32> {
+    pub(in crate::manager::unit) fn collect_fds(&self) -> Vec<(i32, String)> {
         self.0.collect_fds()
     }
 
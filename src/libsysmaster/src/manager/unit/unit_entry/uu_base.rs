@@ -1,5 +1,6 @@
 use crate::manager::unit::unit_rentry::{
-    UeConfigInstall, UeConfigUnit, UnitLoadState, UnitRe, UnitRePps, UnitType,
+    UeConfigInstall, UeConfigResource, UeConfigUnit, UnitLoadState, UnitRe, UnitRePps,
+    UnitTimestamp, UnitType,
 };
 use crate::reliability::ReStation;
 use nix::unistd::Pid;
@@ -59,11 +60,18 @@ impl UeBase {
         self.rentry.load_get(&self.id)
     }
 
-    pub(super) fn rentry_conf_insert(&self, unit: &UeConfigUnit, install: &UeConfigInstall) {
-        self.rentry.conf_insert(&self.id, unit, install);
+    pub(super) fn rentry_conf_insert(
+        &self,
+        unit: &UeConfigUnit,
+        install: &UeConfigInstall,
+        resource: &UeConfigResource,
+    ) {
+        self.rentry.conf_insert(&self.id, unit, install, resource);
     }
 
-    pub(super) fn rentry_conf_get(&self) -> Option<(UeConfigUnit, UeConfigInstall)> {
+    pub(super) fn rentry_conf_get(
+        &self,
+    ) -> Option<(UeConfigUnit, UeConfigInstall, UeConfigResource)> {
         self.rentry.conf_get(&self.id)
     }
 
@@ -75,6 +83,14 @@ impl UeBase {
         self.rentry.cgroup_get(&self.id)
     }
 
+    pub(super) fn rentry_timestamp_insert(&self, timestamp: &UnitTimestamp) {
+        self.rentry.timestamp_insert(&self.id, timestamp);
+    }
+
+    pub(super) fn rentry_timestamp_get(&self) -> Option<UnitTimestamp> {
+        self.rentry.timestamp_get(&self.id)
+    }
+
     pub(super) fn rentry_child_insert(&self, pids: &[Pid]) {
         self.rentry.child_insert(&self.id, pids);
     }
@@ -83,6 +99,22 @@ impl UeBase {
         self.rentry.child_get(&self.id)
     }
 
+    pub(super) fn rentry_dynuser_insert(&self, uid: u32) {
+        self.rentry.dynuser_insert(&self.id, uid);
+    }
+
+    pub(super) fn rentry_dynuser_remove(&self) {
+        self.rentry.dynuser_remove(&self.id);
+    }
+
+    pub(super) fn rentry_dynuser_get(&self) -> Option<u32> {
+        self.rentry.dynuser_get(&self.id)
+    }
+
+    pub(super) fn rentry_dynuser_uids(&self) -> Vec<u32> {
+        self.rentry.dynuser_uids()
+    }
+
     pub(super) fn rentry_pps_set(&self, pps_mask: UnitRePps) {
         self.rentry.pps_set(&self.id, pps_mask);
     }
@@ -1,12 +1,54 @@
 use super::uu_base::UeBase;
+use crate::manager::unit::unit_rentry::{UeConfigResource, UnitType};
 use crate::reliability::ReStation;
-use libcgroup;
+use libcgroup::{self, CgController};
 use libutils::Error;
 use libutils::Result;
 use nix::NixPath;
+use std::path::Path;
 use std::rc::Rc;
 use std::{cell::RefCell, path::PathBuf};
 
+// the period of the cpu bandwidth control, in microseconds
+const CPU_QUOTA_PERIOD_USEC: u64 = 100000;
+// the default value of cpu.weight and io.weight
+const CGROUP_WEIGHT_DEFAULT: u64 = 100;
+
+/// the root slice, its cgroup is the root of the cgroup tree
+pub(super) const SPECIAL_ROOT_SLICE: &str = "-.slice";
+/// the default slice of the units
+pub(super) const SPECIAL_SYSTEM_SLICE: &str = "system.slice";
+
+/// return the parent slice of the slice, which is named by the prefix before the last dash,
+/// like "foo.slice" of "foo-bar.slice", None for the root slice
+pub(super) fn slice_parent(slice: &str) -> Option<String> {
+    if slice == SPECIAL_ROOT_SLICE {
+        return None;
+    }
+
+    let prefix = slice.strip_suffix(".slice")?;
+    match prefix.rfind('-') {
+        Some(pos) => Some(format!("{}.slice", &prefix[..pos])),
+        None => Some(SPECIAL_ROOT_SLICE.to_string()),
+    }
+}
+
+/// return the cgroup path of the slice, which is nested in the cgroups of its parent slices,
+/// like "foo.slice/foo-bar.slice" of "foo-bar.slice"
+pub(super) fn slice_cg_path(slice: &str) -> PathBuf {
+    let mut cg_path = PathBuf::new();
+    if slice == SPECIAL_ROOT_SLICE {
+        return cg_path;
+    }
+
+    let prefix = slice.strip_suffix(".slice").unwrap_or(slice);
+    for (pos, _) in prefix.match_indices('-') {
+        cg_path.push(format!("{}.slice", &prefix[..pos]));
+    }
+    cg_path.push(libcgroup::cg_escape(slice));
+    cg_path
+}
+
 pub(super) struct UeCgroup {
     data: RefCell<UnitCgroupData>,
 }
@@ -35,8 +77,8 @@ impl UeCgroup {
         cgroup
     }
 
-    pub(super) fn setup_cg_path(&self) {
-        self.data.borrow_mut().setup_cg_path();
+    pub(super) fn setup_cg_path(&self, slice: Option<&str>) {
+        self.data.borrow_mut().setup_cg_path(slice);
         self.db_update();
     }
 
@@ -49,6 +91,52 @@ impl UeCgroup {
 
         cg_path
     }
+
+    pub(super) fn apply_resource(&self, resource: &UeConfigResource) {
+        self.data.borrow().apply_resource(resource)
+    }
+}
+
+/// return the controllers which the resource control settings need
+fn resource_controllers(resource: &UeConfigResource) -> CgController {
+    let mut controllers = CgController::empty();
+    if resource.CPUWeight.is_some() || resource.CPUQuota.is_some() {
+        controllers |= CgController::CPU;
+    }
+    if resource.MemoryMax.is_some() || resource.MemoryHigh.is_some() || resource.MemoryLow.is_some()
+    {
+        controllers |= CgController::MEMORY;
+    }
+    if resource.TasksMax.is_some() {
+        controllers |= CgController::PIDS;
+    }
+    if resource.IOWeight.is_some() || resource.IODeviceWeight.is_some() {
+        controllers |= CgController::IO;
+    }
+    if resource.AllowedCPUs.is_some() {
+        controllers |= CgController::CPUSET;
+    }
+    controllers
+}
+
+/// u64::MAX is parsed from "infinity", which means no limit
+fn limit(value: u64) -> Option<u64> {
+    if value == u64::MAX {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// parse the device weight like "/dev/sda 200"
+fn parse_device_weight(value: &str) -> Option<(&str, u64)> {
+    let mut iter = value.split_whitespace();
+    let device = iter.next()?;
+    let weight = iter.next()?.parse::<u64>().ok()?;
+    if iter.next().is_some() {
+        return None;
+    }
+    Some((device, weight))
 }
 
 struct UnitCgroupData {
@@ -73,16 +161,23 @@ impl UnitCgroupData {
         }
     }
 
-    pub(self) fn setup_cg_path(&mut self) {
+    pub(self) fn setup_cg_path(&mut self, slice: Option<&str>) {
         if !self.cg_path.is_empty() {
             return;
         }
 
-        self.set_default_cg_path();
+        self.set_default_cg_path(slice);
     }
 
-    fn set_default_cg_path(&mut self) {
-        let cg_tree_name = PathBuf::from(libcgroup::cg_escape(self.base.id()));
+    fn set_default_cg_path(&mut self, slice: Option<&str>) {
+        let id = self.base.id();
+        let cg_tree_name = if self.base.unit_type() == UnitType::UnitSlice {
+            slice_cg_path(id)
+        } else {
+            let mut cg_path = slice.map(slice_cg_path).unwrap_or_default();
+            cg_path.push(libcgroup::cg_escape(id));
+            cg_path
+        };
 
         self.cg_path = cg_tree_name;
     }
@@ -100,7 +195,134 @@ impl UnitCgroupData {
         self.cg_path.clone()
     }
 
+    pub(self) fn apply_resource(&self, resource: &UeConfigResource) {
+        let id = self.base.id();
+        let cg_path = &self.cg_path;
+        let wanted = resource_controllers(resource);
+        if !wanted.is_empty() {
+            if let Err(e) = libcgroup::cg_enable_controllers(cg_path, wanted) {
+                log::warn!("{} failed to enable cgroup controllers: {}", id, e);
+                return;
+            }
+        }
+
+        // the settings removed from the config are reset to the defaults, so that the limits
+        // applied before the reload do not stay on the cgroup
+        let controllers = wanted | libcgroup::cg_enabled_controllers(cg_path).unwrap_or(wanted);
+        if controllers.is_empty() {
+            return;
+        }
+
+        let mut results = Vec::new();
+        if controllers.contains(CgController::CPU) {
+            let weight = resource.CPUWeight.unwrap_or(CGROUP_WEIGHT_DEFAULT);
+            results.push(("CPUWeight", libcgroup::cg_set_cpu_weight(cg_path, weight)));
+            let quota = resource
+                .CPUQuota
+                .map(|percent| percent.saturating_mul(CPU_QUOTA_PERIOD_USEC) / 100);
+            results.push((
+                "CPUQuota",
+                libcgroup::cg_set_cpu_quota(cg_path, quota, CPU_QUOTA_PERIOD_USEC),
+            ));
+        }
+        if controllers.contains(CgController::MEMORY) {
+            results.push((
+                "MemoryMax",
+                libcgroup::cg_set_memory_max(cg_path, resource.MemoryMax.and_then(limit)),
+            ));
+            results.push((
+                "MemoryHigh",
+                libcgroup::cg_set_memory_high(cg_path, resource.MemoryHigh.and_then(limit)),
+            ));
+            results.push((
+                "MemoryLow",
+                libcgroup::cg_set_memory_low(cg_path, resource.MemoryLow.unwrap_or(0)),
+            ));
+        }
+        if controllers.contains(CgController::PIDS) {
+            results.push((
+                "TasksMax",
+                libcgroup::cg_set_pids_max(cg_path, resource.TasksMax.and_then(limit)),
+            ));
+        }
+        if controllers.contains(CgController::IO) {
+            let weight = resource.IOWeight.unwrap_or(CGROUP_WEIGHT_DEFAULT);
+            results.push(("IOWeight", libcgroup::cg_set_io_weight(cg_path, weight)));
+        }
+        for value in resource.IODeviceWeight.iter().flatten() {
+            match parse_device_weight(value) {
+                Some((device, weight)) => results.push((
+                    "IODeviceWeight",
+                    libcgroup::cg_set_io_device_weight(cg_path, Path::new(device), weight),
+                )),
+                None => log::warn!("{} invalid IODeviceWeight={}, ignoring", id, value),
+            }
+        }
+        if controllers.contains(CgController::CPUSET) {
+            // an empty cpuset.cpus means the cpus of the parent
+            let cpus = resource.AllowedCPUs.as_deref().unwrap_or("");
+            results.push(("AllowedCPUs", libcgroup::cg_set_cpuset_cpus(cg_path, cpus)));
+        }
+
+        for (key, result) in results {
+            if let Err(e) = result {
+                log::warn!("{} failed to apply {}: {}", id, key, e);
+            }
+        }
+    }
+
     pub(self) fn db_insert(&self) {
         self.base.rentry_cgroup_insert(&self.cg_path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_device_weight, resource_controllers, slice_cg_path, slice_parent};
+    use crate::manager::unit::unit_rentry::UeConfigResource;
+    use libcgroup::CgController;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_slice_parent() {
+        assert_eq!(slice_parent("-.slice"), None);
+        assert_eq!(slice_parent("system.slice"), Some("-.slice".to_string()));
+        assert_eq!(slice_parent("foo-bar.slice"), Some("foo.slice".to_string()));
+        assert_eq!(
+            slice_parent("foo-bar-baz.slice"),
+            Some("foo-bar.slice".to_string())
+        );
+        assert_eq!(slice_parent("foo.service"), None);
+    }
+
+    #[test]
+    fn test_slice_cg_path() {
+        assert_eq!(slice_cg_path("-.slice"), PathBuf::from(""));
+        assert_eq!(slice_cg_path("system.slice"), PathBuf::from("system.slice"));
+        assert_eq!(
+            slice_cg_path("foo-bar-baz.slice"),
+            PathBuf::from("foo.slice/foo-bar.slice/foo-bar-baz.slice")
+        );
+    }
+
+    #[test]
+    fn test_resource_controllers() {
+        let mut resource = UeConfigResource::default();
+        assert!(resource_controllers(&resource).is_empty());
+
+        resource.CPUQuota = Some(20);
+        resource.TasksMax = Some(100);
+        assert_eq!(
+            resource_controllers(&resource),
+            CgController::CPU | CgController::PIDS
+        );
+    }
+
+    #[test]
+    fn test_parse_device_weight() {
+        assert_eq!(parse_device_weight("/dev/sda 200"), Some(("/dev/sda", 200)));
+        assert_eq!(parse_device_weight("/dev/sda"), None);
+        assert_eq!(parse_device_weight("/dev/sda two"), None);
+        assert_eq!(parse_device_weight("/dev/sda 200 300"), None);
+    }
+}
@@ -1,13 +1,17 @@
 #![allow(non_snake_case)]
 use super::uu_base::UeBase;
 use crate::manager::unit::uload_util::UnitFile;
-use crate::manager::unit::unit_rentry::{UeConfigInstall, UeConfigUnit};
+use crate::manager::unit::unit_rentry::{UeConfigInstall, UeConfigResource, UeConfigUnit};
 use crate::reliability::ReStation;
 use confique::Config;
 use std::cell::RefCell;
 use std::error::Error as stdError;
 use std::rc::Rc;
 
+// the range of the weight of the cgroup v2 controllers
+const CG_WEIGHT_MIN: u64 = 1;
+const CG_WEIGHT_MAX: u64 = 10000;
+
 pub(crate) struct UeConfig {
     // associated objects
     base: Rc<UeBase>,
@@ -21,15 +25,16 @@ impl ReStation for UeConfig {
 
     // data
     fn db_map(&self) {
-        if let Some((unit, install)) = self.base.rentry_conf_get() {
-            let conf = UeConfigData::new(unit, install);
+        if let Some((unit, install, resource)) = self.base.rentry_conf_get() {
+            let conf = UeConfigData::new(unit, install, resource);
             *self.data.borrow_mut() = conf;
         }
     }
 
     fn db_insert(&self) {
+        let data = self.data.borrow();
         self.base
-            .rentry_conf_insert(&self.data.borrow().Unit, &self.data.borrow().Install);
+            .rentry_conf_insert(&data.Unit, &data.Install, &data.ResourceControl);
     }
 
     // reload: no external connections, no entry
@@ -50,23 +55,7 @@ impl UeConfig {
         files: &UnitFile,
         name: &String,
     ) -> Result<(), Box<dyn stdError>> {
-        let mut builder = UeConfigData::builder().env();
-
-        let unit_conf_frag = files.get_unit_id_fragment_pathbuf(name);
-        if unit_conf_frag.is_empty() {
-            log::error!("config file for {} is not exist", name);
-            return Err(format!("config file for {} is not exist", name).into());
-        }
-        // fragment
-        for v in unit_conf_frag {
-            if !v.exists() {
-                log::error!("config file is not exist");
-                return Err(format!("config file is not exist {}", name).into());
-            }
-            builder = builder.file(&v);
-        }
-
-        let mut configer = builder.load()?;
+        let mut configer = load_fragment(files, name)?;
 
         // dropin
         for v in files.get_unit_id_dropin_wants(name) {
@@ -85,25 +74,85 @@ impl UeConfig {
         Ok(())
     }
 
+    /// re-read the resource control settings from the fragment, the others are kept unchanged
+    pub(super) fn reload_resource(
+        &self,
+        files: &UnitFile,
+        name: &String,
+    ) -> Result<(), Box<dyn stdError>> {
+        let configer = load_fragment(files, name)?;
+        self.data.borrow_mut().ResourceControl = configer.ResourceControl;
+        self.db_update();
+        Ok(())
+    }
+
     pub(crate) fn config_data(&self) -> Rc<RefCell<UeConfigData>> {
         self.data.clone()
     }
 }
 
+fn load_fragment(files: &UnitFile, name: &String) -> Result<UeConfigData, Box<dyn stdError>> {
+    let mut builder = UeConfigData::builder().env();
+
+    let unit_conf_frag = files.get_unit_id_fragment_pathbuf(name);
+    if unit_conf_frag.is_empty() {
+        log::error!("config file for {} is not exist", name);
+        return Err(format!("config file for {} is not exist", name).into());
+    }
+    // fragment
+    for v in unit_conf_frag {
+        if !v.exists() {
+            log::error!("config file is not exist");
+            return Err(format!("config file is not exist {}", name).into());
+        }
+        builder = builder.file(&v);
+    }
+
+    let configer = builder.load()?;
+    check_resource(&configer.ResourceControl)?;
+    Ok(configer)
+}
+
+fn check_resource(resource: &UeConfigResource) -> Result<(), Box<dyn stdError>> {
+    let weights = [
+        ("CPUWeight", resource.CPUWeight),
+        ("IOWeight", resource.IOWeight),
+    ];
+    for (key, weight) in weights {
+        if let Some(w) = weight {
+            if !(CG_WEIGHT_MIN..=CG_WEIGHT_MAX).contains(&w) {
+                return Err(format!(
+                    "{}={} is out of range [{}, {}]",
+                    key, w, CG_WEIGHT_MIN, CG_WEIGHT_MAX
+                )
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
 #[derive(Config, Default, Debug)]
 pub(crate) struct UeConfigData {
     #[config(nested)]
     pub Unit: UeConfigUnit,
     #[config(nested)]
     pub Install: UeConfigInstall,
+    #[config(nested)]
+    pub ResourceControl: UeConfigResource,
 }
 
 // the declaration "pub(self)" is for identification only.
 impl UeConfigData {
-    pub(self) fn new(unit: UeConfigUnit, install: UeConfigInstall) -> UeConfigData {
+    pub(self) fn new(
+        unit: UeConfigUnit,
+        install: UeConfigInstall,
+        resource: UeConfigResource,
+    ) -> UeConfigData {
         UeConfigData {
             Unit: unit,
             Install: install,
+            ResourceControl: resource,
         }
     }
 }
@@ -113,7 +162,7 @@ mod tests {
     use confique::Config;
     use libtests::get_project_root;
 
-    use crate::manager::unit::unit_entry::uu_config::UeConfigData;
+    use crate::manager::unit::unit_entry::uu_config::{check_resource, UeConfigData};
     #[test]
     fn test_service_parse() {
         let mut file_path = get_project_root().unwrap();
@@ -126,4 +175,25 @@ mod tests {
 
         println!("{:?}", result);
     }
+
+    #[test]
+    fn test_resource_parse() {
+        let mut file_path = get_project_root().unwrap();
+        file_path.push("tests/test_units/resource.service.toml");
+
+        let configer = UeConfigData::builder().file(&file_path).load().unwrap();
+        let resource = configer.ResourceControl;
+        assert_eq!(resource.CPUWeight, Some(200));
+        assert_eq!(resource.CPUQuota, Some(20));
+        assert_eq!(resource.MemoryMax, Some(512 * 1024 * 1024));
+        assert_eq!(resource.MemoryHigh, Some(u64::MAX));
+        assert_eq!(resource.MemoryLow, None);
+        assert_eq!(resource.TasksMax, Some(100));
+        assert_eq!(
+            resource.IODeviceWeight,
+            Some(vec!["/dev/sda 200".to_string()])
+        );
+        assert_eq!(resource.AllowedCPUs, Some("0-1".to_string()));
+        assert!(check_resource(&resource).is_ok());
+    }
 }
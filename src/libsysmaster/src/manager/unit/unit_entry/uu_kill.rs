@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 
+use nix::sys::signal::Signal;
 use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::manager::DeserializeWith;
@@ -39,12 +40,14 @@ impl DeserializeWith for KillMode {
 /// kill method context of the unit
 pub struct KillContext {
     kill_mode: RefCell<KillMode>,
+    watchdog_signal: RefCell<Signal>,
 }
 
 impl Default for KillContext {
     fn default() -> Self {
         Self {
             kill_mode: RefCell::new(KillMode::default()),
+            watchdog_signal: RefCell::new(Signal::SIGABRT),
         }
     }
 }
@@ -58,4 +61,13 @@ impl KillContext {
     pub(crate) fn kill_mode(&self) -> KillMode {
         *self.kill_mode.borrow()
     }
+
+    /// set the signal sent when the watchdog of the unit expires
+    pub fn set_watchdog_signal(&self, signal: Signal) {
+        *self.watchdog_signal.borrow_mut() = signal;
+    }
+
+    pub(crate) fn watchdog_signal(&self) -> Signal {
+        *self.watchdog_signal.borrow()
+    }
 }
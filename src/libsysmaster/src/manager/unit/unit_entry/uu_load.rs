@@ -71,6 +71,10 @@ impl UeLoad {
         self.db_update();
     }
 
+    pub(super) fn masked(&self) -> bool {
+        self.file.is_masked(self.base.id())
+    }
+
     pub(super) fn load_state(&self) -> UnitLoadState {
         let state = self.load_state.clone();
         state.into_inner()
@@ -94,12 +98,22 @@ impl UeLoad {
             self.base.id().clone(),
             self.load_state() == UnitLoadState::UnitLoaded,
         );
+        if self.masked() {
+            return Err(format!("unit {} is masked", self.base.id()).into());
+        }
         self.config
             .load_fragment_and_dropin(self.file.as_ref(), self.base.id())?;
         self.parse();
         Ok(())
     }
 
+    /// re-read the resource control settings of the unit from its fragment
+    pub(super) fn reload_resource(&self) -> Result<(), Box<dyn stdError>> {
+        self.file.build_name_map(self.base.id().clone(), true);
+        self.config
+            .reload_resource(self.file.as_ref(), self.base.id())
+    }
+
     pub(super) fn set_in_target_dep_queue(&self, t: bool) {
         self.in_target_dep_queue.replace(t);
         if t {
@@ -136,6 +150,15 @@ impl UeLoad {
             UnitRelations::UnitConflicts,
             self.config.config_data().borrow().Unit.Conflicts.clone(),
         );
+        ud_conf.deps.insert(
+            UnitRelations::UnitJoinsNameSpaceOf,
+            self.config
+                .config_data()
+                .borrow()
+                .Unit
+                .JoinsNamespaceOf
+                .clone(),
+        );
 
         self.dm.insert_ud_config(self.base.id().clone(), ud_conf);
     }
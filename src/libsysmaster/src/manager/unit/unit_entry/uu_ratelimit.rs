@@ -33,7 +33,8 @@ impl StartLimit {
     }
 }
 
-struct RateLimit {
+/// allow at most burst events within the interval in seconds, the rate limit is disabled if either of them is 0
+pub struct RateLimit {
     interval: u64,
     burst: u32,
     begin: Option<Instant>,
@@ -41,7 +42,8 @@ struct RateLimit {
 }
 
 impl RateLimit {
-    fn new(interval: u64, burst: u32) -> Self {
+    /// create the rate limit
+    pub fn new(interval: u64, burst: u32) -> Self {
         RateLimit {
             interval,
             burst,
@@ -50,7 +52,8 @@ impl RateLimit {
         }
     }
 
-    fn ratelimit_below(&mut self) -> bool {
+    /// count one event, return false if the limit is hit
+    pub fn ratelimit_below(&mut self) -> bool {
         if !self.ratelimit_enabled() {
             return true;
         }
@@ -0,0 +1,89 @@
+use super::uu_base::UeBase;
+use crate::manager::unit::data::UnitActiveState;
+use crate::manager::unit::unit_rentry::UnitTimestamp;
+use crate::reliability::ReStation;
+use libutils::time_util;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+pub(super) struct UeTimestamp {
+    // associated objects
+    base: Rc<UeBase>,
+
+    // owned objects
+    data: RefCell<UnitTimestamp>,
+}
+
+impl ReStation for UeTimestamp {
+    // no input, no compensate
+
+    // data
+    fn db_map(&self) {
+        if let Some(timestamp) = self.base.rentry_timestamp_get() {
+            *self.data.borrow_mut() = timestamp;
+        }
+    }
+
+    fn db_insert(&self) {
+        self.base.rentry_timestamp_insert(&self.data.borrow());
+    }
+
+    // reload: no external connections, no entry
+}
+
+impl UeTimestamp {
+    pub(super) fn new(baser: &Rc<UeBase>) -> UeTimestamp {
+        let timestamp = UeTimestamp {
+            base: Rc::clone(baser),
+            data: RefCell::new(UnitTimestamp::default()),
+        };
+        timestamp.db_insert();
+        timestamp
+    }
+
+    /// record the time when the active state of the unit changes
+    pub(super) fn update(&self, os: UnitActiveState, ns: UnitActiveState) {
+        if os == ns {
+            return;
+        }
+
+        let now = time_util::timespec_load(SystemTime::now()) as u64;
+        {
+            let mut data = self.data.borrow_mut();
+            data.state_change = now;
+
+            if is_inactive_or_failed(os) && !is_inactive_or_failed(ns) {
+                data.inactive_exit = now;
+            } else if !is_inactive_or_failed(os) && is_inactive_or_failed(ns) {
+                data.inactive_enter = now;
+            }
+
+            if !is_active_or_reloading(os) && is_active_or_reloading(ns) {
+                data.active_enter = now;
+            } else if is_active_or_reloading(os) && !is_active_or_reloading(ns) {
+                data.active_exit = now;
+            }
+        }
+
+        self.db_update();
+    }
+
+    pub(super) fn timestamp(&self) -> UnitTimestamp {
+        *self.data.borrow()
+    }
+}
+
+fn is_inactive_or_failed(state: UnitActiveState) -> bool {
+    matches!(
+        state,
+        UnitActiveState::UnitInActive | UnitActiveState::UnitFailed
+    )
+}
+
+fn is_active_or_reloading(state: UnitActiveState) -> bool {
+    matches!(
+        state,
+        UnitActiveState::UnitActive | UnitActiveState::UnitReloading
+    )
+}
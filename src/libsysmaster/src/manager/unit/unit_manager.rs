@@ -1,27 +1,35 @@
 use super::execute::{ExecCmdError, ExecParameters, ExecSpawn};
 use super::job::{JobAffect, JobConf, JobKind, JobManager};
+use super::logs::LogCollector;
 use super::notify::NotifyManager;
 use super::sigchld::Sigchld;
+use super::subscribe::Subscribers;
 use super::unit_base::{UnitDependencyMask, UnitRelationAtom};
 use super::unit_datastore::UnitDb;
 use super::unit_entry::{Unit, UnitX};
-use super::unit_rentry::{ExecCommand, JobMode, UnitLoadState, UnitRe, UnitType};
+use super::unit_rentry::{ExecCommand, JobMode, JobTimeoutAction, UnitLoadState, UnitRe, UnitType};
 use super::unit_runtime::UnitRT;
-use super::{ExecContext, UmIf, UnitActionError};
+use super::{ExecContext, UmIf, UnitActionError, UnitActiveState};
+use crate::manager::config::ManagerConfig;
 use crate::manager::pre_install::{Install, PresetMode};
 use crate::manager::rentry::ReliLastFrame;
 use crate::manager::table::{TableOp, TableSubscribe};
 use crate::manager::unit::data::{DataManager, UnitState};
 use crate::manager::{MngErrno, UnitRelations};
 use crate::plugin::Plugin;
+use crate::proto::event::EventData;
+use crate::proto::{JobInfo, LogEntry, UnitRow, UnitStateChanged, UnitStatus};
 use crate::reliability::{ReStation, ReStationKind, Reliability};
 use libevent::Events;
 use libutils::path_lookup::LookupPaths;
 use libutils::process_util;
+use libutils::time_util;
 use libutils::Result;
+use nix::sys::signal::Signal;
 use nix::unistd::Pid;
 use std::convert::TryFrom;
 use std::io::Error;
+use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -49,12 +57,13 @@ impl UnitManagerX {
         eventr: &Rc<Events>,
         relir: &Rc<Reliability>,
         lookup_path: &Rc<LookupPaths>,
+        configr: &Rc<ManagerConfig>,
     ) -> UnitManagerX {
         let _dm = Rc::new(DataManager::new());
         let umx = UnitManagerX {
             dm: Rc::clone(&_dm),
             sub_name: String::from("UnitManagerX"),
-            data: UnitManager::new(eventr, relir, &_dm, lookup_path),
+            data: UnitManager::new(eventr, relir, &_dm, lookup_path, configr),
             lookup_path: Rc::clone(lookup_path),
         };
         umx.register(&_dm, relir);
@@ -75,6 +84,11 @@ impl UnitManagerX {
     }
 
     pub(in crate::manager) fn start_unit(&self, name: &str) -> Result<(), MngErrno> {
+        self.data.start_unit(name).map(|_| ())
+    }
+
+    /// start the unit and return the id of its start job, 0 if there is no job
+    pub(in crate::manager) fn start_unit_job(&self, name: &str) -> Result<u32, MngErrno> {
         self.data.start_unit(name)
     }
 
@@ -82,6 +96,54 @@ impl UnitManagerX {
         self.data.stop_unit(name)
     }
 
+    pub(in crate::manager) fn restart_unit(&self, name: &str) -> Result<(), MngErrno> {
+        self.data
+            .exec_unit_job(name, JobKind::Restart, JobMode::Replace)
+    }
+
+    pub(in crate::manager) fn reload_unit(&self, name: &str) -> Result<(), MngErrno> {
+        self.data
+            .exec_unit_job(name, JobKind::Reload, JobMode::Replace)
+    }
+
+    pub(in crate::manager) fn isolate_unit(&self, name: &str) -> Result<(), MngErrno> {
+        self.data
+            .exec_unit_job(name, JobKind::Start, JobMode::Isolate)
+    }
+
+    pub(in crate::manager) fn kill_unit(&self, name: &str, signal: i32) -> Result<(), MngErrno> {
+        self.data.kill_unit(name, signal)
+    }
+
+    pub(in crate::manager) fn list_units(&self, all: bool) -> Vec<UnitRow> {
+        self.data.list_units(all)
+    }
+
+    pub(in crate::manager) fn list_jobs(&self) -> Vec<JobInfo> {
+        self.data.list_jobs()
+    }
+
+    pub(in crate::manager) fn unit_status(&self, name: &str) -> Result<UnitStatus, MngErrno> {
+        self.data.unit_status(name)
+    }
+
+    pub(in crate::manager) fn unit_logs(
+        &self,
+        name: &str,
+        since: u64,
+    ) -> Result<Vec<LogEntry>, MngErrno> {
+        self.data.unit_logs(name, since)
+    }
+
+    pub(in crate::manager) fn subscribe(&self, stream: UnixStream) {
+        self.data.subscribers.add(stream);
+    }
+
+    pub(in crate::manager) fn cancel_job(&self, id: u32) -> Result<(), MngErrno> {
+        self.data.jm.remove(id)?;
+        Ok(())
+    }
+
     pub(in crate::manager) fn child_sigchld_enable(&self, enable: bool) -> Result<i32> {
         self.data.sigchld.enable(enable)
     }
@@ -90,6 +152,10 @@ impl UnitManagerX {
         self.data.rt.dispatch_load_queue()
     }
 
+    pub(in crate::manager) fn take_job_timeout_action(&self) -> Option<(JobTimeoutAction, String)> {
+        self.data.jm.take_timeout_action()
+    }
+
     fn register(&self, dm: &DataManager, relir: &Reliability) {
         // dm-unit_state
         let subscriber = Rc::clone(&self.data);
@@ -115,6 +181,23 @@ impl UnitManagerX {
         install.unit_disable_files(unit_file)?;
         Ok(())
     }
+
+    pub(in crate::manager) fn mask_unit(&self, unit_file: &str) -> Result<(), Error> {
+        log::debug!("unit mask file {}", unit_file);
+        let install = Install::new(PresetMode::Disable, self.lookup_path.clone());
+        install.unit_mask_files(unit_file)
+    }
+
+    pub(in crate::manager) fn get_default_target(&self) -> Result<String, Error> {
+        let install = Install::new(PresetMode::Disable, self.lookup_path.clone());
+        install.get_default_target()
+    }
+
+    pub(in crate::manager) fn set_default_target(&self, unit_file: &str) -> Result<(), Error> {
+        log::debug!("set default target {}", unit_file);
+        let install = Install::new(PresetMode::Disable, self.lookup_path.clone());
+        install.set_default_target(unit_file)
+    }
 }
 
 /// the struct for manager the unit instance
@@ -123,6 +206,7 @@ pub struct UnitManager {
     events: Rc<Events>,
     reli: Rc<Reliability>,
     plugins: Arc<Plugin>,
+    config: Rc<ManagerConfig>,
 
     // owned objects
     rentry: Rc<UnitRe>,
@@ -131,9 +215,11 @@ pub struct UnitManager {
     load: UnitLoad,
     jm: Rc<JobManager>,
     exec: ExecSpawn,
+    logs: Rc<LogCollector>,
     sigchld: Sigchld,
     notify: NotifyManager,
     sms: UnitSubManagers,
+    subscribers: Rc<Subscribers>,
 }
 
 impl UmIf for UnitManager {
@@ -195,7 +281,7 @@ impl UmIf for UnitManager {
 
     /// start the unit
     fn start_unit(&self, name: &str) -> Result<(), MngErrno> {
-        self.start_unit(name)
+        self.start_unit(name).map(|_| ())
     }
 
     fn events(&self) -> Rc<Events> {
@@ -223,7 +309,12 @@ impl UmIf for UnitManager {
         params: &ExecParameters,
         ctx: Rc<ExecContext>,
     ) -> Result<Pid, ExecCmdError> {
-        self.exec.spawn(unit, cmdline, params, ctx)
+        let joined = self
+            .get_dependency_list(unit.id(), UnitRelationAtom::UnitAtomJoinsNameSpaceOf)
+            .iter()
+            .map(|u| u.id().clone())
+            .collect::<Vec<_>>();
+        self.exec.spawn(unit, cmdline, params, ctx, &joined)
     }
 
     fn child_watch_pid(&self, id: &str, pid: Pid) {
@@ -238,11 +329,19 @@ impl UmIf for UnitManager {
         self.notify_socket()
     }
 
+    fn default_restart_usec(&self) -> u64 {
+        self.config.Manager.DefaultRestartSec * time_util::USEC_PER_MSEC
+    }
+
+    fn default_timeout_usec(&self) -> u64 {
+        self.config.Manager.DefaultTimeoutSec * time_util::USEC_PER_SEC
+    }
+
     fn same_unit_with_pid(&self, unit: &str, pid: Pid) -> bool {
         self.same_unit_with_pid(unit, pid)
     }
 
-    fn collect_socket_fds(&self, name: &str) -> Vec<i32> {
+    fn collect_socket_fds(&self, name: &str) -> Vec<(i32, String)> {
         self.collect_socket_fds(name)
     }
 
@@ -370,9 +469,11 @@ impl UnitManager {
         self.jm.has_stop_job(&u)
     }
 
-    /// return the fds that trigger the unit {name};
-    fn collect_socket_fds(&self, name: &str) -> Vec<i32> {
-        let deps = self.db.dep_gets(name, UnitRelations::UnitTriggeredBy);
+    /// return the fds that trigger the unit {name}, with the names of the fds;
+    fn collect_socket_fds(&self, name: &str) -> Vec<(i32, String)> {
+        let mut deps = self.db.dep_gets(name, UnitRelations::UnitTriggeredBy);
+        // the fds are passed in the order of the socket names, not the order of the hash map
+        deps.sort_by(|a, b| a.id().cmp(b.id()));
         let mut fds = Vec::new();
         for dep in deps.iter() {
             if dep.unit_type() != UnitType::UnitSocket {
@@ -417,7 +518,7 @@ impl UnitManager {
         false
     }
 
-    fn start_unit(&self, name: &str) -> Result<(), MngErrno> {
+    fn start_unit(&self, name: &str) -> Result<u32, MngErrno> {
         if let Some(unit) = self.load_unitx(name) {
             log::debug!("load unit success, send to job manager");
             self.jm.exec(
@@ -426,7 +527,17 @@ impl UnitManager {
                 &mut JobAffect::new(false),
             )?;
             log::debug!("job exec success");
-            Ok(())
+
+            // the start job may be merged into the pending one of the unit
+            let job_id = self
+                .jm
+                .get_all_jobinfos()
+                .iter()
+                .find(|j| {
+                    j.unit.id() == unit.id() && matches!(j.kind, JobKind::Start | JobKind::Restart)
+                })
+                .map_or(0, |j| j.id);
+            Ok(job_id)
         } else {
             Err(MngErrno::Internal)
         }
@@ -452,6 +563,124 @@ impl UnitManager {
         self.db.get_unit_by_pid(pid)
     }
 
+    pub(self) fn exec_unit_job(
+        &self,
+        name: &str,
+        kind: JobKind,
+        mode: JobMode,
+    ) -> Result<(), MngErrno> {
+        if let Some(unit) = self.load_unitx(name) {
+            self.jm
+                .exec(&JobConf::new(&unit, kind), mode, &mut JobAffect::new(false))?;
+            Ok(())
+        } else {
+            Err(MngErrno::Internal)
+        }
+    }
+
+    pub(self) fn kill_unit(&self, name: &str, signal: i32) -> Result<(), MngErrno> {
+        let signal = match signal {
+            0 => Signal::SIGTERM,
+            v => Signal::try_from(v).map_err(|_| MngErrno::Input)?,
+        };
+
+        let unit = self.db.units_get(name).ok_or(MngErrno::NotExisted)?;
+        if !unit.active_or_activating() {
+            return Err(MngErrno::Input);
+        }
+
+        unit.unit().kill(signal).map_err(|_| MngErrno::Internal)
+    }
+
+    pub(self) fn list_units(&self, all: bool) -> Vec<UnitRow> {
+        let mut units = self
+            .db
+            .units_get_all(None)
+            .iter()
+            .filter(|u| all || u.active_state() != UnitActiveState::UnitInActive)
+            .map(|u| {
+                let unit = u.unit();
+                UnitRow {
+                    unitname: u.id().clone(),
+                    load_state: String::from(u.load_state()),
+                    active_state: String::from(u.active_state()),
+                    sub_state: unit.sub_state(),
+                    description: unit.description(),
+                }
+            })
+            .collect::<Vec<_>>();
+        units.sort_by(|a, b| a.unitname.cmp(&b.unitname));
+        units
+    }
+
+    pub(self) fn list_jobs(&self) -> Vec<JobInfo> {
+        self.jm
+            .get_all_jobinfos()
+            .into_iter()
+            .map(|job| JobInfo {
+                id: job.id,
+                unitname: job.unit.id().clone(),
+                kind: String::from(job.kind),
+                stage: String::from(job.stage),
+            })
+            .collect()
+    }
+
+    pub(self) fn unit_status(&self, name: &str) -> Result<UnitStatus, MngErrno> {
+        let unitx = self.load_unitx(name).ok_or(MngErrno::NotExisted)?;
+        let unit = unitx.unit();
+
+        let (main_code, main_status) = match unit.main_exit_status() {
+            Some((code, Signal::SIGCHLD)) => (String::from("exited"), code),
+            Some((_, signal)) => (String::from("killed"), signal as i32),
+            None => (String::new(), 0),
+        };
+        let timestamp = unit.timestamp();
+        let jobs = self
+            .list_jobs()
+            .into_iter()
+            .filter(|j| &j.unitname == unitx.id())
+            .collect();
+
+        Ok(UnitStatus {
+            unitname: unitx.id().clone(),
+            description: unit.description(),
+            load_state: String::from(unitx.load_state()),
+            active_state: String::from(unitx.active_state()),
+            sub_state: unit.sub_state(),
+            fragment_path: unit
+                .fragment_path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            result: unit.result(),
+            main_pid: unit.main_pid().map_or(0, |pid| pid.as_raw() as u32),
+            main_code,
+            main_status,
+            state_change_timestamp: timestamp.state_change,
+            active_enter_timestamp: timestamp.active_enter,
+            active_exit_timestamp: timestamp.active_exit,
+            inactive_enter_timestamp: timestamp.inactive_enter,
+            inactive_exit_timestamp: timestamp.inactive_exit,
+            cgroup: unit.cg_path().to_string_lossy().to_string(),
+            jobs,
+            status_text: unit.status_text(),
+        })
+    }
+
+    pub(self) fn unit_logs(&self, name: &str, since: u64) -> Result<Vec<LogEntry>, MngErrno> {
+        // the name is used as the file name of the logs
+        if name.is_empty() || name.contains('/') {
+            return Err(MngErrno::Input);
+        }
+
+        // the logs of the unit which has been unloaded are kept
+        if self.db.units_get(name).is_none() && !self.logs.has_logs(name) {
+            return Err(MngErrno::NotExisted);
+        }
+
+        self.logs.logs(name, since)
+    }
+
     pub(self) fn stop_unit(&self, name: &str) -> Result<(), MngErrno> {
         if let Some(unit) = self.load_unitx(name) {
             self.jm.exec(
@@ -470,24 +699,30 @@ impl UnitManager {
         relir: &Rc<Reliability>,
         dmr: &Rc<DataManager>,
         lookup_path: &Rc<LookupPaths>,
+        configr: &Rc<ManagerConfig>,
     ) -> Rc<UnitManager> {
         let _rentry = Rc::new(UnitRe::new(relir));
         let _db = Rc::new(UnitDb::new(&_rentry));
         let _rt = Rc::new(UnitRT::new(relir, &_rentry, &_db));
-        let _jm = Rc::new(JobManager::new(eventr, relir, &_db));
+        let _subscribers = Rc::new(Subscribers::new());
+        let _jm = Rc::new(JobManager::new(eventr, relir, &_db, &_subscribers));
+        let _logs = Rc::new(LogCollector::new(eventr));
         let um = Rc::new(UnitManager {
             events: Rc::clone(eventr),
             reli: Rc::clone(relir),
             plugins: Plugin::get_instance(),
+            config: Rc::clone(configr),
             rentry: Rc::clone(&_rentry),
             load: UnitLoad::new(dmr, &_rentry, &_db, &_rt, lookup_path),
             db: Rc::clone(&_db),
             rt: Rc::clone(&_rt),
             jm: Rc::clone(&_jm),
-            exec: ExecSpawn::new(),
+            exec: ExecSpawn::new(&_logs),
+            logs: Rc::clone(&_logs),
             sigchld: Sigchld::new(eventr, relir, &_db, &_jm),
             notify: NotifyManager::new(eventr, relir, &_rentry, &_db, &_jm),
             sms: UnitSubManagers::new(relir),
+            subscribers: Rc::clone(&_subscribers),
         });
         um.load.set_um(&um);
         um.sms.set_um(&um);
@@ -517,10 +752,27 @@ impl UnitManager {
             return;
         };
 
+        if state.os != state.ns {
+            self.subscribers
+                .publish(EventData::UnitStateChanged(UnitStateChanged {
+                    unitname: source.to_string(),
+                    old_state: state.os.into(),
+                    new_state: state.ns.into(),
+                }));
+        }
+
         if let Err(_e) = self.jm.try_finish(&unitx, state.os, state.ns, state.flags) {
             // debug
         }
 
+        // the sandbox runtime is kept while the unit has processes
+        if matches!(
+            state.ns,
+            UnitActiveState::UnitInActive | UnitActiveState::UnitFailed
+        ) {
+            self.exec.runtime_release(source);
+        }
+
         let atom = UnitRelationAtom::UnitAtomTriggeredBy;
         for other in self.db.dep_gets_atom(&unitx, atom) {
             other.trigger(&unitx);
@@ -1070,7 +1322,8 @@ mod tests {
         let event = Rc::new(Events::new().unwrap());
         let dm = Rc::new(DataManager::new());
         let reli = Rc::new(Reliability::new(RELI_HISTORY_MAX_DBS));
-        let um = UnitManager::new(&event, &reli, &dm, &lookup_path);
+        let config = Rc::new(ManagerConfig::new(None));
+        let um = UnitManager::new(&event, &reli, &dm, &lookup_path, &config);
         (dm, event, um)
     }
 
@@ -1,26 +1,48 @@
 #![allow(non_snake_case)]
-use super::unit_base::DeserializeWith;
+use super::unit_base::{
+    deserialize_limit, deserialize_percent, deserialize_timespan, split_command_line,
+    DeserializeWith,
+};
 use crate::manager::rentry::{
     RELI_DB_HUM_NOTIFY, RELI_DB_HUNIT_BASE, RELI_DB_HUNIT_CGROUP, RELI_DB_HUNIT_CHILD,
-    RELI_DB_HUNIT_CONFIG, RELI_DB_HUNIT_DEP, RELI_DB_HUNIT_LOAD, RELI_DB_HUNIT_PPS,
+    RELI_DB_HUNIT_CONFIG, RELI_DB_HUNIT_DEP, RELI_DB_HUNIT_DYNUSER, RELI_DB_HUNIT_LOAD,
+    RELI_DB_HUNIT_PPS, RELI_DB_HUNIT_TIMESTAMP,
 };
 use crate::null_str;
 use crate::reliability::{ReDb, ReDbRoTxn, ReDbRwTxn, ReDbTable, Reliability};
 use bitflags::bitflags;
 use confique::Config;
 use nix::unistd::Pid;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use std::convert::TryFrom;
 use std::num::ParseIntError;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::str::FromStr;
 
+bitflags! {
+    /// the flags of the exec command, which are set by the prefixes of the command line
+    #[derive(Serialize, Deserialize)]
+    pub struct ExecCommandFlags: u8 {
+        /// "-", the failure exit of the command is ignored
+        const IGNORE_FAILURE = 1 << 0;
+        /// "+", the command runs with full privileges, the sandbox, the credentials and the
+        /// security settings are not applied
+        const FULLY_PRIVILEGED = 1 << 1;
+        /// "!", the credentials are not changed
+        const NO_SETUID = 1 << 2;
+        /// "!!", the same as "!" only if the ambient capabilities are not supported
+        const AMBIENT_MAGIC = 1 << 3;
+    }
+}
+
 /// the exec command that was parsed from the unit file
 #[derive(PartialEq, Clone, Eq, Debug, Serialize, Deserialize)]
 pub struct ExecCommand {
     path: String,
     argv: Vec<String>,
+    argv0: Option<String>,
+    flags: ExecCommandFlags,
 }
 
 impl DeserializeWith for Vec<ExecCommand> {
@@ -29,24 +51,31 @@ impl DeserializeWith for Vec<ExecCommand> {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(de)?;
+        let cmds = split_command_line(&s).map_err(de::Error::custom)?;
 
         let mut vec = vec![];
-
-        for cmd in s.trim().split_terminator(';') {
-            if cmd.is_empty() {
-                continue;
-            }
-
-            #[allow(clippy::trim_split_whitespace)]
-            let mut command: Vec<String> = cmd
-                .trim()
-                .split_whitespace()
-                .map(|s| s.to_string())
-                .collect();
-
+        for mut command in cmds {
             // get the command and leave the command args
             let exec_cmd = command.remove(0);
-            let path = Path::new(&exec_cmd);
+            let (prefix, exec_cmd) = exec_cmd.split_at(
+                exec_cmd
+                    .find(|c| !matches!(c, '-' | '@' | '+' | '!'))
+                    .unwrap_or(exec_cmd.len()),
+            );
+            let flags = parse_exec_prefix(prefix).map_err(de::Error::custom)?;
+            // "@" takes the next word as argv[0]
+            let argv0 = if prefix.contains('@') {
+                if command.is_empty() {
+                    return Err(de::Error::custom(format!(
+                        "no argv[0] is specified for {}",
+                        exec_cmd
+                    )));
+                }
+                Some(command.remove(0))
+            } else {
+                None
+            };
+            let path = Path::new(exec_cmd);
 
             if path.is_absolute() && !path.exists() {
                 log::debug!("{:?} is not exist in parse!", path);
@@ -54,7 +83,9 @@ impl DeserializeWith for Vec<ExecCommand> {
             }
 
             let cmd = path.to_str().unwrap().to_string();
-            let new_command = ExecCommand::new(cmd, command);
+            let mut new_command = ExecCommand::new(cmd, command);
+            new_command.argv0 = argv0;
+            new_command.flags = flags;
             vec.push(new_command);
         }
 
@@ -62,10 +93,49 @@ impl DeserializeWith for Vec<ExecCommand> {
     }
 }
 
+// each prefix appears once at most, except "!!", and "+" conflicts with "!"
+fn parse_exec_prefix(prefix: &str) -> Result<ExecCommandFlags, String> {
+    let mut flags = ExecCommandFlags::empty();
+    let mut argv0 = false;
+    for c in prefix.chars() {
+        let flag = match c {
+            '-' => ExecCommandFlags::IGNORE_FAILURE,
+            '+' => ExecCommandFlags::FULLY_PRIVILEGED,
+            '!' if flags.contains(ExecCommandFlags::NO_SETUID) => {
+                flags.remove(ExecCommandFlags::NO_SETUID);
+                ExecCommandFlags::AMBIENT_MAGIC
+            }
+            '!' if !flags.contains(ExecCommandFlags::AMBIENT_MAGIC) => ExecCommandFlags::NO_SETUID,
+            '@' if !argv0 => {
+                argv0 = true;
+                continue;
+            }
+            _ => return Err(format!("invalid prefix {} of the command", prefix)),
+        };
+        if flags.contains(flag) {
+            return Err(format!("invalid prefix {} of the command", prefix));
+        }
+        flags.insert(flag);
+    }
+
+    if flags.contains(ExecCommandFlags::FULLY_PRIVILEGED)
+        && flags.intersects(ExecCommandFlags::NO_SETUID | ExecCommandFlags::AMBIENT_MAGIC)
+    {
+        return Err(format!("prefix + conflicts with ! in {}", prefix));
+    }
+
+    Ok(flags)
+}
+
 impl ExecCommand {
     /// create a new instance of the command
     pub fn new(path: String, argv: Vec<String>) -> ExecCommand {
-        ExecCommand { path, argv }
+        ExecCommand {
+            path,
+            argv,
+            argv0: None,
+            flags: ExecCommandFlags::empty(),
+        }
     }
 
     /// return the path of the command
@@ -77,6 +147,21 @@ impl ExecCommand {
     pub fn argv(&self) -> Vec<&String> {
         self.argv.iter().collect::<Vec<_>>()
     }
+
+    /// return the argv[0] of the command, which is the path if not specified by "@"
+    pub fn argv0(&self) -> &String {
+        self.argv0.as_ref().unwrap_or(&self.path)
+    }
+
+    /// return the flags set by the prefixes
+    pub fn flags(&self) -> ExecCommandFlags {
+        self.flags
+    }
+
+    /// whether the failure exit of the command is ignored
+    pub fn ignore_failure(&self) -> bool {
+        self.flags.contains(ExecCommandFlags::IGNORE_FAILURE)
+    }
 }
 
 #[allow(missing_docs)]
@@ -86,6 +171,9 @@ pub enum UnitType {
     UnitTarget,
     UnitSocket,
     UnitMount,
+    UnitTimer,
+    UnitPath,
+    UnitSlice,
     UnitTypeMax,
     UnitTypeInvalid,
     UnitTypeErrnoMax,
@@ -100,6 +188,9 @@ impl FromStr for UnitType {
             "Target" => UnitType::UnitTarget,
             "Socket" => UnitType::UnitSocket,
             "Mount" => UnitType::UnitMount,
+            "Timer" => UnitType::UnitTimer,
+            "Path" => UnitType::UnitPath,
+            "Slice" => UnitType::UnitSlice,
             _ => UnitType::UnitTypeInvalid,
         };
         Ok(ret)
@@ -113,6 +204,9 @@ impl From<UnitType> for String {
             UnitType::UnitTarget => "target".into(),
             UnitType::UnitSocket => "socket".into(),
             UnitType::UnitMount => "mount".into(),
+            UnitType::UnitTimer => "timer".into(),
+            UnitType::UnitPath => "path".into(),
+            UnitType::UnitSlice => "slice".into(),
             UnitType::UnitTypeMax => null_str!(""),
             UnitType::UnitTypeInvalid => null_str!(""),
             UnitType::UnitTypeErrnoMax => null_str!(""),
@@ -128,6 +222,9 @@ impl TryFrom<u32> for UnitType {
             1 => Ok(UnitType::UnitTarget),
             2 => Ok(UnitType::UnitSocket),
             3 => Ok(UnitType::UnitMount),
+            4 => Ok(UnitType::UnitTimer),
+            5 => Ok(UnitType::UnitPath),
+            6 => Ok(UnitType::UnitSlice),
             v => Err(format!("input {} is invalid", v)),
         }
     }
@@ -140,6 +237,9 @@ pub(crate) fn unit_name_to_type(unit_name: &str) -> UnitType {
         "target" => UnitType::UnitTarget,
         "socket" => UnitType::UnitSocket,
         "mount" => UnitType::UnitMount,
+        "timer" => UnitType::UnitTimer,
+        "path" => UnitType::UnitPath,
+        "slice" => UnitType::UnitSlice,
         _ => UnitType::UnitTypeInvalid,
     }
 }
@@ -166,6 +266,19 @@ pub(super) enum UnitLoadState {
     UnitMasked,
 }
 
+impl From<UnitLoadState> for String {
+    fn from(l_s: UnitLoadState) -> Self {
+        match l_s {
+            UnitLoadState::UnitStub => "stub".into(),
+            UnitLoadState::UnitLoaded => "loaded".into(),
+            UnitLoadState::UnitNotFound => "not-found".into(),
+            UnitLoadState::UnitError => "error".into(),
+            UnitLoadState::UnitMerged => "merged".into(),
+            UnitLoadState::UnitMasked => "masked".into(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct UnitReLoad {
     load_state: UnitLoadState,
@@ -224,6 +337,42 @@ impl DeserializeWith for JobMode {
     }
 }
 
+/// the action taken by the manager when the job of the unit times out
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum JobTimeoutAction {
+    #[serde(alias = "none")]
+    None,
+    #[serde(alias = "reboot")]
+    Reboot,
+    #[serde(alias = "poweroff")]
+    Poweroff,
+}
+
+impl Default for JobTimeoutAction {
+    fn default() -> Self {
+        JobTimeoutAction::None
+    }
+}
+
+impl DeserializeWith for JobTimeoutAction {
+    fn deserialize_with<'de, D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(de)?;
+
+        match s.as_ref() {
+            "none" => Ok(JobTimeoutAction::None),
+            "reboot" => Ok(JobTimeoutAction::Reboot),
+            "poweroff" => Ok(JobTimeoutAction::Poweroff),
+            &_ => Err(de::Error::invalid_value(
+                de::Unexpected::Str(&s),
+                &"none, reboot or poweroff",
+            )),
+        }
+    }
+}
+
 #[derive(Config, Default, Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct UeConfigUnit {
     #[config(default = "")]
@@ -258,6 +407,10 @@ pub(crate) struct UeConfigUnit {
     #[config(deserialize_with = Vec::<String>::deserialize_with)]
     #[config(default = "")]
     pub Conflicts: Vec<String>,
+    // the units whose /tmp, /var/tmp and network namespace are shared with the unit
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    #[config(default = "")]
+    pub JoinsNamespaceOf: Vec<String>,
     #[config(default = "")]
     pub ConditionFileNotEmpty: String,
     #[config(default = "")]
@@ -270,6 +423,21 @@ pub(crate) struct UeConfigUnit {
     pub StartLimitInterval: u64,
     #[config(default = 0)]
     pub StartLimitBurst: u32,
+    // the slice which the cgroup of the unit is placed in, defaults to "system.slice"
+    #[config(default = "")]
+    pub Slice: String,
+    // the time limit of the job since it is queued, no limit if it is not set, 0 or "infinity"
+    #[config(deserialize_with = deserialize_timespan)]
+    pub JobTimeoutSec: Option<u64>,
+    // the time limit of the job since it starts running
+    #[config(deserialize_with = deserialize_timespan)]
+    pub JobRunningTimeoutSec: Option<u64>,
+    #[config(deserialize_with = JobTimeoutAction::deserialize_with)]
+    #[config(default = "none")]
+    pub JobTimeoutAction: JobTimeoutAction,
+    // the argument of the reboot system call when JobTimeoutAction is "reboot"
+    #[config(default = "")]
+    pub JobTimeoutRebootArgument: String,
 }
 
 #[derive(Config, Default, Clone, Debug, Serialize, Deserialize)]
@@ -288,17 +456,46 @@ pub struct UeConfigInstall {
     pub Also: Vec<String>,
 }
 
+/// the resource control settings of the units which have cgroup, the limits of u64::MAX mean infinity
+#[derive(Config, Default, Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct UeConfigResource {
+    pub CPUWeight: Option<u64>,
+    // the percentage of the time of one cpu, which can exceed 100
+    #[config(deserialize_with = deserialize_percent)]
+    pub CPUQuota: Option<u64>,
+    #[config(deserialize_with = deserialize_limit)]
+    pub MemoryMax: Option<u64>,
+    #[config(deserialize_with = deserialize_limit)]
+    pub MemoryHigh: Option<u64>,
+    #[config(deserialize_with = deserialize_limit)]
+    pub MemoryLow: Option<u64>,
+    #[config(deserialize_with = deserialize_limit)]
+    pub TasksMax: Option<u64>,
+    pub IOWeight: Option<u64>,
+    // the block device and the weight separated by space, like "/dev/sda 200", separated by ";"
+    #[config(deserialize_with = Vec::<String>::deserialize_with)]
+    pub IODeviceWeight: Option<Vec<String>>,
+    pub AllowedCPUs: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct UnitReConfig {
     unit: UeConfigUnit,
     install: UeConfigInstall,
+    #[serde(default)]
+    resource: UeConfigResource,
 }
 
 impl UnitReConfig {
-    fn new(unitr: &UeConfigUnit, installr: &UeConfigInstall) -> UnitReConfig {
+    fn new(
+        unitr: &UeConfigUnit,
+        installr: &UeConfigInstall,
+        resourcer: &UeConfigResource,
+    ) -> UnitReConfig {
         UnitReConfig {
             unit: unitr.clone(),
             install: installr.clone(),
+            resource: resourcer.clone(),
         }
     }
 }
@@ -316,6 +513,29 @@ impl UnitReCgroup {
     }
 }
 
+/// the realtime of the active state changes of the unit, in microseconds since the epoch, 0 means never
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct UnitTimestamp {
+    pub(super) state_change: u64,
+    pub(super) inactive_exit: u64,
+    pub(super) active_enter: u64,
+    pub(super) active_exit: u64,
+    pub(super) inactive_enter: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct UnitReTimestamp {
+    timestamp: UnitTimestamp,
+}
+
+impl UnitReTimestamp {
+    fn new(timestampr: &UnitTimestamp) -> UnitReTimestamp {
+        UnitReTimestamp {
+            timestamp: *timestampr,
+        }
+    }
+}
+
 bitflags! {
     #[derive(Serialize, Deserialize)]
     pub(super) struct UnitRePps: u32 {
@@ -405,6 +625,17 @@ impl UnitReDep {
     }
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct UnitReDynUser {
+    uid: u32, // the gid is the same as the uid
+}
+
+impl UnitReDynUser {
+    fn new(uid: u32) -> UnitReDynUser {
+        UnitReDynUser { uid }
+    }
+}
+
 const RELI_LAST_KEY: u32 = 0; // singleton
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -424,9 +655,11 @@ pub(super) struct UnitRe {
     load: Rc<ReDb<String, UnitReLoad>>, // RELI_DB_HUNIT_LOAD; key: unit_id, data: load_state;
     conf: Rc<ReDb<String, UnitReConfig>>, // RELI_DB_HUNIT_CONFIG; key: unit_id, data: unit_conf+install_conf;
     cgroup: Rc<ReDb<String, UnitReCgroup>>, // RELI_DB_HUNIT_CGROUP; key: unit_id, data: cg_path;
+    timestamp: Rc<ReDb<String, UnitReTimestamp>>, // RELI_DB_HUNIT_TIMESTAMP; key: unit_id, data: timestamps;
     child: Rc<ReDb<String, UnitReChild>>, // RELI_DB_HUNIT_CHILD; key: unit_id, data: pid[s];
     pps: Rc<ReDb<String, UnitRePps>>,     // RELI_DB_HUNIT_PPS; key: unit_id, data: pipeline[s];
     dep: Rc<ReDb<String, UnitReDep>>, // RELI_DB_HUNIT_DEP; key: unit_id, data: {UnitRelation+unit_id}[s]
+    dynuser: Rc<ReDb<String, UnitReDynUser>>, // RELI_DB_HUNIT_DYNUSER; key: unit_id, data: uid;
 
     // database: singleton(1)
     notify: Rc<ReDb<u32, UmReNotify>>, // RELI_DB_HUM_NOTIFY; key: RELI_LAST_KEY, data: UmReNotify;
@@ -438,18 +671,22 @@ impl UnitRe {
         let load = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_LOAD));
         let conf = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_CONFIG));
         let cgroup = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_CGROUP));
+        let timestamp = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_TIMESTAMP));
         let child = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_CHILD));
         let pps = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_PPS));
         let dep = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_DEP));
+        let dynuser = Rc::new(ReDb::new(relir, RELI_DB_HUNIT_DYNUSER));
         let notify = Rc::new(ReDb::new(relir, RELI_DB_HUM_NOTIFY));
         let rentry = UnitRe {
             base,
             load,
             conf,
             cgroup,
+            timestamp,
             child,
             pps,
             dep,
+            dynuser,
             notify,
         };
         rentry.register(relir);
@@ -497,10 +734,11 @@ impl UnitRe {
         unit_id: &String,
         unit: &UeConfigUnit,
         install: &UeConfigInstall,
+        resource: &UeConfigResource,
     ) {
         assert!(self.base_contains(unit_id));
 
-        let u_conf = UnitReConfig::new(unit, install);
+        let u_conf = UnitReConfig::new(unit, install, resource);
         self.conf.insert(unit_id.clone(), u_conf);
     }
 
@@ -509,9 +747,12 @@ impl UnitRe {
         self.conf.remove(unit_id);
     }
 
-    pub(super) fn conf_get(&self, unit_id: &String) -> Option<(UeConfigUnit, UeConfigInstall)> {
+    pub(super) fn conf_get(
+        &self,
+        unit_id: &String,
+    ) -> Option<(UeConfigUnit, UeConfigInstall, UeConfigResource)> {
         let u_conf = self.conf.get(unit_id);
-        u_conf.map(|c| (c.unit, c.install))
+        u_conf.map(|c| (c.unit, c.install, c.resource))
     }
 
     pub(super) fn cgroup_insert(&self, unit_id: &String, cg_path: &Path) {
@@ -531,6 +772,23 @@ impl UnitRe {
         u_cgroup.map(|c| c.cg_path)
     }
 
+    pub(super) fn timestamp_insert(&self, unit_id: &String, timestamp: &UnitTimestamp) {
+        assert!(self.base_contains(unit_id));
+
+        let u_timestamp = UnitReTimestamp::new(timestamp);
+        self.timestamp.insert(unit_id.clone(), u_timestamp);
+    }
+
+    #[allow(dead_code)]
+    pub(super) fn timestamp_remove(&self, unit_id: &String) {
+        self.timestamp.remove(unit_id);
+    }
+
+    pub(super) fn timestamp_get(&self, unit_id: &String) -> Option<UnitTimestamp> {
+        let u_timestamp = self.timestamp.get(unit_id);
+        u_timestamp.map(|t| t.timestamp)
+    }
+
     pub(super) fn child_insert(&self, unit_id: &String, pids: &[Pid]) {
         assert!(self.base_contains(unit_id));
 
@@ -619,6 +877,30 @@ impl UnitRe {
         self.dep.keys()
     }
 
+    pub(super) fn dynuser_insert(&self, unit_id: &String, uid: u32) {
+        assert!(self.base_contains(unit_id));
+
+        let u_dynuser = UnitReDynUser::new(uid);
+        self.dynuser.insert(unit_id.clone(), u_dynuser);
+    }
+
+    pub(super) fn dynuser_remove(&self, unit_id: &String) {
+        self.dynuser.remove(unit_id);
+    }
+
+    pub(super) fn dynuser_get(&self, unit_id: &String) -> Option<u32> {
+        let u_dynuser = self.dynuser.get(unit_id);
+        u_dynuser.map(|d| d.uid)
+    }
+
+    pub(super) fn dynuser_uids(&self) -> Vec<u32> {
+        self.dynuser
+            .entries()
+            .into_iter()
+            .map(|(_, d)| d.uid)
+            .collect()
+    }
+
     fn base_contains(&self, unit_id: &String) -> bool {
         self.base.contains_key(unit_id)
     }
@@ -660,6 +942,10 @@ impl UnitRe {
         let db = Rc::clone(&self.cgroup);
         relir.history_db_register(RELI_DB_HUNIT_CGROUP, db);
 
+        // reliability-db: RELI_DB_HUNIT_TIMESTAMP
+        let db = Rc::clone(&self.timestamp);
+        relir.history_db_register(RELI_DB_HUNIT_TIMESTAMP, db);
+
         // reliability-db: RELI_DB_HUNIT_CHILD
         let db = Rc::clone(&self.child);
         relir.history_db_register(RELI_DB_HUNIT_CHILD, db);
@@ -672,6 +958,10 @@ impl UnitRe {
         let db = Rc::clone(&self.dep);
         relir.history_db_register(RELI_DB_HUNIT_DEP, db);
 
+        // reliability-db: RELI_DB_HUNIT_DYNUSER
+        let db = Rc::clone(&self.dynuser);
+        relir.history_db_register(RELI_DB_HUNIT_DYNUSER, db);
+
         // reliability-db: RELI_DB_HUM_NOTIFY
         let db = Rc::clone(&self.notify);
         relir.history_db_register(RELI_DB_HUM_NOTIFY, db);
@@ -750,6 +1040,24 @@ impl ReDbTable for ReDb<String, UnitReCgroup> {
     }
 }
 
+impl ReDbTable for ReDb<String, UnitReTimestamp> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.set_ignore(ignore);
+    }
+}
+
 impl ReDbTable for ReDb<String, UnitReChild> {
     fn clear(&self, wtxn: &mut ReDbRwTxn) {
         self.do_clear(wtxn);
@@ -804,6 +1112,24 @@ impl ReDbTable for ReDb<String, UnitReDep> {
     }
 }
 
+impl ReDbTable for ReDb<String, UnitReDynUser> {
+    fn clear(&self, wtxn: &mut ReDbRwTxn) {
+        self.do_clear(wtxn);
+    }
+
+    fn export(&self, db_wtxn: &mut ReDbRwTxn) {
+        self.cache_2_db(db_wtxn);
+    }
+
+    fn import<'a>(&self, db_rtxn: &'a ReDbRoTxn) {
+        self.db_2_cache(db_rtxn);
+    }
+
+    fn ignore_set(&self, ignore: bool) {
+        self.set_ignore(ignore);
+    }
+}
+
 impl ReDbTable for ReDb<u32, UmReNotify> {
     fn clear(&self, wtxn: &mut ReDbRwTxn) {
         self.do_clear(wtxn);
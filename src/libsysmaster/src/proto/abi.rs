@@ -2,7 +2,7 @@
 #[rustfmt::skip]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CommandRequest {
-    #[prost(oneof="command_request::RequestData", tags="1, 2, 3, 4, 5")]
+    #[prost(oneof="command_request::RequestData", tags="1, 2, 3, 4, 5, 6, 7, 8")]
     pub request_data: ::core::option::Option<command_request::RequestData>,
 }
 /// Nested message and enum types in `CommandRequest`.
@@ -25,6 +25,15 @@ pub mod command_request {
         ///system commands, reboot/shutdown/halt
         #[prost(message, tag="5")]
         Syscomm(super::SysComm),
+        ///list the units
+        #[prost(message, tag="6")]
+        Lunits(super::ListUnits),
+        ///query the logs of the unit
+        #[prost(message, tag="7")]
+        Ulogs(super::UnitLogs),
+        ///keep the connection open and stream the events of the jobs and units
+        #[prost(message, tag="8")]
+        Subscribe(super::Subscribe),
     }
 }
 /// Command Response from server
@@ -37,6 +46,172 @@ pub struct CommandResponse {
     /// if not 2xx，message include more information
     #[prost(string, tag="2")]
     pub message: ::prost::alloc::string::String,
+    /// the units listed by ListUnits
+    #[prost(message, repeated, tag="3")]
+    pub units: ::prost::alloc::vec::Vec<UnitRow>,
+    /// the jobs listed by JobComm LIST
+    #[prost(message, repeated, tag="4")]
+    pub jobs: ::prost::alloc::vec::Vec<JobInfo>,
+    /// the status of the unit queried by UnitComm STATUS
+    #[prost(message, optional, tag="5")]
+    pub unit_status: ::core::option::Option<UnitStatus>,
+    /// the logs queried by UnitLogs, in the order of time
+    #[prost(message, repeated, tag="6")]
+    pub logs: ::prost::alloc::vec::Vec<LogEntry>,
+    /// the event streamed to the subscriber after the response of Subscribe
+    #[prost(message, optional, tag="7")]
+    pub event: ::core::option::Option<Event>,
+    /// the id of the start job created by UnitComm START, 0 if there is no job
+    #[prost(uint32, tag="8")]
+    pub job_id: u32,
+}
+#[rustfmt::skip]
+#[derive(serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitRow {
+    #[prost(string, tag="1")]
+    pub unitname: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub load_state: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub active_state: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub sub_state: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub description: ::prost::alloc::string::String,
+}
+#[rustfmt::skip]
+#[derive(serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JobInfo {
+    #[prost(uint32, tag="1")]
+    pub id: u32,
+    #[prost(string, tag="2")]
+    pub unitname: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub stage: ::prost::alloc::string::String,
+}
+#[rustfmt::skip]
+#[derive(serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitStatus {
+    #[prost(string, tag="1")]
+    pub unitname: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub load_state: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub active_state: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub sub_state: ::prost::alloc::string::String,
+    /// the unit file which the unit is loaded from
+    #[prost(string, tag="6")]
+    pub fragment_path: ::prost::alloc::string::String,
+    /// the result of the last run, such as "success" or "exit-code"
+    #[prost(string, tag="7")]
+    pub result: ::prost::alloc::string::String,
+    /// the main process of the unit, 0 means none
+    #[prost(uint32, tag="8")]
+    pub main_pid: u32,
+    /// how the last main process ended: "exited" or "killed", empty if it has not ended
+    #[prost(string, tag="9")]
+    pub main_code: ::prost::alloc::string::String,
+    /// the exit code if the main process exited, or the signal number if it was killed
+    #[prost(int32, tag="10")]
+    pub main_status: i32,
+    /// the realtime in microseconds since the epoch, 0 means never
+    #[prost(uint64, tag="11")]
+    pub state_change_timestamp: u64,
+    #[prost(uint64, tag="12")]
+    pub active_enter_timestamp: u64,
+    #[prost(uint64, tag="13")]
+    pub active_exit_timestamp: u64,
+    #[prost(uint64, tag="14")]
+    pub inactive_enter_timestamp: u64,
+    #[prost(uint64, tag="15")]
+    pub inactive_exit_timestamp: u64,
+    /// the cgroup of the unit
+    #[prost(string, tag="16")]
+    pub cgroup: ::prost::alloc::string::String,
+    /// the jobs of the unit in the job queue
+    #[prost(message, repeated, tag="17")]
+    pub jobs: ::prost::alloc::vec::Vec<JobInfo>,
+    /// the status text sent by the service with STATUS=
+    #[prost(string, tag="18")]
+    pub status_text: ::prost::alloc::string::String,
+}
+#[rustfmt::skip]
+#[derive(serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LogEntry {
+    /// the realtime in microseconds since the epoch when the line is collected
+    #[prost(uint64, tag="1")]
+    pub timestamp: u64,
+    /// the process which the line is collected from
+    #[prost(uint32, tag="2")]
+    pub pid: u32,
+    #[prost(string, tag="3")]
+    pub unitname: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub message: ::prost::alloc::string::String,
+}
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Event {
+    #[prost(oneof="event::EventData", tags="1, 2, 3")]
+    pub event_data: ::core::option::Option<event::EventData>,
+}
+/// Nested message and enum types in `Event`.
+pub mod event {
+    #[rustfmt::skip]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum EventData {
+        #[prost(message, tag="1")]
+        JobNew(super::JobNew),
+        #[prost(message, tag="2")]
+        JobRemoved(super::JobRemoved),
+        #[prost(message, tag="3")]
+        UnitStateChanged(super::UnitStateChanged),
+    }
+}
+/// a job is added to the job queue
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JobNew {
+    #[prost(uint32, tag="1")]
+    pub id: u32,
+    #[prost(string, tag="2")]
+    pub unitname: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub kind: ::prost::alloc::string::String,
+}
+/// a job is removed from the job queue
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JobRemoved {
+    #[prost(uint32, tag="1")]
+    pub id: u32,
+    #[prost(string, tag="2")]
+    pub unitname: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub kind: ::prost::alloc::string::String,
+    /// how the job finished, such as "done", "failed" or "canceled"
+    #[prost(string, tag="4")]
+    pub result: ::prost::alloc::string::String,
+}
+/// the active state of a unit is changed
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitStateChanged {
+    #[prost(string, tag="1")]
+    pub unitname: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub old_state: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub new_state: ::prost::alloc::string::String,
 }
 #[rustfmt::skip]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -45,6 +220,9 @@ pub struct UnitComm {
     pub action: i32,
     #[prost(string, tag="2")]
     pub unitname: ::prost::alloc::string::String,
+    /// the signal sent by KILL, 0 means SIGTERM
+    #[prost(int32, tag="3")]
+    pub signal: i32,
 }
 /// Nested message and enum types in `UnitComm`.
 pub mod unit_comm {
@@ -58,6 +236,7 @@ pub mod unit_comm {
         Restart = 3,
         Reload = 4,
         Kill = 5,
+        Isolate = 6,
     }
 }
 #[rustfmt::skip]
@@ -118,6 +297,26 @@ pub mod mngr_comm {
 }
 #[rustfmt::skip]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListUnits {
+    /// list the inactive units too
+    #[prost(bool, tag="1")]
+    pub all: bool,
+}
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnitLogs {
+    #[prost(string, tag="1")]
+    pub unitname: ::prost::alloc::string::String,
+    /// only the logs collected at or after the realtime in microseconds are returned
+    #[prost(uint64, tag="2")]
+    pub since: u64,
+}
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Subscribe {
+}
+#[rustfmt::skip]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SysComm {
     #[prost(enumeration="sys_comm::Action", tag="1")]
     pub action: i32,
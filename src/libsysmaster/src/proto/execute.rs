@@ -1,12 +1,16 @@
 //! Convert the command request into the corresponding execution action
 use super::{
-    sys_comm, unit_comm, CommandRequest, CommandResponse, MngrComm, RequestData, SysComm, UnitComm,
-    UnitFile,
+    job_comm, mngr_comm, sys_comm, unit_comm, unit_file, CommandRequest, CommandResponse, JobComm,
+    JobInfo, ListUnits, LogEntry, MngrComm, RequestData, Subscribe, SysComm, UnitComm, UnitFile,
+    UnitLogs, UnitRow, UnitStatus,
 };
 use crate::manager::MngErrno;
 use http::StatusCode;
 use libutils::Result;
+use nix::sys::socket::UnixCredentials;
+use std::fmt::Debug;
 use std::io::Error;
+use std::os::unix::net::UnixStream;
 use std::rc::Rc;
 
 pub(crate) trait Executer {
@@ -16,10 +20,30 @@ pub(crate) trait Executer {
 
 /// ExecuterAction
 pub trait ExecuterAction {
-    /// start the unit_name
-    fn start(&self, unit_name: &str) -> Result<(), MngErrno>;
+    /// start the unit_name, return the id of its start job
+    fn start(&self, unit_name: &str) -> Result<u32, MngErrno>;
     /// stop the unit_name
     fn stop(&self, unit_name: &str) -> Result<(), MngErrno>;
+    /// restart the unit_name
+    fn restart(&self, unit_name: &str) -> Result<(), MngErrno>;
+    /// reload the unit_name
+    fn reload(&self, unit_name: &str) -> Result<(), MngErrno>;
+    /// send the signal to the processes of the unit_name
+    fn kill(&self, unit_name: &str, signal: i32) -> Result<(), MngErrno>;
+    /// start the unit_name and stop all the others
+    fn isolate(&self, unit_name: &str) -> Result<(), MngErrno>;
+    /// list the loaded units, the inactive units are listed only if all is true
+    fn list_units(&self, all: bool) -> Vec<UnitRow>;
+    /// list the jobs in the job queue
+    fn list_jobs(&self) -> Vec<JobInfo>;
+    /// get the status of the unit_name
+    fn status(&self, unit_name: &str) -> Result<UnitStatus, MngErrno>;
+    /// get the logs of the unit_name collected at or after the realtime since
+    fn logs(&self, unit_name: &str, since: u64) -> Result<Vec<LogEntry>, MngErrno>;
+    /// keep the stream open and send the events of the jobs and units to it
+    fn subscribe(&self, stream: UnixStream);
+    /// cancel the job
+    fn cancel_job(&self, job_id: u32) -> Result<(), MngErrno>;
     /// suspend host
     fn suspend(&self) -> Result<i32>;
     /// poweroff host
@@ -32,48 +56,202 @@ pub trait ExecuterAction {
     fn disable(&self, unit_name: &str) -> Result<(), Error>;
     /// enable unit_name
     fn enable(&self, unit_name: &str) -> Result<(), Error>;
+    /// mask unit_name
+    fn mask(&self, unit_name: &str) -> Result<(), Error>;
+    /// get the default target
+    fn get_default(&self) -> Result<String, Error>;
+    /// set the default target
+    fn set_default(&self, unit_name: &str) -> Result<(), Error>;
+    /// reload the manager
+    fn daemon_reload(&self) -> Result<i32>;
+    /// re-execute the manager
+    fn daemon_reexec(&self) -> Result<i32>;
+    /// check whether the peer is allowed to send the request, the peer is identified by SO_PEERCRED.
+    /// only root can send the request which changes the state of the system.
+    fn permitted(&self, cred: &UnixCredentials, mutating: bool) -> bool {
+        !mutating || cred.uid() == 0
+    }
 }
 
 /// Depending on the type of request
-pub(crate) fn dispatch<T>(cmd: CommandRequest, manager: Rc<T>) -> CommandResponse
+pub(crate) fn dispatch<T>(
+    cmd: CommandRequest,
+    manager: Rc<T>,
+    cred: Option<UnixCredentials>,
+) -> CommandResponse
 where
     T: ExecuterAction,
 {
-    println!("commandRequest :{:?}", cmd);
+    log::debug!("commandRequest :{:?}", cmd);
+    if let Some(c) = &cred {
+        log::debug!(
+            "command request from pid: {}, uid: {}, gid: {}",
+            c.pid(),
+            c.uid(),
+            c.gid()
+        );
+    }
+    let permitted = match cred {
+        Some(c) => manager.permitted(&c, cmd.is_mutating()),
+        // the peer is unknown, only the read-only request is allowed
+        None => !cmd.is_mutating(),
+    };
+    if !permitted {
+        log::warn!("refuse command request from peer: {:?}", cred);
+        return CommandResponse {
+            status: StatusCode::FORBIDDEN.as_u16() as _,
+            message: String::from("permission denied."),
+            ..Default::default()
+        };
+    }
+
     let res = match cmd.request_data {
         Some(RequestData::Ucomm(param)) => param.execute(manager),
         Some(RequestData::Mcomm(param)) => param.execute(manager),
         Some(RequestData::Syscomm(param)) => param.execute(manager),
         Some(RequestData::Ufile(param)) => param.execute(manager),
+        Some(RequestData::Jcomm(param)) => param.execute(manager),
+        Some(RequestData::Lunits(param)) => param.execute(manager),
+        Some(RequestData::Ulogs(param)) => param.execute(manager),
+        Some(RequestData::Subscribe(param)) => param.execute(manager),
         _ => CommandResponse::default(),
     };
-    println!("CommandResponse :{:?}", res);
+    log::debug!("CommandResponse :{:?}", res);
     res
 }
 
+fn response_ok(message: String) -> CommandResponse {
+    CommandResponse {
+        status: StatusCode::OK.as_u16() as _,
+        message,
+        ..Default::default()
+    }
+}
+
+fn response_err(status: StatusCode, message: String) -> CommandResponse {
+    CommandResponse {
+        status: status.as_u16() as _,
+        message,
+        ..Default::default()
+    }
+}
+
+fn response_from<T, E: Debug>(ret: std::result::Result<T, E>) -> CommandResponse {
+    match ret {
+        Ok(_) => response_ok(String::new()),
+        Err(e) => response_err(StatusCode::INTERNAL_SERVER_ERROR, format!("error: {:?}", e)),
+    }
+}
+
+fn response_from_errno(errno: MngErrno) -> CommandResponse {
+    match errno {
+        MngErrno::Input => response_err(StatusCode::BAD_REQUEST, "invalid input.".into()),
+        MngErrno::NotExisted => response_err(StatusCode::NOT_FOUND, "not existed.".into()),
+        MngErrno::NotSupported => {
+            response_err(StatusCode::NOT_IMPLEMENTED, "not supported.".into())
+        }
+        MngErrno::Internal => {
+            response_err(StatusCode::INTERNAL_SERVER_ERROR, "internal error.".into())
+        }
+    }
+}
+
+fn response_from_mng(ret: Result<(), MngErrno>) -> CommandResponse {
+    match ret {
+        Ok(_) => response_ok(String::new()),
+        Err(e) => response_from_errno(e),
+    }
+}
+
 impl Executer for UnitComm {
     fn execute(self, manager: Rc<impl ExecuterAction>) -> CommandResponse {
         let ret = match self.action() {
-            unit_comm::Action::Start => manager.start(&self.unitname),
+            unit_comm::Action::Start => {
+                return match manager.start(&self.unitname) {
+                    Ok(job_id) => CommandResponse {
+                        status: StatusCode::OK.as_u16() as _,
+                        job_id,
+                        ..Default::default()
+                    },
+                    Err(e) => response_from_errno(e),
+                }
+            }
             unit_comm::Action::Stop => manager.stop(&self.unitname),
-            _ => todo!(),
+            unit_comm::Action::Restart => manager.restart(&self.unitname),
+            unit_comm::Action::Reload => manager.reload(&self.unitname),
+            unit_comm::Action::Kill => manager.kill(&self.unitname, self.signal),
+            unit_comm::Action::Isolate => manager.isolate(&self.unitname),
+            unit_comm::Action::Status => {
+                return match manager.status(&self.unitname) {
+                    Ok(status) => CommandResponse {
+                        status: StatusCode::OK.as_u16() as _,
+                        unit_status: Some(status),
+                        ..Default::default()
+                    },
+                    Err(e) => response_from_errno(e),
+                }
+            }
         };
-        match ret {
-            Ok(_) => CommandResponse {
+        response_from_mng(ret)
+    }
+}
+
+impl Executer for JobComm {
+    fn execute(self, manager: Rc<impl ExecuterAction>) -> CommandResponse {
+        match self.action() {
+            job_comm::Action::List => CommandResponse {
                 status: StatusCode::OK.as_u16() as _,
+                jobs: manager.list_jobs(),
                 ..Default::default()
             },
-            Err(_e) => CommandResponse {
-                status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
-                message: String::from("error."),
+            job_comm::Action::Cancel => match self.job_id.parse::<u32>() {
+                Ok(id) => response_from_mng(manager.cancel_job(id)),
+                Err(_) => response_err(
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid job id: {}", self.job_id),
+                ),
             },
         }
     }
 }
 
-impl Executer for MngrComm {
+impl Executer for ListUnits {
+    fn execute(self, manager: Rc<impl ExecuterAction>) -> CommandResponse {
+        CommandResponse {
+            status: StatusCode::OK.as_u16() as _,
+            units: manager.list_units(self.all),
+            ..Default::default()
+        }
+    }
+}
+
+impl Executer for UnitLogs {
+    fn execute(self, manager: Rc<impl ExecuterAction>) -> CommandResponse {
+        match manager.logs(&self.unitname, self.since) {
+            Ok(logs) => CommandResponse {
+                status: StatusCode::OK.as_u16() as _,
+                logs,
+                ..Default::default()
+            },
+            Err(e) => response_from_errno(e),
+        }
+    }
+}
+
+impl Executer for Subscribe {
     fn execute(self, _manager: Rc<impl ExecuterAction>) -> CommandResponse {
-        todo!()
+        // the stream is handed over to the manager after the response is sent
+        response_ok(String::new())
+    }
+}
+
+impl Executer for MngrComm {
+    fn execute(self, manager: Rc<impl ExecuterAction>) -> CommandResponse {
+        let ret = match self.action() {
+            mngr_comm::Action::Reload => manager.daemon_reload(),
+            mngr_comm::Action::Reexec => manager.daemon_reexec(),
+        };
+        response_from(ret)
     }
 }
 
@@ -87,35 +265,30 @@ impl Executer for SysComm {
             sys_comm::Action::Shutdown => manager.poweroff(),
             sys_comm::Action::Reboot => manager.reboot(),
         };
-        match ret {
-            Ok(_) => CommandResponse {
-                status: StatusCode::OK.as_u16() as _,
-                ..Default::default()
-            },
-            Err(_e) => CommandResponse {
-                status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
-                message: String::from("error."),
-            },
-        }
+        response_from(ret)
     }
 }
 
 impl Executer for UnitFile {
     fn execute(self, manager: Rc<impl ExecuterAction>) -> CommandResponse {
         let ret = match self.action() {
-            super::unit_file::Action::Enable => manager.enable(&self.unitname),
-            super::unit_file::Action::Disable => manager.disable(&self.unitname),
-            _ => todo!(),
+            unit_file::Action::Enable => manager.enable(&self.unitname),
+            unit_file::Action::Disable => manager.disable(&self.unitname),
+            unit_file::Action::Mask => manager.mask(&self.unitname),
+            unit_file::Action::Setdef => manager.set_default(&self.unitname),
+            unit_file::Action::Getdef => {
+                return match manager.get_default() {
+                    Ok(target) => response_ok(target),
+                    Err(e) => response_err(StatusCode::NOT_FOUND, e.to_string()),
+                }
+            }
+            unit_file::Action::Cat => {
+                return response_err(StatusCode::NOT_IMPLEMENTED, "not supported.".into())
+            }
         };
         match ret {
-            Ok(_) => CommandResponse {
-                status: StatusCode::OK.as_u16() as _,
-                ..Default::default()
-            },
-            Err(_e) => CommandResponse {
-                status: StatusCode::INTERNAL_SERVER_ERROR.as_u16() as _,
-                message: String::from("error."),
-            },
+            Ok(_) => response_ok(String::new()),
+            Err(e) => response_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
         }
     }
 }
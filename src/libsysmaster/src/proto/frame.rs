@@ -1,31 +1,32 @@
 //! Encapsulate the command request into a frame
+use nix::sys::socket::UnixCredentials;
 use prost::bytes::{BufMut, BytesMut};
 use prost::Message;
 use std::{
-    io::{Error, Read, Write},
+    io::{Error, ErrorKind, Read, Write},
     rc::Rc,
 };
 
 use super::execute::ExecuterAction;
-use super::{execute, CommandRequest, CommandResponse};
+use super::{execute, CommandRequest, CommandResponse, Event, StatusCode};
 
-/// const MAX_FRAME: usize = 1436;
-const MAX_FRAME: usize = 1436;
+/// the maximal size of a frame, the response of list-units may be large
+const MAX_FRAME: usize = 1 << 20;
 
 /// Frame : encode/decode
 pub trait FrameCoder
 where
     Self: Message + Sized + Default,
 {
-    /// Encode message into frame
+    /// Encode message into frame, the message is prefixed with its length
     fn encode_frame(&self, buf: &mut BytesMut) -> Result<(), Error> {
         let size = self.encoded_len();
 
         if size > MAX_FRAME {
-            return Err(std::io::Error::last_os_error());
+            return Err(Error::new(ErrorKind::InvalidData, "frame is too large"));
         }
 
-        self.encode(buf)?;
+        self.encode_length_delimited(buf)?;
         Ok(())
     }
 
@@ -39,14 +40,33 @@ where
 impl FrameCoder for CommandRequest {}
 impl FrameCoder for CommandResponse {}
 
-/// read frame from stream
+/// read frame from stream, the length of the frame is encoded as varint in front of it
 pub fn read_frame<S>(stream: &mut S, buf: &mut BytesMut) -> Result<(), Error>
 where
     S: Read + Unpin + Send,
 {
-    let mut tmp = [0u8; MAX_FRAME];
-    let len = stream.read(&mut tmp)?;
-    buf.put_slice(&tmp[..len]);
+    let mut len: usize = 0;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte)?;
+        len |= ((byte[0] & 0x7f) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+        if shift > 28 {
+            return Err(Error::new(ErrorKind::InvalidData, "invalid frame length"));
+        }
+    }
+
+    if len > MAX_FRAME {
+        return Err(Error::new(ErrorKind::InvalidData, "frame is too large"));
+    }
+
+    let mut tmp = vec![0u8; len];
+    stream.read_exact(&mut tmp)?;
+    buf.put_slice(&tmp);
     Ok(())
 }
 
@@ -54,6 +74,7 @@ where
 pub struct ProstServerStream<S, T> {
     inner: S,
     manager: Rc<T>,
+    cred: Option<UnixCredentials>,
 }
 
 /// Handle read and write of client-side socket
@@ -66,21 +87,27 @@ where
     S: Read + Write + Unpin + Send,
     T: ExecuterAction,
 {
-    /// new ProstServerStream
-    pub(crate) fn new(stream: S, manager: Rc<T>) -> Self {
+    /// new ProstServerStream, cred is the credential of the peer
+    pub(crate) fn new(stream: S, manager: Rc<T>, cred: Option<UnixCredentials>) -> Self {
         Self {
             inner: stream,
             manager,
+            cred,
         }
     }
 
-    /// process frame in server-side
-    pub fn process(mut self) -> Result<(), Error> {
+    /// process frame in server-side, the stream is returned if the peer subscribes the events
+    pub fn process(mut self) -> Result<Option<S>, Error> {
         if let Ok(cmd) = self.recv() {
-            let res = execute::dispatch(cmd, Rc::clone(&self.manager));
+            let subscribe = cmd.is_subscribe();
+            let res = execute::dispatch(cmd, Rc::clone(&self.manager), self.cred);
+            let accepted = res.status == StatusCode::OK.as_u16() as u32;
             self.send(res)?;
+            if subscribe && accepted {
+                return Ok(Some(self.inner));
+            }
         };
-        Ok(())
+        Ok(None)
     }
 
     fn send(&mut self, msg: CommandResponse) -> Result<(), Error> {
@@ -116,6 +143,15 @@ where
         self.recv()
     }
 
+    /// wait for the next event after the subscribe request is executed
+    pub fn next_event(&mut self) -> Result<Event, Error> {
+        loop {
+            if let Some(event) = self.recv()?.event {
+                return Ok(event);
+            }
+        }
+    }
+
     fn send(&mut self, msg: CommandRequest) -> Result<(), Error> {
         let mut buf = BytesMut::new();
         msg.encode_frame(&mut buf)?;
@@ -136,11 +172,272 @@ where
 #[cfg(test)]
 mod tests {
     use super::super::abi::unit_comm::Action as UnitAction;
+    use super::super::event::EventData;
+    use super::super::{job_comm, unit_file, JobInfo, JobRemoved, LogEntry, UnitRow, UnitStatus};
     use super::*;
+    use crate::manager::MngErrno;
+    use http::StatusCode;
     use std::net::{SocketAddr, TcpStream};
+    use std::os::unix::net::UnixStream;
     use std::thread;
     use std::time::Duration;
 
+    struct ActionD;
+
+    impl ExecuterAction for ActionD {
+        fn start(&self, _unit_name: &str) -> Result<u32, MngErrno> {
+            Ok(1)
+        }
+        fn stop(&self, _unit_name: &str) -> Result<(), MngErrno> {
+            Ok(())
+        }
+        fn restart(&self, _unit_name: &str) -> Result<(), MngErrno> {
+            Ok(())
+        }
+        fn reload(&self, _unit_name: &str) -> Result<(), MngErrno> {
+            Ok(())
+        }
+        fn kill(&self, _unit_name: &str, _signal: i32) -> Result<(), MngErrno> {
+            Ok(())
+        }
+        fn isolate(&self, _unit_name: &str) -> Result<(), MngErrno> {
+            Err(MngErrno::Input)
+        }
+        fn list_units(&self, all: bool) -> Vec<UnitRow> {
+            let mut units = vec![UnitRow {
+                unitname: "test.service".to_string(),
+                load_state: "loaded".to_string(),
+                active_state: "active".to_string(),
+                sub_state: "running".to_string(),
+                description: "test".to_string(),
+            }];
+            if all {
+                units.push(UnitRow {
+                    unitname: "test.socket".to_string(),
+                    load_state: "loaded".to_string(),
+                    active_state: "inactive".to_string(),
+                    sub_state: "dead".to_string(),
+                    description: String::new(),
+                });
+            }
+            units
+        }
+        fn list_jobs(&self) -> Vec<JobInfo> {
+            Vec::new()
+        }
+        fn status(&self, unit_name: &str) -> Result<UnitStatus, MngErrno> {
+            if unit_name != "test.service" {
+                return Err(MngErrno::NotExisted);
+            }
+            Ok(UnitStatus {
+                unitname: unit_name.to_string(),
+                active_state: "failed".to_string(),
+                result: "exit-code".to_string(),
+                main_code: "exited".to_string(),
+                main_status: 1,
+                ..Default::default()
+            })
+        }
+        fn logs(&self, unit_name: &str, since: u64) -> Result<Vec<LogEntry>, MngErrno> {
+            if unit_name != "test.service" {
+                return Err(MngErrno::NotExisted);
+            }
+            let logs = (1..=3)
+                .map(|i| LogEntry {
+                    timestamp: i,
+                    pid: 100,
+                    unitname: unit_name.to_string(),
+                    message: format!("line {}", i),
+                })
+                .filter(|l| l.timestamp >= since)
+                .collect();
+            Ok(logs)
+        }
+        fn subscribe(&self, mut stream: UnixStream) {
+            let event = Event {
+                event_data: Some(EventData::JobRemoved(JobRemoved {
+                    id: 1,
+                    unitname: "test.service".to_string(),
+                    kind: "start".to_string(),
+                    result: "done".to_string(),
+                })),
+            };
+            let res = CommandResponse {
+                status: StatusCode::OK.as_u16() as _,
+                event: Some(event),
+                ..Default::default()
+            };
+            let mut buf = BytesMut::new();
+            res.encode_frame(&mut buf).unwrap();
+            stream.write_all(&buf[..]).unwrap();
+        }
+        fn cancel_job(&self, _job_id: u32) -> Result<(), MngErrno> {
+            Err(MngErrno::NotExisted)
+        }
+        fn suspend(&self) -> libutils::Result<i32> {
+            Ok(0)
+        }
+        fn poweroff(&self) -> libutils::Result<i32> {
+            Ok(0)
+        }
+        fn reboot(&self) -> libutils::Result<i32> {
+            Ok(0)
+        }
+        fn halt(&self) -> libutils::Result<i32> {
+            Ok(0)
+        }
+        fn disable(&self, _unit_name: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn enable(&self, _unit_name: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn mask(&self, _unit_name: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn get_default(&self) -> Result<String, Error> {
+            Ok("multi-user.target".to_string())
+        }
+        fn set_default(&self, _unit_name: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn daemon_reload(&self) -> libutils::Result<i32> {
+            Ok(0)
+        }
+        fn daemon_reexec(&self) -> libutils::Result<i32> {
+            Ok(0)
+        }
+    }
+
+    fn execute_as(uid: u32, cmd: CommandRequest) -> CommandResponse {
+        let (client, server) = UnixStream::pair().unwrap();
+        let handle = thread::spawn(move || ProstClientStream::new(client).execute(cmd).unwrap());
+
+        let cred = UnixCredentials::from(libc::ucred {
+            pid: 1,
+            uid,
+            gid: uid,
+        });
+        let dispatch = ProstServerStream::new(server, Rc::new(ActionD), Some(cred));
+        dispatch.process().unwrap();
+        handle.join().unwrap()
+    }
+
+    #[test]
+    fn test_peer_permission() {
+        let start = CommandRequest::new_unitcomm(UnitAction::Start, "test.service");
+        let status = CommandRequest::new_unitcomm(UnitAction::Status, "test.service");
+        assert!(start.is_mutating());
+        assert!(!status.is_mutating());
+
+        let res = execute_as(1000, start.clone());
+        assert_eq!(res.status, StatusCode::FORBIDDEN.as_u16() as u32);
+        let res = execute_as(0, start);
+        assert_eq!(res.status, StatusCode::OK.as_u16() as u32);
+    }
+
+    #[test]
+    fn test_query_commands() {
+        let res = execute_as(1000, CommandRequest::new_listunits(false));
+        assert_eq!(res.status, StatusCode::OK.as_u16() as u32);
+        assert_eq!(res.units.len(), 1);
+        assert_eq!(res.units[0].sub_state, "running");
+        let res = execute_as(1000, CommandRequest::new_listunits(true));
+        assert_eq!(res.units.len(), 2);
+
+        let getdef = CommandRequest::new_unitfile(unit_file::Action::Getdef, "");
+        let res = execute_as(1000, getdef);
+        assert_eq!(res.message, "multi-user.target");
+
+        let status = CommandRequest::new_unitcomm(UnitAction::Status, "test.service");
+        let res = execute_as(1000, status);
+        assert_eq!(res.status, StatusCode::OK.as_u16() as u32);
+        let unit_status = res.unit_status.unwrap();
+        assert_eq!(unit_status.result, "exit-code");
+        assert_eq!(unit_status.main_status, 1);
+        let status = CommandRequest::new_unitcomm(UnitAction::Status, "none.service");
+        let res = execute_as(1000, status);
+        assert_eq!(res.status, StatusCode::NOT_FOUND.as_u16() as u32);
+        assert!(res.unit_status.is_none());
+    }
+
+    #[test]
+    fn test_logs_command() {
+        let logs = CommandRequest::new_unitlogs("test.service", 2);
+        assert!(logs.is_mutating());
+        let res = execute_as(1000, logs.clone());
+        assert_eq!(res.status, StatusCode::FORBIDDEN.as_u16() as u32);
+
+        let res = execute_as(0, logs);
+        assert_eq!(res.status, StatusCode::OK.as_u16() as u32);
+        assert_eq!(res.logs.len(), 2);
+        assert_eq!(res.logs[0].message, "line 2");
+
+        let res = execute_as(0, CommandRequest::new_unitlogs("none.service", 0));
+        assert_eq!(res.status, StatusCode::NOT_FOUND.as_u16() as u32);
+    }
+
+    #[test]
+    fn test_job_and_unit_errors() {
+        let cancel = CommandRequest::new_jobcomm(job_comm::Action::Cancel, "abc");
+        let res = execute_as(0, cancel);
+        assert_eq!(res.status, StatusCode::BAD_REQUEST.as_u16() as u32);
+        let cancel = CommandRequest::new_jobcomm(job_comm::Action::Cancel, "10");
+        let res = execute_as(0, cancel);
+        assert_eq!(res.status, StatusCode::NOT_FOUND.as_u16() as u32);
+
+        let isolate = CommandRequest::new_unitcomm(UnitAction::Isolate, "test.service");
+        let res = execute_as(0, isolate);
+        assert_eq!(res.status, StatusCode::BAD_REQUEST.as_u16() as u32);
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let subscribe = CommandRequest::new_subscribe();
+        assert!(!subscribe.is_mutating());
+        let (client, server) = UnixStream::pair().unwrap();
+        let handle = thread::spawn(move || {
+            let mut client = ProstClientStream::new(client);
+            let res = client.execute(subscribe).unwrap();
+            assert_eq!(res.status, StatusCode::OK.as_u16() as u32);
+            client.next_event().unwrap()
+        });
+
+        let dispatch = ProstServerStream::new(server, Rc::new(ActionD), None);
+        let stream = dispatch.process().unwrap().unwrap();
+        ActionD.subscribe(stream);
+        let event = handle.join().unwrap();
+        match event.event_data {
+            Some(EventData::JobRemoved(job)) => assert_eq!(job.result, "done"),
+            _ => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn test_large_frame() {
+        let units = (0..100)
+            .map(|i| UnitRow {
+                unitname: format!("test-{}.service", i),
+                description: "a unit with long description".to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let res = CommandResponse {
+            units,
+            ..Default::default()
+        };
+
+        let mut buf = BytesMut::new();
+        res.encode_frame(&mut buf).unwrap();
+        assert!(buf.len() > 1436);
+
+        let mut stream = &buf[..];
+        let mut frame = BytesMut::new();
+        read_frame(&mut stream, &mut frame).unwrap();
+        let decoded = CommandResponse::decode_frame(&mut frame).unwrap();
+        assert_eq!(decoded, res);
+    }
+
     #[test]
     fn test_send_and_recv() {
         thread::spawn(move || {
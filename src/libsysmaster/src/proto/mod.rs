@@ -13,6 +13,9 @@ pub use frame::ProstServerStream;
 pub use http::StatusCode;
 // use prost::Message;
 
+/// the unix socket that sysmaster serves the command requests on
+pub const SCTL_SOCKET: &str = "/run/sysmaster/sctl";
+
 impl CommandRequest {
     /// Create a new command request for unit
     pub fn new_unitcomm(action: unit_comm::Action, unitname: impl Into<String>) -> Self {
@@ -20,6 +23,45 @@ impl CommandRequest {
             request_data: Some(RequestData::Ucomm(UnitComm {
                 action: action.into(),
                 unitname: unitname.into(),
+                signal: 0,
+            })),
+        }
+    }
+
+    /// Create a new command request to send the signal to the processes of the unit
+    pub fn new_unitkill(unitname: impl Into<String>, signal: i32) -> Self {
+        Self {
+            request_data: Some(RequestData::Ucomm(UnitComm {
+                action: unit_comm::Action::Kill.into(),
+                unitname: unitname.into(),
+                signal,
+            })),
+        }
+    }
+
+    /// Create a new command request for job
+    pub fn new_jobcomm(action: job_comm::Action, job_id: impl Into<String>) -> Self {
+        Self {
+            request_data: Some(RequestData::Jcomm(JobComm {
+                action: action.into(),
+                job_id: job_id.into(),
+            })),
+        }
+    }
+
+    /// Create a new command request to list the units
+    pub fn new_listunits(all: bool) -> Self {
+        Self {
+            request_data: Some(RequestData::Lunits(ListUnits { all })),
+        }
+    }
+
+    /// Create a new command request to query the logs of the unit collected since the realtime
+    pub fn new_unitlogs(unitname: impl Into<String>, since: u64) -> Self {
+        Self {
+            request_data: Some(RequestData::Ulogs(UnitLogs {
+                unitname: unitname.into(),
+                since,
             })),
         }
     }
@@ -51,4 +93,32 @@ impl CommandRequest {
             })),
         }
     }
+
+    /// Create a new command request to subscribe the events of the jobs and units
+    pub fn new_subscribe() -> Self {
+        Self {
+            request_data: Some(RequestData::Subscribe(Subscribe {})),
+        }
+    }
+
+    /// whether the request keeps the connection open to stream the events
+    pub fn is_subscribe(&self) -> bool {
+        matches!(self.request_data, Some(RequestData::Subscribe(_)))
+    }
+
+    /// whether the request needs privilege, like the request which changes the state of the system
+    pub fn is_mutating(&self) -> bool {
+        match &self.request_data {
+            Some(RequestData::Ucomm(param)) => param.action() != unit_comm::Action::Status,
+            Some(RequestData::Ufile(param)) => !matches!(
+                param.action(),
+                unit_file::Action::Cat | unit_file::Action::Getdef
+            ),
+            Some(RequestData::Jcomm(param)) => param.action() != job_comm::Action::List,
+            Some(RequestData::Mcomm(_)) | Some(RequestData::Syscomm(_)) => true,
+            // the output of the services may contain sensitive data
+            Some(RequestData::Ulogs(_)) => true,
+            Some(RequestData::Lunits(_)) | Some(RequestData::Subscribe(_)) | None => false,
+        }
+    }
 }
@@ -1,13 +1,27 @@
 //!
 
-use clap::Parser;
+use clap::{ArgEnum, Parser};
 use libsysmaster::proto::{
-    abi::{sys_comm, unit_comm, CommandRequest},
-    unit_file, ProstClientStream,
+    abi::{job_comm, mngr_comm, sys_comm, unit_comm, CommandRequest, CommandResponse},
+    event::EventData,
+    unit_file, Event, JobInfo, LogEntry, ProstClientStream, StatusCode, UnitRow, UnitStatus,
+    SCTL_SOCKET,
 };
+use libutils::time_util::{self, USEC_PER_DAY, USEC_PER_HOUR, USEC_PER_MINUTE, USEC_PER_SEC};
 use libutils::Error;
 use libutils::Result;
-use std::net::{SocketAddr, TcpStream};
+use nix::sys::signal::Signal;
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+use std::os::unix::net::UnixStream;
+use std::process::exit;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// the interval of querying the new logs when following
+const LOGS_FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
 
 /// parse program arguments
 #[derive(Parser, Debug)]
@@ -20,81 +34,760 @@ struct Args {
     /// Number of times
     #[clap(short, long, default_value_t = 1)]
     count: u8,
+
+    /// Output format of the query commands
+    #[clap(short, long, arg_enum, default_value = "text")]
+    output: Output,
+}
+
+/// the output format of the query commands
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Output {
+    Text,
+    Json,
 }
 
 #[derive(Parser, Debug)]
 enum SubCmd {
     /// [unit] start the unit
     #[clap(display_order = 1)]
-    Start { unit_name: Option<String> },
+    Start {
+        unit_name: String,
+
+        /// wait until the start job finishes, and fail if the job does not succeed
+        #[clap(short, long)]
+        wait: bool,
+    },
 
     /// [unit] stop the unit
     #[clap(display_order = 2)]
-    Stop { unit_name: Option<String> },
+    Stop { unit_name: String },
 
     /// [unit] status of the unit
     #[clap(display_order = 3)]
-    Status { unit_name: Option<String> },
+    Status { unit_name: String },
+
+    /// [unit] restart the unit
+    #[clap(display_order = 4)]
+    Restart { unit_name: String },
+
+    /// [unit] reload the unit
+    #[clap(display_order = 5)]
+    Reload { unit_name: String },
+
+    /// [unit] send the signal to the processes of the unit
+    #[clap(display_order = 6)]
+    Kill {
+        unit_name: String,
+
+        /// the signal to send, such as SIGTERM, TERM or 15
+        #[clap(short, long, default_value = "SIGTERM")]
+        signal: String,
+    },
+
+    /// [unit] start the unit and stop all the others
+    #[clap(display_order = 7)]
+    Isolate { unit_name: String },
+
+    /// [unit] show the logs collected from the standard output and error of the unit
+    #[clap(display_order = 8)]
+    Logs {
+        unit_name: String,
+
+        /// keep showing the new logs
+        #[clap(short, long)]
+        follow: bool,
+
+        /// show the logs since the time, such as "2023-01-02 10:00:00", "2023-01-02" or a time
+        /// span before now like "1h 30min"
+        #[clap(short = 'S', long)]
+        since: Option<String>,
+    },
+
+    /// [unit] list the loaded units
+    ListUnits {
+        /// list the inactive units too
+        #[clap(short, long)]
+        all: bool,
+    },
+
+    /// [job] list the jobs in the job queue
+    ListJobs {},
+
+    /// [job] cancel the job
+    Cancel { job_id: u32 },
 
     /// [system] shutdown the system
     Shutdown {},
 
+    /// [system] reboot the system
+    Reboot {},
+
+    /// [system] halt the system
+    Halt {},
+
+    /// [system] power off the system
+    Poweroff {},
+
+    /// [system] suspend the system
+    Suspend {},
+
+    /// [system] hibernate the system
+    Hibernate {},
+
     /// manager command
     DaemonReload {},
 
-    /// enable one unit file
-    Enable { unit_file: Option<String> },
+    /// re-execute the manager
+    DaemonReexec {},
 
     /// enable one unit file
-    Disable { unit_file: Option<String> },
+    Enable { unit_file: String },
+
+    /// disable one unit file
+    Disable { unit_file: String },
+
+    /// mask one unit file
+    Mask { unit_file: String },
+
+    /// get the default target
+    GetDefault {},
+
+    /// set the default target
+    SetDefault { unit_file: String },
 }
 
-enum CommAction {
-    Unit(unit_comm::Action),
-    Sys(sys_comm::Action),
-    File(unit_file::Action),
+/// parse the signal from the name or the number, the name can omit the SIG prefix
+fn parse_signal(signal: &str) -> Result<Signal, String> {
+    if let Ok(num) = signal.parse::<i32>() {
+        return Signal::try_from(num).map_err(|_| format!("invalid signal: {}", signal));
+    }
+
+    let name = signal.to_uppercase();
+    let name = if name.starts_with("SIG") {
+        name
+    } else {
+        format!("SIG{}", name)
+    };
+    Signal::from_str(&name).map_err(|_| format!("invalid signal: {}", signal))
 }
 
-fn main() -> Result<(), Error> {
-    let args = Args::parse();
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in rows {
+        for (i, col) in row.iter().enumerate() {
+            widths[i] = widths[i].max(col.len());
+        }
+    }
+
+    let format_row = |cols: Vec<&str>| -> String {
+        let last = cols.len() - 1;
+        cols.iter()
+            .enumerate()
+            .map(|(i, col)| {
+                if i == last {
+                    col.to_string()
+                } else {
+                    format!("{:width$}", col, width = widths[i])
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{}", format_row(header.to_vec()));
+    for row in rows {
+        println!("{}", format_row(row.iter().map(|c| c.as_str()).collect()));
+    }
+}
+
+fn print_units(units: &[UnitRow]) {
+    let rows = units
+        .iter()
+        .map(|u| {
+            vec![
+                u.unitname.clone(),
+                u.load_state.clone(),
+                u.active_state.clone(),
+                u.sub_state.clone(),
+                u.description.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    print_table(&["UNIT", "LOAD", "ACTIVE", "SUB", "DESCRIPTION"], &rows);
+    println!("\n{} loaded units listed.", units.len());
+}
+
+fn print_jobs(jobs: &[JobInfo]) {
+    if jobs.is_empty() {
+        println!("No jobs running.");
+        return;
+    }
+
+    let rows = jobs
+        .iter()
+        .map(|j| {
+            vec![
+                j.id.to_string(),
+                j.unitname.clone(),
+                j.kind.clone(),
+                j.stage.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    print_table(&["JOB", "UNIT", "TYPE", "STATE"], &rows);
+    println!("\n{} jobs listed.", jobs.len());
+}
+
+/// parse the realtime in microseconds since the epoch from the local time or the time span before now
+fn parse_since(since: &str, now: u64) -> Result<u64, String> {
+    if let Ok(span) = time_util::parse_timespan(since) {
+        return Ok(now.saturating_sub(span));
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%d"] {
+        if let Some(time) = parse_local_time(since.trim(), format) {
+            return Ok(time as u64 * USEC_PER_SEC);
+        }
+    }
+    Err(format!("invalid time: {}", since))
+}
+
+fn parse_local_time(s: &str, format: &str) -> Option<libc::time_t> {
+    let c_str = CString::new(s).ok()?;
+    let c_format = CString::new(format).ok()?;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let end = unsafe { libc::strptime(c_str.as_ptr(), c_format.as_ptr(), &mut tm) };
+    // the whole string must be matched
+    if end.is_null() || unsafe { *end } != 0 {
+        return None;
+    }
+
+    tm.tm_isdst = -1;
+    let time = unsafe { libc::mktime(&mut tm) };
+    if time < 0 {
+        return None;
+    }
+    Some(time)
+}
+
+fn format_log(entry: &LogEntry) -> String {
+    let time = (entry.timestamp / USEC_PER_SEC) as libc::time_t;
+    format!(
+        "{} {}[{}]: {}",
+        format_time(time, "%b %d %H:%M:%S\0"),
+        entry.unitname,
+        entry.pid,
+        entry.message
+    )
+}
+
+fn print_logs(logs: &[LogEntry], output: Output) {
+    for entry in logs {
+        match output {
+            // one object per line, so the followed logs can be parsed line by line
+            Output::Json => match serde_json::to_string(entry) {
+                Ok(s) => println!("{}", s),
+                Err(e) => eprintln!("Failed to format the output as json: {}", e),
+            },
+            Output::Text => println!("{}", format_log(entry)),
+        }
+    }
+}
+
+/// query the logs page by page until all the logs are shown, and keep querying if following
+fn show_logs(unit_name: &str, follow: bool, since: Option<String>, output: Output) {
+    let now = time_util::timespec_load(SystemTime::now()) as u64;
+    let mut since = match since.map(|s| parse_since(&s, now)).transpose() {
+        Ok(s) => s.unwrap_or(0),
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+
+    loop {
+        let res = execute(CommandRequest::new_unitlogs(unit_name, since));
+        print_logs(&res.logs, output);
+        match res.logs.last() {
+            Some(entry) => since = entry.timestamp + 1,
+            None if follow => thread::sleep(LOGS_FOLLOW_INTERVAL),
+            None => return,
+        }
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(s) => println!("{}", s),
+        Err(e) => eprintln!("Failed to format the output as json: {}", e),
+    }
+}
+
+/// format the realtime in microseconds since the epoch as the local time
+fn format_timestamp(usec: u64) -> String {
+    let time = (usec / USEC_PER_SEC) as libc::time_t;
+    format_time(time, "%a %Y-%m-%d %H:%M:%S %Z\0")
+}
+
+/// format the time as the local time, the format must end with nul
+fn format_time(time: libc::time_t, format: &str) -> String {
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return String::new();
+    }
 
-    let (action, unit_name) = match args.subcmd {
-        SubCmd::Start { unit_name } => (CommAction::Unit(unit_comm::Action::Start), unit_name),
-        SubCmd::Stop { unit_name } => (CommAction::Unit(unit_comm::Action::Stop), unit_name),
-        SubCmd::Status { unit_name } => (CommAction::Unit(unit_comm::Action::Status), unit_name),
-        SubCmd::Shutdown {} => (CommAction::Sys(sys_comm::Action::Shutdown), None),
-        SubCmd::Enable { unit_file } => (CommAction::File(unit_file::Action::Enable), unit_file),
-        SubCmd::Disable { unit_file } => (CommAction::File(unit_file::Action::Disable), unit_file),
-        _ => unreachable!(),
+    let mut buf = [0 as libc::c_char; 64];
+    let len = unsafe {
+        libc::strftime(
+            buf.as_mut_ptr(),
+            buf.len(),
+            format.as_ptr() as *const libc::c_char,
+            &tm,
+        )
     };
+    if len == 0 {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .to_string()
+}
 
-    let addrs = [
-        SocketAddr::from(([127, 0, 0, 1], 9526)),
-        SocketAddr::from(([127, 0, 0, 1], 9527)),
+/// format the timespan in microseconds with the two most significant units, such as "1h 5min"
+fn format_timespan(usec: u64) -> String {
+    let units = [
+        (USEC_PER_DAY, "d"),
+        (USEC_PER_HOUR, "h"),
+        (USEC_PER_MINUTE, "min"),
+        (USEC_PER_SEC, "s"),
     ];
-    let stream = TcpStream::connect(&addrs[..]).unwrap();
 
-    let mut client = ProstClientStream::new(stream);
+    let mut left = usec;
+    let mut parts = Vec::new();
+    for (unit, suffix) in units {
+        if left >= unit {
+            parts.push(format!("{}{}", left / unit, suffix));
+            left %= unit;
+        } else if !parts.is_empty() {
+            // keep the units adjacent, "1d 5s" is more confusing than "1d"
+            break;
+        }
+        if parts.len() == 2 {
+            break;
+        }
+    }
+
+    if parts.is_empty() {
+        return String::from("0s");
+    }
+    parts.join(" ")
+}
+
+/// render the status of the unit like "systemctl status"
+fn format_status(status: &UnitStatus, now: u64) -> String {
+    let mut lines = Vec::new();
+
+    if status.description.is_empty() {
+        lines.push(format!("● {}", status.unitname));
+    } else {
+        lines.push(format!("● {} - {}", status.unitname, status.description));
+    }
+
+    if status.fragment_path.is_empty() {
+        lines.push(format!("     Loaded: {}", status.load_state));
+    } else {
+        lines.push(format!(
+            "     Loaded: {} ({})",
+            status.load_state, status.fragment_path
+        ));
+    }
+
+    let mut active = format!("{} ({})", status.active_state, status.sub_state);
+    if status.active_state == "failed" && !status.result.is_empty() {
+        active = format!("{} (Result: {})", status.active_state, status.result);
+    }
+    let since = match status.active_state.as_str() {
+        "active" | "reloading" => status.active_enter_timestamp,
+        "activating" => status.inactive_exit_timestamp,
+        "deactivating" => status.active_exit_timestamp,
+        _ => status.inactive_enter_timestamp,
+    };
+    if since > 0 {
+        active = format!(
+            "{} since {}; {} ago",
+            active,
+            format_timestamp(since),
+            format_timespan(now.saturating_sub(since))
+        );
+    }
+    lines.push(format!("     Active: {}", active));
+
+    let exit = match status.main_code.as_str() {
+        "exited" => format!("code=exited, status={}", status.main_status),
+        "killed" => match Signal::try_from(status.main_status) {
+            Ok(sig) => format!("code=killed, signal={}", sig),
+            Err(_) => format!("code=killed, signal={}", status.main_status),
+        },
+        _ => String::new(),
+    };
+    if status.main_pid != 0 {
+        lines.push(format!("   Main PID: {}", status.main_pid));
+    } else if !exit.is_empty() {
+        lines.push(format!("   Main PID: ({})", exit));
+    }
+
+    if !status.status_text.is_empty() {
+        lines.push(format!("     Status: \"{}\"", status.status_text));
+    }
+
+    if !status.cgroup.is_empty() {
+        lines.push(format!("     CGroup: {}", status.cgroup));
+    }
+
+    for job in &status.jobs {
+        lines.push(format!(
+            "        Job: {} {} ({})",
+            job.id, job.kind, job.stage
+        ));
+    }
 
-    match action {
-        CommAction::Unit(a) => {
-            let cmd = CommandRequest::new_unitcomm(a, unit_name.unwrap());
-            println!("{:?}", cmd);
-            let data = client.execute(cmd).unwrap();
-            println!("{:?}", data);
+    lines.join("\n")
+}
+
+fn print_status(res: &CommandResponse, output: Output) {
+    let status = match &res.unit_status {
+        Some(s) => s,
+        None => return,
+    };
+
+    match output {
+        Output::Json => print_json(status),
+        Output::Text => {
+            let now = time_util::timespec_load(SystemTime::now()) as u64;
+            println!("{}", format_status(status, now));
         }
-        CommAction::Sys(a) => {
-            let cmd = CommandRequest::new_syscomm(a);
-            println!("{:?}", cmd);
-            let data = client.execute(cmd).unwrap();
-            println!("{:?}", data);
+    }
+}
+
+/// connect to sysmaster, exit if it fails
+fn connect() -> ProstClientStream<UnixStream> {
+    match UnixStream::connect(SCTL_SOCKET) {
+        Ok(s) => ProstClientStream::new(s),
+        Err(e) => {
+            eprintln!("Failed to connect to {}: {}", SCTL_SOCKET, e);
+            exit(1);
         }
-        CommAction::File(a) => {
-            let cmd = CommandRequest::new_unitfile(a, unit_name.unwrap());
-            println!("{:?}", cmd);
-            let data = client.execute(cmd).unwrap();
-            println!("{:?}", data);
+    }
+}
+
+/// send the command to sysmaster, exit if it fails
+fn execute(cmd: CommandRequest) -> CommandResponse {
+    execute_on(&mut connect(), cmd)
+}
+
+/// send the command through the connection, exit if it fails
+fn execute_on(client: &mut ProstClientStream<UnixStream>, cmd: CommandRequest) -> CommandResponse {
+    let res = match client.execute(cmd) {
+        Ok(res) => res,
+        Err(e) => {
+            eprintln!("Failed to execute the command: {}", e);
+            exit(1);
         }
+    };
+
+    let status = StatusCode::from_u16(res.status as u16).unwrap_or(StatusCode::OK);
+    if !status.is_success() {
+        eprintln!("Failed: {}", res.message);
+        exit(1);
+    }
+
+    res
+}
+
+/// get the result of the job if the event is its removal
+fn start_job_result(event: &Event, job_id: u32) -> Option<&str> {
+    match &event.event_data {
+        Some(EventData::JobRemoved(job)) if job.id == job_id => Some(&job.result),
+        _ => None,
     }
+}
+
+/// start the unit and wait for its start job to finish, exit if the job does not succeed
+fn start_and_wait(unit_name: &str) {
+    // subscribe before starting, so the end of the job can not be missed
+    let mut events = connect();
+    execute_on(&mut events, CommandRequest::new_subscribe());
+    let job_id = execute(CommandRequest::new_unitcomm(
+        unit_comm::Action::Start,
+        unit_name,
+    ))
+    .job_id;
+    if job_id == 0 {
+        return;
+    }
+
+    loop {
+        let event = match events.next_event() {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("Failed to wait for the start job of {}: {}", unit_name, e);
+                exit(1);
+            }
+        };
+        match start_job_result(&event, job_id) {
+            Some("done") => return,
+            Some(result) => {
+                eprintln!("Job for {} failed, result: {}", unit_name, result);
+                exit(1);
+            }
+            None => {}
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    let (cmd, print): (CommandRequest, fn(&CommandResponse, Output)) = match args.subcmd {
+        SubCmd::Start { unit_name, wait } => {
+            if wait {
+                start_and_wait(&unit_name);
+                return Ok(());
+            }
+            (
+                CommandRequest::new_unitcomm(unit_comm::Action::Start, unit_name),
+                |_, _| {},
+            )
+        }
+        SubCmd::Stop { unit_name } => (
+            CommandRequest::new_unitcomm(unit_comm::Action::Stop, unit_name),
+            |_, _| {},
+        ),
+        SubCmd::Status { unit_name } => (
+            CommandRequest::new_unitcomm(unit_comm::Action::Status, unit_name),
+            print_status,
+        ),
+        SubCmd::Restart { unit_name } => (
+            CommandRequest::new_unitcomm(unit_comm::Action::Restart, unit_name),
+            |_, _| {},
+        ),
+        SubCmd::Reload { unit_name } => (
+            CommandRequest::new_unitcomm(unit_comm::Action::Reload, unit_name),
+            |_, _| {},
+        ),
+        SubCmd::Kill { unit_name, signal } => {
+            let signal = match parse_signal(&signal) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            };
+            (
+                CommandRequest::new_unitkill(unit_name, signal as i32),
+                |_, _| {},
+            )
+        }
+        SubCmd::Isolate { unit_name } => (
+            CommandRequest::new_unitcomm(unit_comm::Action::Isolate, unit_name),
+            |_, _| {},
+        ),
+        SubCmd::Logs {
+            unit_name,
+            follow,
+            since,
+        } => {
+            show_logs(&unit_name, follow, since, args.output);
+            return Ok(());
+        }
+        SubCmd::ListUnits { all } => (
+            CommandRequest::new_listunits(all),
+            |res, output| match output {
+                Output::Json => print_json(&res.units),
+                Output::Text => print_units(&res.units),
+            },
+        ),
+        SubCmd::ListJobs {} => (
+            CommandRequest::new_jobcomm(job_comm::Action::List, ""),
+            |res, output| match output {
+                Output::Json => print_json(&res.jobs),
+                Output::Text => print_jobs(&res.jobs),
+            },
+        ),
+        SubCmd::Cancel { job_id } => (
+            CommandRequest::new_jobcomm(job_comm::Action::Cancel, job_id.to_string()),
+            |_, _| {},
+        ),
+        SubCmd::Shutdown {} => (
+            CommandRequest::new_syscomm(sys_comm::Action::Shutdown),
+            |_, _| {},
+        ),
+        SubCmd::Reboot {} => (
+            CommandRequest::new_syscomm(sys_comm::Action::Reboot),
+            |_, _| {},
+        ),
+        SubCmd::Halt {} => (
+            CommandRequest::new_syscomm(sys_comm::Action::Halt),
+            |_, _| {},
+        ),
+        SubCmd::Poweroff {} => (
+            CommandRequest::new_syscomm(sys_comm::Action::Poweroff),
+            |_, _| {},
+        ),
+        SubCmd::Suspend {} => (
+            CommandRequest::new_syscomm(sys_comm::Action::Suspend),
+            |_, _| {},
+        ),
+        SubCmd::Hibernate {} => (
+            CommandRequest::new_syscomm(sys_comm::Action::Hibernate),
+            |_, _| {},
+        ),
+        SubCmd::DaemonReload {} => (
+            CommandRequest::new_mngrcomm(mngr_comm::Action::Reload),
+            |_, _| {},
+        ),
+        SubCmd::DaemonReexec {} => (
+            CommandRequest::new_mngrcomm(mngr_comm::Action::Reexec),
+            |_, _| {},
+        ),
+        SubCmd::Enable { unit_file } => (
+            CommandRequest::new_unitfile(unit_file::Action::Enable, unit_file),
+            |_, _| {},
+        ),
+        SubCmd::Disable { unit_file } => (
+            CommandRequest::new_unitfile(unit_file::Action::Disable, unit_file),
+            |_, _| {},
+        ),
+        SubCmd::Mask { unit_file } => (
+            CommandRequest::new_unitfile(unit_file::Action::Mask, unit_file),
+            |_, _| {},
+        ),
+        SubCmd::GetDefault {} => (
+            CommandRequest::new_unitfile(unit_file::Action::Getdef, ""),
+            |res, _| println!("{}", res.message),
+        ),
+        SubCmd::SetDefault { unit_file } => (
+            CommandRequest::new_unitfile(unit_file::Action::Setdef, unit_file),
+            |_, _| {},
+        ),
+    };
+
+    let res = execute(cmd);
+    print(&res, args.output);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_status, format_timespan, parse_local_time, parse_signal, parse_since,
+        start_job_result,
+    };
+    use libsysmaster::proto::event::EventData;
+    use libsysmaster::proto::{Event, JobInfo, JobNew, JobRemoved, UnitStatus};
+    use libutils::time_util::{USEC_PER_HOUR, USEC_PER_MINUTE, USEC_PER_SEC};
+    use nix::sys::signal::Signal;
+
+    #[test]
+    fn test_parse_signal() {
+        assert_eq!(parse_signal("SIGKILL"), Ok(Signal::SIGKILL));
+        assert_eq!(parse_signal("hup"), Ok(Signal::SIGHUP));
+        assert_eq!(parse_signal("15"), Ok(Signal::SIGTERM));
+        assert!(parse_signal("NOSUCH").is_err());
+        assert!(parse_signal("1000").is_err());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = 100 * USEC_PER_HOUR;
+        assert_eq!(parse_since("1h 30min", now), Ok(now - 90 * USEC_PER_MINUTE));
+        assert_eq!(parse_since("1000h", now), Ok(0));
+
+        let time = parse_local_time("2023-01-02 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            parse_since("2023-01-02 10:00:00", now),
+            Ok(time as u64 * USEC_PER_SEC)
+        );
+        assert_eq!(
+            parse_since("2023-01-02 10:00", now),
+            Ok(time as u64 * USEC_PER_SEC)
+        );
+        assert!(parse_since("2023-01-02", now).unwrap() < time as u64 * USEC_PER_SEC);
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("2023-01-02 10:00:00 extra", now).is_err());
+    }
+
+    #[test]
+    fn test_format_timespan() {
+        assert_eq!(format_timespan(0), "0s");
+        assert_eq!(format_timespan(30 * USEC_PER_SEC + 1), "30s");
+        assert_eq!(
+            format_timespan(USEC_PER_HOUR + 5 * USEC_PER_MINUTE + 3 * USEC_PER_SEC),
+            "1h 5min"
+        );
+        assert_eq!(format_timespan(USEC_PER_HOUR + 3 * USEC_PER_SEC), "1h");
+    }
+
+    #[test]
+    fn test_format_status() {
+        let status = UnitStatus {
+            unitname: "foo.service".to_string(),
+            description: "foo daemon".to_string(),
+            load_state: "loaded".to_string(),
+            active_state: "failed".to_string(),
+            sub_state: "failed".to_string(),
+            fragment_path: "/usr/lib/sysmaster/foo.service".to_string(),
+            result: "signal".to_string(),
+            main_code: "killed".to_string(),
+            main_status: 9,
+            cgroup: "foo.service".to_string(),
+            status_text: "waiting for connections".to_string(),
+            jobs: vec![JobInfo {
+                id: 3,
+                unitname: "foo.service".to_string(),
+                kind: "start".to_string(),
+                stage: "waiting".to_string(),
+            }],
+            ..Default::default()
+        };
+        let lines = format_status(&status, 0);
+        let lines = lines.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "● foo.service - foo daemon");
+        assert_eq!(
+            lines[1],
+            "     Loaded: loaded (/usr/lib/sysmaster/foo.service)"
+        );
+        assert_eq!(lines[2], "     Active: failed (Result: signal)");
+        assert_eq!(lines[3], "   Main PID: (code=killed, signal=SIGKILL)");
+        assert_eq!(lines[4], "     Status: \"waiting for connections\"");
+        assert_eq!(lines[5], "     CGroup: foo.service");
+        assert_eq!(lines[6], "        Job: 3 start (waiting)");
+    }
+
+    #[test]
+    fn test_start_job_result() {
+        let removed = |id: u32| Event {
+            event_data: Some(EventData::JobRemoved(JobRemoved {
+                id,
+                unitname: "foo.service".to_string(),
+                kind: "start".to_string(),
+                result: "failed".to_string(),
+            })),
+        };
+        assert_eq!(start_job_result(&removed(1), 1), Some("failed"));
+        // another start job of the same unit
+        assert_eq!(start_job_result(&removed(2), 1), None);
+
+        let new = Event {
+            event_data: Some(EventData::JobNew(JobNew {
+                id: 1,
+                unitname: "foo.service".to_string(),
+                kind: "start".to_string(),
+            })),
+        };
+        assert_eq!(start_job_result(&new, 1), None);
+    }
+}
@@ -25,7 +25,7 @@ function load_docker_img() {
 
 function build_base_img() {
     local bin_list='pctrl init sysmaster fstab sysmonitor random_seed rc-local-generator'
-    local lib_list='libmount.so libservice.so libsocket.so libtarget.so'
+    local lib_list='libmount.so libservice.so libsocket.so libtarget.so libtimer.so libpath.so libslice.so'
 
     mkdir "${TMP_DIR}"/bin "${TMP_DIR}"/lib
     pushd "${SYSMST_INSTALL_SOURCE}" || return 1